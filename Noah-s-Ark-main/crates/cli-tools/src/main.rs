@@ -1,7 +1,13 @@
-use anyhow::{Context, Result};
-use database::DatabasePool;
-use genealogy_types::{Person, PersonId, Sex};
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDateTime;
+use database::{
+    DatabasePool, DispatchReport, FamilyStore, GraphRepository, OutboxDispatcher, PersonStore,
+    SqliteStore, Storage,
+};
+use genealogy_types::{ImportReport, Person, PersonId, Sex};
+use importer::{GedcomStreamImporter, ImportFormat, ImportOutcome};
 use std::env;
+use std::fs::File;
 use tokio::runtime::Runtime;
 use uuid::Uuid;
 
@@ -14,14 +20,50 @@ fn main() -> Result<()> {
             let last = args.next().unwrap_or_else(|| "Doe".into());
             Runtime::new()?.block_on(seed_person(&first, &last))
         }
+        Some("import-gedcom") => {
+            let path = args.next().context("chemin du fichier GEDCOM manquant")?;
+            let import_id = args.next().map(|id| Uuid::parse_str(&id)).transpose()?;
+            Runtime::new()?.block_on(import_gedcom(&path, import_id))
+        }
+        Some("preview-gedcom") => {
+            let path = args.next().context("chemin du fichier GEDCOM manquant")?;
+            Runtime::new()?.block_on(preview_gedcom(&path))
+        }
+        Some("reconcile-graph") => {
+            let fix = args.next().as_deref() == Some("--fix");
+            Runtime::new()?.block_on(reconcile_graph(fix))
+        }
+        Some("dispatch-outbox") => Runtime::new()?.block_on(dispatch_outbox()),
+        Some("purge-trash") => {
+            let days = match args.next() {
+                Some(days) => days.parse().context("nombre de jours invalide")?,
+                None => 30,
+            };
+            Runtime::new()?.block_on(purge_trash(days))
+        }
+        Some("check-import") => {
+            let path = args.next().context("chemin du fichier manquant")?;
+            check_import(&path)
+        }
         _ => {
-            println!("Utilisation : cli-tools <ping-db|seed-person>");
+            println!(
+                "Utilisation : cli-tools <ping-db|seed-person|import-gedcom <fichier> [import-id]|preview-gedcom <fichier>|reconcile-graph [--fix]|dispatch-outbox|purge-trash [jours]|check-import <fichier>>"
+            );
             Ok(())
         }
     }
 }
 
 async fn ping_db() -> Result<()> {
+    if let Some(store) = connect_sqlite().await? {
+        store
+            .pool
+            .acquire()
+            .await
+            .context("Impossible d'ouvrir la base SQLite")?;
+        println!("SQLite OK");
+        return Ok(());
+    }
     let db = connect().await?;
     db.postgres
         .acquire()
@@ -32,8 +74,6 @@ async fn ping_db() -> Result<()> {
 }
 
 async fn seed_person(first_name: &str, surname: &str) -> Result<()> {
-    let db = connect().await?;
-    let repo = database::PersonRepository::new(db);
     let now = chrono::Utc::now().naive_utc();
     let person = Person {
         id: PersonId(Uuid::new_v4()),
@@ -56,15 +96,169 @@ async fn seed_person(first_name: &str, surname: &str) -> Result<()> {
         updated_by: None,
     };
 
-    repo.create(&person).await?;
+    match connect_sqlite().await? {
+        Some(store) => store.persons().create(&person).await?,
+        None => {
+            database::PersonRepository::new(connect().await?)
+                .create(&person)
+                .await?
+        }
+    };
     println!("Personne {} {} insérée", first_name, surname);
     Ok(())
 }
 
+/// Import en flux ; relancer avec le même `import-id` reprend un import interrompu.
+async fn import_gedcom(path: &str, import_id: Option<Uuid>) -> Result<()> {
+    let db = connect().await?;
+    let file = File::open(path).with_context(|| format!("Impossible d'ouvrir {path}"))?;
+    let import_id = import_id.unwrap_or_else(Uuid::new_v4);
+    println!("Import {import_id}");
+
+    let outcome = GedcomStreamImporter::new(db)
+        .import(import_id, Some(path), file, |progress| {
+            eprintln!(
+                "{:?} : {} enregistrements, {} personnes, {} familles, {} liens",
+                progress.phase,
+                progress.records,
+                progress.persons,
+                progress.families,
+                progress.edges
+            );
+        })
+        .await?;
+    eprintln!(
+        "{} personnes et {} familles importées",
+        outcome.report.persons, outcome.report.families
+    );
+    print_outcome(&outcome)
+}
+
+/// Personnes que créerait ou modifierait `import-gedcom`, sans écrire en base.
+async fn preview_gedcom(path: &str) -> Result<()> {
+    let db = connect().await?;
+    let file = File::open(path).with_context(|| format!("Impossible d'ouvrir {path}"))?;
+    print_outcome(&GedcomStreamImporter::new(db).preview(file).await?)
+}
+
+/// Arêtes `CHILD_OF` de Neo4j à créer ou supprimer pour refléter PostgreSQL ;
+/// `--fix` les corrige.
+async fn reconcile_graph(fix: bool) -> Result<()> {
+    let report = GraphRepository::new(connect().await?)
+        .reconcile(fix)
+        .await?;
+    eprintln!(
+        "{} arêtes manquantes, {} en trop{}",
+        report.missing.len(),
+        report.stale.len(),
+        if fix && !report.is_consistent() {
+            " : corrigé"
+        } else {
+            ""
+        }
+    );
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+/// Applique les effets Neo4j et Redis en attente dans `outbox`, puis oublie ceux
+/// appliqués depuis plus d'une semaine.
+async fn dispatch_outbox() -> Result<()> {
+    let dispatcher = OutboxDispatcher::new(connect().await?);
+    let mut total = DispatchReport::default();
+    loop {
+        // Un événement en échec est reporté et arrête la file jusqu'à son nouvel essai.
+        let report = dispatcher.dispatch_pending().await?;
+        if report.delivered + report.failed == 0 {
+            break;
+        }
+        total.delivered += report.delivered;
+        total.failed += report.failed;
+    }
+    let purged = dispatcher.purge_processed(7).await?;
+    println!(
+        "{} événements appliqués, {} en échec, {} anciens supprimés",
+        total.delivered, total.failed, purged
+    );
+    Ok(())
+}
+
+/// Rapport d'import d'un GEDCOM ou d'un `.gw`, sans écrire en base.
+fn check_import(path: &str) -> Result<()> {
+    let format = ImportFormat::from_path(path)
+        .with_context(|| format!("format inconnu pour {path} (.ged ou .gw attendu)"))?;
+    let file = File::open(path).with_context(|| format!("Impossible d'ouvrir {path}"))?;
+    print_report(&importer::check_import(file, format)?)
+}
+
+/// Résumé sur la sortie d'erreur, rapport complet en JSON sur la sortie standard.
+fn print_report(report: &ImportReport) -> Result<()> {
+    for (kind, count) in report.summary() {
+        eprintln!("{kind:?} : {count}");
+    }
+    println!("{}", serde_json::to_string_pretty(report)?);
+    Ok(())
+}
+
+/// Résumé du rapport et des changements sur la sortie d'erreur, détail en JSON.
+fn print_outcome(outcome: &ImportOutcome) -> Result<()> {
+    for (kind, count) in outcome.report.summary() {
+        eprintln!("{kind:?} : {count}");
+    }
+    let diff = &outcome.diff;
+    eprintln!(
+        "{} personnes créées, {} modifiées, {} inchangées ; {} familles créées, {} retrouvées",
+        diff.created.len(),
+        diff.updated.len(),
+        diff.unchanged,
+        diff.families_created,
+        diff.families_matched
+    );
+    println!("{}", serde_json::to_string_pretty(outcome)?);
+    Ok(())
+}
+
+/// Supprime définitivement ce qui est à la corbeille depuis plus de `days` jours.
+async fn purge_trash(days: i64) -> Result<()> {
+    let before = chrono::Utc::now().naive_utc() - chrono::Duration::days(days);
+    let (families, persons) = match connect_sqlite().await? {
+        Some(store) => purge_before(&store, before).await?,
+        None => purge_before(&connect().await?, before).await?,
+    };
+    println!("{persons} personnes et {families} familles purgées");
+    Ok(())
+}
+
+async fn purge_before<S: Storage>(store: &S, before: NaiveDateTime) -> Result<(u64, u64)> {
+    let families = store.families().purge_trash(before).await?;
+    let persons = store.persons().purge_trash(before).await?;
+    Ok((families, persons))
+}
+
+fn database_url() -> String {
+    env::var("DATABASE_URL").unwrap_or_else(|_| "postgres://localhost/geneweb".into())
+}
+
+/// Base SQLite si `DATABASE_URL` commence par `sqlite:`.
+async fn connect_sqlite() -> Result<Option<SqliteStore>> {
+    let url = database_url();
+    if !url.starts_with("sqlite:") {
+        return Ok(None);
+    }
+    let redis_url = env::var("REDIS_URL").ok();
+    Ok(Some(
+        SqliteStore::connect(&url, redis_url.as_deref()).await?,
+    ))
+}
+
 async fn connect() -> Result<DatabasePool> {
+    let url = database_url();
+    if url.starts_with("sqlite:") {
+        bail!("les imports GEDCOM nécessitent PostgreSQL, pas une base SQLite ({url})");
+    }
     DatabasePool::new(
-        &env::var("DATABASE_URL").unwrap_or_else(|_| "postgres://localhost/geneweb".into()),
-        &env::var("NEO4J_URL").unwrap_or_else(|_| "neo4j://localhost:7687".into()),
+        &url,
+        env::var("NEO4J_URL").ok().as_deref(),
         &env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".into()),
     )
     .await
@@ -0,0 +1,21 @@
+-- Imports GEDCOM en flux : suivi et correspondance xref → identifiant
+CREATE TABLE IF NOT EXISTS gedcom_imports (
+    id UUID PRIMARY KEY,
+    file_name TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'running',
+    persons INTEGER NOT NULL DEFAULT 0,
+    families INTEGER NOT NULL DEFAULT 0,
+    started_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMP
+);
+
+-- Un xref (`I12`, `F3`) n'est unique qu'au sein d'un fichier
+CREATE TABLE IF NOT EXISTS gedcom_xrefs (
+    import_id UUID REFERENCES gedcom_imports(id) ON DELETE CASCADE,
+    xref TEXT NOT NULL,
+    record_type VARCHAR(10) NOT NULL,
+    entity_id UUID NOT NULL,
+    PRIMARY KEY (import_id, xref)
+);
+
+CREATE INDEX IF NOT EXISTS idx_gedcom_xrefs_type ON gedcom_xrefs(import_id, record_type);
@@ -0,0 +1,2 @@
+-- Rapport d'import (tags ignorés, valeurs perdues, xrefs orphelins ou en double)
+ALTER TABLE gedcom_imports ADD COLUMN IF NOT EXISTS report JSONB;
@@ -0,0 +1,10 @@
+-- Identifiants externes (_UID, REFN, EXID) pour rapprocher les réimports
+CREATE TABLE IF NOT EXISTS person_external_ids (
+    kind VARCHAR(255) NOT NULL,
+    value TEXT NOT NULL,
+    person_id UUID NOT NULL REFERENCES persons(id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (kind, value)
+);
+
+CREATE INDEX IF NOT EXISTS idx_person_external_ids_person ON person_external_ids(person_id);
@@ -0,0 +1,2 @@
+-- Date complète des événements (qualificatif, calendrier, texte d'origine)
+ALTER TABLE events ADD COLUMN IF NOT EXISTS date_json JSONB;
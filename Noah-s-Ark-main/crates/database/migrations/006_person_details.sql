@@ -0,0 +1,27 @@
+-- Agrégat complet des personnes : professions, sources, témoins et ordre des éléments
+ALTER TABLE persons ADD COLUMN IF NOT EXISTS occupations TEXT[] DEFAULT ARRAY[]::TEXT[];
+
+-- `slot` distingue la naissance et le décès (ou le mariage et le divorce d'une famille)
+-- des autres événements, rangés par `position`
+ALTER TABLE events ADD COLUMN IF NOT EXISTS slot VARCHAR(10) NOT NULL DEFAULT 'other';
+ALTER TABLE events ADD COLUMN IF NOT EXISTS position INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE events ADD COLUMN IF NOT EXISTS witnesses UUID[] DEFAULT ARRAY[]::UUID[];
+
+ALTER TABLE event_sources ADD COLUMN IF NOT EXISTS position INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE person_titles ADD COLUMN IF NOT EXISTS date_start_json JSONB;
+ALTER TABLE person_titles ADD COLUMN IF NOT EXISTS date_end_json JSONB;
+ALTER TABLE person_titles ADD COLUMN IF NOT EXISTS position INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE person_images ADD COLUMN IF NOT EXISTS position INTEGER NOT NULL DEFAULT 0;
+
+-- Sources citées pour la personne elle-même
+CREATE TABLE IF NOT EXISTS person_sources (
+    person_id UUID REFERENCES persons(id) ON DELETE CASCADE,
+    source_id UUID REFERENCES sources(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (person_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_person_titles_person ON person_titles(person_id);
+CREATE INDEX IF NOT EXISTS idx_person_images_person ON person_images(person_id);
@@ -0,0 +1,7 @@
+-- Sources citées pour la famille elle-même
+CREATE TABLE IF NOT EXISTS family_sources (
+    family_id UUID REFERENCES families(id) ON DELETE CASCADE,
+    source_id UUID REFERENCES sources(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (family_id, position)
+);
@@ -0,0 +1,16 @@
+-- Arêtes `(enfant)-[:CHILD_OF]->(parent)` déduites des familles, pour parcourir
+-- le graphe par des CTE récursives quand Neo4j n'est pas configuré.
+CREATE OR REPLACE VIEW child_of (child_id, parent_id) AS
+    SELECT fc.child_id, f.father_id
+    FROM family_children fc
+    JOIN families f ON f.id = fc.family_id
+    WHERE f.father_id IS NOT NULL
+    UNION
+    SELECT fc.child_id, f.mother_id
+    FROM family_children fc
+    JOIN families f ON f.id = fc.family_id
+    WHERE f.mother_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_families_father ON families(father_id);
+CREATE INDEX IF NOT EXISTS idx_families_mother ON families(mother_id);
+CREATE INDEX IF NOT EXISTS idx_family_children_child ON family_children(child_id);
@@ -0,0 +1,28 @@
+-- Corbeille : une personne ou une famille supprimée garde ses lignes, masquées de
+-- la recherche et du graphe, jusqu'à sa restauration ou sa purge.
+ALTER TABLE persons
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS deleted_by UUID REFERENCES wizards(id);
+
+ALTER TABLE families
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS deleted_by UUID REFERENCES wizards(id);
+
+CREATE INDEX IF NOT EXISTS idx_persons_trash ON persons(deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_families_trash ON families(deleted_at) WHERE deleted_at IS NOT NULL;
+
+-- Une famille, un enfant ou un parent à la corbeille ne porte plus d'arête.
+CREATE OR REPLACE VIEW child_of (child_id, parent_id) AS
+    SELECT fc.child_id, f.father_id
+    FROM family_children fc
+    JOIN families f ON f.id = fc.family_id
+    JOIN persons c ON c.id = fc.child_id
+    JOIN persons p ON p.id = f.father_id
+    WHERE f.deleted_at IS NULL AND c.deleted_at IS NULL AND p.deleted_at IS NULL
+    UNION
+    SELECT fc.child_id, f.mother_id
+    FROM family_children fc
+    JOIN families f ON f.id = fc.family_id
+    JOIN persons c ON c.id = fc.child_id
+    JOIN persons p ON p.id = f.mother_id
+    WHERE f.deleted_at IS NULL AND c.deleted_at IS NULL AND p.deleted_at IS NULL;
@@ -0,0 +1,15 @@
+-- Historique des personnes et des familles : chaque création, modification,
+-- suppression ou restauration garde l'état complet avant et après, en JSON. Un
+-- événement est versionné avec la personne ou la famille qui le porte.
+CREATE TABLE IF NOT EXISTS revisions (
+    id BIGSERIAL PRIMARY KEY,
+    entity_type VARCHAR(16) NOT NULL CHECK (entity_type IN ('person', 'family')),
+    entity_id UUID NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    before_snapshot JSONB,
+    after_snapshot JSONB,
+    changed_by UUID REFERENCES wizards(id),
+    changed_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_revisions_entity ON revisions(entity_type, entity_id, id);
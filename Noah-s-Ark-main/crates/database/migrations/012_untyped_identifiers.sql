@@ -0,0 +1,3 @@
+-- Un REFN ou un EXID sans TYPE n'est qu'un numéro propre au fichier : il ne sert
+-- plus à rapprocher les réimports.
+DELETE FROM person_external_ids WHERE kind IN ('REFN', 'EXID');
@@ -0,0 +1,3 @@
+-- Lot d'événements réservé par un dispatcher : les appels Neo4j et Redis se font
+-- hors transaction, et un lot abandonné redevient disponible à l'échéance.
+ALTER TABLE outbox ADD COLUMN IF NOT EXISTS claimed_until TIMESTAMP;
@@ -0,0 +1,22 @@
+-- Les types d'événements personnalisés (`EVEN` + `TYPE`, `#xxx` d'un .gw) et les
+-- textes importés n'ont pas de longueur maximale : une valeur trop longue ne doit
+-- plus faire échouer tout le lot.
+ALTER TABLE events ALTER COLUMN event_type TYPE TEXT;
+ALTER TABLE persons
+    ALTER COLUMN first_name TYPE TEXT,
+    ALTER COLUMN surname TYPE TEXT,
+    ALTER COLUMN surname_prefix TYPE TEXT;
+ALTER TABLE places
+    ALTER COLUMN name TYPE TEXT,
+    ALTER COLUMN locality TYPE TEXT,
+    ALTER COLUMN city TYPE TEXT,
+    ALTER COLUMN county TYPE TEXT,
+    ALTER COLUMN state TYPE TEXT,
+    ALTER COLUMN country TYPE TEXT;
+ALTER TABLE person_titles
+    ALTER COLUMN name TYPE TEXT,
+    ALTER COLUMN place TYPE TEXT;
+ALTER TABLE sources
+    ALTER COLUMN title TYPE TEXT,
+    ALTER COLUMN author TYPE TEXT,
+    ALTER COLUMN repository TYPE TEXT;
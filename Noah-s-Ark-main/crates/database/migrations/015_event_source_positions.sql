@@ -0,0 +1,14 @@
+-- Une source citée plusieurs fois pour un même événement garde chacune de ses
+-- citations : la clé devient le rang de la citation, comme pour `person_sources`.
+WITH ranked AS (
+    SELECT ctid, ROW_NUMBER() OVER (PARTITION BY event_id ORDER BY position, source_id) - 1 AS rank
+    FROM event_sources
+)
+UPDATE event_sources es SET position = ranked.rank
+FROM ranked
+WHERE es.ctid = ranked.ctid;
+
+ALTER TABLE event_sources DROP CONSTRAINT IF EXISTS event_sources_pkey;
+ALTER TABLE event_sources ALTER COLUMN event_id SET NOT NULL;
+ALTER TABLE event_sources ALTER COLUMN source_id SET NOT NULL;
+ALTER TABLE event_sources ADD PRIMARY KEY (event_id, position);
@@ -0,0 +1,5 @@
+-- Emplacement cité (`PAGE` GEDCOM) : propre à chaque citation, il est porté par le
+-- lien et non par la source partagée.
+ALTER TABLE event_sources ADD COLUMN IF NOT EXISTS page TEXT;
+ALTER TABLE person_sources ADD COLUMN IF NOT EXISTS page TEXT;
+ALTER TABLE family_sources ADD COLUMN IF NOT EXISTS page TEXT;
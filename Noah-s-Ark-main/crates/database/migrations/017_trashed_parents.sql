@@ -0,0 +1,8 @@
+-- Familles dont une personne mise à la corbeille avec `force` a été retirée comme
+-- père ou mère ; sa restauration l'y remet si la place est restée libre.
+CREATE TABLE IF NOT EXISTS trashed_parents (
+    person_id UUID NOT NULL REFERENCES persons(id) ON DELETE CASCADE,
+    family_id UUID NOT NULL REFERENCES families(id) ON DELETE CASCADE,
+    role VARCHAR(6) NOT NULL CHECK (role IN ('father', 'mother')),
+    PRIMARY KEY (person_id, family_id, role)
+);
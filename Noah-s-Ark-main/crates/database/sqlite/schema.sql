@@ -0,0 +1,115 @@
+-- Schéma du backend SQLite (`SqliteStore`), appliqué à chaque ouverture de la base.
+-- Identifiants en texte (UUID), dates au format ISO ; lieux, témoins, sources,
+-- titres et images en JSON.
+
+CREATE TABLE IF NOT EXISTS persons (
+    id TEXT PRIMARY KEY,
+    first_name TEXT NOT NULL,
+    surname TEXT NOT NULL,
+    surname_prefix TEXT,
+    nicknames TEXT NOT NULL DEFAULT '[]',
+    sex TEXT NOT NULL,
+    occupations TEXT NOT NULL DEFAULT '[]',
+    titles TEXT NOT NULL DEFAULT '[]',
+    sources TEXT NOT NULL DEFAULT '[]',
+    images TEXT NOT NULL DEFAULT '[]',
+    public INTEGER NOT NULL DEFAULT 1,
+    notes TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    updated_by TEXT,
+    deleted_at TEXT,
+    deleted_by TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_persons_name ON persons (surname, first_name);
+CREATE INDEX IF NOT EXISTS idx_persons_trash ON persons (deleted_at) WHERE deleted_at IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS families (
+    id TEXT PRIMARY KEY,
+    father_id TEXT REFERENCES persons (id) ON DELETE SET NULL,
+    mother_id TEXT REFERENCES persons (id) ON DELETE SET NULL,
+    notes TEXT,
+    sources TEXT NOT NULL DEFAULT '[]',
+    public INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    deleted_at TEXT,
+    deleted_by TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_families_father ON families (father_id);
+CREATE INDEX IF NOT EXISTS idx_families_mother ON families (mother_id);
+CREATE INDEX IF NOT EXISTS idx_families_trash ON families (deleted_at) WHERE deleted_at IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS family_children (
+    family_id TEXT NOT NULL REFERENCES families (id) ON DELETE CASCADE,
+    child_id TEXT NOT NULL REFERENCES persons (id) ON DELETE CASCADE,
+    child_order INTEGER NOT NULL,
+    PRIMARY KEY (family_id, child_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_family_children_child ON family_children (child_id);
+
+-- Familles dont une personne mise à la corbeille avec `force` a été retirée comme
+-- père (`father`) ou mère (`mother`), pour l'y remettre à sa restauration.
+CREATE TABLE IF NOT EXISTS trashed_parents (
+    person_id TEXT NOT NULL REFERENCES persons (id) ON DELETE CASCADE,
+    family_id TEXT NOT NULL REFERENCES families (id) ON DELETE CASCADE,
+    role TEXT NOT NULL,
+    PRIMARY KEY (person_id, family_id, role)
+);
+
+-- Événement d'une personne (`birth`, `death`, `other`) ou d'une famille
+-- (`marriage`, `divorce`, `other`).
+CREATE TABLE IF NOT EXISTS events (
+    id TEXT PRIMARY KEY,
+    person_id TEXT REFERENCES persons (id) ON DELETE CASCADE,
+    family_id TEXT REFERENCES families (id) ON DELETE CASCADE,
+    slot TEXT NOT NULL DEFAULT 'other',
+    position INTEGER NOT NULL DEFAULT 0,
+    event_type TEXT NOT NULL,
+    date_value TEXT,
+    date_value_end TEXT,
+    date_json TEXT,
+    place TEXT,
+    witnesses TEXT NOT NULL DEFAULT '[]',
+    notes TEXT,
+    sources TEXT NOT NULL DEFAULT '[]'
+);
+
+CREATE INDEX IF NOT EXISTS idx_events_person ON events (person_id);
+CREATE INDEX IF NOT EXISTS idx_events_family ON events (family_id);
+
+-- Historique des personnes et des familles, états avant et après en JSON.
+CREATE TABLE IF NOT EXISTS revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    before_snapshot TEXT,
+    after_snapshot TEXT,
+    changed_by TEXT,
+    changed_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_revisions_entity ON revisions (entity_type, entity_id, id);
+
+-- Arêtes `(enfant)-[:CHILD_OF]->(parent)`, parcourues par des CTE récursives ; une
+-- famille, un enfant ou un parent à la corbeille n'en porte plus. Recréée à chaque
+-- ouverture pour suivre le schéma.
+DROP VIEW IF EXISTS child_of;
+CREATE VIEW child_of (child_id, parent_id) AS
+    SELECT fc.child_id, f.father_id
+    FROM family_children fc
+    JOIN families f ON f.id = fc.family_id
+    JOIN persons c ON c.id = fc.child_id
+    JOIN persons p ON p.id = f.father_id
+    WHERE f.deleted_at IS NULL AND c.deleted_at IS NULL AND p.deleted_at IS NULL
+    UNION
+    SELECT fc.child_id, f.mother_id
+    FROM family_children fc
+    JOIN families f ON f.id = fc.family_id
+    JOIN persons c ON c.id = fc.child_id
+    JOIN persons p ON p.id = f.mother_id
+    WHERE f.deleted_at IS NULL AND c.deleted_at IS NULL AND p.deleted_at IS NULL;
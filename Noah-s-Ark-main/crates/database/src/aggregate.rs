@@ -0,0 +1,656 @@
+//! Lignes satellites des personnes et des familles : événements (lieux, témoins,
+//! sources), titres, images et sources. Elles sont réécrites en bloc, dans la
+//! transaction de leur propriétaire.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use genealogy_types::{
+    EventType, Family, ImageReference, LifeEvent, Person, PersonId, Place, PreciseDate, Source,
+    Title,
+};
+use sqlx::{PgConnection, Postgres, QueryBuilder, Row};
+use uuid::Uuid;
+
+use crate::DateColumns;
+
+/// Lignes par insertion, sous la limite de 65 535 paramètres de PostgreSQL.
+const CHUNK: usize = 1000;
+
+/// Propriétaire des lignes de `events` et des liens vers `sources`.
+#[derive(Clone, Copy)]
+pub(crate) enum Owner {
+    Person,
+    Family,
+}
+
+impl Owner {
+    pub(crate) fn column(self) -> &'static str {
+        match self {
+            Owner::Person => "person_id",
+            Owner::Family => "family_id",
+        }
+    }
+
+    fn sources_table(self) -> &'static str {
+        match self {
+            Owner::Person => "person_sources",
+            Owner::Family => "family_sources",
+        }
+    }
+}
+
+/// Événements et sources d'une famille, empruntés à un `Family` ou à un brouillon.
+pub(crate) struct FamilyDetails<'a> {
+    pub id: Uuid,
+    pub marriage: Option<&'a LifeEvent>,
+    pub divorce: Option<&'a LifeEvent>,
+    pub other_events: &'a [LifeEvent],
+    pub sources: &'a [Source],
+}
+
+/// Événement à écrire : `slot` vaut `birth`, `death`, `marriage`, `divorce` ou `other`.
+pub(crate) struct EventRow<'a> {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub slot: &'static str,
+    pub position: i32,
+    pub event: &'a LifeEvent,
+}
+
+/// Événement relu, avec son emplacement.
+pub(crate) struct StoredEvent {
+    pub id: Uuid,
+    pub slot: String,
+    pub event: LifeEvent,
+}
+
+/// Insère des événements avec leurs lieux et leurs sources.
+pub(crate) async fn insert_events(
+    conn: &mut PgConnection,
+    owner: Owner,
+    rows: &[EventRow<'_>],
+) -> Result<()> {
+    // Chaque événement a son propre lieu, supprimé avec lui.
+    let rows: Vec<(Uuid, Option<Uuid>, &EventRow)> = rows
+        .iter()
+        .map(|row| {
+            let place_id = row.event.place.as_ref().map(|_| Uuid::new_v4());
+            (row.id, place_id, row)
+        })
+        .collect();
+
+    let places: Vec<(Uuid, &Place)> = rows
+        .iter()
+        .filter_map(|(_, place_id, row)| Some(((*place_id)?, row.event.place.as_ref()?)))
+        .collect();
+    for chunk in places.chunks(CHUNK) {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "INSERT INTO places (id, name, locality, city, county, state, country, latitude, longitude) ",
+        );
+        builder.push_values(chunk, |mut row, (id, place)| {
+            row.push_bind(*id)
+                .push_bind(&place.name)
+                .push_bind(&place.locality)
+                .push_bind(&place.city)
+                .push_bind(&place.county)
+                .push_bind(&place.state)
+                .push_bind(&place.country)
+                .push_bind(place.latitude)
+                .push_bind(place.longitude);
+        });
+        builder.build().execute(&mut *conn).await?;
+    }
+
+    upsert_sources(conn, rows.iter().flat_map(|(_, _, row)| &row.event.sources)).await?;
+
+    for chunk in rows.chunks(CHUNK) {
+        let dates = chunk
+            .iter()
+            .map(|(_, _, row)| DateColumns::new(Some(&row.event.date)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut builder = QueryBuilder::<Postgres>::new(format!(
+            "INSERT INTO events (id, {}, event_type, slot, position, date_type, date_value, \
+             date_value_end, date_json, place_id, witnesses, notes) ",
+            owner.column()
+        ));
+        builder.push_values(
+            chunk.iter().zip(dates),
+            |mut values, ((id, place_id, row), date)| {
+                let witnesses: Vec<Uuid> = row.event.witnesses.iter().map(|w| w.0).collect();
+                values
+                    .push_bind(*id)
+                    .push_bind(row.owner_id)
+                    .push_bind(row.event.event_type.name().to_string())
+                    .push_bind(row.slot)
+                    .push_bind(row.position)
+                    .push_bind(date.date_type)
+                    .push_bind(date.value)
+                    .push_bind(date.value_end)
+                    .push_bind(date.json)
+                    .push_unseparated("::jsonb")
+                    .push_bind(*place_id)
+                    .push_bind(witnesses)
+                    .push_bind(row.event.notes.clone());
+            },
+        );
+        builder.build().execute(&mut *conn).await?;
+    }
+
+    let links: Vec<(Uuid, &Source, i32)> = rows
+        .iter()
+        .flat_map(|(event_id, _, row)| {
+            row.event
+                .sources
+                .iter()
+                .enumerate()
+                .map(move |(position, source)| (*event_id, source, position as i32))
+        })
+        .collect();
+    for chunk in links.chunks(CHUNK) {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "INSERT INTO event_sources (event_id, source_id, position, page) ",
+        );
+        builder.push_values(chunk, |mut row, (event_id, source, position)| {
+            row.push_bind(*event_id)
+                .push_bind(source.id)
+                .push_bind(*position)
+                .push_bind(&source.page);
+        });
+        builder.build().execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+/// Supprime les événements de ces propriétaires et leurs lieux.
+pub(crate) async fn delete_events(
+    conn: &mut PgConnection,
+    owner: Owner,
+    ids: &[Uuid],
+) -> Result<()> {
+    delete_events_where(conn, owner.column(), ids).await
+}
+
+/// Supprime ces événements et leurs lieux.
+pub(crate) async fn delete_events_by_id(conn: &mut PgConnection, ids: &[Uuid]) -> Result<()> {
+    delete_events_where(conn, "id", ids).await
+}
+
+async fn delete_events_where(conn: &mut PgConnection, column: &str, ids: &[Uuid]) -> Result<()> {
+    let places: Vec<Option<Uuid>> = sqlx::query_scalar(&format!(
+        "DELETE FROM events WHERE {column} = ANY($1) RETURNING place_id"
+    ))
+    .bind(ids)
+    .fetch_all(&mut *conn)
+    .await?;
+    let places: Vec<Uuid> = places.into_iter().flatten().collect();
+    if !places.is_empty() {
+        sqlx::query("DELETE FROM places WHERE id = ANY($1)")
+            .bind(places)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Événements de ces propriétaires, dans l'ordre de `position`.
+pub(crate) async fn load_events(
+    conn: &mut PgConnection,
+    owner: Owner,
+    ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<StoredEvent>>> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT e.id, e.{column} AS owner_id, e.event_type, e.slot, e.date_value,
+               e.date_json::text AS date_json, e.witnesses, e.notes, e.place_id,
+               p.name, p.locality, p.city, p.county, p.state, p.country, p.latitude, p.longitude
+        FROM events e
+        LEFT JOIN places p ON p.id = e.place_id
+        WHERE e.{column} = ANY($1)
+        ORDER BY e.{column}, e.position, e.created_at
+        "#,
+        column = owner.column()
+    ))
+    .bind(ids)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let event_ids: Vec<Uuid> = rows.iter().map(|row| row.get("id")).collect();
+    let mut sources = load_sources(
+        conn,
+        r#"
+        SELECT l.event_id AS owner_id, s.id, s.title, s.author, s.publication_info,
+               s.repository, s.notes, l.page
+        FROM event_sources l
+        JOIN sources s ON s.id = l.source_id
+        WHERE l.event_id = ANY($1)
+        ORDER BY l.event_id, l.position
+        "#,
+        &event_ids,
+    )
+    .await?;
+
+    let mut events: HashMap<Uuid, Vec<StoredEvent>> = HashMap::new();
+    for row in &rows {
+        let place = row.get::<Option<Uuid>, _>("place_id").map(|_| Place {
+            name: row.get("name"),
+            locality: row.get("locality"),
+            city: row.get("city"),
+            county: row.get("county"),
+            state: row.get("state"),
+            country: row.get("country"),
+            latitude: row.get("latitude"),
+            longitude: row.get("longitude"),
+        });
+        let event = LifeEvent {
+            event_type: EventType::from_name(row.get("event_type")),
+            date: DateColumns::read(row)?.unwrap_or(PreciseDate::Unknown),
+            place,
+            witnesses: row
+                .get::<Option<Vec<Uuid>>, _>("witnesses")
+                .unwrap_or_default()
+                .into_iter()
+                .map(PersonId)
+                .collect(),
+            notes: row.get("notes"),
+            sources: sources
+                .remove(&row.get::<Uuid, _>("id"))
+                .unwrap_or_default(),
+        };
+        events
+            .entry(row.get("owner_id"))
+            .or_default()
+            .push(StoredEvent {
+                id: row.get("id"),
+                slot: row.get("slot"),
+                event,
+            });
+    }
+    Ok(events)
+}
+
+/// Écrit les événements, titres, images et sources de ces personnes.
+pub(crate) async fn insert_person_details(
+    conn: &mut PgConnection,
+    persons: &[Person],
+) -> Result<()> {
+    let events: Vec<EventRow> = persons
+        .iter()
+        .flat_map(|person| {
+            event_rows(
+                person.id.0,
+                [
+                    ("birth", person.birth.as_ref()),
+                    ("death", person.death.as_ref()),
+                ],
+                &person.other_events,
+            )
+        })
+        .collect();
+    insert_events(conn, Owner::Person, &events).await?;
+
+    let titles: Vec<(Uuid, i32, &Title)> = persons
+        .iter()
+        .flat_map(|person| {
+            person
+                .titles
+                .iter()
+                .enumerate()
+                .map(|(position, title)| (person.id.0, position as i32, title))
+        })
+        .collect();
+    for chunk in titles.chunks(CHUNK) {
+        let dates = chunk
+            .iter()
+            .map(|(_, _, title)| {
+                Ok((
+                    DateColumns::new(title.date_start.as_ref())?,
+                    DateColumns::new(title.date_end.as_ref())?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "INSERT INTO person_titles (id, person_id, position, name, place, date_start, date_end, \
+             date_start_json, date_end_json, nth) ",
+        );
+        builder.push_values(
+            chunk.iter().zip(dates),
+            |mut row, ((person_id, position, title), (start, end))| {
+                row.push_bind(Uuid::new_v4())
+                    .push_bind(*person_id)
+                    .push_bind(*position)
+                    .push_bind(&title.name)
+                    .push_bind(&title.place)
+                    .push_bind(start.value)
+                    .push_bind(end.value_end)
+                    .push_bind(start.json)
+                    .push_unseparated("::jsonb")
+                    .push_bind(end.json)
+                    .push_unseparated("::jsonb")
+                    .push_bind(title.nth.map(|nth| nth as i32));
+            },
+        );
+        builder.build().execute(&mut *conn).await?;
+    }
+
+    let images: Vec<(Uuid, i32, &ImageReference)> = persons
+        .iter()
+        .flat_map(|person| {
+            person
+                .images
+                .iter()
+                .enumerate()
+                .map(|(position, image)| (person.id.0, position as i32, image))
+        })
+        .collect();
+    for chunk in images.chunks(CHUNK) {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "INSERT INTO person_images (id, person_id, position, url, description, is_primary) ",
+        );
+        builder.push_values(chunk, |mut row, (person_id, position, image)| {
+            row.push_bind(Uuid::new_v4())
+                .push_bind(*person_id)
+                .push_bind(*position)
+                .push_bind(&image.url)
+                .push_bind(&image.description)
+                .push_bind(image.is_primary);
+        });
+        builder.build().execute(&mut *conn).await?;
+    }
+
+    let sources: Vec<(Uuid, &[Source])> = persons
+        .iter()
+        .map(|person| (person.id.0, person.sources.as_slice()))
+        .collect();
+    insert_source_links(conn, Owner::Person, &sources).await
+}
+
+/// Supprime les lignes écrites par [`insert_person_details`] ; les sources restent partagées.
+pub(crate) async fn delete_person_details(conn: &mut PgConnection, ids: &[Uuid]) -> Result<()> {
+    delete_events(conn, Owner::Person, ids).await?;
+    for table in ["person_titles", "person_images", "person_sources"] {
+        sqlx::query(&format!("DELETE FROM {table} WHERE person_id = ANY($1)"))
+            .bind(ids)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Complète des personnes lues dans `persons` avec leurs lignes satellites.
+pub(crate) async fn load_person_details(
+    conn: &mut PgConnection,
+    persons: &mut [Person],
+) -> Result<()> {
+    if persons.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<Uuid> = persons.iter().map(|person| person.id.0).collect();
+    let mut events = load_events(conn, Owner::Person, &ids).await?;
+
+    let rows = sqlx::query(
+        r#"
+        SELECT person_id, name, place, date_start, date_end,
+               date_start_json::text AS date_start_json, date_end_json::text AS date_end_json, nth
+        FROM person_titles
+        WHERE person_id = ANY($1)
+        ORDER BY person_id, position
+        "#,
+    )
+    .bind(&ids)
+    .fetch_all(&mut *conn)
+    .await?;
+    let mut titles: HashMap<Uuid, Vec<Title>> = HashMap::new();
+    for row in &rows {
+        let title = Title {
+            name: row.get("name"),
+            place: row.get("place"),
+            date_start: DateColumns::decode(row.get("date_start_json"), row.get("date_start"))?,
+            date_end: DateColumns::decode(row.get("date_end_json"), row.get("date_end"))?,
+            nth: row.get::<Option<i32>, _>("nth").map(|nth| nth as u32),
+        };
+        titles.entry(row.get("person_id")).or_default().push(title);
+    }
+
+    let rows = sqlx::query(
+        r#"
+        SELECT person_id, url, description, is_primary
+        FROM person_images
+        WHERE person_id = ANY($1)
+        ORDER BY person_id, position
+        "#,
+    )
+    .bind(&ids)
+    .fetch_all(&mut *conn)
+    .await?;
+    let mut images: HashMap<Uuid, Vec<ImageReference>> = HashMap::new();
+    for row in &rows {
+        images
+            .entry(row.get("person_id"))
+            .or_default()
+            .push(ImageReference {
+                url: row.get("url"),
+                description: row.get("description"),
+                is_primary: row.get::<Option<bool>, _>("is_primary").unwrap_or(false),
+            });
+    }
+
+    let mut sources = load_source_links(conn, Owner::Person, &ids).await?;
+
+    for person in persons.iter_mut() {
+        let id = person.id.0;
+        person.birth = None;
+        person.death = None;
+        person.other_events.clear();
+        for stored in events.remove(&id).unwrap_or_default() {
+            match stored.slot.as_str() {
+                "birth" => person.birth = Some(stored.event),
+                "death" => person.death = Some(stored.event),
+                _ => person.other_events.push(stored.event),
+            }
+        }
+        person.titles = titles.remove(&id).unwrap_or_default();
+        person.images = images.remove(&id).unwrap_or_default();
+        person.sources = sources.remove(&id).unwrap_or_default();
+    }
+    Ok(())
+}
+
+/// Écrit les événements et les sources de ces familles.
+pub(crate) async fn insert_family_details(
+    conn: &mut PgConnection,
+    families: &[FamilyDetails<'_>],
+) -> Result<()> {
+    let events: Vec<EventRow> = families
+        .iter()
+        .flat_map(|family| {
+            event_rows(
+                family.id,
+                [("marriage", family.marriage), ("divorce", family.divorce)],
+                family.other_events,
+            )
+        })
+        .collect();
+    insert_events(conn, Owner::Family, &events).await?;
+
+    let sources: Vec<(Uuid, &[Source])> = families
+        .iter()
+        .map(|family| (family.id, family.sources))
+        .collect();
+    insert_source_links(conn, Owner::Family, &sources).await
+}
+
+/// Supprime les lignes écrites par [`insert_family_details`].
+pub(crate) async fn delete_family_details(conn: &mut PgConnection, ids: &[Uuid]) -> Result<()> {
+    delete_events(conn, Owner::Family, ids).await?;
+    sqlx::query("DELETE FROM family_sources WHERE family_id = ANY($1)")
+        .bind(ids)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Complète des familles lues dans `families` avec leurs événements et leurs sources.
+pub(crate) async fn load_family_details(
+    conn: &mut PgConnection,
+    families: &mut [Family],
+) -> Result<()> {
+    if families.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<Uuid> = families.iter().map(|family| family.id.0).collect();
+    let mut events = load_events(conn, Owner::Family, &ids).await?;
+    let mut sources = load_source_links(conn, Owner::Family, &ids).await?;
+
+    for family in families.iter_mut() {
+        let id = family.id.0;
+        family.marriage = None;
+        family.divorce = None;
+        family.other_events.clear();
+        for stored in events.remove(&id).unwrap_or_default() {
+            match stored.slot.as_str() {
+                "marriage" => family.marriage = Some(stored.event),
+                "divorce" => family.divorce = Some(stored.event),
+                _ => family.other_events.push(stored.event),
+            }
+        }
+        family.sources = sources.remove(&id).unwrap_or_default();
+    }
+    Ok(())
+}
+
+/// Événements à emplacement unique (naissance, mariage…) puis les autres, dans l'ordre.
+pub(crate) fn event_rows<'a>(
+    owner_id: Uuid,
+    slots: [(&'static str, Option<&'a LifeEvent>); 2],
+    others: &'a [LifeEvent],
+) -> Vec<EventRow<'a>> {
+    let slotted = slots.into_iter().filter_map(|(slot, event)| {
+        Some(EventRow {
+            id: Uuid::new_v4(),
+            owner_id,
+            slot,
+            position: 0,
+            event: event?,
+        })
+    });
+    let others = others.iter().enumerate().map(|(position, event)| EventRow {
+        id: Uuid::new_v4(),
+        owner_id,
+        slot: "other",
+        position: position as i32,
+        event,
+    });
+    slotted.chain(others).collect()
+}
+
+/// Crée ou met à jour des sources ; une source citée plusieurs fois n'est écrite qu'une fois.
+async fn upsert_sources<'a>(
+    conn: &mut PgConnection,
+    sources: impl Iterator<Item = &'a Source>,
+) -> Result<()> {
+    let sources: HashMap<Uuid, &Source> = sources.map(|source| (source.id, source)).collect();
+    let sources: Vec<&Source> = sources.into_values().collect();
+    for chunk in sources.chunks(CHUNK) {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "INSERT INTO sources (id, title, author, publication_info, repository, notes) ",
+        );
+        builder.push_values(chunk, |mut row, source| {
+            row.push_bind(source.id)
+                .push_bind(&source.title)
+                .push_bind(&source.author)
+                .push_bind(&source.publication_info)
+                .push_bind(&source.repository)
+                .push_bind(&source.notes);
+        });
+        builder.push(
+            " ON CONFLICT (id) DO UPDATE SET title = EXCLUDED.title, author = EXCLUDED.author, \
+             publication_info = EXCLUDED.publication_info, repository = EXCLUDED.repository, \
+             notes = EXCLUDED.notes",
+        );
+        builder.build().execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+/// Crée les sources citées et les lie à leur propriétaire, dans l'ordre de citation.
+async fn insert_source_links(
+    conn: &mut PgConnection,
+    owner: Owner,
+    owners: &[(Uuid, &[Source])],
+) -> Result<()> {
+    upsert_sources(conn, owners.iter().flat_map(|(_, sources)| sources.iter())).await?;
+    let links: Vec<(Uuid, &Source, i32)> = owners
+        .iter()
+        .flat_map(|(owner_id, sources)| {
+            sources
+                .iter()
+                .enumerate()
+                .map(move |(position, source)| (*owner_id, source, position as i32))
+        })
+        .collect();
+    for chunk in links.chunks(CHUNK) {
+        let mut builder = QueryBuilder::<Postgres>::new(format!(
+            "INSERT INTO {} ({}, source_id, position, page) ",
+            owner.sources_table(),
+            owner.column()
+        ));
+        builder.push_values(chunk, |mut row, (owner_id, source, position)| {
+            row.push_bind(*owner_id)
+                .push_bind(source.id)
+                .push_bind(*position)
+                .push_bind(&source.page);
+        });
+        builder.build().execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+/// Sources liées à ces propriétaires.
+async fn load_source_links(
+    conn: &mut PgConnection,
+    owner: Owner,
+    ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<Source>>> {
+    let sql = format!(
+        r#"
+        SELECT l.{column} AS owner_id, s.id, s.title, s.author, s.publication_info,
+               s.repository, s.notes, l.page
+        FROM {table} l
+        JOIN sources s ON s.id = l.source_id
+        WHERE l.{column} = ANY($1)
+        ORDER BY l.{column}, l.position
+        "#,
+        column = owner.column(),
+        table = owner.sources_table()
+    );
+    load_sources(conn, &sql, ids).await
+}
+
+/// Sources groupées par `owner_id`, dans l'ordre de la requête.
+async fn load_sources(
+    conn: &mut PgConnection,
+    sql: &str,
+    ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<Source>>> {
+    let mut sources: HashMap<Uuid, Vec<Source>> = HashMap::new();
+    if ids.is_empty() {
+        return Ok(sources);
+    }
+    let rows = sqlx::query(sql).bind(ids).fetch_all(&mut *conn).await?;
+    for row in &rows {
+        sources
+            .entry(row.get("owner_id"))
+            .or_default()
+            .push(Source {
+                id: row.get("id"),
+                title: row.get("title"),
+                author: row.get("author"),
+                publication_info: row.get("publication_info"),
+                repository: row.get("repository"),
+                notes: row.get("notes"),
+                page: row.get("page"),
+            });
+    }
+    Ok(sources)
+}
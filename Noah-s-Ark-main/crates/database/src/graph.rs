@@ -0,0 +1,449 @@
+use std::collections::{HashSet, VecDeque};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use genealogy_types::PersonId;
+use neo4rs::{query, Graph, Query, Row};
+use serde::Serialize;
+use uuid::Uuid;
+
+use sqlx::PgConnection;
+
+use crate::outbox::MAX_ATTEMPTS;
+use crate::{DatabasePool, GraphStore, OutboxEvent};
+
+/// Graphe de filiation : Neo4j s'il est configuré, sinon la vue PostgreSQL
+/// `child_of` parcourue par des CTE récursives.
+pub struct GraphRepository {
+    pub pool: DatabasePool,
+}
+
+/// Écarts entre les arêtes `CHILD_OF` de Neo4j et celles que justifient les familles.
+#[derive(Debug, Default, Serialize)]
+pub struct GraphReconciliation {
+    /// Arêtes `(enfant, parent)` de la vue `child_of` absentes de Neo4j.
+    pub missing: Vec<(PersonId, PersonId)>,
+    /// Arêtes de Neo4j qu'aucune famille ne justifie.
+    pub stale: Vec<(PersonId, PersonId)>,
+    /// Vrai si les écarts ont été corrigés dans Neo4j.
+    pub fixed: bool,
+}
+
+impl GraphReconciliation {
+    pub fn is_consistent(&self) -> bool {
+        self.missing.is_empty() && self.stale.is_empty()
+    }
+}
+
+/// Crée les arêtes `$children[i] -> $parents[i]`, et les nœuds qui manqueraient.
+pub(crate) const MERGE_EDGES: &str = "UNWIND range(0, size($children) - 1) AS i
+     MERGE (c:Person {id: $children[i]})
+     MERGE (p:Person {id: $parents[i]})
+     MERGE (c)-[:CHILD_OF]->(p)";
+
+/// Supprime les arêtes `$children[i] -> $parents[i]`.
+pub(crate) const DELETE_EDGES: &str = "UNWIND range(0, size($children) - 1) AS i
+     MATCH (:Person {id: $children[i]})-[r:CHILD_OF]->(:Person {id: $parents[i]})
+     DELETE r";
+
+/// Taille des lots d'écritures Cypher de la réconciliation, et des lectures de
+/// la vue `child_of`.
+const RECONCILE_BATCH: usize = 1000;
+
+/// Arêtes et personnes que des événements `outbox` non encore appliqués, ou
+/// appliqués pendant la réconciliation, peuvent modifier dans Neo4j.
+#[derive(Default)]
+struct InFlight {
+    edges: HashSet<(Uuid, Uuid)>,
+    persons: HashSet<Uuid>,
+}
+
+impl InFlight {
+    /// Événements en attente (hors abandonnés) ou appliqués depuis `since`.
+    async fn load(conn: &mut PgConnection, since: NaiveDateTime) -> Result<Self> {
+        let events: Vec<String> = sqlx::query_scalar(
+            "SELECT event::text FROM outbox
+             WHERE (processed_at IS NULL AND attempts < $1) OR processed_at >= $2",
+        )
+        .bind(MAX_ATTEMPTS)
+        .bind(since)
+        .fetch_all(conn)
+        .await?;
+        let mut in_flight = Self::default();
+        for event in events {
+            match serde_json::from_str(&event)? {
+                OutboxEvent::MergeEdges { edges } | OutboxEvent::DeleteEdges { edges } => in_flight
+                    .edges
+                    .extend(edges.iter().map(|(child, parent)| (child.0, parent.0))),
+                OutboxEvent::DeletePersons { ids } => {
+                    in_flight.persons.extend(ids.iter().map(|id| id.0))
+                }
+                OutboxEvent::MergePersons { .. } | OutboxEvent::InvalidatePersons { .. } => {}
+            }
+        }
+        Ok(in_flight)
+    }
+
+    fn contains(&self, (child, parent): &(Uuid, Uuid)) -> bool {
+        self.edges.contains(&(*child, *parent))
+            || self.persons.contains(child)
+            || self.persons.contains(parent)
+    }
+}
+
+/// Arêtes du curseur `expected_edges`, lues par lots.
+struct SqlEdges<'c> {
+    conn: &'c mut PgConnection,
+    batch: VecDeque<(Uuid, Uuid)>,
+    exhausted: bool,
+}
+
+impl<'c> SqlEdges<'c> {
+    fn new(conn: &'c mut PgConnection) -> Self {
+        Self {
+            conn,
+            batch: VecDeque::new(),
+            exhausted: false,
+        }
+    }
+
+    async fn next(&mut self) -> Result<Option<(Uuid, Uuid)>> {
+        if self.batch.is_empty() && !self.exhausted {
+            let rows: Vec<(Uuid, Uuid)> =
+                sqlx::query_as(&format!("FETCH {RECONCILE_BATCH} FROM expected_edges"))
+                    .fetch_all(&mut *self.conn)
+                    .await?;
+            self.exhausted = rows.len() < RECONCILE_BATCH;
+            self.batch.extend(rows);
+        }
+        Ok(self.batch.pop_front())
+    }
+}
+
+/// Arête d'une ligne `RETURN child, parent`.
+fn neo4j_edge(row: Row) -> Result<(Uuid, Uuid)> {
+    let child: String = row.get("child")?;
+    let parent: String = row.get("parent")?;
+    Ok((Uuid::parse_str(&child)?, Uuid::parse_str(&parent)?))
+}
+
+impl GraphRepository {
+    pub fn new(pool: DatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Compare les arêtes `CHILD_OF` de Neo4j à la vue `child_of` de PostgreSQL,
+    /// seule source de vérité ; avec `fix`, crée les manquantes et supprime les autres.
+    ///
+    /// Les deux côtés sont parcourus en flux, triés, sans charger toutes les arêtes.
+    /// Neo4j est lu d'abord (son tri fige le résultat), puis un instantané
+    /// PostgreSQL : toute arête ou personne
+    /// visée par un événement `outbox` en attente, ou appliqué depuis le début de la
+    /// lecture de Neo4j, est ignorée, son écriture pouvant être en cours.
+    pub async fn reconcile(&self, fix: bool) -> Result<GraphReconciliation> {
+        let neo4j = self
+            .pool
+            .neo4j
+            .as_ref()
+            .ok_or_else(|| anyhow!("Neo4j n'est pas configuré : aucune arête à réconcilier"))?;
+
+        let started: NaiveDateTime = sqlx::query_scalar("SELECT clock_timestamp()::timestamp")
+            .fetch_one(&self.pool.postgres)
+            .await?;
+        let mut actual = neo4j
+            .execute(query(
+                "MATCH (c:Person)-[:CHILD_OF]->(p:Person)
+                 RETURN DISTINCT c.id as child, p.id as parent
+                 ORDER BY child, parent",
+            ))
+            .await?;
+
+        let mut tx = self.pool.postgres.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+        let in_flight = InFlight::load(&mut tx, started).await?;
+        sqlx::query(
+            "DECLARE expected_edges NO SCROLL CURSOR FOR
+             SELECT child_id, parent_id FROM child_of ORDER BY child_id, parent_id",
+        )
+        .execute(&mut *tx)
+        .await?;
+        let mut expected = SqlEdges::new(&mut tx);
+
+        let mut missing = Vec::new();
+        let mut stale = Vec::new();
+        let mut next_expected = expected.next().await?;
+        let mut next_actual = actual.next().await?.map(neo4j_edge).transpose()?;
+        loop {
+            let (edge, is_missing) = match (next_expected, next_actual) {
+                (None, None) => break,
+                (Some(sql), Some(graph)) if sql == graph => {
+                    next_expected = expected.next().await?;
+                    next_actual = actual.next().await?.map(neo4j_edge).transpose()?;
+                    continue;
+                }
+                (Some(sql), Some(graph)) if sql < graph => {
+                    next_expected = expected.next().await?;
+                    (sql, true)
+                }
+                (Some(sql), None) => {
+                    next_expected = expected.next().await?;
+                    (sql, true)
+                }
+                (_, Some(graph)) => {
+                    next_actual = actual.next().await?.map(neo4j_edge).transpose()?;
+                    (graph, false)
+                }
+            };
+            if in_flight.contains(&edge) {
+                continue;
+            }
+            if is_missing {
+                missing.push(edge);
+            } else {
+                stale.push(edge);
+            }
+        }
+        tx.commit().await?;
+
+        if fix {
+            // Les nœuds sont créés au besoin : une personne peut manquer si son
+            // écriture Neo4j a échoué.
+            for batch in missing.chunks(RECONCILE_BATCH) {
+                neo4j.run(edge_query(MERGE_EDGES, batch)).await?;
+            }
+            for batch in stale.chunks(RECONCILE_BATCH) {
+                neo4j.run(edge_query(DELETE_EDGES, batch)).await?;
+            }
+        }
+
+        let edges = |edges: Vec<(Uuid, Uuid)>| {
+            edges
+                .into_iter()
+                .map(|(child, parent)| (PersonId(child), PersonId(parent)))
+                .collect()
+        };
+        Ok(GraphReconciliation {
+            missing: edges(missing),
+            stale: edges(stale),
+            fixed: fix,
+        })
+    }
+
+    /// Identifiants de la colonne `id` de chaque ligne.
+    async fn ids(neo4j: &Graph, cypher: Query) -> Result<Vec<PersonId>> {
+        let mut result = neo4j.execute(cypher).await?;
+        let mut ids = Vec::new();
+        while let Some(row) = result.next().await? {
+            let id: String = row.get("id")?;
+            ids.push(PersonId(Uuid::parse_str(&id)?));
+        }
+        Ok(ids)
+    }
+
+    /// Identifiants renvoyés par une requête SQL dont l'unique colonne est un UUID.
+    async fn sql_ids(
+        &self,
+        sql: &str,
+        ids: &[Uuid],
+        generations: Option<u32>,
+    ) -> Result<Vec<PersonId>> {
+        let mut query = sqlx::query_scalar::<_, Uuid>(sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        if let Some(generations) = generations {
+            query = query.bind(generations as i32);
+        }
+        let ids = query.fetch_all(&self.pool.postgres).await?;
+        Ok(ids.into_iter().map(PersonId).collect())
+    }
+
+    /// Parcours en largeur, une requête par niveau : une CTE récursive sur un graphe
+    /// non orienté énumérerait tous les chemins avant de s'arrêter.
+    async fn sql_distance(&self, first: PersonId, second: PersonId) -> Result<Option<u32>> {
+        let mut seen = HashSet::from([first.0]);
+        let mut frontier = vec![first.0];
+        let mut distance = 0;
+        while !frontier.is_empty() {
+            if frontier.contains(&second.0) {
+                return Ok(Some(distance));
+            }
+            let next: Vec<Uuid> = sqlx::query_scalar(
+                "SELECT parent_id FROM child_of WHERE child_id = ANY($1)
+                 UNION
+                 SELECT child_id FROM child_of WHERE parent_id = ANY($1)",
+            )
+            .bind(&frontier)
+            .fetch_all(&self.pool.postgres)
+            .await?;
+            frontier = next.into_iter().filter(|id| seen.insert(*id)).collect();
+            distance += 1;
+        }
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl GraphStore for GraphRepository {
+    async fn parents(&self, person_id: PersonId) -> Result<Vec<PersonId>> {
+        let Some(neo4j) = &self.pool.neo4j else {
+            return self
+                .sql_ids(
+                    "SELECT parent_id FROM child_of WHERE child_id = $1 ORDER BY parent_id",
+                    &[person_id.0],
+                    None,
+                )
+                .await;
+        };
+        let cypher = query(
+            "MATCH (child:Person {id: $id})-[:CHILD_OF]->(parent:Person) RETURN parent.id as id ORDER BY id",
+        )
+        .param("id", person_id.0.to_string());
+        Self::ids(neo4j, cypher).await
+    }
+
+    async fn ancestors(&self, person_id: PersonId, generations: u32) -> Result<Vec<PersonId>> {
+        let Some(neo4j) = &self.pool.neo4j else {
+            return self
+                .sql_ids(
+                    "WITH RECURSIVE ancestry (id, depth) AS (
+                         SELECT parent_id, 1 FROM child_of WHERE child_id = $1 AND $2 > 0
+                         UNION
+                         SELECT c.parent_id, a.depth + 1
+                         FROM ancestry a
+                         JOIN child_of c ON c.child_id = a.id
+                         WHERE a.depth < $2
+                     )
+                     SELECT id FROM ancestry GROUP BY id ORDER BY MIN(depth)",
+                    &[person_id.0],
+                    Some(generations),
+                )
+                .await;
+        };
+        // Cypher n'accepte pas de paramètre dans la longueur d'un chemin, ni `*1..0`.
+        if generations == 0 {
+            return Ok(Vec::new());
+        }
+        let cypher = query(&format!(
+            "MATCH (p:Person {{id: $id}})-[:CHILD_OF*1..{generations}]->(ancestor:Person)
+             RETURN DISTINCT ancestor.id as id"
+        ))
+        .param("id", person_id.0.to_string());
+        Self::ids(neo4j, cypher).await
+    }
+
+    async fn common_ancestors(&self, first: PersonId, second: PersonId) -> Result<Vec<PersonId>> {
+        let Some(neo4j) = &self.pool.neo4j else {
+            return self
+                .sql_ids(
+                    "WITH RECURSIVE
+                         first_line (id) AS (
+                             SELECT parent_id FROM child_of WHERE child_id = $1
+                             UNION
+                             SELECT c.parent_id FROM first_line f JOIN child_of c ON c.child_id = f.id
+                         ),
+                         second_line (id) AS (
+                             SELECT parent_id FROM child_of WHERE child_id = $2
+                             UNION
+                             SELECT c.parent_id FROM second_line s JOIN child_of c ON c.child_id = s.id
+                         )
+                     SELECT id FROM first_line INTERSECT SELECT id FROM second_line",
+                    &[first.0, second.0],
+                    None,
+                )
+                .await;
+        };
+        let cypher = query(
+            "MATCH (a:Person {id: $first})-[:CHILD_OF*1..]->(ancestor:Person),
+                   (b:Person {id: $second})-[:CHILD_OF*1..]->(ancestor)
+             RETURN DISTINCT ancestor.id as id",
+        )
+        .param("first", first.0.to_string())
+        .param("second", second.0.to_string());
+        Self::ids(neo4j, cypher).await
+    }
+
+    async fn paths(&self, from: PersonId, to: PersonId) -> Result<Vec<Vec<PersonId>>> {
+        let Some(neo4j) = &self.pool.neo4j else {
+            // Chaque ligne porte le chemin parcouru, pour ne jamais repasser par une
+            // personne ; le parcours ne suit que les descendants de `to`, seuls à
+            // pouvoir y mener.
+            let paths: Vec<Vec<Uuid>> = sqlx::query_scalar(
+                "WITH RECURSIVE
+                     descendants (id) AS (
+                         SELECT $2::uuid
+                         UNION
+                         SELECT c.child_id FROM descendants d JOIN child_of c ON c.parent_id = d.id
+                     ),
+                     walk (id, path) AS (
+                         SELECT $1::uuid, ARRAY[$1::uuid]
+                         UNION ALL
+                         SELECT c.parent_id, w.path || c.parent_id
+                         FROM walk w
+                         JOIN child_of c ON c.child_id = w.id
+                         JOIN descendants d ON d.id = c.parent_id
+                         WHERE w.id <> $2 AND c.parent_id <> ALL(w.path)
+                     )
+                 SELECT path FROM walk WHERE id = $2 AND cardinality(path) > 1",
+            )
+            .bind(from.0)
+            .bind(to.0)
+            .fetch_all(&self.pool.postgres)
+            .await?;
+            return Ok(paths
+                .into_iter()
+                .map(|path| path.into_iter().map(PersonId).collect())
+                .collect());
+        };
+        let cypher = query(
+            "MATCH path = (start:Person {id: $from})-[:CHILD_OF*1..]->(end:Person {id: $to})
+             RETURN [node in nodes(path) | node.id] as ids",
+        )
+        .param("from", from.0.to_string())
+        .param("to", to.0.to_string());
+
+        let mut result = neo4j.execute(cypher).await?;
+        let mut paths = Vec::new();
+        while let Some(row) = result.next().await? {
+            let ids: Vec<String> = row.get("ids")?;
+            let path: Vec<PersonId> = ids
+                .into_iter()
+                .map(|id| Uuid::parse_str(&id).map(PersonId))
+                .collect::<Result<_, _>>()?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    async fn distance(&self, first: PersonId, second: PersonId) -> Result<Option<u32>> {
+        let Some(neo4j) = &self.pool.neo4j else {
+            return self.sql_distance(first, second).await;
+        };
+        let cypher = query(
+            "MATCH path = shortestPath((p1:Person {id: $id1})-[*]-(p2:Person {id: $id2})) RETURN length(path) as distance",
+        )
+        .param("id1", first.0.to_string())
+        .param("id2", second.0.to_string());
+
+        let mut result = neo4j.execute(cypher).await?;
+        match result.next().await? {
+            Some(row) => {
+                let distance: i64 = row.get("distance")?;
+                Ok(Some(distance as u32))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Requête Cypher paramétrée par les listes `$children` et `$parents` d'un lot d'arêtes.
+pub(crate) fn edge_query(cypher: &str, edges: &[(Uuid, Uuid)]) -> Query {
+    let children: Vec<String> = edges.iter().map(|(child, _)| child.to_string()).collect();
+    let parents: Vec<String> = edges.iter().map(|(_, parent)| parent.to_string()).collect();
+    query(cypher)
+        .param("children", children)
+        .param("parents", parents)
+}
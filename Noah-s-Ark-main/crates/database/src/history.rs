@@ -0,0 +1,300 @@
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDateTime;
+use genealogy_types::{Family, FamilyId, Person, PersonId, WizardId};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use sqlx::postgres::PgRow;
+use sqlx::{PgConnection, Postgres, QueryBuilder, Row};
+use uuid::Uuid;
+
+/// Type d'enregistrement versionné ; ses événements sont versionnés avec lui.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionEntity {
+    Person,
+    Family,
+}
+
+/// Opération qui a produit une révision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionAction {
+    Create,
+    Update,
+    /// Mise à la corbeille.
+    Delete,
+    /// Sortie de la corbeille.
+    Restore,
+    /// Retour à l'état d'une révision antérieure.
+    Revert,
+    /// Suppression définitive depuis la corbeille ; les révisions précédentes restent.
+    Purge,
+}
+
+/// États complets d'une personne ou d'une famille avant et après une opération.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revision {
+    pub id: i64,
+    pub entity: RevisionEntity,
+    pub entity_id: Uuid,
+    pub action: RevisionAction,
+    /// `None` pour une création, une restauration ou une purge.
+    pub before: Option<Value>,
+    /// `None` pour une mise à la corbeille ou une purge.
+    pub after: Option<Value>,
+    pub changed_by: Option<WizardId>,
+    pub changed_at: NaiveDateTime,
+}
+
+/// Champ de premier niveau qui diffère entre deux révisions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// Révision à enregistrer, avant que la base lui attribue un numéro et une date.
+pub(crate) struct Change {
+    pub entity: RevisionEntity,
+    pub entity_id: Uuid,
+    pub action: RevisionAction,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub changed_by: Option<WizardId>,
+}
+
+impl RevisionEntity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RevisionEntity::Person => "person",
+            RevisionEntity::Family => "family",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Result<Self> {
+        match value {
+            "person" => Ok(RevisionEntity::Person),
+            "family" => Ok(RevisionEntity::Family),
+            _ => Err(anyhow!("Type d'enregistrement inconnu : {value}")),
+        }
+    }
+}
+
+impl RevisionAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RevisionAction::Create => "create",
+            RevisionAction::Update => "update",
+            RevisionAction::Delete => "delete",
+            RevisionAction::Restore => "restore",
+            RevisionAction::Revert => "revert",
+            RevisionAction::Purge => "purge",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Result<Self> {
+        match value {
+            "create" => Ok(RevisionAction::Create),
+            "update" => Ok(RevisionAction::Update),
+            "delete" => Ok(RevisionAction::Delete),
+            "restore" => Ok(RevisionAction::Restore),
+            "revert" => Ok(RevisionAction::Revert),
+            "purge" => Ok(RevisionAction::Purge),
+            _ => Err(anyhow!("Action de révision inconnue : {value}")),
+        }
+    }
+}
+
+impl Revision {
+    /// État laissé par la révision ; une mise à la corbeille n'en laisse aucun.
+    pub fn snapshot<T: DeserializeOwned>(&self) -> Result<T> {
+        let after = self.after.clone().ok_or_else(|| match self.action {
+            RevisionAction::Purge => anyhow!(
+                "La révision {} supprime définitivement l'enregistrement",
+                self.id
+            ),
+            _ => anyhow!(
+                "La révision {} met l'enregistrement à la corbeille : restaurez-le",
+                self.id
+            ),
+        })?;
+        Ok(serde_json::from_value(after)?)
+    }
+}
+
+impl Change {
+    /// Purge de la corbeille : l'état final reste celui de la mise à la corbeille.
+    pub(crate) fn purge(entity: RevisionEntity, entity_id: Uuid) -> Self {
+        Self {
+            entity,
+            entity_id,
+            action: RevisionAction::Purge,
+            before: None,
+            after: None,
+            changed_by: None,
+        }
+    }
+
+    pub(crate) fn person(
+        id: PersonId,
+        action: RevisionAction,
+        before: Option<&Person>,
+        after: Option<&Person>,
+        changed_by: Option<WizardId>,
+    ) -> Result<Self> {
+        Ok(Self {
+            entity: RevisionEntity::Person,
+            entity_id: id.0,
+            action,
+            before: before.map(serde_json::to_value).transpose()?,
+            after: after.map(serde_json::to_value).transpose()?,
+            changed_by,
+        })
+    }
+
+    pub(crate) fn family(
+        id: FamilyId,
+        action: RevisionAction,
+        before: Option<&Family>,
+        after: Option<&Family>,
+        changed_by: Option<WizardId>,
+    ) -> Result<Self> {
+        Ok(Self {
+            entity: RevisionEntity::Family,
+            entity_id: id.0,
+            action,
+            before: before.map(serde_json::to_value).transpose()?,
+            after: after.map(serde_json::to_value).transpose()?,
+            changed_by,
+        })
+    }
+}
+
+/// Champs qui diffèrent entre les états laissés par `from` et par `to`, triés par nom.
+pub fn diff_revisions(from: &Revision, to: &Revision) -> Vec<FieldChange> {
+    let fields = |state: &Option<Value>| match state {
+        Some(Value::Object(fields)) => fields.clone(),
+        _ => Map::new(),
+    };
+    let before = fields(&from.after);
+    let after = fields(&to.after);
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| before.get(*name) != after.get(*name))
+        .map(|name| FieldChange {
+            field: name.clone(),
+            before: before.get(name).cloned(),
+            after: after.get(name).cloned(),
+        })
+        .collect()
+}
+
+pub(crate) fn revision_not_found(revision: i64) -> anyhow::Error {
+    anyhow!("Révision {revision} introuvable pour cet enregistrement")
+}
+
+/// Snapshot stocké en texte JSON.
+pub(crate) fn decode(snapshot: Option<String>) -> Result<Option<Value>> {
+    Ok(snapshot
+        .map(|json| serde_json::from_str(&json))
+        .transpose()?)
+}
+
+pub(crate) fn encode(snapshot: &Option<Value>) -> Option<String> {
+    snapshot.as_ref().map(Value::to_string)
+}
+
+const SELECT_REVISIONS: &str = r#"
+    SELECT id, entity_type, entity_id, action, before_snapshot::text AS before_snapshot,
+           after_snapshot::text AS after_snapshot, changed_by, changed_at
+    FROM revisions
+    WHERE entity_type = $1 AND entity_id = $2
+"#;
+
+/// Enregistre la révision dans la transaction en cours.
+pub(crate) async fn record(conn: &mut PgConnection, change: &Change) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO revisions
+            (entity_type, entity_id, action, before_snapshot, after_snapshot, changed_by)
+        VALUES ($1, $2, $3, $4::jsonb, $5::jsonb, $6)
+        "#,
+    )
+    .bind(change.entity.as_str())
+    .bind(change.entity_id)
+    .bind(change.action.as_str())
+    .bind(encode(&change.before))
+    .bind(encode(&change.after))
+    .bind(change.changed_by.map(|w| w.0))
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Comme [`record`], pour un lot de révisions (import en masse).
+pub(crate) async fn record_all(conn: &mut PgConnection, changes: &[Change]) -> Result<()> {
+    // Reste sous la limite de 65 535 paramètres par requête.
+    for chunk in changes.chunks(10_000) {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "INSERT INTO revisions \
+             (entity_type, entity_id, action, before_snapshot, after_snapshot, changed_by) ",
+        );
+        builder.push_values(chunk, |mut row, change| {
+            row.push_bind(change.entity.as_str())
+                .push_bind(change.entity_id)
+                .push_bind(change.action.as_str())
+                .push_bind(encode(&change.before))
+                .push_unseparated("::jsonb")
+                .push_bind(encode(&change.after))
+                .push_unseparated("::jsonb")
+                .push_bind(change.changed_by.map(|w| w.0));
+        });
+        builder.build().execute(&mut *conn).await?;
+    }
+    Ok(())
+}
+
+/// Révisions de l'enregistrement, de la plus ancienne à la plus récente.
+pub(crate) async fn list(
+    conn: &mut PgConnection,
+    entity: RevisionEntity,
+    entity_id: Uuid,
+) -> Result<Vec<Revision>> {
+    let rows = sqlx::query(&format!("{SELECT_REVISIONS} ORDER BY id"))
+        .bind(entity.as_str())
+        .bind(entity_id)
+        .fetch_all(conn)
+        .await?;
+    rows.iter().map(row_to_revision).collect()
+}
+
+pub(crate) async fn find(
+    conn: &mut PgConnection,
+    entity: RevisionEntity,
+    entity_id: Uuid,
+    revision: i64,
+) -> Result<Revision> {
+    let row = sqlx::query(&format!("{SELECT_REVISIONS} AND id = $3"))
+        .bind(entity.as_str())
+        .bind(entity_id)
+        .bind(revision)
+        .fetch_optional(conn)
+        .await?
+        .ok_or_else(|| revision_not_found(revision))?;
+    row_to_revision(&row)
+}
+
+fn row_to_revision(row: &PgRow) -> Result<Revision> {
+    Ok(Revision {
+        id: row.get("id"),
+        entity: RevisionEntity::parse(row.get("entity_type"))?,
+        entity_id: row.get("entity_id"),
+        action: RevisionAction::parse(row.get("action"))?,
+        before: decode(row.get("before_snapshot"))?,
+        after: decode(row.get("after_snapshot"))?,
+        changed_by: row.get::<Option<Uuid>, _>("changed_by").map(WizardId),
+        changed_at: row.get("changed_at"),
+    })
+}
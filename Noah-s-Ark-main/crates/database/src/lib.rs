@@ -1,30 +1,57 @@
+use std::collections::{HashMap, HashSet};
+use std::slice;
+
 use anyhow::{anyhow, Result};
-use genealogy_types::{Family, FamilyId, Person, PersonId, Sex, WizardId};
-use neo4rs::{query, Graph};
+use genealogy_types::{
+    EventType, Family, FamilyId, ImageReference, ImportReport, LifeEvent, Person, PersonId,
+    PreciseDate, Sex, Source, Title, WizardId,
+};
+use neo4rs::{Graph, Query};
 use redis::{aio::ConnectionManager, AsyncCommands};
 use serde_json::json;
-use sqlx::{postgres::PgPoolOptions, PgPool, Row, Transaction, QueryBuilder, Postgres};
+use sqlx::{postgres::PgPoolOptions, PgConnection, PgPool, Row, Transaction, QueryBuilder, Postgres};
 use chrono::{NaiveDate, NaiveDateTime};
 use uuid::Uuid;
 
+mod aggregate;
+mod graph;
+mod history;
+mod memory;
+mod outbox;
+mod sqlite;
+mod store;
+
+pub use graph::{GraphReconciliation, GraphRepository};
+pub use history::{diff_revisions, FieldChange, Revision, RevisionAction, RevisionEntity};
+pub use memory::{MemoryFamilies, MemoryGraph, MemoryPersons, MemoryStore};
+pub use outbox::{DispatchReport, OutboxDispatcher, OutboxEvent};
+pub use sqlite::{SqliteFamilies, SqliteGraph, SqlitePersons, SqliteStore};
+pub use store::{FamilyStore, GraphStore, PersonStore, Storage};
+
+use history::Change;
+
 type PgTx<'a> = Transaction<'a, Postgres>;
 
 /// Pools partagés pour l'ensemble de l'application.
 #[derive(Clone)]
 pub struct DatabasePool {
     pub postgres: PgPool,
-    pub neo4j: Graph,
+    /// Accélérateur facultatif : sans lui, le graphe est parcouru en SQL.
+    pub neo4j: Option<Graph>,
     pub redis: ConnectionManager,
 }
 
 impl DatabasePool {
-    pub async fn new(postgres_url: &str, neo4j_url: &str, redis_url: &str) -> Result<Self> {
+    pub async fn new(postgres_url: &str, neo4j_url: Option<&str>, redis_url: &str) -> Result<Self> {
         let postgres = PgPoolOptions::new()
             .max_connections(50)
             .connect(postgres_url)
             .await?;
 
-        let neo4j = Graph::new(neo4j_url, "", "").await?;
+        let neo4j = match neo4j_url {
+            Some(url) => Some(Graph::new(url, "", "").await?),
+            None => None,
+        };
 
         let redis_client = redis::Client::open(redis_url)?;
         let redis = ConnectionManager::new(redis_client).await?;
@@ -35,6 +62,14 @@ impl DatabasePool {
             redis,
         })
     }
+
+    /// Exécute une écriture Cypher ; sans Neo4j, il n'y a rien à synchroniser.
+    pub async fn run_cypher(&self, cypher: Query) -> Result<()> {
+        if let Some(neo4j) = &self.neo4j {
+            neo4j.run(cypher).await?;
+        }
+        Ok(())
+    }
 }
 
 /// Repository centré sur les personnes.
@@ -42,12 +77,22 @@ pub struct PersonRepository {
     pub pool: DatabasePool,
 }
 
+/// Champs à modifier ; les listes remplacent entièrement les précédentes.
+#[derive(Default)]
 pub struct PersonUpdate {
     pub first_name: Option<String>,
     pub surname: Option<String>,
     pub surname_prefix: Option<Option<String>>,
+    pub nicknames: Option<Vec<String>>,
     pub sex: Option<Sex>,
+    pub birth: Option<Option<LifeEvent>>,
+    pub death: Option<Option<LifeEvent>>,
+    pub other_events: Option<Vec<LifeEvent>>,
+    pub occupation: Option<Vec<String>>,
+    pub titles: Option<Vec<Title>>,
     pub notes: Option<Option<String>>,
+    pub sources: Option<Vec<Source>>,
+    pub images: Option<Vec<ImageReference>>,
     pub public: Option<bool>,
     pub updated_by: Option<WizardId>,
 }
@@ -57,10 +102,93 @@ impl PersonUpdate {
         self.first_name.is_some()
             || self.surname.is_some()
             || self.surname_prefix.is_some()
+            || self.nicknames.is_some()
             || self.sex.is_some()
+            || self.occupation.is_some()
             || self.notes.is_some()
             || self.public.is_some()
             || self.updated_by.is_some()
+            || self.has_detail_changes()
+    }
+
+    /// Changements portant sur les lignes satellites (événements, titres, sources, images).
+    fn has_detail_changes(&self) -> bool {
+        self.birth.is_some()
+            || self.death.is_some()
+            || self.other_events.is_some()
+            || self.titles.is_some()
+            || self.sources.is_some()
+            || self.images.is_some()
+    }
+
+    /// Reporte les changements sur une personne déjà chargée, hors `updated_at`.
+    fn apply(self, person: &mut Person) {
+        if let Some(first_name) = self.first_name {
+            person.first_name = first_name;
+        }
+        if let Some(surname) = self.surname {
+            person.surname = surname;
+        }
+        if let Some(surname_prefix) = self.surname_prefix {
+            person.surname_prefix = surname_prefix;
+        }
+        if let Some(nicknames) = self.nicknames {
+            person.nicknames = nicknames;
+        }
+        if let Some(sex) = self.sex {
+            person.sex = sex;
+        }
+        if let Some(birth) = self.birth {
+            person.birth = birth;
+        }
+        if let Some(death) = self.death {
+            person.death = death;
+        }
+        if let Some(other_events) = self.other_events {
+            person.other_events = other_events;
+        }
+        if let Some(occupation) = self.occupation {
+            person.occupation = occupation;
+        }
+        if let Some(titles) = self.titles {
+            person.titles = titles;
+        }
+        if let Some(notes) = self.notes {
+            person.notes = notes;
+        }
+        if let Some(sources) = self.sources {
+            person.sources = sources;
+        }
+        if let Some(images) = self.images {
+            person.images = images;
+        }
+        if let Some(public) = self.public {
+            person.public = public;
+        }
+        if self.updated_by.is_some() {
+            person.updated_by = self.updated_by;
+        }
+    }
+
+    /// Remplace tous les champs par ceux d'une révision antérieure.
+    fn reverting_to(person: Person, updated_by: Option<WizardId>) -> Self {
+        Self {
+            first_name: Some(person.first_name),
+            surname: Some(person.surname),
+            surname_prefix: Some(person.surname_prefix),
+            nicknames: Some(person.nicknames),
+            sex: Some(person.sex),
+            birth: Some(person.birth),
+            death: Some(person.death),
+            other_events: Some(person.other_events),
+            occupation: Some(person.occupation),
+            titles: Some(person.titles),
+            notes: Some(person.notes),
+            sources: Some(person.sources),
+            images: Some(person.images),
+            public: Some(person.public),
+            updated_by,
+        }
     }
 }
 
@@ -69,52 +197,230 @@ impl PersonRepository {
         Self { pool }
     }
 
+    /// Enregistre la personne et ses événements, titres, sources et images.
     pub async fn create(&self, person: &Person) -> Result<PersonId> {
         let mut tx = self.pool.postgres.begin().await?;
 
         sqlx::query(
             r#"
-            INSERT INTO persons (id, first_name, surname, surname_prefix, sex, public, notes, updated_by)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            INSERT INTO persons (id, first_name, surname, surname_prefix, nicknames, sex, occupations,
+                                 public, notes, created_at, updated_at, updated_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             "#,
         )
         .bind(person.id.0)
         .bind(&person.first_name)
         .bind(&person.surname)
         .bind(&person.surname_prefix)
+        .bind(&person.nicknames)
         .bind(format!("{:?}", person.sex))
+        .bind(&person.occupation)
         .bind(person.public)
         .bind(&person.notes)
+        .bind(person.created_at)
+        .bind(person.updated_at)
         .bind(person.updated_by.map(|w| w.0))
         .execute(&mut *tx)
         .await?;
+        aggregate::insert_person_details(&mut tx, slice::from_ref(person)).await?;
+        let created = fetch_person(&mut tx, person.id).await?;
+        let change = Change::person(
+            person.id,
+            RevisionAction::Create,
+            None,
+            created.as_ref(),
+            person.updated_by,
+        )?;
+        history::record(&mut tx, &change).await?;
+        let merge = OutboxEvent::MergePersons {
+            ids: vec![person.id],
+        };
+        let invalidate = OutboxEvent::InvalidatePersons {
+            ids: vec![person.id],
+        };
+        let ids = [
+            outbox::enqueue(&mut tx, &merge).await?,
+            outbox::enqueue(&mut tx, &invalidate).await?,
+        ];
 
         tx.commit().await?;
-
-        self.create_neo4j_node(&person.id).await?;
-        self.invalidate_cache(&person.id).await?;
+        outbox::deliver_now(&self.pool, &ids).await;
 
         Ok(person.id)
     }
 
+    /// Insère un lot de personnes dans une seule transaction (import en masse).
+    /// Les identifiants déjà présents sont ignorés, ce qui permet de reprendre un import.
+    pub async fn create_batch(&self, persons: &[Person]) -> Result<()> {
+        if persons.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.postgres.begin().await?;
+
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "INSERT INTO persons (id, first_name, surname, surname_prefix, nicknames, sex, occupations, public, notes, created_at, updated_at, updated_by) ",
+        );
+        builder.push_values(persons, |mut row, person| {
+            row.push_bind(person.id.0)
+                .push_bind(&person.first_name)
+                .push_bind(&person.surname)
+                .push_bind(&person.surname_prefix)
+                .push_bind(&person.nicknames)
+                .push_bind(format!("{:?}", person.sex))
+                .push_bind(&person.occupation)
+                .push_bind(person.public)
+                .push_bind(&person.notes)
+                .push_bind(person.created_at)
+                .push_bind(person.updated_at)
+                .push_bind(person.updated_by.map(|w| w.0));
+        });
+        builder.push(" ON CONFLICT (id) DO NOTHING RETURNING id");
+        let inserted: HashSet<Uuid> = builder
+            .build_query_scalar()
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+        // Une personne déjà présente (import repris) garde ses lignes satellites.
+        let created: Vec<Person> = persons
+            .iter()
+            .filter(|person| inserted.contains(&person.id.0))
+            .cloned()
+            .collect();
+        aggregate::insert_person_details(&mut tx, &created).await?;
+        let inserted: Vec<Uuid> = inserted.into_iter().collect();
+        let changes = fetch_persons(&mut tx, &inserted)
+            .await?
+            .iter()
+            .map(|person| {
+                Change::person(
+                    person.id,
+                    RevisionAction::Create,
+                    None,
+                    Some(person),
+                    person.updated_by,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        history::record_all(&mut tx, &changes).await?;
+        let ids = persons.iter().map(|p| p.id).collect();
+        let event = outbox::enqueue(&mut tx, &OutboxEvent::MergePersons { ids }).await?;
+
+        tx.commit().await?;
+        outbox::deliver_now(&self.pool, &[event]).await;
+
+        Ok(())
+    }
+
+    /// Met à jour un lot de personnes existantes dans une seule transaction
+    /// (réimport) : colonnes, événements, titres, images et sources sont remplacés.
+    /// Les changements de visibilité sont journalisés et chaque personne reçoit
+    /// une révision.
+    pub async fn update_batch(&self, persons: &[Person]) -> Result<()> {
+        if persons.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<Uuid> = persons.iter().map(|person| person.id.0).collect();
+        let mut tx = self.pool.postgres.begin().await?;
+        sqlx::query("SELECT id FROM persons WHERE id = ANY($1) ORDER BY id FOR UPDATE")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+        let mut before: HashMap<PersonId, Person> = fetch_persons(&mut tx, &ids)
+            .await?
+            .into_iter()
+            .map(|person| (person.id, person))
+            .collect();
+        for person in persons {
+            sqlx::query(
+                r#"
+                INSERT INTO privacy_logs (id, person_id, changed_by, old_public, new_public)
+                SELECT $1, id, $2, public, $3
+                FROM persons
+                WHERE id = $4 AND public IS DISTINCT FROM $3
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(person.updated_by.map(|w| w.0))
+            .bind(person.public)
+            .bind(person.id.0)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                r#"
+                UPDATE persons
+                SET first_name = $2, surname = $3, surname_prefix = $4, nicknames = $5, sex = $6,
+                    occupations = $7, public = $8, notes = $9,
+                    updated_by = COALESCE($10, updated_by), updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(person.id.0)
+            .bind(&person.first_name)
+            .bind(&person.surname)
+            .bind(&person.surname_prefix)
+            .bind(&person.nicknames)
+            .bind(format!("{:?}", person.sex))
+            .bind(&person.occupation)
+            .bind(person.public)
+            .bind(&person.notes)
+            .bind(person.updated_by.map(|w| w.0))
+            .execute(&mut *tx)
+            .await?;
+        }
+        aggregate::delete_person_details(&mut tx, &ids).await?;
+        aggregate::insert_person_details(&mut tx, persons).await?;
+        let changed_by: HashMap<PersonId, Option<WizardId>> = persons
+            .iter()
+            .map(|person| (person.id, person.updated_by))
+            .collect();
+        let changes = fetch_persons(&mut tx, &ids)
+            .await?
+            .iter()
+            .map(|after| {
+                Change::person(
+                    after.id,
+                    RevisionAction::Update,
+                    before.remove(&after.id).as_ref(),
+                    Some(after),
+                    changed_by[&after.id],
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        history::record_all(&mut tx, &changes).await?;
+        let invalidate = OutboxEvent::InvalidatePersons {
+            ids: persons.iter().map(|person| person.id).collect(),
+        };
+        let event = outbox::enqueue(&mut tx, &invalidate).await?;
+        tx.commit().await?;
+
+        outbox::deliver_now(&self.pool, &[event]).await;
+        Ok(())
+    }
+
+    /// Charge un lot de personnes depuis PostgreSQL, sans passer par le cache.
+    pub async fn find_many(&self, ids: &[PersonId]) -> Result<Vec<Person>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids: Vec<Uuid> = ids.iter().map(|id| id.0).collect();
+        let mut tx = read_snapshot(&self.pool.postgres).await?;
+        let persons = fetch_persons(&mut tx, &ids).await?;
+        tx.commit().await?;
+        Ok(persons)
+    }
+
     pub async fn find_by_id(&self, id: PersonId) -> Result<Option<Person>> {
         if let Some(person) = self.get_from_cache(&id).await? {
             return Ok(Some(person));
         }
 
-        let row = sqlx::query(
-            r#"
-            SELECT id, first_name, surname, surname_prefix, sex, public, notes,
-                   created_at, updated_at, updated_by
-            FROM persons
-            WHERE id = $1
-            "#,
-        )
-        .bind(id.0)
-        .fetch_optional(&self.pool.postgres)
-        .await?;
-
-        let person = row.map(|r| row_to_person(&r));
+        let mut tx = read_snapshot(&self.pool.postgres).await?;
+        let person = fetch_person(&mut tx, id).await?;
+        tx.commit().await?;
 
         if let Some(ref p) = person {
             self.set_cache(p).await?;
@@ -124,40 +430,70 @@ impl PersonRepository {
     }
 
     pub async fn search_by_name(&self, surname: &str, first_name: &str) -> Result<Vec<Person>> {
+        let mut tx = read_snapshot(&self.pool.postgres).await?;
         let rows = sqlx::query(
             r#"
-            SELECT id, first_name, surname, surname_prefix, sex, public, notes,
-                   created_at, updated_at, updated_by
+            SELECT id, first_name, surname, surname_prefix, nicknames, sex, occupations,
+                   public, notes, created_at, updated_at, updated_by
             FROM persons
-            WHERE (surname || ' ' || first_name) ILIKE $1
+            WHERE (surname || ' ' || first_name) ILIKE $1 AND deleted_at IS NULL
             ORDER BY surname, first_name
             LIMIT 50
             "#,
         )
         .bind(format!("%{} {}%", surname, first_name))
-        .fetch_all(&self.pool.postgres)
+        .fetch_all(&mut *tx)
         .await?;
 
-        Ok(rows.iter().map(row_to_person).collect())
+        let mut persons: Vec<Person> = rows.iter().map(row_to_person).collect();
+        aggregate::load_person_details(&mut tx, &mut persons).await?;
+        tx.commit().await?;
+        Ok(persons)
     }
 
     pub async fn update(&self, id: PersonId, updates: PersonUpdate) -> Result<Option<Person>> {
+        self.update_as(id, updates, RevisionAction::Update).await
+    }
+
+    /// Modifie la personne et enregistre la révision `action`.
+    async fn update_as(
+        &self,
+        id: PersonId,
+        updates: PersonUpdate,
+        action: RevisionAction,
+    ) -> Result<Option<Person>> {
         if !updates.has_changes() {
             return self.find_by_id(id).await;
         }
 
-        let existing = self.find_by_id(id).await?;
-        if existing.is_none() {
+        let mut tx = self.pool.postgres.begin().await?;
+        let previous_public: Option<Option<bool>> = sqlx::query_scalar(
+            "SELECT public FROM persons WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(id.0)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(previous_public) = previous_public else {
             return Ok(None);
-        }
-        let previous_public = existing.as_ref().map(|p| p.public).unwrap_or(true);
+        };
+        let previous_public = previous_public.unwrap_or(true);
+        let before = fetch_person(&mut tx, id).await?;
 
+        let rewrite_details = updates.has_detail_changes();
         let PersonUpdate {
             first_name,
             surname,
             surname_prefix,
+            nicknames,
             sex,
+            birth,
+            death,
+            other_events,
+            occupation,
+            titles,
             notes,
+            sources,
+            images,
             public,
             updated_by,
         } = updates;
@@ -167,77 +503,431 @@ impl PersonRepository {
 
         if let Some(value) = first_name {
             separated.push("first_name = ");
-            separated.push_bind(value);
+            separated.push_bind_unseparated(value);
         }
         if let Some(value) = surname {
             separated.push("surname = ");
-            separated.push_bind(value);
+            separated.push_bind_unseparated(value);
         }
         if let Some(prefix_option) = surname_prefix {
             separated.push("surname_prefix = ");
             match prefix_option {
-                Some(prefix) => separated.push_bind(prefix),
-                None => separated.push("NULL"),
+                Some(prefix) => separated.push_bind_unseparated(prefix),
+                None => separated.push_unseparated("NULL"),
             };
         }
+        if let Some(nicknames) = nicknames {
+            separated.push("nicknames = ");
+            separated.push_bind_unseparated(nicknames);
+        }
         if let Some(sex) = sex {
             separated.push("sex = ");
-            separated.push_bind(format!("{:?}", sex));
+            separated.push_bind_unseparated(format!("{:?}", sex));
+        }
+        if let Some(occupation) = occupation {
+            separated.push("occupations = ");
+            separated.push_bind_unseparated(occupation);
         }
         if let Some(notes_option) = notes {
             separated.push("notes = ");
             match notes_option {
-                Some(notes) => separated.push_bind(notes),
-                None => separated.push("NULL"),
+                Some(notes) => separated.push_bind_unseparated(notes),
+                None => separated.push_unseparated("NULL"),
             };
         }
         if let Some(public) = public {
             separated.push("public = ");
-            separated.push_bind(public);
+            separated.push_bind_unseparated(public);
         }
         if let Some(wizard) = updated_by {
             separated.push("updated_by = ");
-            separated.push_bind(wizard.0);
+            separated.push_bind_unseparated(wizard.0);
         }
         separated.push("updated_at = NOW()");
 
         builder.push(" WHERE id = ");
         builder.push_bind(id.0);
         builder.push(
-            " RETURNING id, first_name, surname, surname_prefix, sex, public, notes, created_at, updated_at, updated_by",
+            " RETURNING id, first_name, surname, surname_prefix, nicknames, sex, occupations, public, notes, created_at, updated_at, updated_by",
         );
 
-        let row = builder
-            .build()
-            .fetch_optional(&self.pool.postgres)
-            .await?;
+        let row = builder.build().fetch_one(&mut *tx).await?;
+        let mut person = row_to_person(&row);
+        aggregate::load_person_details(&mut tx, slice::from_mut(&mut person)).await?;
 
-        let person = row.map(|r| row_to_person(&r));
+        if rewrite_details {
+            if let Some(birth) = birth {
+                person.birth = birth;
+            }
+            if let Some(death) = death {
+                person.death = death;
+            }
+            if let Some(other_events) = other_events {
+                person.other_events = other_events;
+            }
+            if let Some(titles) = titles {
+                person.titles = titles;
+            }
+            if let Some(sources) = sources {
+                person.sources = sources;
+            }
+            if let Some(images) = images {
+                person.images = images;
+            }
+            aggregate::delete_person_details(&mut tx, &[id.0]).await?;
+            aggregate::insert_person_details(&mut tx, slice::from_ref(&person)).await?;
+        }
 
-        if let Some(ref updated) = person {
-            if let Some(new_public) = public {
-                if new_public != previous_public {
-                    self.log_privacy_change(&id, updated_by, previous_public, new_public)
-                        .await?;
-                }
+        if let Some(new_public) = public {
+            if new_public != previous_public {
+                log_privacy_change(&mut tx, &id, updated_by, previous_public, new_public).await?;
             }
-            self.set_cache(updated).await?;
         }
+        let change = Change::person(id, action, before.as_ref(), Some(&person), updated_by)?;
+        history::record(&mut tx, &change).await?;
+        let event = invalidate_person(&mut tx, id).await?;
+        tx.commit().await?;
 
-        Ok(person)
+        outbox::deliver_now(&self.pool, &[event]).await;
+        Ok(Some(person))
     }
 
-    async fn create_neo4j_node(&self, person_id: &PersonId) -> Result<()> {
-        let query = query("MERGE (:Person {id: $id})").param("id", person_id.0.to_string());
-        self.pool.neo4j.run(query).await?;
-        Ok(())
+    /// Événements de la personne dans l'ordre de [`Person::timeline`].
+    pub async fn events(&self, person_id: PersonId) -> Result<Vec<PersonEventRecord>> {
+        let mut tx = read_snapshot(&self.pool.postgres).await?;
+        let stored = aggregate::load_events(&mut tx, aggregate::Owner::Person, &[person_id.0])
+            .await?
+            .remove(&person_id.0)
+            .unwrap_or_default();
+        tx.commit().await?;
+
+        Ok(in_timeline_order(stored.into_iter().map(|stored| {
+            let record = PersonEventRecord {
+                id: stored.id,
+                person_id,
+                event: stored.event,
+            };
+            (stored.slot, record)
+        })))
     }
 
-    async fn invalidate_cache(&self, person_id: &PersonId) -> Result<()> {
-        let mut conn = self.pool.redis.clone();
-        let key = format!("person:{}", person_id.0);
-        conn.del::<_, ()>(key).await?;
-        Ok(())
+    /// Ajoute un événement ; une naissance ou un décès devient `birth` ou `death`
+    /// et ne peut pas remplacer celui déjà enregistré.
+    pub async fn add_event(
+        &self,
+        person_id: PersonId,
+        event: LifeEvent,
+        changed_by: Option<WizardId>,
+    ) -> Result<Option<PersonEventRecord>> {
+        let mut tx = self.pool.postgres.begin().await?;
+        if !lock_person(&mut tx, person_id).await? {
+            return Ok(None);
+        }
+        let before = fetch_person(&mut tx, person_id).await?;
+        let slot = event_slot(&event.event_type);
+        let position = free_position(&mut tx, person_id, slot, None).await?;
+        let id = Uuid::new_v4();
+        aggregate::insert_events(
+            &mut tx,
+            aggregate::Owner::Person,
+            &[aggregate::EventRow {
+                id,
+                owner_id: person_id.0,
+                slot,
+                position,
+                event: &event,
+            }],
+        )
+        .await?;
+        touch_person(&mut tx, person_id).await?;
+        record_event_change(&mut tx, person_id, before, changed_by).await?;
+        let invalidate = invalidate_person(&mut tx, person_id).await?;
+        tx.commit().await?;
+
+        outbox::deliver_now(&self.pool, &[invalidate]).await;
+        Ok(Some(PersonEventRecord {
+            id,
+            person_id,
+            event,
+        }))
+    }
+
+    /// Remplace un événement ; changer son type le déplace vers `birth`, `death` ou les
+    /// autres événements.
+    pub async fn update_event(
+        &self,
+        event_id: Uuid,
+        event: LifeEvent,
+        changed_by: Option<WizardId>,
+    ) -> Result<Option<PersonEventRecord>> {
+        let mut tx = self.pool.postgres.begin().await?;
+        let Some((person_id, previous_slot, previous_position)) =
+            lock_event(&mut tx, event_id).await?
+        else {
+            return Ok(None);
+        };
+        let before = fetch_person(&mut tx, person_id).await?;
+        let slot = event_slot(&event.event_type);
+        let position = if slot == previous_slot {
+            previous_position
+        } else {
+            free_position(&mut tx, person_id, slot, Some(event_id)).await?
+        };
+        aggregate::delete_events_by_id(&mut tx, &[event_id]).await?;
+        aggregate::insert_events(
+            &mut tx,
+            aggregate::Owner::Person,
+            &[aggregate::EventRow {
+                id: event_id,
+                owner_id: person_id.0,
+                slot,
+                position,
+                event: &event,
+            }],
+        )
+        .await?;
+        touch_person(&mut tx, person_id).await?;
+        record_event_change(&mut tx, person_id, before, changed_by).await?;
+        let invalidate = invalidate_person(&mut tx, person_id).await?;
+        tx.commit().await?;
+
+        outbox::deliver_now(&self.pool, &[invalidate]).await;
+        Ok(Some(PersonEventRecord {
+            id: event_id,
+            person_id,
+            event,
+        }))
+    }
+
+    /// Supprime un événement de personne ; `false` s'il n'existe pas.
+    pub async fn delete_event(&self, event_id: Uuid, changed_by: Option<WizardId>) -> Result<bool> {
+        let mut tx = self.pool.postgres.begin().await?;
+        let Some((person_id, _, _)) = lock_event(&mut tx, event_id).await? else {
+            return Ok(false);
+        };
+        let before = fetch_person(&mut tx, person_id).await?;
+        aggregate::delete_events_by_id(&mut tx, &[event_id]).await?;
+        touch_person(&mut tx, person_id).await?;
+        record_event_change(&mut tx, person_id, before, changed_by).await?;
+        let invalidate = invalidate_person(&mut tx, person_id).await?;
+        tx.commit().await?;
+
+        outbox::deliver_now(&self.pool, &[invalidate]).await;
+        Ok(true)
+    }
+
+    /// Met la personne à la corbeille : elle disparaît de la recherche et du graphe
+    /// jusqu'à sa restauration ; `false` si elle n'existe pas ou y est déjà. Une
+    /// personne encore parent n'y est mise qu'avec `force`, qui la retire de ces
+    /// familles (une révision chacune) ; sa restauration l'y remet.
+    pub async fn delete(
+        &self,
+        id: PersonId,
+        force: bool,
+        deleted_by: Option<WizardId>,
+    ) -> Result<bool> {
+        let mut tx = self.pool.postgres.begin().await?;
+        if !lock_person(&mut tx, id).await? {
+            return Ok(false);
+        }
+        let parent_of: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM families
+            WHERE (father_id = $1 OR mother_id = $1) AND deleted_at IS NULL
+            "#,
+        )
+        .bind(id.0)
+        .fetch_one(&mut *tx)
+        .await?;
+        if parent_of > 0 && !force {
+            return Err(person_is_parent(parent_of));
+        }
+        let before = fetch_person(&mut tx, id).await?;
+        if parent_of > 0 {
+            unlink_parent(&mut tx, id, deleted_by).await?;
+        }
+
+        sqlx::query("UPDATE persons SET deleted_at = NOW(), deleted_by = $2 WHERE id = $1")
+            .bind(id.0)
+            .bind(deleted_by.map(|w| w.0))
+            .execute(&mut *tx)
+            .await?;
+        let change = Change::person(
+            id,
+            RevisionAction::Delete,
+            before.as_ref(),
+            None,
+            deleted_by,
+        )?;
+        history::record(&mut tx, &change).await?;
+        // La vue `child_of` ignore déjà la personne ; Neo4j perd son nœud et ses arêtes.
+        let detach = OutboxEvent::DeletePersons { ids: vec![id] };
+        let invalidate = OutboxEvent::InvalidatePersons { ids: vec![id] };
+        let events = [
+            outbox::enqueue(&mut tx, &detach).await?,
+            outbox::enqueue(&mut tx, &invalidate).await?,
+        ];
+
+        tx.commit().await?;
+        outbox::deliver_now(&self.pool, &events).await;
+        Ok(true)
+    }
+
+    /// Sort la personne de la corbeille avec ses arêtes `CHILD_OF`, et la remet dans
+    /// les familles dont `force` l'avait retirée si sa place y est restée libre ;
+    /// `false` si elle n'y est pas.
+    pub async fn restore(&self, id: PersonId, restored_by: Option<WizardId>) -> Result<bool> {
+        let mut tx = self.pool.postgres.begin().await?;
+        let restored = sqlx::query(
+            r#"
+            UPDATE persons SET deleted_at = NULL, deleted_by = NULL
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(id.0)
+        .execute(&mut *tx)
+        .await?;
+        if restored.rows_affected() == 0 {
+            return Ok(false);
+        }
+        let after = fetch_person(&mut tx, id).await?;
+        let change = Change::person(
+            id,
+            RevisionAction::Restore,
+            None,
+            after.as_ref(),
+            restored_by,
+        )?;
+        history::record(&mut tx, &change).await?;
+        relink_parent(&mut tx, id, restored_by).await?;
+
+        let edges: Vec<(Uuid, Uuid)> = sqlx::query_as(
+            "SELECT child_id, parent_id FROM child_of WHERE child_id = $1 OR parent_id = $1",
+        )
+        .bind(id.0)
+        .fetch_all(&mut *tx)
+        .await?;
+        let merge = OutboxEvent::MergePersons { ids: vec![id] };
+        let invalidate = OutboxEvent::InvalidatePersons { ids: vec![id] };
+        let mut events = vec![
+            outbox::enqueue(&mut tx, &merge).await?,
+            outbox::enqueue(&mut tx, &invalidate).await?,
+        ];
+        if !edges.is_empty() {
+            let edges = edges
+                .into_iter()
+                .map(|(child, parent)| (PersonId(child), PersonId(parent)))
+                .collect();
+            events.push(outbox::enqueue(&mut tx, &OutboxEvent::MergeEdges { edges }).await?);
+        }
+
+        tx.commit().await?;
+        outbox::deliver_now(&self.pool, &events).await;
+        Ok(true)
+    }
+
+    /// Personnes à la corbeille, les plus récemment supprimées d'abord.
+    pub async fn trash(&self) -> Result<Vec<Trashed<Person>>> {
+        let mut tx = read_snapshot(&self.pool.postgres).await?;
+        let rows = sqlx::query(
+            r#"
+            SELECT id, first_name, surname, surname_prefix, nicknames, sex, occupations,
+                   public, notes, created_at, updated_at, updated_by, deleted_at, deleted_by
+            FROM persons
+            WHERE deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut persons: Vec<Person> = rows.iter().map(row_to_person).collect();
+        aggregate::load_person_details(&mut tx, &mut persons).await?;
+        tx.commit().await?;
+        Ok(rows
+            .iter()
+            .zip(persons)
+            .map(|(row, person)| row_to_trashed(row, person))
+            .collect())
+    }
+
+    /// Supprime définitivement les personnes mises à la corbeille avant `before`, en
+    /// les retirant des familles dont elles sont parents ou enfants (révision `update`
+    /// de chacune) ; renvoie leur nombre.
+    pub async fn purge_trash(&self, before: NaiveDateTime) -> Result<u64> {
+        let mut tx = self.pool.postgres.begin().await?;
+        let ids: Vec<Uuid> =
+            sqlx::query_scalar("SELECT id FROM persons WHERE deleted_at < $1 FOR UPDATE")
+                .bind(before)
+                .fetch_all(&mut *tx)
+                .await?;
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let family_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM families
+            WHERE father_id = ANY($1) OR mother_id = ANY($1)
+               OR id IN (SELECT family_id FROM family_children WHERE child_id = ANY($1))
+            ORDER BY id
+            FOR UPDATE
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await?;
+        let families = fetch_families(&mut tx, &family_ids, true).await?;
+        sqlx::query(
+            r#"
+            UPDATE families
+            SET father_id = CASE WHEN father_id = ANY($1) THEN NULL ELSE father_id END,
+                mother_id = CASE WHEN mother_id = ANY($1) THEN NULL ELSE mother_id END,
+                updated_at = NOW()
+            WHERE father_id = ANY($1) OR mother_id = ANY($1)
+            "#,
+        )
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?;
+        // Événements, filiations comme enfant, titres et sources suivent par `ON DELETE CASCADE`.
+        sqlx::query("DELETE FROM persons WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+        let purges: Vec<Change> = ids
+            .iter()
+            .map(|id| Change::purge(RevisionEntity::Person, *id))
+            .collect();
+        history::record_all(&mut tx, &purges).await?;
+        let after = fetch_families(&mut tx, &family_ids, true).await?;
+        history::record_all(&mut tx, &family_updates(families, &after, None)?).await?;
+        tx.commit().await?;
+        Ok(ids.len() as u64)
+    }
+
+    /// Révisions de la personne, de la plus ancienne à la plus récente.
+    pub async fn history(&self, id: PersonId) -> Result<Vec<Revision>> {
+        let mut conn = self.pool.postgres.acquire().await?;
+        history::list(&mut conn, RevisionEntity::Person, id.0).await
+    }
+
+    /// Ramène la personne à l'état laissé par `revision`, dans une nouvelle révision ;
+    /// `None` si elle n'existe pas ou est à la corbeille.
+    pub async fn revert(
+        &self,
+        id: PersonId,
+        revision: i64,
+        reverted_by: Option<WizardId>,
+    ) -> Result<Option<Person>> {
+        let mut conn = self.pool.postgres.acquire().await?;
+        let person: Person = history::find(&mut conn, RevisionEntity::Person, id.0, revision)
+            .await?
+            .snapshot()?;
+        drop(conn);
+        let updates = PersonUpdate::reverting_to(person, reverted_by);
+        self.update_as(id, updates, RevisionAction::Revert).await
     }
 
     async fn get_from_cache(&self, person_id: &PersonId) -> Result<Option<Person>> {
@@ -257,31 +947,407 @@ impl PersonRepository {
         conn.set_ex::<_, _, ()>(key, json, 3600).await?;
         Ok(())
     }
+}
 
-    async fn log_privacy_change(
-        &self,
-        person_id: &PersonId,
-        changed_by: Option<WizardId>,
-        old_public: bool,
-        new_public: bool,
-    ) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT INTO privacy_logs (id, person_id, changed_by, old_public, new_public)
-            VALUES ($1, $2, $3, $4, $5)
-            "#,
+/// Événement d'une personne, avec l'identifiant de sa ligne `events`.
+pub struct PersonEventRecord {
+    pub id: Uuid,
+    pub person_id: PersonId,
+    pub event: LifeEvent,
+}
+
+/// Personne ou famille à la corbeille, avec la date et l'auteur de sa suppression.
+#[derive(Debug, Clone)]
+pub struct Trashed<T> {
+    pub record: T,
+    pub deleted_at: NaiveDateTime,
+    pub deleted_by: Option<WizardId>,
+}
+
+/// Invalide l'entrée Redis de la personne une fois la transaction validée.
+async fn invalidate_person(conn: &mut PgConnection, person_id: PersonId) -> Result<i64> {
+    let event = OutboxEvent::InvalidatePersons {
+        ids: vec![person_id],
+    };
+    outbox::enqueue(conn, &event).await
+}
+
+/// Emplacement d'un événement de personne : `Person::birth`, `Person::death` ou les autres.
+fn event_slot(event_type: &EventType) -> &'static str {
+    match event_type {
+        EventType::Birth => "birth",
+        EventType::Death => "death",
+        _ => "other",
+    }
+}
+
+/// Révisions `update` des familles qui diffèrent entre `before` et `after`.
+pub(crate) fn family_updates(
+    before: Vec<Family>,
+    after: &[Family],
+    changed_by: Option<WizardId>,
+) -> Result<Vec<Change>> {
+    let mut before: HashMap<FamilyId, Family> = before
+        .into_iter()
+        .map(|family| (family.id, family))
+        .collect();
+    after
+        .iter()
+        .filter_map(|after| {
+            let before = before.remove(&after.id).filter(|before| before != after)?;
+            Some(Change::family(
+                after.id,
+                RevisionAction::Update,
+                Some(&before),
+                Some(after),
+                changed_by,
+            ))
+        })
+        .collect()
+}
+
+/// Emplacement d'un événement de famille : `Family::marriage`, `Family::divorce` ou les autres.
+fn family_event_slot(event_type: &EventType) -> &'static str {
+    match event_type {
+        EventType::Marriage => "marriage",
+        EventType::Divorce => "divorce",
+        _ => "other",
+    }
+}
+
+/// Tri de [`Person::timeline`] ; à date égale : naissance, autres événements dans leur
+/// ordre, puis décès.
+fn in_timeline_order<S: AsRef<str>>(
+    events: impl IntoIterator<Item = (S, PersonEventRecord)>,
+) -> Vec<PersonEventRecord> {
+    let mut ranked: Vec<(u8, PersonEventRecord)> = events
+        .into_iter()
+        .map(|(slot, record)| {
+            let rank = match slot.as_ref() {
+                "birth" => 0,
+                "death" => 2,
+                _ => 1,
+            };
+            (rank, record)
+        })
+        .collect();
+    ranked.sort_by_key(|(rank, record)| record.event.date.timeline_key(*rank));
+    ranked.into_iter().map(|(_, record)| record).collect()
+}
+
+fn slot_taken(slot: &str) -> anyhow::Error {
+    anyhow!("La personne a déjà un événement `{slot}`")
+}
+
+fn family_slot_taken(slot: &str) -> anyhow::Error {
+    anyhow!("La famille a déjà un événement `{slot}`")
+}
+
+fn person_is_parent(families: i64) -> anyhow::Error {
+    anyhow!("La personne est parent dans {families} famille(s) : `force` l'en retire")
+}
+
+fn family_has_children(children: i64) -> anyhow::Error {
+    anyhow!("La famille a encore {children} enfant(s) : `force` la supprime quand même")
+}
+
+/// Position libre pour `slot` : après les autres événements, ou la place de la
+/// naissance ou du décès si `ignored` est seul à l'occuper.
+async fn free_position(
+    conn: &mut PgConnection,
+    person_id: PersonId,
+    slot: &str,
+    ignored: Option<Uuid>,
+) -> Result<i32> {
+    let (count, next): (i64, Option<i32>) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*), MAX(position) + 1
+        FROM events
+        WHERE person_id = $1 AND slot = $2 AND id IS DISTINCT FROM $3
+        "#,
+    )
+    .bind(person_id.0)
+    .bind(slot)
+    .bind(ignored)
+    .fetch_one(&mut *conn)
+    .await?;
+    match slot {
+        "other" => Ok(next.unwrap_or(0)),
+        _ if count > 0 => Err(slot_taken(slot)),
+        _ => Ok(0),
+    }
+}
+
+/// Verrouille la personne ; `false` si elle n'existe pas ou est à la corbeille.
+async fn lock_person(conn: &mut PgConnection, person_id: PersonId) -> Result<bool> {
+    let found: Option<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM persons WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+    )
+    .bind(person_id.0)
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(found.is_some())
+}
+
+/// Verrouille un événement de personne et sa personne : propriétaire, emplacement, position.
+async fn lock_event(
+    conn: &mut PgConnection,
+    event_id: Uuid,
+) -> Result<Option<(PersonId, String, i32)>> {
+    let row = sqlx::query(
+        r#"
+        SELECT e.person_id, e.slot, e.position
+        FROM events e
+        JOIN persons p ON p.id = e.person_id
+        WHERE e.id = $1 AND p.deleted_at IS NULL
+        FOR UPDATE
+        "#,
+    )
+    .bind(event_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(row.map(|row| {
+        (
+            PersonId(row.get("person_id")),
+            row.get("slot"),
+            row.get("position"),
         )
-        .bind(uuid::Uuid::new_v4())
-        .bind(person_id.0)
-        .bind(changed_by.map(|w| w.0))
-        .bind(old_public)
-        .bind(new_public)
-        .execute(&self.pool.postgres)
+    }))
+}
+
+/// Personne hors corbeille et ses lignes satellites.
+async fn fetch_person(conn: &mut PgConnection, id: PersonId) -> Result<Option<Person>> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, first_name, surname, surname_prefix, nicknames, sex, occupations,
+               public, notes, created_at, updated_at, updated_by
+        FROM persons
+        WHERE id = $1 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(id.0)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let mut person = row.map(|r| row_to_person(&r));
+    aggregate::load_person_details(conn, person.as_mut_slice()).await?;
+    Ok(person)
+}
+
+/// Lot de personnes hors corbeille et leurs lignes satellites.
+async fn fetch_persons(conn: &mut PgConnection, ids: &[Uuid]) -> Result<Vec<Person>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, first_name, surname, surname_prefix, nicknames, sex, occupations,
+               public, notes, created_at, updated_at, updated_by
+        FROM persons
+        WHERE id = ANY($1) AND deleted_at IS NULL
+        "#,
+    )
+    .bind(ids)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut persons: Vec<Person> = rows.iter().map(row_to_person).collect();
+    aggregate::load_person_details(conn, &mut persons).await?;
+    Ok(persons)
+}
+
+/// Lot de familles hors corbeille (ou aussi à la corbeille avec `with_trash`), avec
+/// leurs enfants et leurs événements.
+async fn fetch_families(
+    conn: &mut PgConnection,
+    ids: &[Uuid],
+    with_trash: bool,
+) -> Result<Vec<Family>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, father_id, mother_id, notes, public, created_at, updated_at
+        FROM families
+        WHERE id = ANY($1) AND (deleted_at IS NULL OR $2)
+        "#,
+    )
+    .bind(ids)
+    .bind(with_trash)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let child_rows = sqlx::query(
+        r#"
+        SELECT family_id, child_id
+        FROM family_children
+        WHERE family_id = ANY($1)
+        ORDER BY family_id, child_order
+        "#,
+    )
+    .bind(ids)
+    .fetch_all(&mut *conn)
+    .await?;
+    let mut children: HashMap<Uuid, Vec<PersonId>> = HashMap::new();
+    for row in &child_rows {
+        children
+            .entry(row.get("family_id"))
+            .or_default()
+            .push(PersonId(row.get("child_id")));
+    }
+
+    let mut families: Vec<Family> = rows
+        .iter()
+        .map(|row| {
+            let id: Uuid = row.get("id");
+            row_to_family(row, children.remove(&id).unwrap_or_default())
+        })
+        .collect();
+    aggregate::load_family_details(conn, &mut families).await?;
+    Ok(families)
+}
+
+/// Retire une personne mise à la corbeille des familles dont elle est parent, et
+/// garde ces liens dans `trashed_parents` pour sa restauration.
+async fn unlink_parent(
+    conn: &mut PgConnection,
+    id: PersonId,
+    changed_by: Option<WizardId>,
+) -> Result<()> {
+    let ids: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT id FROM families
+        WHERE (father_id = $1 OR mother_id = $1) AND deleted_at IS NULL
+        ORDER BY id
+        FOR UPDATE
+        "#,
+    )
+    .bind(id.0)
+    .fetch_all(&mut *conn)
+    .await?;
+    let before = fetch_families(conn, &ids, false).await?;
+    sqlx::query(
+        r#"
+        INSERT INTO trashed_parents (person_id, family_id, role)
+        SELECT $1, id, 'father' FROM families WHERE id = ANY($2) AND father_id = $1
+        UNION ALL
+        SELECT $1, id, 'mother' FROM families WHERE id = ANY($2) AND mother_id = $1
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(id.0)
+    .bind(&ids)
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query(
+        r#"
+        UPDATE families
+        SET father_id = CASE WHEN father_id = $1 THEN NULL ELSE father_id END,
+            mother_id = CASE WHEN mother_id = $1 THEN NULL ELSE mother_id END,
+            updated_at = NOW()
+        WHERE id = ANY($2)
+        "#,
+    )
+    .bind(id.0)
+    .bind(&ids)
+    .execute(&mut *conn)
+    .await?;
+    let after = fetch_families(conn, &ids, false).await?;
+    history::record_all(conn, &family_updates(before, &after, changed_by)?).await
+}
+
+/// Remet une personne restaurée dans les familles dont [`unlink_parent`] l'a retirée,
+/// là où sa place est restée libre.
+async fn relink_parent(
+    conn: &mut PgConnection,
+    id: PersonId,
+    changed_by: Option<WizardId>,
+) -> Result<()> {
+    let links: Vec<(Uuid, String)> = sqlx::query_as(
+        "DELETE FROM trashed_parents WHERE person_id = $1 RETURNING family_id, role",
+    )
+    .bind(id.0)
+    .fetch_all(&mut *conn)
+    .await?;
+    if links.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<Uuid> = links.iter().map(|(family_id, _)| *family_id).collect();
+    let before = fetch_families(conn, &ids, false).await?;
+    for (family_id, role) in &links {
+        let column = if role == "father" {
+            "father_id"
+        } else {
+            "mother_id"
+        };
+        sqlx::query(&format!(
+            "UPDATE families SET {column} = $1, updated_at = NOW() \
+             WHERE id = $2 AND {column} IS NULL AND deleted_at IS NULL"
+        ))
+        .bind(id.0)
+        .bind(family_id)
+        .execute(&mut *conn)
         .await?;
-        Ok(())
     }
+    let after = fetch_families(conn, &ids, false).await?;
+    history::record_all(conn, &family_updates(before, &after, changed_by)?).await
+}
+
+/// Révision d'une personne dont un événement a changé ; `before` a été lu sous verrou.
+async fn record_event_change(
+    conn: &mut PgConnection,
+    person_id: PersonId,
+    before: Option<Person>,
+    changed_by: Option<WizardId>,
+) -> Result<()> {
+    let after = fetch_person(conn, person_id).await?;
+    let change = Change::person(
+        person_id,
+        RevisionAction::Update,
+        before.as_ref(),
+        after.as_ref(),
+        changed_by,
+    )?;
+    history::record(conn, &change).await
 }
 
+async fn touch_person(conn: &mut PgConnection, person_id: PersonId) -> Result<()> {
+    sqlx::query("UPDATE persons SET updated_at = NOW() WHERE id = $1")
+        .bind(person_id.0)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Transaction en lecture seule sur un instantané : une ligne et ses lignes
+/// satellites sont lues dans le même état.
+async fn read_snapshot(pool: &PgPool) -> Result<PgTx<'static>> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+    Ok(tx)
+}
+
+async fn log_privacy_change(
+    conn: &mut PgConnection,
+    person_id: &PersonId,
+    changed_by: Option<WizardId>,
+    old_public: bool,
+    new_public: bool,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO privacy_logs (id, person_id, changed_by, old_public, new_public)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(uuid::Uuid::new_v4())
+    .bind(person_id.0)
+    .bind(changed_by.map(|w| w.0))
+    .bind(old_public)
+    .bind(new_public)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Colonnes de `persons` ; les lignes satellites viennent de `aggregate::load_person_details`.
 fn row_to_person(row: &sqlx::postgres::PgRow) -> Person {
     use chrono::NaiveDateTime;
     let created_at: NaiveDateTime = row.get("created_at");
@@ -294,12 +1360,16 @@ fn row_to_person(row: &sqlx::postgres::PgRow) -> Person {
         first_name: row.get("first_name"),
         surname: row.get("surname"),
         surname_prefix: row.get::<Option<String>, _>("surname_prefix"),
-        nicknames: Vec::new(),
+        nicknames: row
+            .get::<Option<Vec<String>>, _>("nicknames")
+            .unwrap_or_default(),
         sex: parse_sex(&sex_str),
         birth: None,
         death: None,
         other_events: Vec::new(),
-        occupation: Vec::new(),
+        occupation: row
+            .get::<Option<Vec<String>>, _>("occupations")
+            .unwrap_or_default(),
         titles: Vec::new(),
         notes: row.get::<Option<String>, _>("notes"),
         sources: Vec::new(),
@@ -313,6 +1383,15 @@ fn row_to_person(row: &sqlx::postgres::PgRow) -> Person {
     }
 }
 
+/// Colonnes `deleted_at` et `deleted_by` d'une ligne de la corbeille.
+fn row_to_trashed<T>(row: &sqlx::postgres::PgRow, record: T) -> Trashed<T> {
+    Trashed {
+        record,
+        deleted_at: row.get("deleted_at"),
+        deleted_by: row.get::<Option<Uuid>, _>("deleted_by").map(WizardId),
+    }
+}
+
 fn parse_sex(input: &str) -> Sex {
     match input {
         "Male" | "male" | "M" => Sex::Male,
@@ -335,17 +1414,41 @@ pub struct FamilyDraft {
     pub father_id: Option<PersonId>,
     pub mother_id: Option<PersonId>,
     pub children: Vec<PersonId>,
+    pub marriage: Option<LifeEvent>,
+    pub divorce: Option<LifeEvent>,
+    pub other_events: Vec<LifeEvent>,
     pub notes: Option<String>,
+    pub sources: Vec<Source>,
     pub public: bool,
+    pub created_by: Option<WizardId>,
+}
+
+impl FamilyDraft {
+    fn details(&self) -> aggregate::FamilyDetails<'_> {
+        aggregate::FamilyDetails {
+            id: self.id.0,
+            marriage: self.marriage.as_ref(),
+            divorce: self.divorce.as_ref(),
+            other_events: &self.other_events,
+            sources: &self.sources,
+        }
+    }
 }
 
+/// Champs à modifier ; les listes remplacent entièrement les précédentes.
 #[derive(Default)]
 pub struct FamilyChanges {
     pub father_id: Option<Option<PersonId>>,
     pub mother_id: Option<Option<PersonId>>,
     pub children: Option<Vec<PersonId>>,
+    pub marriage: Option<Option<LifeEvent>>,
+    pub divorce: Option<Option<LifeEvent>>,
+    pub other_events: Option<Vec<LifeEvent>>,
     pub notes: Option<Option<String>>,
+    pub sources: Option<Vec<Source>>,
     pub public: Option<bool>,
+    /// Auteur de la révision ; `families` ne le conserve pas.
+    pub updated_by: Option<WizardId>,
 }
 
 impl FamilyChanges {
@@ -355,24 +1458,145 @@ impl FamilyChanges {
             || self.children.is_some()
             || self.notes.is_some()
             || self.public.is_some()
+            || self.has_detail_changes()
+    }
+
+    /// Changements portant sur les événements ou les sources.
+    fn has_detail_changes(&self) -> bool {
+        self.marriage.is_some()
+            || self.divorce.is_some()
+            || self.other_events.is_some()
+            || self.sources.is_some()
+    }
+
+    /// Changements qui modifient la ligne `families` et donc son `updated_at`.
+    fn touches_family(&self) -> bool {
+        self.father_id.is_some()
+            || self.mother_id.is_some()
+            || self.notes.is_some()
+            || self.public.is_some()
+            || self.has_detail_changes()
+    }
+
+    /// Reporte les changements sur une famille déjà chargée, hors `updated_at`.
+    fn apply(self, family: &mut Family) {
+        if let Some(father) = self.father_id {
+            family.father = father;
+        }
+        if let Some(mother) = self.mother_id {
+            family.mother = mother;
+        }
+        if let Some(children) = self.children {
+            family.children = children;
+        }
+        if let Some(marriage) = self.marriage {
+            family.marriage = marriage;
+        }
+        if let Some(divorce) = self.divorce {
+            family.divorce = divorce;
+        }
+        if let Some(other_events) = self.other_events {
+            family.other_events = other_events;
+        }
+        if let Some(notes) = self.notes {
+            family.notes = notes;
+        }
+        if let Some(sources) = self.sources {
+            family.sources = sources;
+        }
+        if let Some(public) = self.public {
+            family.public = public;
+        }
+    }
+
+    /// Remplace tous les champs par ceux d'une révision antérieure.
+    fn reverting_to(family: Family, updated_by: Option<WizardId>) -> Self {
+        Self {
+            father_id: Some(family.father),
+            mother_id: Some(family.mother),
+            children: Some(family.children),
+            marriage: Some(family.marriage),
+            divorce: Some(family.divorce),
+            other_events: Some(family.other_events),
+            notes: Some(family.notes),
+            sources: Some(family.sources),
+            public: Some(family.public),
+            updated_by,
+        }
     }
 }
 
 pub struct FamilyEventNew {
     pub family_id: FamilyId,
     pub event_type: String,
-    pub date: Option<NaiveDate>,
+    pub date: Option<PreciseDate>,
     pub notes: Option<String>,
+    pub created_by: Option<WizardId>,
 }
 
 pub struct FamilyEventRecord {
     pub id: Uuid,
     pub family_id: FamilyId,
     pub event_type: String,
-    pub date: Option<NaiveDate>,
+    pub date: Option<PreciseDate>,
     pub notes: Option<String>,
 }
 
+/// Colonnes `date_*` d'un événement.
+///
+/// `date_value` et `date_value_end` portent les bornes grégoriennes pour les requêtes
+/// SQL ; `date_json` conserve la date complète (qualificatif, calendrier, texte d'origine).
+struct DateColumns {
+    date_type: &'static str,
+    value: Option<NaiveDate>,
+    value_end: Option<NaiveDate>,
+    json: Option<String>,
+}
+
+impl DateColumns {
+    fn new(date: Option<&PreciseDate>) -> Result<Self> {
+        let Some(date) = date else {
+            return Ok(Self {
+                date_type: "unknown",
+                value: None,
+                value_end: None,
+                json: None,
+            });
+        };
+        let date_type = match date.gregorian() {
+            PreciseDate::Exact(_) => "exact",
+            PreciseDate::About(_) => "about",
+            PreciseDate::Before(_) => "before",
+            PreciseDate::After(_) => "after",
+            PreciseDate::Between(_, _) => "between",
+            PreciseDate::YearOnly(_) => "year",
+            PreciseDate::AboutYear(_) => "about_year",
+            PreciseDate::Verbatim { .. } => "verbatim",
+            PreciseDate::InCalendar { .. } | PreciseDate::Unknown => "unknown",
+        };
+        let interval = date.interval();
+        Ok(Self {
+            date_type,
+            value: interval.earliest,
+            value_end: interval.latest,
+            json: Some(serde_json::to_string(date)?),
+        })
+    }
+
+    /// Date d'une ligne lue avec `date_json::text AS date_json`.
+    fn read(row: &sqlx::postgres::PgRow) -> Result<Option<PreciseDate>> {
+        Self::decode(row.get("date_json"), row.get("date_value"))
+    }
+
+    /// Les lignes écrites avant la colonne JSON n'ont qu'une date exacte.
+    fn decode(json: Option<String>, value: Option<NaiveDate>) -> Result<Option<PreciseDate>> {
+        match json {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(value.map(PreciseDate::Exact)),
+        }
+    }
+}
+
 pub struct FamilyRepository {
     pub pool: DatabasePool,
 }
@@ -399,29 +1623,203 @@ impl FamilyRepository {
         .execute(&mut *tx)
         .await?;
 
-        self.replace_children(&mut tx, draft.id, &draft.children).await?;
-
-        tx.commit().await?;
+        self.replace_children(&mut tx, draft.id, &draft.children).await?;
+        aggregate::insert_family_details(&mut tx, &[draft.details()]).await?;
+        let events = self
+            .link_family_edges(&mut tx, &draft.children, draft.father_id, draft.mother_id)
+            .await?;
+        let family = self
+            .fetch(&mut tx, draft.id)
+            .await?
+            .ok_or_else(|| anyhow!("Family not found after creation"))?;
+        let change = Change::family(
+            draft.id,
+            RevisionAction::Create,
+            None,
+            Some(&family),
+            draft.created_by,
+        )?;
+        history::record(&mut tx, &change).await?;
+
+        tx.commit().await?;
+        outbox::deliver_now(&self.pool, events.as_slice()).await;
+
+        Ok(family)
+    }
+
+    /// Insère un lot de familles et leurs enfants dans une seule transaction.
+    /// Une famille déjà présente reçoit les enfants qui lui manquent ; chaque
+    /// famille créée ou complétée reçoit une révision.
+    ///
+    /// Les arêtes Neo4j ne sont pas créées ici : l'import en masse les pose en fin
+    /// de traitement avec [`FamilyRepository::link_edges`].
+    pub async fn create_batch(&self, drafts: &[FamilyDraft]) -> Result<()> {
+        if drafts.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<Uuid> = drafts.iter().map(|draft| draft.id.0).collect();
+        let mut tx = self.pool.postgres.begin().await?;
+        sqlx::query("SELECT id FROM families WHERE id = ANY($1) ORDER BY id FOR UPDATE")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+        let mut before: HashMap<FamilyId, Family> = fetch_families(&mut tx, &ids, false)
+            .await?
+            .into_iter()
+            .map(|family| (family.id, family))
+            .collect();
+
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "INSERT INTO families (id, father_id, mother_id, notes, public) ",
+        );
+        builder.push_values(drafts, |mut row, draft| {
+            row.push_bind(draft.id.0)
+                .push_bind(draft.father_id.map(|id| id.0))
+                .push_bind(draft.mother_id.map(|id| id.0))
+                .push_bind(draft.notes.as_deref())
+                .push_bind(draft.public);
+        });
+        builder.push(" ON CONFLICT (id) DO NOTHING RETURNING id");
+        let inserted: HashSet<Uuid> = builder
+            .build_query_scalar()
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+        // Une famille déjà présente (import repris) garde ses événements.
+        let details: Vec<aggregate::FamilyDetails> = drafts
+            .iter()
+            .filter(|draft| inserted.contains(&draft.id.0))
+            .map(FamilyDraft::details)
+            .collect();
+        aggregate::insert_family_details(&mut tx, &details).await?;
+
+        let children: Vec<(Uuid, Uuid, i32)> = drafts
+            .iter()
+            .flat_map(|draft| {
+                draft
+                    .children
+                    .iter()
+                    .enumerate()
+                    .map(move |(order, child)| (draft.id.0, child.0, order as i32))
+            })
+            .collect();
+        // Reste sous la limite de 65 535 paramètres par requête.
+        for chunk in children.chunks(10_000) {
+            let mut builder = QueryBuilder::<Postgres>::new(
+                "INSERT INTO family_children (family_id, child_id, child_order) ",
+            );
+            builder.push_values(chunk, |mut row, (family, child, order)| {
+                row.push_bind(*family).push_bind(*child).push_bind(*order);
+            });
+            builder.push(" ON CONFLICT DO NOTHING");
+            builder.build().execute(&mut *tx).await?;
+        }
+
+        let created_by: HashMap<FamilyId, Option<WizardId>> = drafts
+            .iter()
+            .map(|draft| (draft.id, draft.created_by))
+            .collect();
+        let mut changes = Vec::new();
+        for after in fetch_families(&mut tx, &ids, false).await? {
+            let (action, before) = match before.remove(&after.id) {
+                None => (RevisionAction::Create, None),
+                Some(before) if before != after => (RevisionAction::Update, Some(before)),
+                Some(_) => continue,
+            };
+            let change = Change::family(
+                after.id,
+                action,
+                before.as_ref(),
+                Some(&after),
+                created_by[&after.id],
+            )?;
+            changes.push(change);
+        }
+        history::record_all(&mut tx, &changes).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Crée un lot d'arêtes `(enfant)-[:CHILD_OF]->(parent)` en une requête.
+    pub async fn link_edges(&self, edges: &[(PersonId, PersonId)]) -> Result<()> {
+        if edges.is_empty() {
+            return Ok(());
+        }
+        let event = OutboxEvent::MergeEdges {
+            edges: edges.to_vec(),
+        };
+        let id = outbox::enqueue(&mut *self.pool.postgres.acquire().await?, &event).await?;
+        outbox::deliver_now(&self.pool, &[id]).await;
+        Ok(())
+    }
+
+    /// Familles existantes ayant exactement ce père et cette mère (réimport).
+    pub async fn find_by_parents(
+        &self,
+        parents: &[(Option<PersonId>, Option<PersonId>)],
+    ) -> Result<HashMap<(Option<PersonId>, Option<PersonId>), FamilyId>> {
+        if parents.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let fathers: Vec<Option<Uuid>> = parents.iter().map(|(f, _)| f.map(|id| id.0)).collect();
+        let mothers: Vec<Option<Uuid>> = parents.iter().map(|(_, m)| m.map(|id| id.0)).collect();
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT ON (f.father_id, f.mother_id) f.id, f.father_id, f.mother_id
+            FROM families f
+            JOIN UNNEST($1::uuid[], $2::uuid[]) AS p(father_id, mother_id)
+              ON f.father_id IS NOT DISTINCT FROM p.father_id
+             AND f.mother_id IS NOT DISTINCT FROM p.mother_id
+            WHERE f.deleted_at IS NULL
+            ORDER BY f.father_id, f.mother_id, f.created_at
+            "#,
+        )
+        .bind(fathers)
+        .bind(mothers)
+        .fetch_all(&self.pool.postgres)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let father = row.get::<Option<Uuid>, _>("father_id").map(PersonId);
+                let mother = row.get::<Option<Uuid>, _>("mother_id").map(PersonId);
+                ((father, mother), FamilyId(row.get::<Uuid, _>("id")))
+            })
+            .collect())
+    }
 
-        self.link_family_edges(&draft.children, draft.father_id, draft.mother_id)
-            .await?;
 
-        self.find_by_id(draft.id)
-            .await?
-            .ok_or_else(|| anyhow!("Family not found after creation"))
+    pub async fn update(&self, id: FamilyId, changes: FamilyChanges) -> Result<Option<Family>> {
+        self.update_as(id, changes, RevisionAction::Update).await
     }
 
-    pub async fn update(&self, id: FamilyId, changes: FamilyChanges) -> Result<Option<Family>> {
+    /// Modifie la famille et enregistre la révision `action`.
+    async fn update_as(
+        &self,
+        id: FamilyId,
+        changes: FamilyChanges,
+        action: RevisionAction,
+    ) -> Result<Option<Family>> {
         if !changes.has_changes() {
             return self.find_by_id(id).await;
         }
 
+        let rewrite_details = changes.has_detail_changes();
         let FamilyChanges {
             father_id,
             mother_id,
             children,
+            marriage,
+            divorce,
+            other_events,
             notes,
+            sources,
             public,
+            updated_by,
         } = changes;
 
         let father_field = father_id;
@@ -430,11 +1828,26 @@ impl FamilyRepository {
         let public_field = public;
 
         let mut tx = self.pool.postgres.begin().await?;
+        let mut events = Vec::new();
+
+        if !lock_family(&mut tx, id).await? {
+            return Ok(None);
+        }
+        let before = self.fetch(&mut tx, id).await?;
+
+        // Arêtes de la famille avant modification, sous verrou, pour en calculer le delta.
+        let edges_before = if father_field.is_some() || mother_field.is_some() || children.is_some()
+        {
+            Some(family_edges(&mut tx, id).await?)
+        } else {
+            None
+        };
 
         if father_field.is_some()
             || mother_field.is_some()
             || notes_field.is_some()
             || public_field.is_some()
+            || rewrite_details
         {
             let mut builder = QueryBuilder::<Postgres>::new("UPDATE families SET ");
             let mut separated = builder.separated(", ");
@@ -442,30 +1855,30 @@ impl FamilyRepository {
             if let Some(ref father) = father_field {
                 separated.push("father_id = ");
                 match father {
-                    Some(fid) => separated.push_bind(fid.0),
-                    None => separated.push("NULL"),
+                    Some(fid) => separated.push_bind_unseparated(fid.0),
+                    None => separated.push_unseparated("NULL"),
                 };
             }
 
             if let Some(ref mother) = mother_field {
                 separated.push("mother_id = ");
                 match mother {
-                    Some(mid) => separated.push_bind(mid.0),
-                    None => separated.push("NULL"),
+                    Some(mid) => separated.push_bind_unseparated(mid.0),
+                    None => separated.push_unseparated("NULL"),
                 };
             }
 
             if let Some(ref notes) = notes_field {
                 separated.push("notes = ");
                 match notes {
-                    Some(text) => separated.push_bind(text),
-                    None => separated.push("NULL"),
+                    Some(text) => separated.push_bind_unseparated(text),
+                    None => separated.push_unseparated("NULL"),
                 };
             }
 
             if let Some(public) = public_field {
                 separated.push("public = ");
-                separated.push_bind(public);
+                separated.push_bind_unseparated(public);
             }
 
             separated.push("updated_at = NOW()");
@@ -475,29 +1888,63 @@ impl FamilyRepository {
             builder.build().execute(&mut *tx).await?;
         }
 
-        let father_for_edges = father_field.and_then(|opt| opt);
-        let mother_for_edges = mother_field.and_then(|opt| opt);
-
         if let Some(children) = children {
             self.replace_children(&mut tx, id, &children).await?;
-            self.link_family_edges(&children, father_for_edges, mother_for_edges)
-                .await?;
-        } else if father_field.is_some() || mother_field.is_some() {
-            let current_children = self.fetch_children_inner(&mut tx, id).await?;
-            self.link_family_edges(
-                &current_children,
-                father_for_edges,
-                mother_for_edges,
+        }
+        if let Some(before) = edges_before {
+            let after = family_edges(&mut tx, id).await?;
+            events.extend(enqueue_edge_delta(&mut tx, &before, &after).await?);
+        }
+
+        if rewrite_details {
+            let row = sqlx::query(
+                r#"
+                SELECT id, father_id, mother_id, notes, public, created_at, updated_at
+                FROM families
+                WHERE id = $1
+                FOR UPDATE
+                "#,
             )
+            .bind(id.0)
+            .fetch_optional(&mut *tx)
             .await?;
+            let Some(row) = row else {
+                return Ok(None);
+            };
+            let mut family = row_to_family(&row, Vec::new());
+            aggregate::load_family_details(&mut tx, slice::from_mut(&mut family)).await?;
+            if let Some(marriage) = marriage {
+                family.marriage = marriage;
+            }
+            if let Some(divorce) = divorce {
+                family.divorce = divorce;
+            }
+            if let Some(other_events) = other_events {
+                family.other_events = other_events;
+            }
+            if let Some(sources) = sources {
+                family.sources = sources;
+            }
+            aggregate::delete_family_details(&mut tx, &[id.0]).await?;
+            aggregate::insert_family_details(&mut tx, &[family_details(&family)]).await?;
         }
 
+        let after = self.fetch(&mut tx, id).await?;
+        let change = Change::family(id, action, before.as_ref(), after.as_ref(), updated_by)?;
+        history::record(&mut tx, &change).await?;
+
         tx.commit().await?;
+        outbox::deliver_now(&self.pool, &events).await;
 
-        self.find_by_id(id).await
+        Ok(after)
     }
 
-    pub async fn append_child(&self, id: FamilyId, child: PersonId) -> Result<Family> {
+    pub async fn append_child(
+        &self,
+        id: FamilyId,
+        child: PersonId,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
         let family = self
             .find_by_id(id)
             .await?
@@ -510,6 +1957,7 @@ impl FamilyRepository {
             id,
             FamilyChanges {
                 children: Some(children),
+                updated_by: changed_by,
                 ..Default::default()
             },
         )
@@ -517,7 +1965,12 @@ impl FamilyRepository {
         .ok_or_else(|| anyhow!("Family not found after update"))
     }
 
-    pub async fn remove_child(&self, id: FamilyId, child: PersonId) -> Result<Family> {
+    pub async fn remove_child(
+        &self,
+        id: FamilyId,
+        child: PersonId,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
         let family = self
             .find_by_id(id)
             .await?
@@ -531,6 +1984,7 @@ impl FamilyRepository {
             id,
             FamilyChanges {
                 children: Some(children),
+                updated_by: changed_by,
                 ..Default::default()
             },
         )
@@ -538,11 +1992,17 @@ impl FamilyRepository {
         .ok_or_else(|| anyhow!("Family not found after update"))
     }
 
-    pub async fn set_privacy(&self, id: FamilyId, public: bool) -> Result<Family> {
+    pub async fn set_privacy(
+        &self,
+        id: FamilyId,
+        public: bool,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
         self.update(
             id,
             FamilyChanges {
                 public: Some(public),
+                updated_by: changed_by,
                 ..Default::default()
             },
         )
@@ -550,51 +2010,248 @@ impl FamilyRepository {
         .ok_or_else(|| anyhow!("Family not found after update"))
     }
 
+    /// Ajoute un événement ; un mariage ou un divorce devient `marriage` ou `divorce`
+    /// et ne peut pas remplacer celui déjà enregistré, les autres vont après les autres.
     pub async fn add_event(&self, event: FamilyEventNew) -> Result<FamilyEventRecord> {
-        let date_type = if event.date.is_some() { "exact" } else { "unknown" };
+        let columns = DateColumns::new(event.date.as_ref())?;
+        let slot = family_event_slot(&EventType::from_name(&event.event_type));
+        let mut tx = self.pool.postgres.begin().await?;
+        if !lock_family(&mut tx, event.family_id).await? {
+            return Err(anyhow!("Family not found"));
+        }
+        let before = self.fetch(&mut tx, event.family_id).await?;
+        if slot != "other" {
+            let taken: bool = sqlx::query_scalar(
+                "SELECT EXISTS (SELECT 1 FROM events WHERE family_id = $1 AND slot = $2)",
+            )
+            .bind(event.family_id.0)
+            .bind(slot)
+            .fetch_one(&mut *tx)
+            .await?;
+            if taken {
+                return Err(family_slot_taken(slot));
+            }
+        }
         let row = sqlx::query(
             r#"
-            INSERT INTO events (id, family_id, event_type, date_type, date_value, person_id, notes)
-            VALUES ($1, $2, $3, $4, $5, NULL, $6)
-            RETURNING id, family_id, event_type, date_value, notes
+            INSERT INTO events
+                (id, family_id, event_type, slot, position, date_type, date_value, date_value_end,
+                 date_json, person_id, notes)
+            SELECT $1, $2, $3, $9::text,
+                   COALESCE(MAX(position) + 1, 0), $4, $5, $6, $7::jsonb, NULL, $8
+            FROM events
+            WHERE family_id = $2 AND slot = $9::text
+            RETURNING id, family_id, event_type, date_value, date_json::text AS date_json, notes
             "#,
         )
         .bind(Uuid::new_v4())
         .bind(event.family_id.0)
         .bind(&event.event_type)
-        .bind(date_type)
-        .bind(event.date)
+        .bind(columns.date_type)
+        .bind(columns.value)
+        .bind(columns.value_end)
+        .bind(columns.json)
         .bind(event.notes.as_deref())
-        .fetch_one(&self.pool.postgres)
+        .bind(slot)
+        .fetch_one(&mut *tx)
         .await?;
+        let after = self.fetch(&mut tx, event.family_id).await?;
+        let change = Change::family(
+            event.family_id,
+            RevisionAction::Update,
+            before.as_ref(),
+            after.as_ref(),
+            event.created_by,
+        )?;
+        history::record(&mut tx, &change).await?;
+        tx.commit().await?;
 
         Ok(FamilyEventRecord {
             id: row.get::<Uuid, _>("id"),
             family_id: FamilyId(row.get::<Uuid, _>("family_id")),
             event_type: row.get("event_type"),
-            date: row.get::<Option<NaiveDate>, _>("date_value"),
+            date: DateColumns::read(&row)?,
             notes: row.get::<Option<String>, _>("notes"),
         })
     }
 
+    /// Met la famille à la corbeille : ses arêtes `CHILD_OF` disparaissent jusqu'à sa
+    /// restauration ; `false` si elle n'existe pas ou y est déjà. Une famille qui a
+    /// encore des enfants n'y est mise qu'avec `force`.
+    pub async fn delete(
+        &self,
+        id: FamilyId,
+        force: bool,
+        deleted_by: Option<WizardId>,
+    ) -> Result<bool> {
+        let mut tx = self.pool.postgres.begin().await?;
+        if !lock_family(&mut tx, id).await? {
+            return Ok(false);
+        }
+        let children: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM family_children WHERE family_id = $1")
+                .bind(id.0)
+                .fetch_one(&mut *tx)
+                .await?;
+        if children > 0 && !force {
+            return Err(family_has_children(children));
+        }
+        let before = family_edges(&mut tx, id).await?;
+        let family = self.fetch(&mut tx, id).await?;
+
+        sqlx::query("UPDATE families SET deleted_at = NOW(), deleted_by = $2 WHERE id = $1")
+            .bind(id.0)
+            .bind(deleted_by.map(|w| w.0))
+            .execute(&mut *tx)
+            .await?;
+        let events = enqueue_edge_delta(&mut tx, &before, &[]).await?;
+        let change = Change::family(
+            id,
+            RevisionAction::Delete,
+            family.as_ref(),
+            None,
+            deleted_by,
+        )?;
+        history::record(&mut tx, &change).await?;
+
+        tx.commit().await?;
+        outbox::deliver_now(&self.pool, &events).await;
+        Ok(true)
+    }
+
+    /// Sort la famille de la corbeille avec ses arêtes `CHILD_OF` ; `false` si elle
+    /// n'y est pas.
+    pub async fn restore(&self, id: FamilyId, restored_by: Option<WizardId>) -> Result<bool> {
+        let mut tx = self.pool.postgres.begin().await?;
+        let restored = sqlx::query(
+            r#"
+            UPDATE families SET deleted_at = NULL, deleted_by = NULL
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(id.0)
+        .execute(&mut *tx)
+        .await?;
+        if restored.rows_affected() == 0 {
+            return Ok(false);
+        }
+        let after = family_edges(&mut tx, id).await?;
+        let events = enqueue_edge_delta(&mut tx, &[], &after).await?;
+        let family = self.fetch(&mut tx, id).await?;
+        let change = Change::family(
+            id,
+            RevisionAction::Restore,
+            None,
+            family.as_ref(),
+            restored_by,
+        )?;
+        history::record(&mut tx, &change).await?;
+
+        tx.commit().await?;
+        outbox::deliver_now(&self.pool, &events).await;
+        Ok(true)
+    }
+
+    /// Familles à la corbeille, les plus récemment supprimées d'abord.
+    pub async fn trash(&self) -> Result<Vec<Trashed<Family>>> {
+        let mut tx = read_snapshot(&self.pool.postgres).await?;
+        let rows = sqlx::query(
+            r#"
+            SELECT id, father_id, mother_id, notes, public, created_at, updated_at,
+                   deleted_at, deleted_by
+            FROM families
+            WHERE deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut families = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let id = FamilyId(row.get::<Uuid, _>("id"));
+            let children = self.fetch_children_inner(&mut tx, id).await?;
+            families.push(row_to_family(row, children));
+        }
+        aggregate::load_family_details(&mut tx, &mut families).await?;
+        tx.commit().await?;
+        Ok(rows
+            .iter()
+            .zip(families)
+            .map(|(row, family)| row_to_trashed(row, family))
+            .collect())
+    }
+
+    /// Supprime définitivement les familles mises à la corbeille avant `before` ;
+    /// renvoie leur nombre.
+    pub async fn purge_trash(&self, before: NaiveDateTime) -> Result<u64> {
+        let mut tx = self.pool.postgres.begin().await?;
+        let ids: Vec<Uuid> =
+            sqlx::query_scalar("DELETE FROM families WHERE deleted_at < $1 RETURNING id")
+                .bind(before)
+                .fetch_all(&mut *tx)
+                .await?;
+        let purges: Vec<Change> = ids
+            .iter()
+            .map(|id| Change::purge(RevisionEntity::Family, *id))
+            .collect();
+        history::record_all(&mut tx, &purges).await?;
+        tx.commit().await?;
+        Ok(ids.len() as u64)
+    }
+
+    /// Révisions de la famille, de la plus ancienne à la plus récente.
+    pub async fn history(&self, id: FamilyId) -> Result<Vec<Revision>> {
+        let mut conn = self.pool.postgres.acquire().await?;
+        history::list(&mut conn, RevisionEntity::Family, id.0).await
+    }
+
+    /// Ramène la famille à l'état laissé par `revision`, dans une nouvelle révision ;
+    /// `None` si elle n'existe pas ou est à la corbeille.
+    pub async fn revert(
+        &self,
+        id: FamilyId,
+        revision: i64,
+        reverted_by: Option<WizardId>,
+    ) -> Result<Option<Family>> {
+        let mut conn = self.pool.postgres.acquire().await?;
+        let family: Family = history::find(&mut conn, RevisionEntity::Family, id.0, revision)
+            .await?
+            .snapshot()?;
+        drop(conn);
+        let changes = FamilyChanges::reverting_to(family, reverted_by);
+        self.update_as(id, changes, RevisionAction::Revert).await
+    }
+
     pub async fn find_by_id(&self, id: FamilyId) -> Result<Option<Family>> {
+        let mut tx = read_snapshot(&self.pool.postgres).await?;
+        let family = self.fetch(&mut tx, id).await?;
+        tx.commit().await?;
+        Ok(family)
+    }
+
+    /// Famille hors corbeille, avec ses enfants et ses événements.
+    async fn fetch(&self, tx: &mut PgTx<'_>, id: FamilyId) -> Result<Option<Family>> {
         let row = sqlx::query(
             r#"
             SELECT id, father_id, mother_id, notes, public, created_at, updated_at
             FROM families
-            WHERE id = $1
+            WHERE id = $1 AND deleted_at IS NULL
             "#,
         )
         .bind(id.0)
-        .fetch_optional(&self.pool.postgres)
+        .fetch_optional(&mut **tx)
         .await?;
 
-        if let Some(row) = row {
-            let children = self.fetch_children(id).await?;
-            Ok(Some(row_to_family(&row, children)))
-        } else {
-            Ok(None)
-        }
+        let mut family = match row {
+            Some(row) => {
+                let children = self.fetch_children_inner(tx, id).await?;
+                Some(row_to_family(&row, children))
+            }
+            None => None,
+        };
+        aggregate::load_family_details(tx, family.as_mut_slice()).await?;
+        Ok(family)
     }
 
     async fn replace_children(
@@ -624,39 +2281,31 @@ impl FamilyRepository {
         Ok(())
     }
 
+    /// Met en file, dans la transaction, les arêtes `CHILD_OF` des enfants vers leurs parents.
     async fn link_family_edges(
         &self,
+        tx: &mut PgTx<'_>,
         children: &[PersonId],
         father: Option<PersonId>,
         mother: Option<PersonId>,
-    ) -> Result<()> {
-        for child in children {
-            if let Some(father) = father {
-                self.link_child_parent(child, &father).await?;
-            }
-            if let Some(mother) = mother {
-                self.link_child_parent(child, &mother).await?;
-            }
+    ) -> Result<Option<i64>> {
+        let parents: Vec<PersonId> = [father, mother].into_iter().flatten().collect();
+        let edges: Vec<(PersonId, PersonId)> = children
+            .iter()
+            .flat_map(|child| parents.iter().map(|parent| (*child, *parent)))
+            .collect();
+        if edges.is_empty() {
+            return Ok(None);
         }
-        Ok(())
+        let event = outbox::enqueue(tx, &OutboxEvent::MergeEdges { edges }).await?;
+        Ok(Some(event))
     }
 
-    async fn link_child_parent(
+    async fn fetch_children_inner(
         &self,
-        child: &PersonId,
-        parent: &PersonId,
-    ) -> Result<()> {
-        let cypher = query(
-            "MATCH (c:Person {id: $child}), (p:Person {id: $parent})
-             MERGE (c)-[:CHILD_OF]->(p)",
-        )
-        .param("child", child.0.to_string())
-        .param("parent", parent.0.to_string());
-        self.pool.neo4j.run(cypher).await?;
-        Ok(())
-    }
-
-    async fn fetch_children(&self, family_id: FamilyId) -> Result<Vec<PersonId>> {
+        tx: &mut PgTx<'_>,
+        family_id: FamilyId,
+    ) -> Result<Vec<PersonId>> {
         let rows = sqlx::query(
             r#"
             SELECT child_id
@@ -666,7 +2315,7 @@ impl FamilyRepository {
             "#,
         )
         .bind(family_id.0)
-        .fetch_all(&self.pool.postgres)
+        .fetch_all(&mut **tx)
         .await?;
 
         Ok(rows
@@ -674,31 +2323,107 @@ impl FamilyRepository {
             .map(|row| PersonId(row.get::<Uuid, _>("child_id")))
             .collect())
     }
+}
 
-    async fn fetch_children_inner(
-        &self,
-        tx: &mut PgTx<'_>,
-        family_id: FamilyId,
-    ) -> Result<Vec<PersonId>> {
-        let rows = sqlx::query(
+/// Verrouille la famille ; `false` si elle n'existe pas ou est à la corbeille.
+async fn lock_family(conn: &mut PgConnection, id: FamilyId) -> Result<bool> {
+    let found: Option<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM families WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+    )
+    .bind(id.0)
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(found.is_some())
+}
+
+/// Arêtes `(enfant, parent)` que justifie cette famille ; aucune si la famille,
+/// l'enfant ou le parent est à la corbeille, comme dans la vue `child_of`.
+async fn family_edges(tx: &mut PgTx<'_>, id: FamilyId) -> Result<Vec<(PersonId, PersonId)>> {
+    let edges: Vec<(Uuid, Uuid)> = sqlx::query_as(
+        r#"
+        SELECT fc.child_id, parent.id
+        FROM family_children fc
+        JOIN families f ON f.id = fc.family_id
+        CROSS JOIN LATERAL (VALUES (f.father_id), (f.mother_id)) AS parent(id)
+        JOIN persons c ON c.id = fc.child_id
+        JOIN persons p ON p.id = parent.id
+        WHERE fc.family_id = $1
+          AND f.deleted_at IS NULL AND c.deleted_at IS NULL AND p.deleted_at IS NULL
+        "#,
+    )
+    .bind(id.0)
+    .fetch_all(&mut **tx)
+    .await?;
+    Ok(edges
+        .into_iter()
+        .map(|(child, parent)| (PersonId(child), PersonId(parent)))
+        .collect())
+}
+
+/// Met en file les arêtes `CHILD_OF` apparues et celles disparues qu'aucune autre
+/// famille ne justifie plus ; à appeler après l'écriture des familles.
+async fn enqueue_edge_delta(
+    tx: &mut PgTx<'_>,
+    before: &[(PersonId, PersonId)],
+    after: &[(PersonId, PersonId)],
+) -> Result<Vec<i64>> {
+    let mut events = Vec::new();
+
+    let added: Vec<(PersonId, PersonId)> = after
+        .iter()
+        .filter(|edge| !before.contains(edge))
+        .copied()
+        .collect();
+    if !added.is_empty() {
+        let event = OutboxEvent::MergeEdges { edges: added };
+        events.push(outbox::enqueue(tx, &event).await?);
+    }
+
+    let (children, parents): (Vec<Uuid>, Vec<Uuid>) = before
+        .iter()
+        .filter(|edge| !after.contains(edge))
+        .map(|(child, parent)| (child.0, parent.0))
+        .unzip();
+    if !children.is_empty() {
+        // Un demi-frère peut tenir le même parent d'une autre famille : la vue
+        // `child_of` reflète déjà l'état écrit dans cette transaction.
+        let orphaned: Vec<(Uuid, Uuid)> = sqlx::query_as(
             r#"
-            SELECT child_id
-            FROM family_children
-            WHERE family_id = $1
-            ORDER BY child_order
+            SELECT e.child_id, e.parent_id
+            FROM UNNEST($1::uuid[], $2::uuid[]) AS e(child_id, parent_id)
+            WHERE NOT EXISTS (
+                SELECT 1 FROM child_of c
+                WHERE c.child_id = e.child_id AND c.parent_id = e.parent_id
+            )
             "#,
         )
-        .bind(family_id.0)
+        .bind(&children)
+        .bind(&parents)
         .fetch_all(&mut **tx)
         .await?;
+        if !orphaned.is_empty() {
+            let edges = orphaned
+                .into_iter()
+                .map(|(child, parent)| (PersonId(child), PersonId(parent)))
+                .collect();
+            events.push(outbox::enqueue(tx, &OutboxEvent::DeleteEdges { edges }).await?);
+        }
+    }
 
-        Ok(rows
-            .into_iter()
-            .map(|row| PersonId(row.get::<Uuid, _>("child_id")))
-            .collect())
+    Ok(events)
+}
+
+fn family_details(family: &Family) -> aggregate::FamilyDetails<'_> {
+    aggregate::FamilyDetails {
+        id: family.id.0,
+        marriage: family.marriage.as_ref(),
+        divorce: family.divorce.as_ref(),
+        other_events: &family.other_events,
+        sources: &family.sources,
     }
 }
 
+/// Colonnes de `families` ; événements et sources viennent de `aggregate::load_family_details`.
 fn row_to_family(row: &sqlx::postgres::PgRow, children: Vec<PersonId>) -> Family {
     let created_at: NaiveDateTime = row.get("created_at");
     let updated_at: NaiveDateTime = row.get("updated_at");
@@ -717,3 +2442,247 @@ fn row_to_family(row: &sqlx::postgres::PgRow, children: Vec<PersonId>) -> Family
         updated_at,
     }
 }
+
+/// Correspondance xref GEDCOM → identifiant, conservée pour un import donné.
+pub struct XrefEntry {
+    pub xref: String,
+    /// `INDI` ou `FAM`.
+    pub record_type: &'static str,
+    pub entity_id: Uuid,
+}
+
+/// Lien parent/enfant d'une famille importée, pour créer les arêtes `CHILD_OF`.
+pub struct FamilyLink {
+    pub family_id: FamilyId,
+    pub child: PersonId,
+    pub father: Option<PersonId>,
+    pub mother: Option<PersonId>,
+}
+
+/// Suivi des imports GEDCOM en flux (`gedcom_imports`, `gedcom_xrefs`).
+pub struct ImportRepository {
+    pub pool: DatabasePool,
+}
+
+impl ImportRepository {
+    pub fn new(pool: DatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Déclare un import, ou le relance s'il existe déjà.
+    pub async fn start(&self, import_id: Uuid, file_name: Option<&str>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO gedcom_imports (id, file_name)
+            VALUES ($1, $2)
+            ON CONFLICT (id) DO UPDATE SET status = 'running', finished_at = NULL
+            "#,
+        )
+        .bind(import_id)
+        .bind(file_name)
+        .execute(&self.pool.postgres)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn finish(&self, import_id: Uuid, report: &ImportReport) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE gedcom_imports
+            SET status = 'done', persons = $2, families = $3, report = $4::jsonb,
+                finished_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(import_id)
+        .bind(report.persons as i32)
+        .bind(report.families as i32)
+        .bind(serde_json::to_string(report)?)
+        .execute(&self.pool.postgres)
+        .await?;
+        Ok(())
+    }
+
+    /// Rapport d'un import terminé.
+    pub async fn report(&self, import_id: Uuid) -> Result<Option<ImportReport>> {
+        let report: Option<Option<String>> =
+            sqlx::query_scalar("SELECT report::text FROM gedcom_imports WHERE id = $1")
+                .bind(import_id)
+                .fetch_optional(&self.pool.postgres)
+                .await?;
+        match report.flatten() {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn mark_failed(&self, import_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE gedcom_imports SET status = 'failed', finished_at = NOW() WHERE id = $1")
+            .bind(import_id)
+            .execute(&self.pool.postgres)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn save_xrefs(&self, import_id: Uuid, entries: &[XrefEntry]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.pool.postgres.begin().await?;
+        for chunk in entries.chunks(10_000) {
+            let mut builder = QueryBuilder::<Postgres>::new(
+                "INSERT INTO gedcom_xrefs (import_id, xref, record_type, entity_id) ",
+            );
+            builder.push_values(chunk, |mut row, entry| {
+                row.push_bind(import_id)
+                    .push_bind(&entry.xref)
+                    .push_bind(entry.record_type)
+                    .push_bind(entry.entity_id);
+            });
+            builder.push(" ON CONFLICT DO NOTHING");
+            builder.build().execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// xrefs déjà attribués pour cet import (vides s'il n'a jamais été lancé).
+    pub async fn load_xrefs(
+        &self,
+        import_id: Uuid,
+    ) -> Result<(HashMap<String, PersonId>, HashMap<String, FamilyId>)> {
+        let rows = sqlx::query(
+            "SELECT xref, record_type, entity_id FROM gedcom_xrefs WHERE import_id = $1",
+        )
+        .bind(import_id)
+        .fetch_all(&self.pool.postgres)
+        .await?;
+
+        let mut persons = HashMap::new();
+        let mut families = HashMap::new();
+        for row in rows {
+            let xref: String = row.get("xref");
+            let id: Uuid = row.get("entity_id");
+            match row.get::<String, _>("record_type").as_str() {
+                "INDI" => {
+                    persons.insert(xref, PersonId(id));
+                }
+                "FAM" => {
+                    families.insert(xref, FamilyId(id));
+                }
+                _ => {}
+            }
+        }
+        Ok((persons, families))
+    }
+
+    /// Liens parent/enfant des familles de l'import, par pages triées
+    /// (`after` : dernier couple famille/enfant de la page précédente).
+    pub async fn family_links(
+        &self,
+        import_id: Uuid,
+        after: Option<(FamilyId, PersonId)>,
+        limit: i64,
+    ) -> Result<Vec<FamilyLink>> {
+        let (after_family, after_child) = after
+            .map(|(family, child)| (family.0, child.0))
+            .unwrap_or((Uuid::nil(), Uuid::nil()));
+        let rows = sqlx::query(
+            r#"
+            SELECT fc.family_id, fc.child_id, f.father_id, f.mother_id
+            FROM gedcom_xrefs x
+            JOIN families f ON f.id = x.entity_id
+            JOIN family_children fc ON fc.family_id = f.id
+            WHERE x.import_id = $1
+              AND x.record_type = 'FAM'
+              AND (fc.family_id, fc.child_id) > ($2, $3)
+            ORDER BY fc.family_id, fc.child_id
+            LIMIT $4
+            "#,
+        )
+        .bind(import_id)
+        .bind(after_family)
+        .bind(after_child)
+        .bind(limit)
+        .fetch_all(&self.pool.postgres)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| FamilyLink {
+                family_id: FamilyId(row.get::<Uuid, _>("family_id")),
+                child: PersonId(row.get::<Uuid, _>("child_id")),
+                father: row.get::<Option<Uuid>, _>("father_id").map(PersonId),
+                mother: row.get::<Option<Uuid>, _>("mother_id").map(PersonId),
+            })
+            .collect())
+    }
+}
+
+/// Identifiants externes des personnes (`_UID`, `REFN`, `EXID`), pour les réimports.
+pub struct ExternalIdRepository {
+    pub pool: DatabasePool,
+}
+
+impl ExternalIdRepository {
+    pub fn new(pool: DatabasePool) -> Self {
+        Self { pool }
+    }
+
+    /// Personnes déjà connues sous l'un de ces identifiants (type, valeur).
+    pub async fn find_persons(
+        &self,
+        identifiers: &[(String, String)],
+    ) -> Result<HashMap<(String, String), PersonId>> {
+        if identifiers.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let kinds: Vec<&str> = identifiers.iter().map(|(kind, _)| kind.as_str()).collect();
+        let values: Vec<&str> = identifiers
+            .iter()
+            .map(|(_, value)| value.as_str())
+            .collect();
+        let rows = sqlx::query(
+            r#"
+            SELECT e.kind, e.value, e.person_id
+            FROM person_external_ids e
+            JOIN UNNEST($1::text[], $2::text[]) AS i(kind, value)
+              ON e.kind = i.kind AND e.value = i.value
+            "#,
+        )
+        .bind(kinds)
+        .bind(values)
+        .fetch_all(&self.pool.postgres)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    (row.get("kind"), row.get("value")),
+                    PersonId(row.get::<Uuid, _>("person_id")),
+                )
+            })
+            .collect())
+    }
+
+    /// Enregistre des identifiants ; un identifiant déjà attribué n'est pas déplacé.
+    pub async fn link(&self, identifiers: &[(String, String, PersonId)]) -> Result<()> {
+        if identifiers.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.pool.postgres.begin().await?;
+        for chunk in identifiers.chunks(10_000) {
+            let mut builder = QueryBuilder::<Postgres>::new(
+                "INSERT INTO person_external_ids (kind, value, person_id) ",
+            );
+            builder.push_values(chunk, |mut row, (kind, value, person)| {
+                row.push_bind(kind).push_bind(value).push_bind(person.0);
+            });
+            builder.push(" ON CONFLICT DO NOTHING");
+            builder.build().execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
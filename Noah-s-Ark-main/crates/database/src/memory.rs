@@ -0,0 +1,1067 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use genealogy_types::{
+    EventType, Family, FamilyId, LifeEvent, Person, PersonId, PreciseDate, WizardId,
+};
+use uuid::Uuid;
+
+use crate::history::{revision_not_found, Change};
+use crate::{
+    event_slot, family_event_slot, family_has_children, family_slot_taken, family_updates,
+    in_timeline_order, person_is_parent, slot_taken, FamilyChanges, FamilyDraft, FamilyEventNew,
+    FamilyEventRecord, FamilyStore, GraphStore, PersonEventRecord, PersonStore, PersonUpdate,
+    Revision, RevisionAction, RevisionEntity, Storage, Trashed,
+};
+
+/// Backend en mémoire, sans PostgreSQL, Neo4j ni Redis : pour les tests et le développement.
+///
+/// Les clones partagent les mêmes données ; le graphe est déduit des familles.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    state: Arc<RwLock<MemoryState>>,
+}
+
+/// Personnes d'un [`MemoryStore`].
+#[derive(Clone)]
+pub struct MemoryPersons {
+    store: MemoryStore,
+}
+
+/// Familles d'un [`MemoryStore`].
+#[derive(Clone)]
+pub struct MemoryFamilies {
+    store: MemoryStore,
+}
+
+/// Graphe de filiation d'un [`MemoryStore`], déduit de ses familles.
+#[derive(Clone)]
+pub struct MemoryGraph {
+    store: MemoryStore,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    persons: HashMap<PersonId, PersonEntry>,
+    families: HashMap<FamilyId, Family>,
+    /// Corbeille : ce qui s'y trouve n'est plus dans `persons` ni `families`.
+    trashed_persons: HashMap<PersonId, Trashed<PersonEntry>>,
+    trashed_families: HashMap<FamilyId, Trashed<Family>>,
+    /// Familles dont une personne mise à la corbeille avec `force` a été retirée,
+    /// avec sa place (`father` ou `mother`), comme la table `trashed_parents`.
+    trashed_parents: HashMap<PersonId, Vec<(FamilyId, &'static str)>>,
+    revisions: Vec<Revision>,
+    /// Dernier numéro de révision attribué.
+    last_revision: i64,
+}
+
+/// Personne et identifiants de ses événements, alignés sur `birth`, `death` et `other_events`.
+#[derive(Clone)]
+struct PersonEntry {
+    person: Person,
+    birth_id: Option<Uuid>,
+    death_id: Option<Uuid>,
+    other_ids: Vec<Uuid>,
+}
+
+#[derive(Clone, Copy)]
+enum Slot {
+    Birth,
+    Death,
+    Other(usize),
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, MemoryState> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, MemoryState> {
+        self.state.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Storage for MemoryStore {
+    type Persons = MemoryPersons;
+    type Families = MemoryFamilies;
+    type Graph = MemoryGraph;
+
+    fn persons(&self) -> MemoryPersons {
+        MemoryPersons {
+            store: self.clone(),
+        }
+    }
+
+    fn families(&self) -> MemoryFamilies {
+        MemoryFamilies {
+            store: self.clone(),
+        }
+    }
+
+    fn graph(&self) -> MemoryGraph {
+        MemoryGraph {
+            store: self.clone(),
+        }
+    }
+}
+
+impl PersonEntry {
+    fn new(person: Person) -> Self {
+        Self {
+            birth_id: person.birth.as_ref().map(|_| Uuid::new_v4()),
+            death_id: person.death.as_ref().map(|_| Uuid::new_v4()),
+            other_ids: person.other_events.iter().map(|_| Uuid::new_v4()).collect(),
+            person,
+        }
+    }
+
+    fn records(&self) -> Vec<PersonEventRecord> {
+        let person_id = self.person.id;
+        let record = |id: Uuid, event: &LifeEvent| PersonEventRecord {
+            id,
+            person_id,
+            event: event.clone(),
+        };
+        let birth = self.birth_id.zip(self.person.birth.as_ref());
+        let death = self.death_id.zip(self.person.death.as_ref());
+        let others = self
+            .other_ids
+            .iter()
+            .copied()
+            .zip(&self.person.other_events);
+        in_timeline_order(
+            birth
+                .map(|(id, event)| ("birth", record(id, event)))
+                .into_iter()
+                .chain(others.map(|(id, event)| ("other", record(id, event))))
+                .chain(death.map(|(id, event)| ("death", record(id, event)))),
+        )
+    }
+
+    fn locate(&self, event_id: Uuid) -> Option<Slot> {
+        if self.birth_id == Some(event_id) {
+            Some(Slot::Birth)
+        } else if self.death_id == Some(event_id) {
+            Some(Slot::Death)
+        } else {
+            self.other_ids
+                .iter()
+                .position(|id| *id == event_id)
+                .map(Slot::Other)
+        }
+    }
+
+    fn remove(&mut self, slot: Slot) {
+        match slot {
+            Slot::Birth => {
+                self.birth_id = None;
+                self.person.birth = None;
+            }
+            Slot::Death => {
+                self.death_id = None;
+                self.person.death = None;
+            }
+            Slot::Other(index) => {
+                self.other_ids.remove(index);
+                self.person.other_events.remove(index);
+            }
+        }
+    }
+
+    /// Range l'événement comme `PersonRepository` : naissance, décès, ou autre événement
+    /// à `index` (à la fin par défaut).
+    fn insert(&mut self, id: Uuid, event: LifeEvent, index: Option<usize>) -> Result<()> {
+        match event_slot(&event.event_type) {
+            "birth" if self.birth_id.is_some() => return Err(slot_taken("birth")),
+            "death" if self.death_id.is_some() => return Err(slot_taken("death")),
+            "birth" => {
+                self.birth_id = Some(id);
+                self.person.birth = Some(event);
+            }
+            "death" => {
+                self.death_id = Some(id);
+                self.person.death = Some(event);
+            }
+            _ => {
+                let index = index.unwrap_or(self.other_ids.len());
+                self.other_ids.insert(index, id);
+                self.person.other_events.insert(index, event);
+            }
+        }
+        self.person.updated_at = Utc::now().naive_utc();
+        Ok(())
+    }
+}
+
+impl MemoryState {
+    /// Numérote et date la révision, comme la table `revisions`.
+    fn record(&mut self, change: Change) {
+        self.last_revision += 1;
+        self.revisions.push(Revision {
+            id: self.last_revision,
+            entity: change.entity,
+            entity_id: change.entity_id,
+            action: change.action,
+            before: change.before,
+            after: change.after,
+            changed_by: change.changed_by,
+            changed_at: Utc::now().naive_utc(),
+        });
+    }
+
+    fn history(&self, entity: RevisionEntity, entity_id: Uuid) -> Vec<Revision> {
+        self.revisions
+            .iter()
+            .filter(|revision| revision.entity == entity && revision.entity_id == entity_id)
+            .cloned()
+            .collect()
+    }
+
+    fn revision(&self, entity: RevisionEntity, entity_id: Uuid, id: i64) -> Result<Revision> {
+        self.history(entity, entity_id)
+            .into_iter()
+            .find(|revision| revision.id == id)
+            .ok_or_else(|| revision_not_found(id))
+    }
+
+    /// Retire `id` des familles dont il est parent et garde ces liens pour sa restauration.
+    fn unlink_parent(&mut self, id: PersonId, changed_by: Option<WizardId>) -> Result<()> {
+        let now = Utc::now().naive_utc();
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+        let mut links = Vec::new();
+        for family in self.families.values_mut() {
+            if family.father != Some(id) && family.mother != Some(id) {
+                continue;
+            }
+            before.push(family.clone());
+            if family.father == Some(id) {
+                family.father = None;
+                links.push((family.id, "father"));
+            }
+            if family.mother == Some(id) {
+                family.mother = None;
+                links.push((family.id, "mother"));
+            }
+            family.updated_at = now;
+            after.push(family.clone());
+        }
+        self.trashed_parents.entry(id).or_default().extend(links);
+        for change in family_updates(before, &after, changed_by)? {
+            self.record(change);
+        }
+        Ok(())
+    }
+
+    /// Remet `id` dans les familles dont [`Self::unlink_parent`] l'a retiré,
+    /// là où sa place est restée libre.
+    fn relink_parent(&mut self, id: PersonId, changed_by: Option<WizardId>) -> Result<()> {
+        let now = Utc::now().naive_utc();
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+        for (family_id, role) in self.trashed_parents.remove(&id).unwrap_or_default() {
+            let Some(family) = self.families.get_mut(&family_id) else {
+                continue;
+            };
+            let slot = if role == "father" {
+                family.father
+            } else {
+                family.mother
+            };
+            if slot.is_some() {
+                continue;
+            }
+            before.push(family.clone());
+            if role == "father" {
+                family.father = Some(id);
+            } else {
+                family.mother = Some(id);
+            }
+            family.updated_at = now;
+            after.push(family.clone());
+        }
+        for change in family_updates(before, &after, changed_by)? {
+            self.record(change);
+        }
+        Ok(())
+    }
+
+    fn find_event(&mut self, event_id: Uuid) -> Option<(&mut PersonEntry, Slot)> {
+        self.persons
+            .values_mut()
+            .find_map(|entry| entry.locate(event_id).map(|slot| (entry, slot)))
+    }
+
+    /// Arêtes `CHILD_OF` : parents de chaque enfant, sans doublon et triés par identifiant.
+    /// Comme la vue `child_of`, seules les personnes hors corbeille en portent.
+    fn parent_index(&self) -> HashMap<PersonId, Vec<PersonId>> {
+        let mut index: HashMap<PersonId, Vec<PersonId>> = HashMap::new();
+        for family in self.families.values() {
+            let family_parents: Vec<PersonId> = family
+                .father
+                .iter()
+                .chain(&family.mother)
+                .filter(|parent| self.persons.contains_key(parent))
+                .copied()
+                .collect();
+            if family_parents.is_empty() {
+                continue;
+            }
+            for child in &family.children {
+                if !self.persons.contains_key(child) {
+                    continue;
+                }
+                let parents = index.entry(*child).or_default();
+                for parent in &family_parents {
+                    if !parents.contains(parent) {
+                        parents.push(*parent);
+                    }
+                }
+            }
+        }
+        for parents in index.values_mut() {
+            parents.sort_by_key(|id| id.0);
+        }
+        index
+    }
+}
+
+fn trashed<T>(record: T, deleted_by: Option<WizardId>) -> Trashed<T> {
+    Trashed {
+        record,
+        deleted_at: Utc::now().naive_utc(),
+        deleted_by,
+    }
+}
+
+/// Ancêtres distincts par génération croissante, au plus `generations` générations.
+fn ancestors_within(
+    index: &HashMap<PersonId, Vec<PersonId>>,
+    person_id: PersonId,
+    generations: Option<u32>,
+) -> Vec<PersonId> {
+    let mut seen = HashSet::new();
+    let mut ancestors = Vec::new();
+    let mut queue = VecDeque::from([(person_id, 0)]);
+    while let Some((current, depth)) = queue.pop_front() {
+        if generations.is_some_and(|limit| depth >= limit) {
+            continue;
+        }
+        for parent in index.get(&current).into_iter().flatten() {
+            if seen.insert(*parent) {
+                ancestors.push(*parent);
+                queue.push_back((*parent, depth + 1));
+            }
+        }
+    }
+    ancestors
+}
+
+/// Personnes dont `ancestor` est un ancêtre, et `ancestor` lui-même.
+fn descendants(index: &HashMap<PersonId, Vec<PersonId>>, ancestor: PersonId) -> HashSet<PersonId> {
+    let mut children: HashMap<PersonId, Vec<PersonId>> = HashMap::new();
+    for (child, parents) in index {
+        for parent in parents {
+            children.entry(*parent).or_default().push(*child);
+        }
+    }
+    let mut seen = HashSet::from([ancestor]);
+    let mut queue = vec![ancestor];
+    while let Some(current) = queue.pop() {
+        for child in children.get(&current).into_iter().flatten() {
+            if seen.insert(*child) {
+                queue.push(*child);
+            }
+        }
+    }
+    seen
+}
+
+/// Chemins ascendants de la fin de `path` jusqu'à `to`, par les seules personnes
+/// de `reaching` (celles qui mènent à `to`).
+fn collect_paths(
+    index: &HashMap<PersonId, Vec<PersonId>>,
+    reaching: &HashSet<PersonId>,
+    path: &mut Vec<PersonId>,
+    to: PersonId,
+    paths: &mut Vec<Vec<PersonId>>,
+) {
+    let current = path[path.len() - 1];
+    for parent in index.get(&current).into_iter().flatten() {
+        if !reaching.contains(parent) || path.contains(parent) {
+            continue;
+        }
+        path.push(*parent);
+        if *parent == to {
+            paths.push(path.clone());
+        } else {
+            collect_paths(index, reaching, path, to, paths);
+        }
+        path.pop();
+    }
+}
+
+impl MemoryPersons {
+    fn update_as(
+        &self,
+        id: PersonId,
+        mut updates: PersonUpdate,
+        action: RevisionAction,
+    ) -> Result<Option<Person>> {
+        let mut state = self.store.write();
+        let Some(entry) = state.persons.get_mut(&id) else {
+            return Ok(None);
+        };
+        if !updates.has_changes() {
+            return Ok(Some(entry.person.clone()));
+        }
+        let before = entry.person.clone();
+        let updated_by = updates.updated_by;
+
+        // Comme en base, un événement remplacé reçoit un nouvel identifiant.
+        if let Some(birth) = updates.birth.take() {
+            entry.birth_id = birth.as_ref().map(|_| Uuid::new_v4());
+            entry.person.birth = birth;
+        }
+        if let Some(death) = updates.death.take() {
+            entry.death_id = death.as_ref().map(|_| Uuid::new_v4());
+            entry.person.death = death;
+        }
+        if let Some(other_events) = updates.other_events.take() {
+            entry.other_ids = other_events.iter().map(|_| Uuid::new_v4()).collect();
+            entry.person.other_events = other_events;
+        }
+        updates.apply(&mut entry.person);
+        entry.person.updated_at = Utc::now().naive_utc();
+        let after = entry.person.clone();
+        state.record(Change::person(
+            id,
+            action,
+            Some(&before),
+            Some(&after),
+            updated_by,
+        )?);
+        Ok(Some(after))
+    }
+}
+
+/// Révision d'une personne dont un événement a changé.
+fn event_change(before: &Person, after: &Person, changed_by: Option<WizardId>) -> Result<Change> {
+    Change::person(
+        before.id,
+        RevisionAction::Update,
+        Some(before),
+        Some(after),
+        changed_by,
+    )
+}
+
+#[async_trait]
+impl PersonStore for MemoryPersons {
+    async fn create(&self, person: &Person) -> Result<PersonId> {
+        let mut state = self.store.write();
+        if state.persons.contains_key(&person.id) || state.trashed_persons.contains_key(&person.id)
+        {
+            return Err(anyhow!("La personne {} existe déjà", person.id.0));
+        }
+        state
+            .persons
+            .insert(person.id, PersonEntry::new(person.clone()));
+        state.record(Change::person(
+            person.id,
+            RevisionAction::Create,
+            None,
+            Some(person),
+            person.updated_by,
+        )?);
+        Ok(person.id)
+    }
+
+    async fn find_by_id(&self, id: PersonId) -> Result<Option<Person>> {
+        Ok(self
+            .store
+            .read()
+            .persons
+            .get(&id)
+            .map(|entry| entry.person.clone()))
+    }
+
+    async fn find_many(&self, ids: &[PersonId]) -> Result<Vec<Person>> {
+        let state = self.store.read();
+        Ok(ids
+            .iter()
+            .filter_map(|id| state.persons.get(id))
+            .map(|entry| entry.person.clone())
+            .collect())
+    }
+
+    async fn search_by_name(&self, surname: &str, first_name: &str) -> Result<Vec<Person>> {
+        let pattern = format!("{surname} {first_name}").to_lowercase();
+        let state = self.store.read();
+        let mut persons: Vec<Person> = state
+            .persons
+            .values()
+            .map(|entry| &entry.person)
+            .filter(|person| {
+                format!("{} {}", person.surname, person.first_name)
+                    .to_lowercase()
+                    .contains(&pattern)
+            })
+            .cloned()
+            .collect();
+        persons.sort_by(|a, b| (&a.surname, &a.first_name).cmp(&(&b.surname, &b.first_name)));
+        persons.truncate(50);
+        Ok(persons)
+    }
+
+    async fn update(&self, id: PersonId, updates: PersonUpdate) -> Result<Option<Person>> {
+        self.update_as(id, updates, RevisionAction::Update)
+    }
+
+    async fn events(&self, person_id: PersonId) -> Result<Vec<PersonEventRecord>> {
+        Ok(self
+            .store
+            .read()
+            .persons
+            .get(&person_id)
+            .map(PersonEntry::records)
+            .unwrap_or_default())
+    }
+
+    async fn add_event(
+        &self,
+        person_id: PersonId,
+        event: LifeEvent,
+        changed_by: Option<WizardId>,
+    ) -> Result<Option<PersonEventRecord>> {
+        let mut state = self.store.write();
+        let Some(entry) = state.persons.get_mut(&person_id) else {
+            return Ok(None);
+        };
+        let before = entry.person.clone();
+        let id = Uuid::new_v4();
+        entry.insert(id, event.clone(), None)?;
+        let change = event_change(&before, &entry.person, changed_by)?;
+        state.record(change);
+        Ok(Some(PersonEventRecord {
+            id,
+            person_id,
+            event,
+        }))
+    }
+
+    async fn update_event(
+        &self,
+        event_id: Uuid,
+        event: LifeEvent,
+        changed_by: Option<WizardId>,
+    ) -> Result<Option<PersonEventRecord>> {
+        let mut state = self.store.write();
+        let Some((entry, slot)) = state.find_event(event_id) else {
+            return Ok(None);
+        };
+        let index = match slot {
+            Slot::Other(index) if event_slot(&event.event_type) == "other" => Some(index),
+            _ => None,
+        };
+        let mut updated = entry.clone();
+        updated.remove(slot);
+        updated.insert(event_id, event.clone(), index)?;
+        let change = event_change(&entry.person, &updated.person, changed_by)?;
+        let person_id = updated.person.id;
+        *entry = updated;
+        state.record(change);
+        Ok(Some(PersonEventRecord {
+            id: event_id,
+            person_id,
+            event,
+        }))
+    }
+
+    async fn delete_event(&self, event_id: Uuid, changed_by: Option<WizardId>) -> Result<bool> {
+        let mut state = self.store.write();
+        let Some((entry, slot)) = state.find_event(event_id) else {
+            return Ok(false);
+        };
+        let before = entry.person.clone();
+        entry.remove(slot);
+        entry.person.updated_at = Utc::now().naive_utc();
+        let change = event_change(&before, &entry.person, changed_by)?;
+        state.record(change);
+        Ok(true)
+    }
+
+    async fn delete(
+        &self,
+        id: PersonId,
+        force: bool,
+        deleted_by: Option<WizardId>,
+    ) -> Result<bool> {
+        let mut state = self.store.write();
+        if !state.persons.contains_key(&id) {
+            return Ok(false);
+        }
+        let parent_of = state
+            .families
+            .values()
+            .filter(|family| family.father == Some(id) || family.mother == Some(id))
+            .count();
+        if parent_of > 0 && !force {
+            return Err(person_is_parent(parent_of as i64));
+        }
+
+        if parent_of > 0 {
+            state.unlink_parent(id, deleted_by)?;
+        }
+        if let Some(entry) = state.persons.remove(&id) {
+            let change = Change::person(
+                id,
+                RevisionAction::Delete,
+                Some(&entry.person),
+                None,
+                deleted_by,
+            )?;
+            state.trashed_persons.insert(id, trashed(entry, deleted_by));
+            state.record(change);
+        }
+        Ok(true)
+    }
+
+    async fn restore(&self, id: PersonId, restored_by: Option<WizardId>) -> Result<bool> {
+        let mut state = self.store.write();
+        let Some(trashed) = state.trashed_persons.remove(&id) else {
+            return Ok(false);
+        };
+        let change = Change::person(
+            id,
+            RevisionAction::Restore,
+            None,
+            Some(&trashed.record.person),
+            restored_by,
+        )?;
+        state.persons.insert(id, trashed.record);
+        state.record(change);
+        state.relink_parent(id, restored_by)?;
+        Ok(true)
+    }
+
+    async fn trash(&self) -> Result<Vec<Trashed<Person>>> {
+        let state = self.store.read();
+        let mut trash: Vec<Trashed<Person>> = state
+            .trashed_persons
+            .values()
+            .map(|trashed| Trashed {
+                record: trashed.record.person.clone(),
+                deleted_at: trashed.deleted_at,
+                deleted_by: trashed.deleted_by,
+            })
+            .collect();
+        trash.sort_by_key(|trashed| Reverse(trashed.deleted_at));
+        Ok(trash)
+    }
+
+    async fn purge_trash(&self, before: NaiveDateTime) -> Result<u64> {
+        let mut state = self.store.write();
+        let expired: HashSet<PersonId> = state
+            .trashed_persons
+            .iter()
+            .filter(|(_, trashed)| trashed.deleted_at < before)
+            .map(|(id, _)| *id)
+            .collect();
+        if expired.is_empty() {
+            return Ok(0);
+        }
+
+        let now = Utc::now().naive_utc();
+        let MemoryState {
+            families,
+            trashed_persons,
+            trashed_families,
+            trashed_parents,
+            ..
+        } = &mut *state;
+        let all_families = families.values_mut().chain(
+            trashed_families
+                .values_mut()
+                .map(|trashed| &mut trashed.record),
+        );
+        let mut touched = Vec::new();
+        let mut after = Vec::new();
+        for family in all_families {
+            let is_expired = |person: &PersonId| expired.contains(person);
+            let is_parent = family.father.as_ref().is_some_and(is_expired)
+                || family.mother.as_ref().is_some_and(is_expired);
+            if !is_parent && !family.children.iter().any(is_expired) {
+                continue;
+            }
+            touched.push(family.clone());
+            if is_parent {
+                family.father = family.father.filter(|father| !expired.contains(father));
+                family.mother = family.mother.filter(|mother| !expired.contains(mother));
+                family.updated_at = now;
+            }
+            family.children.retain(|child| !expired.contains(child));
+            after.push(family.clone());
+        }
+        trashed_persons.retain(|id, _| !expired.contains(id));
+        trashed_parents.retain(|id, _| !expired.contains(id));
+        for id in &expired {
+            state.record(Change::purge(RevisionEntity::Person, id.0));
+        }
+        for change in family_updates(touched, &after, None)? {
+            state.record(change);
+        }
+        Ok(expired.len() as u64)
+    }
+
+    async fn history(&self, id: PersonId) -> Result<Vec<Revision>> {
+        Ok(self.store.read().history(RevisionEntity::Person, id.0))
+    }
+
+    async fn revert(
+        &self,
+        id: PersonId,
+        revision: i64,
+        reverted_by: Option<WizardId>,
+    ) -> Result<Option<Person>> {
+        let revision = self
+            .store
+            .read()
+            .revision(RevisionEntity::Person, id.0, revision)?;
+        let updates = PersonUpdate::reverting_to(revision.snapshot()?, reverted_by);
+        self.update_as(id, updates, RevisionAction::Revert)
+    }
+}
+
+impl MemoryFamilies {
+    fn update_as(
+        &self,
+        id: FamilyId,
+        changes: FamilyChanges,
+        action: RevisionAction,
+    ) -> Result<Option<Family>> {
+        let mut state = self.store.write();
+        let Some(family) = state.families.get_mut(&id) else {
+            return Ok(None);
+        };
+        if !changes.has_changes() {
+            return Ok(Some(family.clone()));
+        }
+        let before = family.clone();
+        let updated_by = changes.updated_by;
+
+        // Seuls les champs de `families` mettent à jour `updated_at`, comme en base.
+        let touched = changes.touches_family();
+        changes.apply(family);
+        if touched {
+            family.updated_at = Utc::now().naive_utc();
+        }
+        let after = family.clone();
+        state.record(Change::family(
+            id,
+            action,
+            Some(&before),
+            Some(&after),
+            updated_by,
+        )?);
+        Ok(Some(after))
+    }
+
+    /// Modifie la famille en place et enregistre la révision.
+    fn change(
+        &self,
+        id: FamilyId,
+        changed_by: Option<WizardId>,
+        modify: impl FnOnce(&mut Family),
+    ) -> Result<Family> {
+        let mut state = self.store.write();
+        let family = state
+            .families
+            .get_mut(&id)
+            .ok_or_else(|| anyhow!("Family not found"))?;
+        let before = family.clone();
+        modify(family);
+        let after = family.clone();
+        state.record(Change::family(
+            id,
+            RevisionAction::Update,
+            Some(&before),
+            Some(&after),
+            changed_by,
+        )?);
+        Ok(after)
+    }
+}
+
+#[async_trait]
+impl FamilyStore for MemoryFamilies {
+    async fn create(&self, draft: FamilyDraft) -> Result<Family> {
+        let mut state = self.store.write();
+        if state.families.contains_key(&draft.id) || state.trashed_families.contains_key(&draft.id)
+        {
+            return Err(anyhow!("La famille {} existe déjà", draft.id.0));
+        }
+        let now = Utc::now().naive_utc();
+        let family = Family {
+            id: draft.id,
+            father: draft.father_id,
+            mother: draft.mother_id,
+            children: draft.children,
+            marriage: draft.marriage,
+            divorce: draft.divorce,
+            other_events: draft.other_events,
+            notes: draft.notes,
+            sources: draft.sources,
+            public: draft.public,
+            created_at: now,
+            updated_at: now,
+        };
+        state.families.insert(family.id, family.clone());
+        state.record(Change::family(
+            family.id,
+            RevisionAction::Create,
+            None,
+            Some(&family),
+            draft.created_by,
+        )?);
+        Ok(family)
+    }
+
+    async fn find_by_id(&self, id: FamilyId) -> Result<Option<Family>> {
+        Ok(self.store.read().families.get(&id).cloned())
+    }
+
+    async fn update(&self, id: FamilyId, changes: FamilyChanges) -> Result<Option<Family>> {
+        self.update_as(id, changes, RevisionAction::Update)
+    }
+
+    async fn append_child(
+        &self,
+        id: FamilyId,
+        child: PersonId,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
+        self.change(id, changed_by, |family| {
+            if !family.children.contains(&child) {
+                family.children.push(child);
+            }
+        })
+    }
+
+    async fn remove_child(
+        &self,
+        id: FamilyId,
+        child: PersonId,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
+        self.change(id, changed_by, |family| {
+            family.children.retain(|c| *c != child)
+        })
+    }
+
+    async fn set_privacy(
+        &self,
+        id: FamilyId,
+        public: bool,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
+        self.update(
+            id,
+            FamilyChanges {
+                public: Some(public),
+                updated_by: changed_by,
+                ..Default::default()
+            },
+        )
+        .await?
+        .ok_or_else(|| anyhow!("Family not found after update"))
+    }
+
+    async fn add_event(&self, event: FamilyEventNew) -> Result<FamilyEventRecord> {
+        let life_event = LifeEvent {
+            event_type: EventType::from_name(&event.event_type),
+            date: event.date.clone().unwrap_or(PreciseDate::Unknown),
+            place: None,
+            witnesses: Vec::new(),
+            notes: event.notes.clone(),
+            sources: Vec::new(),
+        };
+        let slot = family_event_slot(&life_event.event_type);
+        if let Some(family) = self.store.read().families.get(&event.family_id) {
+            let taken = match slot {
+                "marriage" => family.marriage.is_some(),
+                "divorce" => family.divorce.is_some(),
+                _ => false,
+            };
+            if taken {
+                return Err(family_slot_taken(slot));
+            }
+        }
+        self.change(event.family_id, event.created_by, |family| match slot {
+            "marriage" => family.marriage = Some(life_event),
+            "divorce" => family.divorce = Some(life_event),
+            _ => family.other_events.push(life_event),
+        })?;
+        Ok(FamilyEventRecord {
+            id: Uuid::new_v4(),
+            family_id: event.family_id,
+            event_type: event.event_type,
+            date: event.date,
+            notes: event.notes,
+        })
+    }
+
+    async fn delete(
+        &self,
+        id: FamilyId,
+        force: bool,
+        deleted_by: Option<WizardId>,
+    ) -> Result<bool> {
+        let mut state = self.store.write();
+        let Some(family) = state.families.get(&id) else {
+            return Ok(false);
+        };
+        if !family.children.is_empty() && !force {
+            return Err(family_has_children(family.children.len() as i64));
+        }
+        if let Some(family) = state.families.remove(&id) {
+            let change =
+                Change::family(id, RevisionAction::Delete, Some(&family), None, deleted_by)?;
+            state
+                .trashed_families
+                .insert(id, trashed(family, deleted_by));
+            state.record(change);
+        }
+        Ok(true)
+    }
+
+    async fn restore(&self, id: FamilyId, restored_by: Option<WizardId>) -> Result<bool> {
+        let mut state = self.store.write();
+        let Some(trashed) = state.trashed_families.remove(&id) else {
+            return Ok(false);
+        };
+        let change = Change::family(
+            id,
+            RevisionAction::Restore,
+            None,
+            Some(&trashed.record),
+            restored_by,
+        )?;
+        state.families.insert(id, trashed.record);
+        state.record(change);
+        Ok(true)
+    }
+
+    async fn trash(&self) -> Result<Vec<Trashed<Family>>> {
+        let mut trash: Vec<Trashed<Family>> = self
+            .store
+            .read()
+            .trashed_families
+            .values()
+            .cloned()
+            .collect();
+        trash.sort_by_key(|trashed| Reverse(trashed.deleted_at));
+        Ok(trash)
+    }
+
+    async fn purge_trash(&self, before: NaiveDateTime) -> Result<u64> {
+        let mut state = self.store.write();
+        let expired: HashSet<FamilyId> = state
+            .trashed_families
+            .iter()
+            .filter(|(_, trashed)| trashed.deleted_at < before)
+            .map(|(id, _)| *id)
+            .collect();
+        state.trashed_families.retain(|id, _| !expired.contains(id));
+        for id in &expired {
+            state.record(Change::purge(RevisionEntity::Family, id.0));
+        }
+        Ok(expired.len() as u64)
+    }
+
+    async fn history(&self, id: FamilyId) -> Result<Vec<Revision>> {
+        Ok(self.store.read().history(RevisionEntity::Family, id.0))
+    }
+
+    async fn revert(
+        &self,
+        id: FamilyId,
+        revision: i64,
+        reverted_by: Option<WizardId>,
+    ) -> Result<Option<Family>> {
+        let revision = self
+            .store
+            .read()
+            .revision(RevisionEntity::Family, id.0, revision)?;
+        let changes = FamilyChanges::reverting_to(revision.snapshot()?, reverted_by);
+        self.update_as(id, changes, RevisionAction::Revert)
+    }
+}
+
+#[async_trait]
+impl GraphStore for MemoryGraph {
+    async fn parents(&self, person_id: PersonId) -> Result<Vec<PersonId>> {
+        Ok(self
+            .store
+            .read()
+            .parent_index()
+            .remove(&person_id)
+            .unwrap_or_default())
+    }
+
+    async fn ancestors(&self, person_id: PersonId, generations: u32) -> Result<Vec<PersonId>> {
+        let index = self.store.read().parent_index();
+        Ok(ancestors_within(&index, person_id, Some(generations)))
+    }
+
+    async fn common_ancestors(&self, first: PersonId, second: PersonId) -> Result<Vec<PersonId>> {
+        let index = self.store.read().parent_index();
+        let others: HashSet<PersonId> =
+            ancestors_within(&index, second, None).into_iter().collect();
+        Ok(ancestors_within(&index, first, None)
+            .into_iter()
+            .filter(|ancestor| others.contains(ancestor))
+            .collect())
+    }
+
+    async fn paths(&self, from: PersonId, to: PersonId) -> Result<Vec<Vec<PersonId>>> {
+        let index = self.store.read().parent_index();
+        let reaching = descendants(&index, to);
+        let mut paths = Vec::new();
+        collect_paths(&index, &reaching, &mut vec![from], to, &mut paths);
+        Ok(paths)
+    }
+
+    async fn distance(&self, first: PersonId, second: PersonId) -> Result<Option<u32>> {
+        let index = self.store.read().parent_index();
+        let mut neighbours: HashMap<PersonId, Vec<PersonId>> = HashMap::new();
+        for (child, parents) in &index {
+            for parent in parents {
+                neighbours.entry(*child).or_default().push(*parent);
+                neighbours.entry(*parent).or_default().push(*child);
+            }
+        }
+
+        let mut seen = HashSet::from([first]);
+        let mut queue = VecDeque::from([(first, 0)]);
+        while let Some((current, distance)) = queue.pop_front() {
+            if current == second {
+                return Ok(Some(distance));
+            }
+            for next in neighbours.get(&current).into_iter().flatten() {
+                if seen.insert(*next) {
+                    queue.push_back((*next, distance + 1));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,259 @@
+use anyhow::Result;
+use genealogy_types::PersonId;
+use neo4rs::query;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::graph::{edge_query, DELETE_EDGES, MERGE_EDGES};
+use crate::DatabasePool;
+
+/// Effet de bord à appliquer hors de PostgreSQL. Chacun est idempotent, ce qui
+/// permet de le rejouer sans risque après un échec ou un arrêt du dispatcher ; ils
+/// ne commutent pas (un `MERGE` puis un `DELETE` de la même arête n'équivaut pas à
+/// l'inverse) et s'appliquent donc dans l'ordre de la table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OutboxEvent {
+    /// Nœuds `Person` de Neo4j.
+    MergePersons { ids: Vec<PersonId> },
+    /// Nœuds `Person` à supprimer avec toutes leurs arêtes.
+    DeletePersons { ids: Vec<PersonId> },
+    /// Arêtes `(enfant)-[:CHILD_OF]->(parent)` à créer.
+    MergeEdges { edges: Vec<(PersonId, PersonId)> },
+    /// Arêtes `(enfant)-[:CHILD_OF]->(parent)` à supprimer.
+    DeleteEdges { edges: Vec<(PersonId, PersonId)> },
+    /// Entrées `person:{uuid}` du cache Redis.
+    InvalidatePersons { ids: Vec<PersonId> },
+}
+
+/// Résultat d'un passage du dispatcher.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DispatchReport {
+    pub delivered: usize,
+    pub failed: usize,
+}
+
+/// Enregistre l'événement dans la transaction en cours ; renvoie son identifiant.
+pub(crate) async fn enqueue(conn: &mut PgConnection, event: &OutboxEvent) -> Result<i64> {
+    let id = sqlx::query_scalar("INSERT INTO outbox (event) VALUES ($1::jsonb) RETURNING id")
+        .bind(serde_json::to_string(event)?)
+        .fetch_one(conn)
+        .await?;
+    Ok(id)
+}
+
+/// Applique tout de suite les événements d'une transaction validée, après ceux
+/// encore en attente. Un échec n'est pas remonté : l'événement reste dans `outbox`
+/// et le dispatcher le rejouera.
+pub(crate) async fn deliver_now(pool: &DatabasePool, ids: &[i64]) {
+    if ids.is_empty() {
+        return;
+    }
+    let _ = OutboxDispatcher::new(pool.clone()).deliver(ids).await;
+}
+
+/// Tentatives par défaut d'un événement avant qu'il soit abandonné.
+pub(crate) const MAX_ATTEMPTS: i32 = 20;
+
+/// Applique les événements de la table `outbox`, au moins une fois chacun et
+/// strictement dans l'ordre de leurs identifiants : le premier échec arrête le
+/// passage, et un événement reporté bloque les suivants jusqu'à son nouvel essai.
+///
+/// Un passage réserve d'abord son lot (`claimed_until`) dans une courte
+/// transaction, puis appelle Neo4j et Redis sans verrou ni transaction ouverte.
+pub struct OutboxDispatcher {
+    pub pool: DatabasePool,
+    /// Événements traités par passage.
+    pub batch_size: i64,
+    /// Au-delà, l'événement reste en table pour inspection, n'est plus retenté et
+    /// ne bloque plus les suivants.
+    pub max_attempts: i32,
+    /// Durée de la réservation d'un lot, en secondes : le lot d'un dispatcher
+    /// arrêté en cours de route revient ensuite aux autres.
+    pub lease_secs: i32,
+}
+
+impl OutboxDispatcher {
+    pub fn new(pool: DatabasePool) -> Self {
+        Self {
+            pool,
+            batch_size: 100,
+            max_attempts: MAX_ATTEMPTS,
+            lease_secs: 120,
+        }
+    }
+
+    /// Traite un lot d'événements en attente, du plus ancien au plus récent.
+    pub async fn dispatch_pending(&self) -> Result<DispatchReport> {
+        self.process(None).await
+    }
+
+    /// Traite ces événements s'ils sont encore en attente, avec ceux qui les précèdent.
+    pub async fn deliver(&self, ids: &[i64]) -> Result<DispatchReport> {
+        self.process(ids.iter().max().copied()).await
+    }
+
+    /// Supprime les événements appliqués depuis plus de `days` jours.
+    pub async fn purge_processed(&self, days: i32) -> Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM outbox WHERE processed_at < NOW() - make_interval(days => $1)",
+        )
+        .bind(days)
+        .execute(&self.pool.postgres)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Événements en attente jusqu'à `up_to` compris, dans l'ordre.
+    async fn process(&self, up_to: Option<i64>) -> Result<DispatchReport> {
+        let claimed = self.claim(up_to).await?;
+        let postgres = &self.pool.postgres;
+
+        let mut report = DispatchReport::default();
+        let mut remaining = claimed.into_iter();
+        for (id, event, attempts) in remaining.by_ref() {
+            let outcome = match serde_json::from_str(&event) {
+                Ok(event) => self.apply(&event).await,
+                Err(err) => Err(err.into()),
+            };
+            match outcome {
+                Ok(()) => {
+                    sqlx::query(
+                        r#"
+                        UPDATE outbox
+                        SET processed_at = NOW(), attempts = attempts + 1, last_error = NULL,
+                            claimed_until = NULL
+                        WHERE id = $1
+                        "#,
+                    )
+                    .bind(id)
+                    .execute(postgres)
+                    .await?;
+                    report.delivered += 1;
+                }
+                Err(err) => {
+                    // Attente doublée à chaque échec, plafonnée à une heure environ.
+                    let backoff = 1_i32 << attempts.clamp(0, 12);
+                    sqlx::query(
+                        r#"
+                        UPDATE outbox
+                        SET attempts = attempts + 1,
+                            last_error = $2,
+                            next_attempt_at = NOW() + make_interval(secs => $3),
+                            claimed_until = NULL
+                        WHERE id = $1
+                        "#,
+                    )
+                    .bind(id)
+                    .bind(format!("{err:#}"))
+                    .bind(f64::from(backoff))
+                    .execute(postgres)
+                    .await?;
+                    report.failed += 1;
+                    break;
+                }
+            }
+        }
+
+        let released: Vec<i64> = remaining.map(|(id, ..)| id).collect();
+        if !released.is_empty() {
+            sqlx::query("UPDATE outbox SET claimed_until = NULL WHERE id = ANY($1)")
+                .bind(released)
+                .execute(postgres)
+                .await?;
+        }
+        Ok(report)
+    }
+
+    /// Réserve le début de la file : les événements dus qui précèdent le premier
+    /// reporté ou déjà réservé par un autre dispatcher.
+    async fn claim(&self, up_to: Option<i64>) -> Result<Vec<(i64, String, i32)>> {
+        let mut tx = self.pool.postgres.begin().await?;
+        // Sans `SKIP LOCKED` : un dispatcher concurrent attend la fin de cette
+        // réservation, puis voit les lignes réservées et s'arrête avant elles.
+        let rows: Vec<(i64, String, i32, bool)> = sqlx::query_as(
+            r#"
+            SELECT id, event::text, attempts,
+                   next_attempt_at <= NOW() AND COALESCE(claimed_until <= NOW(), TRUE)
+            FROM outbox
+            WHERE processed_at IS NULL
+              AND attempts < $1
+              AND ($2::bigint IS NULL OR id <= $2)
+            ORDER BY id
+            LIMIT $3
+            FOR UPDATE
+            "#,
+        )
+        .bind(self.max_attempts)
+        .bind(up_to)
+        .bind(self.batch_size)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let claimed: Vec<(i64, String, i32)> = rows
+            .into_iter()
+            .take_while(|(.., ready)| *ready)
+            .map(|(id, event, attempts, _)| (id, event, attempts))
+            .collect();
+        let ids: Vec<i64> = claimed.iter().map(|(id, ..)| *id).collect();
+        sqlx::query(
+            "UPDATE outbox SET claimed_until = NOW() + make_interval(secs => $2) WHERE id = ANY($1)",
+        )
+        .bind(ids)
+        .bind(f64::from(self.lease_secs))
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(claimed)
+    }
+
+    async fn apply(&self, event: &OutboxEvent) -> Result<()> {
+        match event {
+            OutboxEvent::MergePersons { ids } => {
+                let ids: Vec<String> = ids.iter().map(|id| id.0.to_string()).collect();
+                self.pool
+                    .run_cypher(
+                        query("UNWIND $ids AS id MERGE (:Person {id: id})").param("ids", ids),
+                    )
+                    .await
+            }
+            OutboxEvent::DeletePersons { ids } => {
+                let ids: Vec<String> = ids.iter().map(|id| id.0.to_string()).collect();
+                self.pool
+                    .run_cypher(
+                        query("UNWIND $ids AS id MATCH (p:Person {id: id}) DETACH DELETE p")
+                            .param("ids", ids),
+                    )
+                    .await
+            }
+            OutboxEvent::MergeEdges { edges } => {
+                self.pool
+                    .run_cypher(edge_query(MERGE_EDGES, &uuid_edges(edges)))
+                    .await
+            }
+            OutboxEvent::DeleteEdges { edges } => {
+                self.pool
+                    .run_cypher(edge_query(DELETE_EDGES, &uuid_edges(edges)))
+                    .await
+            }
+            OutboxEvent::InvalidatePersons { ids } => {
+                if ids.is_empty() {
+                    return Ok(());
+                }
+                let keys: Vec<String> = ids.iter().map(|id| format!("person:{}", id.0)).collect();
+                let mut conn = self.pool.redis.clone();
+                conn.del::<_, ()>(keys).await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+fn uuid_edges(edges: &[(PersonId, PersonId)]) -> Vec<(Uuid, Uuid)> {
+    edges
+        .iter()
+        .map(|(child, parent)| (child.0, parent.0))
+        .collect()
+}
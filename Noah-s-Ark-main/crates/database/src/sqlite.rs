@@ -0,0 +1,1903 @@
+//! Backend SQLite pour les bases familiales et la CI : un seul fichier, sans
+//! Neo4j (le graphe est parcouru par des CTE récursives) et avec un cache Redis
+//! facultatif.
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use genealogy_types::{
+    EventType, Family, FamilyId, LifeEvent, Person, PersonId, PreciseDate, WizardId,
+};
+use redis::{aio::ConnectionManager, AsyncCommands};
+use serde::de::DeserializeOwned;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteRow};
+use sqlx::{Executor, Row, SqliteConnection, SqlitePool};
+use uuid::fmt::Hyphenated;
+use uuid::Uuid;
+
+use crate::aggregate::{self, EventRow, Owner, StoredEvent};
+use crate::history::{self, revision_not_found, Change};
+use crate::{
+    event_slot, family_event_slot, family_has_children, family_slot_taken, family_updates,
+    in_timeline_order, parse_sex, person_is_parent, slot_taken, DateColumns, FamilyChanges,
+    FamilyDraft, FamilyEventNew, FamilyEventRecord, FamilyStore, GraphStore, PersonEventRecord,
+    PersonStore, PersonUpdate, Revision, RevisionAction, RevisionEntity, Storage, Trashed,
+};
+
+const SCHEMA: &str = include_str!("../sqlite/schema.sql");
+
+/// Colonnes apparues après la création de bases existantes, ajoutées à l'ouverture.
+const ADDED_COLUMNS: &[(&str, &str)] = &[
+    ("persons", "deleted_at"),
+    ("persons", "deleted_by"),
+    ("families", "deleted_at"),
+    ("families", "deleted_by"),
+];
+
+const PERSON_COLUMNS: &str = "id, first_name, surname, surname_prefix, nicknames, sex, \
+     occupations, titles, sources, images, public, notes, created_at, updated_at, updated_by";
+
+const FAMILY_COLUMNS: &str =
+    "id, father_id, mother_id, notes, sources, public, created_at, updated_at";
+
+/// Base SQLite (`sqlite://chemin.db`, `sqlite::memory:`), avec cache Redis facultatif.
+#[derive(Clone)]
+pub struct SqliteStore {
+    pub pool: SqlitePool,
+    pub redis: Option<ConnectionManager>,
+}
+
+/// Personnes d'un [`SqliteStore`].
+#[derive(Clone)]
+pub struct SqlitePersons {
+    store: SqliteStore,
+}
+
+/// Familles d'un [`SqliteStore`].
+#[derive(Clone)]
+pub struct SqliteFamilies {
+    store: SqliteStore,
+}
+
+/// Graphe de filiation d'un [`SqliteStore`], lu dans la vue `child_of`.
+#[derive(Clone)]
+pub struct SqliteGraph {
+    store: SqliteStore,
+}
+
+impl SqliteStore {
+    /// Ouvre la base, la crée au besoin et applique `sqlite/schema.sql`.
+    pub async fn connect(url: &str, redis_url: Option<&str>) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(url)?
+            .create_if_missing(true)
+            .foreign_keys(true);
+        // Une base `:memory:` disparaît avec sa dernière connexion : on en garde une ouverte.
+        let pool = SqlitePoolOptions::new()
+            .max_connections(8)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await?;
+        // Avant le schéma, dont les index et la vue lisent ces colonnes.
+        add_missing_columns(&pool).await?;
+        pool.execute(SCHEMA).await?;
+
+        let redis = match redis_url {
+            Some(url) => Some(ConnectionManager::new(redis::Client::open(url)?).await?),
+            None => None,
+        };
+        Ok(Self { pool, redis })
+    }
+
+    async fn cached(&self, id: PersonId) -> Result<Option<Person>> {
+        let Some(mut conn) = self.redis.clone() else {
+            return Ok(None);
+        };
+        let cached: Option<String> = conn.get(format!("person:{}", id.0)).await?;
+        Ok(cached.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    async fn cache(&self, person: &Person) -> Result<()> {
+        if let Some(mut conn) = self.redis.clone() {
+            let json = serde_json::to_string(person)?;
+            conn.set_ex::<_, _, ()>(format!("person:{}", person.id.0), json, 3600)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn uncache(&self, id: PersonId) -> Result<()> {
+        if let Some(mut conn) = self.redis.clone() {
+            conn.del::<_, ()>(format!("person:{}", id.0)).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Storage for SqliteStore {
+    type Persons = SqlitePersons;
+    type Families = SqliteFamilies;
+    type Graph = SqliteGraph;
+
+    fn persons(&self) -> SqlitePersons {
+        SqlitePersons {
+            store: self.clone(),
+        }
+    }
+
+    fn families(&self) -> SqliteFamilies {
+        SqliteFamilies {
+            store: self.clone(),
+        }
+    }
+
+    fn graph(&self) -> SqliteGraph {
+        SqliteGraph {
+            store: self.clone(),
+        }
+    }
+}
+
+/// Complète les tables déjà créées avec les colonnes de [`ADDED_COLUMNS`].
+async fn add_missing_columns(pool: &SqlitePool) -> Result<()> {
+    for (table, column) in ADDED_COLUMNS {
+        let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+            .bind(table)
+            .fetch_all(pool)
+            .await?;
+        if !columns.is_empty() && !columns.iter().any(|name| name == column) {
+            pool.execute(format!("ALTER TABLE {table} ADD COLUMN {column} TEXT").as_str())
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+fn uuid(row: &SqliteRow, column: &str) -> Uuid {
+    row.get::<Hyphenated, _>(column).into_uuid()
+}
+
+fn optional_uuid(row: &SqliteRow, column: &str) -> Option<Uuid> {
+    row.get::<Option<Hyphenated>, _>(column)
+        .map(Hyphenated::into_uuid)
+}
+
+fn json<T: DeserializeOwned>(row: &SqliteRow, column: &str) -> Result<T> {
+    Ok(serde_json::from_str(row.get(column))?)
+}
+
+/// Liste d'identifiants à passer à `json_each(?)`, SQLite n'ayant pas de tableaux.
+fn id_list(ids: impl IntoIterator<Item = Uuid>) -> Result<String> {
+    Ok(serde_json::to_string(&ids.into_iter().collect::<Vec<_>>())?)
+}
+
+fn now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+fn row_to_person(row: &SqliteRow) -> Result<Person> {
+    Ok(Person {
+        id: PersonId(uuid(row, "id")),
+        first_name: row.get("first_name"),
+        surname: row.get("surname"),
+        surname_prefix: row.get("surname_prefix"),
+        nicknames: json(row, "nicknames")?,
+        sex: parse_sex(row.get("sex")),
+        birth: None,
+        death: None,
+        other_events: Vec::new(),
+        occupation: json(row, "occupations")?,
+        titles: json(row, "titles")?,
+        notes: row.get("notes"),
+        sources: json(row, "sources")?,
+        images: json(row, "images")?,
+        public: row.get("public"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        updated_by: optional_uuid(row, "updated_by").map(WizardId),
+    })
+}
+
+fn row_to_trashed<T>(row: &SqliteRow, record: T) -> Trashed<T> {
+    Trashed {
+        record,
+        deleted_at: row.get("deleted_at"),
+        deleted_by: optional_uuid(row, "deleted_by").map(WizardId),
+    }
+}
+
+fn row_to_family(row: &SqliteRow) -> Result<Family> {
+    Ok(Family {
+        id: FamilyId(uuid(row, "id")),
+        father: optional_uuid(row, "father_id").map(PersonId),
+        mother: optional_uuid(row, "mother_id").map(PersonId),
+        children: Vec::new(),
+        marriage: None,
+        divorce: None,
+        other_events: Vec::new(),
+        notes: row.get("notes"),
+        sources: json(row, "sources")?,
+        public: row.get("public"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+async fn insert_events(
+    conn: &mut SqliteConnection,
+    owner: Owner,
+    rows: &[EventRow<'_>],
+) -> Result<()> {
+    let sql = format!(
+        "INSERT INTO events (id, {}, slot, position, event_type, date_value, date_value_end, \
+         date_json, place, witnesses, notes, sources) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        owner.column()
+    );
+    for row in rows {
+        let event = row.event;
+        let dates = DateColumns::new(Some(&event.date))?;
+        sqlx::query(&sql)
+            .bind(row.id.hyphenated())
+            .bind(row.owner_id.hyphenated())
+            .bind(row.slot)
+            .bind(row.position)
+            .bind(event.event_type.name())
+            .bind(dates.value)
+            .bind(dates.value_end)
+            .bind(dates.json)
+            .bind(
+                event
+                    .place
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+            )
+            .bind(serde_json::to_string(&event.witnesses)?)
+            .bind(event.notes.as_deref())
+            .bind(serde_json::to_string(&event.sources)?)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Événements de ces propriétaires, dans l'ordre de `position`.
+async fn load_events(
+    conn: &mut SqliteConnection,
+    owner: Owner,
+    ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<StoredEvent>>> {
+    let rows = sqlx::query(&format!(
+        "SELECT id, {column} AS owner_id, slot, event_type, date_value, date_json, place, \
+         witnesses, notes, sources \
+         FROM events \
+         WHERE {column} IN (SELECT value FROM json_each(?)) \
+         ORDER BY position, rowid",
+        column = owner.column()
+    ))
+    .bind(id_list(ids.iter().copied())?)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut events: HashMap<Uuid, Vec<StoredEvent>> = HashMap::new();
+    for row in &rows {
+        let place: Option<String> = row.get("place");
+        let event = LifeEvent {
+            event_type: EventType::from_name(row.get("event_type")),
+            date: DateColumns::decode(row.get("date_json"), row.get("date_value"))?
+                .unwrap_or(PreciseDate::Unknown),
+            place: place
+                .map(|place| serde_json::from_str(&place))
+                .transpose()?,
+            witnesses: json(row, "witnesses")?,
+            notes: row.get("notes"),
+            sources: json(row, "sources")?,
+        };
+        events
+            .entry(uuid(row, "owner_id"))
+            .or_default()
+            .push(StoredEvent {
+                id: uuid(row, "id"),
+                slot: row.get("slot"),
+                event,
+            });
+    }
+    Ok(events)
+}
+
+async fn delete_events(conn: &mut SqliteConnection, owner: Owner, id: Uuid) -> Result<()> {
+    sqlx::query(&format!("DELETE FROM events WHERE {} = ?", owner.column()))
+        .bind(id.hyphenated())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+fn person_event_rows(person: &Person) -> Vec<EventRow<'_>> {
+    aggregate::event_rows(
+        person.id.0,
+        [
+            ("birth", person.birth.as_ref()),
+            ("death", person.death.as_ref()),
+        ],
+        &person.other_events,
+    )
+}
+
+fn family_event_rows(family: &Family) -> Vec<EventRow<'_>> {
+    aggregate::event_rows(
+        family.id.0,
+        [
+            ("marriage", family.marriage.as_ref()),
+            ("divorce", family.divorce.as_ref()),
+        ],
+        &family.other_events,
+    )
+}
+
+/// Écrit toutes les colonnes de `persons` ; `replace` remplace une ligne existante.
+async fn write_person(conn: &mut SqliteConnection, person: &Person, replace: bool) -> Result<()> {
+    // `REPLACE` supprimerait la ligne et, en cascade, ses liens familiaux et ses événements.
+    let sql = if replace {
+        "UPDATE persons SET first_name = ?2, surname = ?3, surname_prefix = ?4, \
+         nicknames = ?5, sex = ?6, occupations = ?7, titles = ?8, sources = ?9, images = ?10, \
+         public = ?11, notes = ?12, created_at = ?13, updated_at = ?14, updated_by = ?15 \
+         WHERE id = ?1"
+            .to_string()
+    } else {
+        format!(
+            "INSERT INTO persons ({PERSON_COLUMNS}) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)"
+        )
+    };
+    sqlx::query(&sql)
+        .bind(person.id.0.hyphenated())
+        .bind(&person.first_name)
+        .bind(&person.surname)
+        .bind(person.surname_prefix.as_deref())
+        .bind(serde_json::to_string(&person.nicknames)?)
+        .bind(format!("{:?}", person.sex))
+        .bind(serde_json::to_string(&person.occupation)?)
+        .bind(serde_json::to_string(&person.titles)?)
+        .bind(serde_json::to_string(&person.sources)?)
+        .bind(serde_json::to_string(&person.images)?)
+        .bind(person.public)
+        .bind(person.notes.as_deref())
+        .bind(person.created_at)
+        .bind(person.updated_at)
+        .bind(person.updated_by.map(|wizard| wizard.0.hyphenated()))
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Personnes et leurs événements ; `filter` complète `SELECT … FROM persons`.
+async fn select_persons(
+    conn: &mut SqliteConnection,
+    filter: &str,
+    bind: Vec<String>,
+) -> Result<Vec<Person>> {
+    let sql = format!("SELECT {PERSON_COLUMNS} FROM persons {filter}");
+    let mut query = sqlx::query(&sql);
+    for value in bind {
+        query = query.bind(value);
+    }
+    let rows = query.fetch_all(&mut *conn).await?;
+    let mut persons = rows.iter().map(row_to_person).collect::<Result<Vec<_>>>()?;
+
+    let ids: Vec<Uuid> = persons.iter().map(|person| person.id.0).collect();
+    let mut events = load_events(conn, Owner::Person, &ids).await?;
+    for person in &mut persons {
+        for stored in events.remove(&person.id.0).unwrap_or_default() {
+            match stored.slot.as_str() {
+                "birth" => person.birth = Some(stored.event),
+                "death" => person.death = Some(stored.event),
+                _ => person.other_events.push(stored.event),
+            }
+        }
+    }
+    Ok(persons)
+}
+
+async fn find_person(conn: &mut SqliteConnection, id: PersonId) -> Result<Option<Person>> {
+    let persons = select_persons(
+        conn,
+        "WHERE id = ? AND deleted_at IS NULL",
+        vec![id.0.to_string()],
+    )
+    .await?;
+    Ok(persons.into_iter().next())
+}
+
+/// Position libre pour `slot`, comme pour PostgreSQL.
+async fn free_position(
+    conn: &mut SqliteConnection,
+    person_id: PersonId,
+    slot: &str,
+    ignored: Option<Uuid>,
+) -> Result<i32> {
+    let (count, next): (i64, Option<i32>) = sqlx::query_as(
+        "SELECT COUNT(*), MAX(position) + 1 FROM events \
+         WHERE person_id = ? AND slot = ? AND id IS NOT ?",
+    )
+    .bind(person_id.0.hyphenated())
+    .bind(slot)
+    .bind(ignored.map(Uuid::hyphenated))
+    .fetch_one(&mut *conn)
+    .await?;
+    match slot {
+        "other" => Ok(next.unwrap_or(0)),
+        _ if count > 0 => Err(slot_taken(slot)),
+        _ => Ok(0),
+    }
+}
+
+/// Propriétaire, emplacement et position d'un événement de personne hors corbeille.
+async fn find_event(
+    conn: &mut SqliteConnection,
+    event_id: Uuid,
+) -> Result<Option<(PersonId, String, i32)>> {
+    let row = sqlx::query(
+        "SELECT e.person_id, e.slot, e.position FROM events e \
+         JOIN persons p ON p.id = e.person_id \
+         WHERE e.id = ? AND p.deleted_at IS NULL",
+    )
+    .bind(event_id.hyphenated())
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(row.map(|row| {
+        (
+            PersonId(uuid(&row, "person_id")),
+            row.get("slot"),
+            row.get("position"),
+        )
+    }))
+}
+
+/// Enregistre la révision dans la transaction en cours.
+async fn record(conn: &mut SqliteConnection, change: &Change) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO revisions (entity_type, entity_id, action, before_snapshot, \
+         after_snapshot, changed_by, changed_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(change.entity.as_str())
+    .bind(change.entity_id.hyphenated())
+    .bind(change.action.as_str())
+    .bind(history::encode(&change.before))
+    .bind(history::encode(&change.after))
+    .bind(change.changed_by.map(|wizard| wizard.0.hyphenated()))
+    .bind(now())
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Révisions de l'enregistrement dans l'ordre, ou seulement `revision`.
+async fn revisions(
+    conn: &mut SqliteConnection,
+    entity: RevisionEntity,
+    entity_id: Uuid,
+    revision: Option<i64>,
+) -> Result<Vec<Revision>> {
+    let rows = sqlx::query(
+        "SELECT id, entity_type, entity_id, action, before_snapshot, after_snapshot, \
+         changed_by, changed_at FROM revisions \
+         WHERE entity_type = ?1 AND entity_id = ?2 AND (?3 IS NULL OR id = ?3) ORDER BY id",
+    )
+    .bind(entity.as_str())
+    .bind(entity_id.hyphenated())
+    .bind(revision)
+    .fetch_all(&mut *conn)
+    .await?;
+    rows.iter()
+        .map(|row| {
+            Ok(Revision {
+                id: row.get("id"),
+                entity: RevisionEntity::parse(row.get("entity_type"))?,
+                entity_id: uuid(row, "entity_id"),
+                action: RevisionAction::parse(row.get("action"))?,
+                before: history::decode(row.get("before_snapshot"))?,
+                after: history::decode(row.get("after_snapshot"))?,
+                changed_by: optional_uuid(row, "changed_by").map(WizardId),
+                changed_at: row.get("changed_at"),
+            })
+        })
+        .collect()
+}
+
+async fn find_revision(
+    conn: &mut SqliteConnection,
+    entity: RevisionEntity,
+    entity_id: Uuid,
+    revision: i64,
+) -> Result<Revision> {
+    revisions(conn, entity, entity_id, Some(revision))
+        .await?
+        .pop()
+        .ok_or_else(|| revision_not_found(revision))
+}
+
+/// Révision d'une personne dont un événement a changé ; `before` a été lu dans la transaction.
+async fn record_event_change(
+    conn: &mut SqliteConnection,
+    person_id: PersonId,
+    before: Option<Person>,
+    changed_by: Option<WizardId>,
+) -> Result<()> {
+    let after = find_person(conn, person_id).await?;
+    let change = Change::person(
+        person_id,
+        RevisionAction::Update,
+        before.as_ref(),
+        after.as_ref(),
+        changed_by,
+    )?;
+    record(conn, &change).await
+}
+
+async fn touch_person(conn: &mut SqliteConnection, person_id: PersonId) -> Result<()> {
+    sqlx::query("UPDATE persons SET updated_at = ? WHERE id = ?")
+        .bind(now())
+        .bind(person_id.0.hyphenated())
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+impl SqlitePersons {
+    async fn update_as(
+        &self,
+        id: PersonId,
+        updates: PersonUpdate,
+        action: RevisionAction,
+    ) -> Result<Option<Person>> {
+        if !updates.has_changes() {
+            return self.find_by_id(id).await;
+        }
+        let mut tx = self.store.pool.begin().await?;
+        let Some(mut person) = find_person(&mut tx, id).await? else {
+            return Ok(None);
+        };
+        let before = person.clone();
+        let updated_by = updates.updated_by;
+        let rewrite_details = updates.has_detail_changes();
+        updates.apply(&mut person);
+        person.updated_at = now();
+        write_person(&mut tx, &person, true).await?;
+        if rewrite_details {
+            delete_events(&mut tx, Owner::Person, id.0).await?;
+            insert_events(&mut tx, Owner::Person, &person_event_rows(&person)).await?;
+        }
+        let change = Change::person(id, action, Some(&before), Some(&person), updated_by)?;
+        record(&mut tx, &change).await?;
+        tx.commit().await?;
+
+        self.store.cache(&person).await?;
+        Ok(Some(person))
+    }
+}
+
+#[async_trait]
+impl PersonStore for SqlitePersons {
+    async fn create(&self, person: &Person) -> Result<PersonId> {
+        let mut tx = self.store.pool.begin().await?;
+        write_person(&mut tx, person, false).await?;
+        insert_events(&mut tx, Owner::Person, &person_event_rows(person)).await?;
+        let created = find_person(&mut tx, person.id).await?;
+        let change = Change::person(
+            person.id,
+            RevisionAction::Create,
+            None,
+            created.as_ref(),
+            person.updated_by,
+        )?;
+        record(&mut tx, &change).await?;
+        tx.commit().await?;
+        Ok(person.id)
+    }
+
+    async fn find_by_id(&self, id: PersonId) -> Result<Option<Person>> {
+        if let Some(person) = self.store.cached(id).await? {
+            return Ok(Some(person));
+        }
+        let mut conn = self.store.pool.acquire().await?;
+        let person = find_person(&mut conn, id).await?;
+        if let Some(person) = &person {
+            self.store.cache(person).await?;
+        }
+        Ok(person)
+    }
+
+    async fn find_many(&self, ids: &[PersonId]) -> Result<Vec<Person>> {
+        let mut conn = self.store.pool.acquire().await?;
+        select_persons(
+            &mut conn,
+            "WHERE id IN (SELECT value FROM json_each(?)) AND deleted_at IS NULL",
+            vec![id_list(ids.iter().map(|id| id.0))?],
+        )
+        .await
+    }
+
+    async fn search_by_name(&self, surname: &str, first_name: &str) -> Result<Vec<Person>> {
+        let mut conn = self.store.pool.acquire().await?;
+        // `LIKE` ignore la casse des lettres ASCII, comme `ILIKE` pour ce motif.
+        select_persons(
+            &mut conn,
+            "WHERE (surname || ' ' || first_name) LIKE ? AND deleted_at IS NULL \
+             ORDER BY surname, first_name LIMIT 50",
+            vec![format!("%{} {}%", surname, first_name)],
+        )
+        .await
+    }
+
+    async fn update(&self, id: PersonId, updates: PersonUpdate) -> Result<Option<Person>> {
+        self.update_as(id, updates, RevisionAction::Update).await
+    }
+
+    async fn events(&self, person_id: PersonId) -> Result<Vec<PersonEventRecord>> {
+        let mut conn = self.store.pool.acquire().await?;
+        let stored = load_events(&mut conn, Owner::Person, &[person_id.0])
+            .await?
+            .remove(&person_id.0)
+            .unwrap_or_default();
+        Ok(in_timeline_order(stored.into_iter().map(|stored| {
+            let record = PersonEventRecord {
+                id: stored.id,
+                person_id,
+                event: stored.event,
+            };
+            (stored.slot, record)
+        })))
+    }
+
+    async fn add_event(
+        &self,
+        person_id: PersonId,
+        event: LifeEvent,
+        changed_by: Option<WizardId>,
+    ) -> Result<Option<PersonEventRecord>> {
+        let mut tx = self.store.pool.begin().await?;
+        let before = find_person(&mut tx, person_id).await?;
+        if before.is_none() {
+            return Ok(None);
+        }
+        let slot = event_slot(&event.event_type);
+        let position = free_position(&mut tx, person_id, slot, None).await?;
+        let id = Uuid::new_v4();
+        insert_events(
+            &mut tx,
+            Owner::Person,
+            &[EventRow {
+                id,
+                owner_id: person_id.0,
+                slot,
+                position,
+                event: &event,
+            }],
+        )
+        .await?;
+        touch_person(&mut tx, person_id).await?;
+        record_event_change(&mut tx, person_id, before, changed_by).await?;
+        tx.commit().await?;
+
+        self.store.uncache(person_id).await?;
+        Ok(Some(PersonEventRecord {
+            id,
+            person_id,
+            event,
+        }))
+    }
+
+    async fn update_event(
+        &self,
+        event_id: Uuid,
+        event: LifeEvent,
+        changed_by: Option<WizardId>,
+    ) -> Result<Option<PersonEventRecord>> {
+        let mut tx = self.store.pool.begin().await?;
+        let Some((person_id, previous_slot, previous_position)) =
+            find_event(&mut tx, event_id).await?
+        else {
+            return Ok(None);
+        };
+        let before = find_person(&mut tx, person_id).await?;
+        let slot = event_slot(&event.event_type);
+        let position = if slot == previous_slot {
+            previous_position
+        } else {
+            free_position(&mut tx, person_id, slot, Some(event_id)).await?
+        };
+        sqlx::query("DELETE FROM events WHERE id = ?")
+            .bind(event_id.hyphenated())
+            .execute(&mut *tx)
+            .await?;
+        insert_events(
+            &mut tx,
+            Owner::Person,
+            &[EventRow {
+                id: event_id,
+                owner_id: person_id.0,
+                slot,
+                position,
+                event: &event,
+            }],
+        )
+        .await?;
+        touch_person(&mut tx, person_id).await?;
+        record_event_change(&mut tx, person_id, before, changed_by).await?;
+        tx.commit().await?;
+
+        self.store.uncache(person_id).await?;
+        Ok(Some(PersonEventRecord {
+            id: event_id,
+            person_id,
+            event,
+        }))
+    }
+
+    async fn delete_event(&self, event_id: Uuid, changed_by: Option<WizardId>) -> Result<bool> {
+        let mut tx = self.store.pool.begin().await?;
+        let Some((person_id, _, _)) = find_event(&mut tx, event_id).await? else {
+            return Ok(false);
+        };
+        let before = find_person(&mut tx, person_id).await?;
+        sqlx::query("DELETE FROM events WHERE id = ?")
+            .bind(event_id.hyphenated())
+            .execute(&mut *tx)
+            .await?;
+        touch_person(&mut tx, person_id).await?;
+        record_event_change(&mut tx, person_id, before, changed_by).await?;
+        tx.commit().await?;
+
+        self.store.uncache(person_id).await?;
+        Ok(true)
+    }
+
+    async fn delete(
+        &self,
+        id: PersonId,
+        force: bool,
+        deleted_by: Option<WizardId>,
+    ) -> Result<bool> {
+        let mut tx = self.store.pool.begin().await?;
+        let parent_of: Option<i64> = sqlx::query_scalar(
+            "SELECT (SELECT COUNT(*) FROM families \
+                     WHERE (father_id = ?1 OR mother_id = ?1) AND deleted_at IS NULL) \
+             FROM persons WHERE id = ?1 AND deleted_at IS NULL",
+        )
+        .bind(id.0.hyphenated())
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(parent_of) = parent_of else {
+            return Ok(false);
+        };
+        if parent_of > 0 && !force {
+            return Err(person_is_parent(parent_of));
+        }
+        let before = find_person(&mut tx, id).await?;
+        if parent_of > 0 {
+            unlink_parent(&mut tx, id, deleted_by).await?;
+        }
+
+        sqlx::query("UPDATE persons SET deleted_at = ?2, deleted_by = ?3 WHERE id = ?1")
+            .bind(id.0.hyphenated())
+            .bind(now())
+            .bind(deleted_by.map(|wizard| wizard.0.hyphenated()))
+            .execute(&mut *tx)
+            .await?;
+        let change = Change::person(
+            id,
+            RevisionAction::Delete,
+            before.as_ref(),
+            None,
+            deleted_by,
+        )?;
+        record(&mut tx, &change).await?;
+        tx.commit().await?;
+
+        self.store.uncache(id).await?;
+        Ok(true)
+    }
+
+    async fn restore(&self, id: PersonId, restored_by: Option<WizardId>) -> Result<bool> {
+        let mut tx = self.store.pool.begin().await?;
+        let restored = sqlx::query(
+            "UPDATE persons SET deleted_at = NULL, deleted_by = NULL \
+             WHERE id = ? AND deleted_at IS NOT NULL",
+        )
+        .bind(id.0.hyphenated())
+        .execute(&mut *tx)
+        .await?;
+        if restored.rows_affected() == 0 {
+            return Ok(false);
+        }
+        let after = find_person(&mut tx, id).await?;
+        let change = Change::person(
+            id,
+            RevisionAction::Restore,
+            None,
+            after.as_ref(),
+            restored_by,
+        )?;
+        record(&mut tx, &change).await?;
+        relink_parent(&mut tx, id, restored_by).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn trash(&self) -> Result<Vec<Trashed<Person>>> {
+        let mut tx = self.store.pool.begin().await?;
+        let rows = sqlx::query(
+            "SELECT id, deleted_at, deleted_by FROM persons \
+             WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let mut persons: HashMap<PersonId, Person> =
+            select_persons(&mut tx, "WHERE deleted_at IS NOT NULL", Vec::new())
+                .await?
+                .into_iter()
+                .map(|person| (person.id, person))
+                .collect();
+        tx.commit().await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let person = persons.remove(&PersonId(uuid(row, "id")))?;
+                Some(row_to_trashed(row, person))
+            })
+            .collect())
+    }
+
+    async fn purge_trash(&self, before: NaiveDateTime) -> Result<u64> {
+        let mut tx = self.store.pool.begin().await?;
+        let family_ids: Vec<FamilyId> = sqlx::query_scalar(
+            "WITH expired (id) AS (SELECT id FROM persons WHERE deleted_at < ?1) \
+             SELECT id FROM families \
+             WHERE father_id IN expired OR mother_id IN expired \
+                OR id IN (SELECT family_id FROM family_children WHERE child_id IN expired)",
+        )
+        .bind(before)
+        .fetch_all(&mut *tx)
+        .await?
+        .iter()
+        .map(|id: &String| Uuid::parse_str(id).map(FamilyId))
+        .collect::<Result<_, _>>()?;
+        let families = find_families(&mut tx, &family_ids, true).await?;
+        sqlx::query(
+            "WITH expired (id) AS (SELECT id FROM persons WHERE deleted_at < ?1) \
+             UPDATE families \
+             SET father_id = CASE WHEN father_id IN expired THEN NULL ELSE father_id END, \
+                 mother_id = CASE WHEN mother_id IN expired THEN NULL ELSE mother_id END, \
+                 updated_at = ?2 \
+             WHERE father_id IN expired OR mother_id IN expired",
+        )
+        .bind(before)
+        .bind(now())
+        .execute(&mut *tx)
+        .await?;
+        let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM persons WHERE deleted_at < ?")
+            .bind(before)
+            .fetch_all(&mut *tx)
+            .await?;
+        for id in &ids {
+            record(
+                &mut tx,
+                &Change::purge(RevisionEntity::Person, Uuid::parse_str(id)?),
+            )
+            .await?;
+        }
+        // Événements et filiations comme enfant suivent par `ON DELETE CASCADE`.
+        let purged = sqlx::query("DELETE FROM persons WHERE deleted_at < ?")
+            .bind(before)
+            .execute(&mut *tx)
+            .await?;
+        let after = find_families(&mut tx, &family_ids, true).await?;
+        for change in family_updates(families, &after, None)? {
+            record(&mut tx, &change).await?;
+        }
+        tx.commit().await?;
+        Ok(purged.rows_affected())
+    }
+
+    async fn history(&self, id: PersonId) -> Result<Vec<Revision>> {
+        let mut conn = self.store.pool.acquire().await?;
+        revisions(&mut conn, RevisionEntity::Person, id.0, None).await
+    }
+
+    async fn revert(
+        &self,
+        id: PersonId,
+        revision: i64,
+        reverted_by: Option<WizardId>,
+    ) -> Result<Option<Person>> {
+        let mut conn = self.store.pool.acquire().await?;
+        let person: Person = find_revision(&mut conn, RevisionEntity::Person, id.0, revision)
+            .await?
+            .snapshot()?;
+        drop(conn);
+        let updates = PersonUpdate::reverting_to(person, reverted_by);
+        self.update_as(id, updates, RevisionAction::Revert).await
+    }
+}
+
+async fn find_family(conn: &mut SqliteConnection, id: FamilyId) -> Result<Option<Family>> {
+    let row = sqlx::query(&format!(
+        "SELECT {FAMILY_COLUMNS} FROM families WHERE id = ? AND deleted_at IS NULL"
+    ))
+    .bind(id.0.hyphenated())
+    .fetch_optional(&mut *conn)
+    .await?;
+    match row {
+        Some(row) => Ok(Some(read_family(conn, &row).await?)),
+        None => Ok(None),
+    }
+}
+
+/// Familles hors corbeille (ou aussi à la corbeille avec `with_trash`) parmi `ids`,
+/// dans cet ordre.
+async fn find_families(
+    conn: &mut SqliteConnection,
+    ids: &[FamilyId],
+    with_trash: bool,
+) -> Result<Vec<Family>> {
+    let mut families = Vec::new();
+    for id in ids {
+        let row = sqlx::query(&format!(
+            "SELECT {FAMILY_COLUMNS} FROM families WHERE id = ?1 AND (deleted_at IS NULL OR ?2)"
+        ))
+        .bind(id.0.hyphenated())
+        .bind(with_trash)
+        .fetch_optional(&mut *conn)
+        .await?;
+        if let Some(row) = row {
+            families.push(read_family(conn, &row).await?);
+        }
+    }
+    Ok(families)
+}
+
+/// Retire une personne mise à la corbeille des familles dont elle est parent, et
+/// garde ces liens dans `trashed_parents` pour sa restauration.
+async fn unlink_parent(
+    conn: &mut SqliteConnection,
+    id: PersonId,
+    changed_by: Option<WizardId>,
+) -> Result<()> {
+    let ids: Vec<FamilyId> = sqlx::query_scalar(
+        "SELECT id FROM families WHERE (father_id = ?1 OR mother_id = ?1) AND deleted_at IS NULL",
+    )
+    .bind(id.0.hyphenated())
+    .fetch_all(&mut *conn)
+    .await?
+    .iter()
+    .map(|id: &String| Uuid::parse_str(id).map(FamilyId))
+    .collect::<Result<_, _>>()?;
+    let before = find_families(conn, &ids, false).await?;
+    for role in ["father", "mother"] {
+        let column = format!("{role}_id");
+        sqlx::query(&format!(
+            "INSERT OR IGNORE INTO trashed_parents (person_id, family_id, role) \
+             SELECT ?1, id, ?2 FROM families WHERE {column} = ?1 AND deleted_at IS NULL"
+        ))
+        .bind(id.0.hyphenated())
+        .bind(role)
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query(&format!(
+            "UPDATE families SET {column} = NULL, updated_at = ?2 \
+             WHERE {column} = ?1 AND deleted_at IS NULL"
+        ))
+        .bind(id.0.hyphenated())
+        .bind(now())
+        .execute(&mut *conn)
+        .await?;
+    }
+    let after = find_families(conn, &ids, false).await?;
+    for change in family_updates(before, &after, changed_by)? {
+        record(conn, &change).await?;
+    }
+    Ok(())
+}
+
+/// Remet une personne restaurée dans les familles dont [`unlink_parent`] l'a retirée,
+/// là où sa place est restée libre.
+async fn relink_parent(
+    conn: &mut SqliteConnection,
+    id: PersonId,
+    changed_by: Option<WizardId>,
+) -> Result<()> {
+    let links: Vec<(String, String)> =
+        sqlx::query_as("DELETE FROM trashed_parents WHERE person_id = ? RETURNING family_id, role")
+            .bind(id.0.hyphenated())
+            .fetch_all(&mut *conn)
+            .await?;
+    let ids: Vec<FamilyId> = links
+        .iter()
+        .map(|(family_id, _)| Uuid::parse_str(family_id).map(FamilyId))
+        .collect::<Result<_, _>>()?;
+    let before = find_families(conn, &ids, false).await?;
+    for (family_id, role) in &links {
+        let column = if role == "father" {
+            "father_id"
+        } else {
+            "mother_id"
+        };
+        sqlx::query(&format!(
+            "UPDATE families SET {column} = ?1, updated_at = ?3 \
+             WHERE id = ?2 AND {column} IS NULL AND deleted_at IS NULL"
+        ))
+        .bind(id.0.hyphenated())
+        .bind(family_id)
+        .bind(now())
+        .execute(&mut *conn)
+        .await?;
+    }
+    let after = find_families(conn, &ids, false).await?;
+    for change in family_updates(before, &after, changed_by)? {
+        record(conn, &change).await?;
+    }
+    Ok(())
+}
+
+/// Famille d'une ligne de `families`, avec ses enfants et ses événements.
+async fn read_family(conn: &mut SqliteConnection, row: &SqliteRow) -> Result<Family> {
+    let mut family = row_to_family(row)?;
+    let id = family.id;
+
+    family.children = sqlx::query(
+        "SELECT child_id FROM family_children WHERE family_id = ? ORDER BY child_order",
+    )
+    .bind(id.0.hyphenated())
+    .fetch_all(&mut *conn)
+    .await?
+    .iter()
+    .map(|row| PersonId(uuid(row, "child_id")))
+    .collect();
+
+    let events = load_events(conn, Owner::Family, &[id.0])
+        .await?
+        .remove(&id.0)
+        .unwrap_or_default();
+    for stored in events {
+        match stored.slot.as_str() {
+            "marriage" => family.marriage = Some(stored.event),
+            "divorce" => family.divorce = Some(stored.event),
+            _ => family.other_events.push(stored.event),
+        }
+    }
+    Ok(family)
+}
+
+/// Écrit toutes les colonnes de `families` ; `replace` remplace une ligne existante.
+async fn write_family(conn: &mut SqliteConnection, family: &Family, replace: bool) -> Result<()> {
+    // `REPLACE` supprimerait la ligne et, en cascade, ses enfants et ses événements.
+    let sql = if replace {
+        "UPDATE families SET father_id = ?2, mother_id = ?3, notes = ?4, sources = ?5, \
+         public = ?6, created_at = ?7, updated_at = ?8 WHERE id = ?1"
+            .to_string()
+    } else {
+        format!("INSERT INTO families ({FAMILY_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")
+    };
+    sqlx::query(&sql)
+        .bind(family.id.0.hyphenated())
+        .bind(family.father.map(|id| id.0.hyphenated()))
+        .bind(family.mother.map(|id| id.0.hyphenated()))
+        .bind(family.notes.as_deref())
+        .bind(serde_json::to_string(&family.sources)?)
+        .bind(family.public)
+        .bind(family.created_at)
+        .bind(family.updated_at)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+async fn replace_children(
+    conn: &mut SqliteConnection,
+    family_id: FamilyId,
+    children: &[PersonId],
+) -> Result<()> {
+    sqlx::query("DELETE FROM family_children WHERE family_id = ?")
+        .bind(family_id.0.hyphenated())
+        .execute(&mut *conn)
+        .await?;
+    for (order, child) in children.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO family_children (family_id, child_id, child_order) VALUES (?, ?, ?)",
+        )
+        .bind(family_id.0.hyphenated())
+        .bind(child.0.hyphenated())
+        .bind(order as i32)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+impl SqliteFamilies {
+    async fn update_as(
+        &self,
+        id: FamilyId,
+        changes: FamilyChanges,
+        action: RevisionAction,
+    ) -> Result<Option<Family>> {
+        let mut tx = self.store.pool.begin().await?;
+        let Some(mut family) = find_family(&mut tx, id).await? else {
+            return Ok(None);
+        };
+        if !changes.has_changes() {
+            return Ok(Some(family));
+        }
+        let before = family.clone();
+        let updated_by = changes.updated_by;
+        let touched = changes.touches_family();
+        let rewrite_children = changes.children.is_some();
+        let rewrite_details = changes.has_detail_changes();
+        changes.apply(&mut family);
+        if touched {
+            family.updated_at = now();
+            write_family(&mut tx, &family, true).await?;
+        }
+        if rewrite_children {
+            replace_children(&mut tx, id, &family.children).await?;
+        }
+        if rewrite_details {
+            delete_events(&mut tx, Owner::Family, id.0).await?;
+            insert_events(&mut tx, Owner::Family, &family_event_rows(&family)).await?;
+        }
+        let change = Change::family(id, action, Some(&before), Some(&family), updated_by)?;
+        record(&mut tx, &change).await?;
+        tx.commit().await?;
+        Ok(Some(family))
+    }
+}
+
+#[async_trait]
+impl FamilyStore for SqliteFamilies {
+    async fn create(&self, draft: FamilyDraft) -> Result<Family> {
+        let now = now();
+        let family = Family {
+            id: draft.id,
+            father: draft.father_id,
+            mother: draft.mother_id,
+            children: draft.children,
+            marriage: draft.marriage,
+            divorce: draft.divorce,
+            other_events: draft.other_events,
+            notes: draft.notes,
+            sources: draft.sources,
+            public: draft.public,
+            created_at: now,
+            updated_at: now,
+        };
+        let mut tx = self.store.pool.begin().await?;
+        write_family(&mut tx, &family, false).await?;
+        replace_children(&mut tx, family.id, &family.children).await?;
+        insert_events(&mut tx, Owner::Family, &family_event_rows(&family)).await?;
+        let change = Change::family(
+            family.id,
+            RevisionAction::Create,
+            None,
+            Some(&family),
+            draft.created_by,
+        )?;
+        record(&mut tx, &change).await?;
+        tx.commit().await?;
+        Ok(family)
+    }
+
+    async fn find_by_id(&self, id: FamilyId) -> Result<Option<Family>> {
+        let mut conn = self.store.pool.acquire().await?;
+        find_family(&mut conn, id).await
+    }
+
+    async fn update(&self, id: FamilyId, changes: FamilyChanges) -> Result<Option<Family>> {
+        self.update_as(id, changes, RevisionAction::Update).await
+    }
+
+    async fn append_child(
+        &self,
+        id: FamilyId,
+        child: PersonId,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
+        let family = self
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow!("Family not found"))?;
+        let mut children = family.children;
+        if !children.contains(&child) {
+            children.push(child);
+        }
+        self.update(
+            id,
+            FamilyChanges {
+                children: Some(children),
+                updated_by: changed_by,
+                ..Default::default()
+            },
+        )
+        .await?
+        .ok_or_else(|| anyhow!("Family not found after update"))
+    }
+
+    async fn remove_child(
+        &self,
+        id: FamilyId,
+        child: PersonId,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
+        let family = self
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow!("Family not found"))?;
+        let children = family
+            .children
+            .into_iter()
+            .filter(|c| *c != child)
+            .collect();
+        self.update(
+            id,
+            FamilyChanges {
+                children: Some(children),
+                updated_by: changed_by,
+                ..Default::default()
+            },
+        )
+        .await?
+        .ok_or_else(|| anyhow!("Family not found after update"))
+    }
+
+    async fn set_privacy(
+        &self,
+        id: FamilyId,
+        public: bool,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
+        self.update(
+            id,
+            FamilyChanges {
+                public: Some(public),
+                updated_by: changed_by,
+                ..Default::default()
+            },
+        )
+        .await?
+        .ok_or_else(|| anyhow!("Family not found after update"))
+    }
+
+    async fn add_event(&self, event: FamilyEventNew) -> Result<FamilyEventRecord> {
+        let mut tx = self.store.pool.begin().await?;
+        let Some(before) = find_family(&mut tx, event.family_id).await? else {
+            return Err(anyhow!("Family not found"));
+        };
+        let event_type = EventType::from_name(&event.event_type);
+        let slot = family_event_slot(&event_type);
+        let position: i32 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(position) + 1, 0) FROM events \
+             WHERE family_id = ? AND slot = ?",
+        )
+        .bind(event.family_id.0.hyphenated())
+        .bind(slot)
+        .fetch_one(&mut *tx)
+        .await?;
+        if slot != "other" && position > 0 {
+            return Err(family_slot_taken(slot));
+        }
+        let id = Uuid::new_v4();
+        let life_event = LifeEvent {
+            event_type,
+            date: event.date.clone().unwrap_or(PreciseDate::Unknown),
+            place: None,
+            witnesses: Vec::new(),
+            notes: event.notes.clone(),
+            sources: Vec::new(),
+        };
+        insert_events(
+            &mut tx,
+            Owner::Family,
+            &[EventRow {
+                id,
+                owner_id: event.family_id.0,
+                slot,
+                position,
+                event: &life_event,
+            }],
+        )
+        .await?;
+        let after = find_family(&mut tx, event.family_id).await?;
+        let change = Change::family(
+            event.family_id,
+            RevisionAction::Update,
+            Some(&before),
+            after.as_ref(),
+            event.created_by,
+        )?;
+        record(&mut tx, &change).await?;
+        tx.commit().await?;
+
+        Ok(FamilyEventRecord {
+            id,
+            family_id: event.family_id,
+            event_type: event.event_type,
+            date: event.date,
+            notes: event.notes,
+        })
+    }
+
+    async fn delete(
+        &self,
+        id: FamilyId,
+        force: bool,
+        deleted_by: Option<WizardId>,
+    ) -> Result<bool> {
+        let mut tx = self.store.pool.begin().await?;
+        let children: Option<i64> = sqlx::query_scalar(
+            "SELECT (SELECT COUNT(*) FROM family_children WHERE family_id = ?1) \
+             FROM families WHERE id = ?1 AND deleted_at IS NULL",
+        )
+        .bind(id.0.hyphenated())
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(children) = children else {
+            return Ok(false);
+        };
+        if children > 0 && !force {
+            return Err(family_has_children(children));
+        }
+        let before = find_family(&mut tx, id).await?;
+        sqlx::query("UPDATE families SET deleted_at = ?2, deleted_by = ?3 WHERE id = ?1")
+            .bind(id.0.hyphenated())
+            .bind(now())
+            .bind(deleted_by.map(|wizard| wizard.0.hyphenated()))
+            .execute(&mut *tx)
+            .await?;
+        let change = Change::family(
+            id,
+            RevisionAction::Delete,
+            before.as_ref(),
+            None,
+            deleted_by,
+        )?;
+        record(&mut tx, &change).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn restore(&self, id: FamilyId, restored_by: Option<WizardId>) -> Result<bool> {
+        let mut tx = self.store.pool.begin().await?;
+        let restored = sqlx::query(
+            "UPDATE families SET deleted_at = NULL, deleted_by = NULL \
+             WHERE id = ? AND deleted_at IS NOT NULL",
+        )
+        .bind(id.0.hyphenated())
+        .execute(&mut *tx)
+        .await?;
+        if restored.rows_affected() == 0 {
+            return Ok(false);
+        }
+        let after = find_family(&mut tx, id).await?;
+        let change = Change::family(
+            id,
+            RevisionAction::Restore,
+            None,
+            after.as_ref(),
+            restored_by,
+        )?;
+        record(&mut tx, &change).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn trash(&self) -> Result<Vec<Trashed<Family>>> {
+        let mut tx = self.store.pool.begin().await?;
+        let rows = sqlx::query(&format!(
+            "SELECT {FAMILY_COLUMNS}, deleted_at, deleted_by FROM families \
+             WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC"
+        ))
+        .fetch_all(&mut *tx)
+        .await?;
+        let mut trash = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let family = read_family(&mut tx, row).await?;
+            trash.push(row_to_trashed(row, family));
+        }
+        tx.commit().await?;
+        Ok(trash)
+    }
+
+    async fn purge_trash(&self, before: NaiveDateTime) -> Result<u64> {
+        let mut tx = self.store.pool.begin().await?;
+        let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM families WHERE deleted_at < ?")
+            .bind(before)
+            .fetch_all(&mut *tx)
+            .await?;
+        for id in &ids {
+            record(
+                &mut tx,
+                &Change::purge(RevisionEntity::Family, Uuid::parse_str(id)?),
+            )
+            .await?;
+        }
+        let purged = sqlx::query("DELETE FROM families WHERE deleted_at < ?")
+            .bind(before)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(purged.rows_affected())
+    }
+
+    async fn history(&self, id: FamilyId) -> Result<Vec<Revision>> {
+        let mut conn = self.store.pool.acquire().await?;
+        revisions(&mut conn, RevisionEntity::Family, id.0, None).await
+    }
+
+    async fn revert(
+        &self,
+        id: FamilyId,
+        revision: i64,
+        reverted_by: Option<WizardId>,
+    ) -> Result<Option<Family>> {
+        let mut conn = self.store.pool.acquire().await?;
+        let family: Family = find_revision(&mut conn, RevisionEntity::Family, id.0, revision)
+            .await?
+            .snapshot()?;
+        drop(conn);
+        let changes = FamilyChanges::reverting_to(family, reverted_by);
+        self.update_as(id, changes, RevisionAction::Revert).await
+    }
+}
+
+impl SqliteGraph {
+    async fn ids(&self, sql: &str, binds: &[Uuid], limit: Option<u32>) -> Result<Vec<PersonId>> {
+        let mut query = sqlx::query(sql);
+        for id in binds {
+            query = query.bind(id.hyphenated());
+        }
+        if let Some(limit) = limit {
+            query = query.bind(limit);
+        }
+        let rows = query.fetch_all(&self.store.pool).await?;
+        Ok(rows.iter().map(|row| PersonId(uuid(row, "id"))).collect())
+    }
+}
+
+#[async_trait]
+impl GraphStore for SqliteGraph {
+    async fn parents(&self, person_id: PersonId) -> Result<Vec<PersonId>> {
+        self.ids(
+            "SELECT parent_id AS id FROM child_of WHERE child_id = ? ORDER BY parent_id",
+            &[person_id.0],
+            None,
+        )
+        .await
+    }
+
+    async fn ancestors(&self, person_id: PersonId, generations: u32) -> Result<Vec<PersonId>> {
+        self.ids(
+            "WITH RECURSIVE ancestry (id, depth) AS (
+                 SELECT parent_id, 1 FROM child_of WHERE child_id = ?1 AND ?2 > 0
+                 UNION
+                 SELECT c.parent_id, a.depth + 1
+                 FROM ancestry a
+                 JOIN child_of c ON c.child_id = a.id
+                 WHERE a.depth < ?2
+             )
+             SELECT id FROM ancestry GROUP BY id ORDER BY MIN(depth)",
+            &[person_id.0],
+            Some(generations),
+        )
+        .await
+    }
+
+    async fn common_ancestors(&self, first: PersonId, second: PersonId) -> Result<Vec<PersonId>> {
+        self.ids(
+            "WITH RECURSIVE
+                 first_line (id) AS (
+                     SELECT parent_id FROM child_of WHERE child_id = ?1
+                     UNION
+                     SELECT c.parent_id FROM first_line f JOIN child_of c ON c.child_id = f.id
+                 ),
+                 second_line (id) AS (
+                     SELECT parent_id FROM child_of WHERE child_id = ?2
+                     UNION
+                     SELECT c.parent_id FROM second_line s JOIN child_of c ON c.child_id = s.id
+                 )
+             SELECT id FROM first_line INTERSECT SELECT id FROM second_line",
+            &[first.0, second.0],
+            None,
+        )
+        .await
+    }
+
+    async fn paths(&self, from: PersonId, to: PersonId) -> Result<Vec<Vec<PersonId>>> {
+        // Chaque ligne porte le chemin parcouru, en JSON, pour ne jamais repasser par une
+        // personne ; le parcours ne suit que les descendants de `to`, seuls à pouvoir y mener.
+        let rows = sqlx::query(
+            "WITH RECURSIVE
+                 descendants (id) AS (
+                     SELECT ?2
+                     UNION
+                     SELECT c.child_id FROM descendants d JOIN child_of c ON c.parent_id = d.id
+                 ),
+                 walk (id, path) AS (
+                     SELECT ?1, json_array(?1)
+                     UNION ALL
+                     SELECT c.parent_id, json_insert(w.path, '$[#]', c.parent_id)
+                     FROM walk w
+                     JOIN child_of c ON c.child_id = w.id
+                     JOIN descendants d ON d.id = c.parent_id
+                     WHERE w.id <> ?2
+                       AND NOT EXISTS (SELECT 1 FROM json_each(w.path) WHERE value = c.parent_id)
+                 )
+             SELECT path FROM walk WHERE id = ?2 AND json_array_length(path) > 1",
+        )
+        .bind(from.0.hyphenated())
+        .bind(to.0.hyphenated())
+        .fetch_all(&self.store.pool)
+        .await?;
+        rows.iter().map(|row| json(row, "path")).collect()
+    }
+
+    async fn distance(&self, first: PersonId, second: PersonId) -> Result<Option<u32>> {
+        // Parcours en largeur, une requête par niveau : une CTE récursive sur un graphe
+        // non orienté ne s'arrêterait pas d'elle-même.
+        let mut seen = HashSet::from([first]);
+        let mut frontier = vec![first];
+        let mut distance = 0;
+        while !frontier.is_empty() {
+            if frontier.contains(&second) {
+                return Ok(Some(distance));
+            }
+            let rows = sqlx::query(
+                "SELECT parent_id AS id FROM child_of
+                 WHERE child_id IN (SELECT value FROM json_each(?1))
+                 UNION
+                 SELECT child_id FROM child_of
+                 WHERE parent_id IN (SELECT value FROM json_each(?1))",
+            )
+            .bind(id_list(frontier.iter().map(|id| id.0))?)
+            .fetch_all(&self.store.pool)
+            .await?;
+            frontier = rows
+                .iter()
+                .map(|row| PersonId(uuid(row, "id")))
+                .filter(|id| seen.insert(*id))
+                .collect();
+            distance += 1;
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use genealogy_types::Sex;
+
+    use super::*;
+
+    async fn store() -> SqliteStore {
+        let path = std::env::temp_dir().join(format!("noah-{}.db", Uuid::new_v4()));
+        SqliteStore::connect(&format!("sqlite://{}", path.display()), None)
+            .await
+            .unwrap()
+    }
+
+    fn person(first_name: &str, birth_year: i32) -> Person {
+        let now = now();
+        Person {
+            id: PersonId(Uuid::new_v4()),
+            first_name: first_name.to_string(),
+            surname: "Martin".to_string(),
+            surname_prefix: None,
+            nicknames: Vec::new(),
+            sex: Sex::Unknown,
+            birth: Some(LifeEvent {
+                event_type: EventType::Birth,
+                date: PreciseDate::YearOnly(birth_year),
+                place: None,
+                witnesses: Vec::new(),
+                notes: None,
+                sources: Vec::new(),
+            }),
+            death: None,
+            other_events: Vec::new(),
+            occupation: Vec::new(),
+            titles: Vec::new(),
+            notes: None,
+            sources: Vec::new(),
+            images: Vec::new(),
+            public: true,
+            created_at: now,
+            updated_at: now,
+            updated_by: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn updating_a_person_keeps_family_links_and_events() {
+        let store = store().await;
+        let (father, mother, child) = (
+            person("Jean", 1700),
+            person("Anne", 1702),
+            person("Paul", 1730),
+        );
+        for person in [&father, &mother, &child] {
+            store.persons().create(person).await.unwrap();
+        }
+        let family = store
+            .families()
+            .create(FamilyDraft {
+                id: FamilyId(Uuid::new_v4()),
+                father_id: Some(father.id),
+                mother_id: Some(mother.id),
+                children: vec![child.id],
+                marriage: None,
+                divorce: None,
+                other_events: Vec::new(),
+                notes: None,
+                sources: Vec::new(),
+                public: true,
+                created_by: None,
+            })
+            .await
+            .unwrap();
+
+        let rename = PersonUpdate {
+            first_name: Some("Jean-Baptiste".to_string()),
+            ..Default::default()
+        };
+        store.persons().update(father.id, rename).await.unwrap();
+        let hide = PersonUpdate {
+            public: Some(false),
+            ..Default::default()
+        };
+        store.persons().update(child.id, hide).await.unwrap();
+
+        let family = store
+            .families()
+            .find_by_id(family.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(family.father, Some(father.id));
+        assert_eq!(family.mother, Some(mother.id));
+        assert_eq!(family.children, vec![child.id]);
+        assert_eq!(store.graph().parents(child.id).await.unwrap().len(), 2);
+        for (id, year) in [(father.id, 1700), (child.id, 1730)] {
+            let stored = store.persons().find_by_id(id).await.unwrap().unwrap();
+            assert_eq!(stored.birth.unwrap().date, PreciseDate::YearOnly(year));
+        }
+    }
+
+    #[tokio::test]
+    async fn purging_the_trash_keeps_the_history() {
+        let store = store().await;
+        let jean = person("Jean", 1700);
+        store.persons().create(&jean).await.unwrap();
+        store.persons().delete(jean.id, false, None).await.unwrap();
+
+        let later = now() + chrono::Duration::seconds(1);
+        assert_eq!(store.persons().purge_trash(later).await.unwrap(), 1);
+        let actions: Vec<RevisionAction> = store
+            .persons()
+            .history(jean.id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|revision| revision.action)
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                RevisionAction::Create,
+                RevisionAction::Delete,
+                RevisionAction::Purge
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn purging_a_child_records_a_family_revision() {
+        let store = store().await;
+        let (jean, paul) = (person("Jean", 1700), person("Paul", 1730));
+        for person in [&jean, &paul] {
+            store.persons().create(person).await.unwrap();
+        }
+        let family = store
+            .families()
+            .create(FamilyDraft {
+                id: FamilyId(Uuid::new_v4()),
+                father_id: Some(jean.id),
+                mother_id: None,
+                children: vec![paul.id],
+                marriage: None,
+                divorce: None,
+                other_events: Vec::new(),
+                notes: None,
+                sources: Vec::new(),
+                public: true,
+                created_by: None,
+            })
+            .await
+            .unwrap();
+        store.persons().delete(paul.id, false, None).await.unwrap();
+
+        let later = now() + chrono::Duration::seconds(1);
+        assert_eq!(store.persons().purge_trash(later).await.unwrap(), 1);
+        let history = store.families().history(family.id).await.unwrap();
+        let purge = history.last().unwrap();
+        assert_eq!(purge.action, RevisionAction::Update);
+        let before: Family = serde_json::from_value(purge.before.clone().unwrap()).unwrap();
+        let after: Family = serde_json::from_value(purge.after.clone().unwrap()).unwrap();
+        assert_eq!(before.children, [paul.id]);
+        assert!(after.children.is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_forced_delete_unlinks_the_parent_until_restored() {
+        let store = store().await;
+        let jean = person("Jean", 1700);
+        store.persons().create(&jean).await.unwrap();
+        let family = store
+            .families()
+            .create(FamilyDraft {
+                id: FamilyId(Uuid::new_v4()),
+                father_id: Some(jean.id),
+                mother_id: None,
+                children: Vec::new(),
+                marriage: None,
+                divorce: None,
+                other_events: Vec::new(),
+                notes: None,
+                sources: Vec::new(),
+                public: true,
+                created_by: None,
+            })
+            .await
+            .unwrap();
+
+        assert!(store.persons().delete(jean.id, false, None).await.is_err());
+        assert!(store.persons().delete(jean.id, true, None).await.unwrap());
+        let father = |family: Option<Family>| family.unwrap().father;
+        let families = store.families();
+        assert_eq!(father(families.find_by_id(family.id).await.unwrap()), None);
+
+        assert!(store.persons().restore(jean.id, None).await.unwrap());
+        assert_eq!(
+            father(families.find_by_id(family.id).await.unwrap()),
+            Some(jean.id)
+        );
+        let actions: Vec<RevisionAction> = families
+            .history(family.id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|revision| revision.action)
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                RevisionAction::Create,
+                RevisionAction::Update,
+                RevisionAction::Update
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn family_events_go_to_their_slot() {
+        let store = store().await;
+        let family = store
+            .families()
+            .create(FamilyDraft {
+                id: FamilyId(Uuid::new_v4()),
+                father_id: None,
+                mother_id: None,
+                children: Vec::new(),
+                marriage: None,
+                divorce: None,
+                other_events: Vec::new(),
+                notes: None,
+                sources: Vec::new(),
+                public: true,
+                created_by: None,
+            })
+            .await
+            .unwrap();
+        let event = |event_type: &str, year| FamilyEventNew {
+            family_id: family.id,
+            event_type: event_type.to_string(),
+            date: Some(PreciseDate::YearOnly(year)),
+            notes: None,
+            created_by: None,
+        };
+
+        for (event_type, year) in [("marriage", 1725), ("engagement", 1724), ("divorce", 1740)] {
+            store
+                .families()
+                .add_event(event(event_type, year))
+                .await
+                .unwrap();
+        }
+        assert!(store
+            .families()
+            .add_event(event("marriage", 1741))
+            .await
+            .is_err());
+
+        let family = store
+            .families()
+            .find_by_id(family.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(family.marriage.unwrap().date, PreciseDate::YearOnly(1725));
+        assert_eq!(family.divorce.unwrap().date, PreciseDate::YearOnly(1740));
+        assert_eq!(family.other_events.len(), 1);
+        assert_eq!(family.other_events[0].event_type, EventType::Engagement);
+    }
+
+    #[tokio::test]
+    async fn paths_reach_the_ancestor_through_each_line() {
+        let store = store().await;
+        let [grandfather, grandmother, brother, sister, aunt, uncle, cousin1, cousin2, child] = [
+            "Jean", "Anne", "Paul", "Marie", "Louise", "Pierre", "Jacques", "Claire", "Lucie",
+        ]
+        .map(|name| person(name, 1700));
+        for person in [
+            &grandfather,
+            &grandmother,
+            &brother,
+            &sister,
+            &aunt,
+            &uncle,
+            &cousin1,
+            &cousin2,
+            &child,
+        ] {
+            store.persons().create(person).await.unwrap();
+        }
+        for (father, mother, child) in [
+            (&grandfather, &grandmother, &brother),
+            (&grandfather, &grandmother, &sister),
+            (&brother, &aunt, &cousin1),
+            (&uncle, &sister, &cousin2),
+            (&cousin1, &cousin2, &child),
+        ] {
+            store
+                .families()
+                .create(FamilyDraft {
+                    id: FamilyId(Uuid::new_v4()),
+                    father_id: Some(father.id),
+                    mother_id: Some(mother.id),
+                    children: vec![child.id],
+                    marriage: None,
+                    divorce: None,
+                    other_events: Vec::new(),
+                    notes: None,
+                    sources: Vec::new(),
+                    public: true,
+                    created_by: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let paths = store.graph().paths(child.id, grandfather.id).await.unwrap();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.contains(&vec![child.id, cousin1.id, brother.id, grandfather.id]));
+        assert!(paths.contains(&vec![child.id, cousin2.id, sister.id, grandfather.id]));
+        assert_eq!(
+            store.graph().paths(child.id, aunt.id).await.unwrap().len(),
+            1
+        );
+    }
+}
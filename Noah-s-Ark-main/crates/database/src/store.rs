@@ -0,0 +1,311 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use genealogy_types::{Family, FamilyId, LifeEvent, Person, PersonId, WizardId};
+use uuid::Uuid;
+
+use crate::{
+    DatabasePool, FamilyChanges, FamilyDraft, FamilyEventNew, FamilyEventRecord, FamilyRepository,
+    GraphRepository, PersonEventRecord, PersonRepository, PersonUpdate, Revision, Trashed,
+};
+
+/// Lecture et écriture des personnes et de leurs événements.
+#[async_trait]
+pub trait PersonStore: Send + Sync {
+    async fn create(&self, person: &Person) -> Result<PersonId>;
+    async fn find_by_id(&self, id: PersonId) -> Result<Option<Person>>;
+    async fn find_many(&self, ids: &[PersonId]) -> Result<Vec<Person>>;
+    async fn search_by_name(&self, surname: &str, first_name: &str) -> Result<Vec<Person>>;
+    async fn update(&self, id: PersonId, updates: PersonUpdate) -> Result<Option<Person>>;
+    async fn events(&self, person_id: PersonId) -> Result<Vec<PersonEventRecord>>;
+    async fn add_event(
+        &self,
+        person_id: PersonId,
+        event: LifeEvent,
+        changed_by: Option<WizardId>,
+    ) -> Result<Option<PersonEventRecord>>;
+    async fn update_event(
+        &self,
+        event_id: Uuid,
+        event: LifeEvent,
+        changed_by: Option<WizardId>,
+    ) -> Result<Option<PersonEventRecord>>;
+    async fn delete_event(&self, event_id: Uuid, changed_by: Option<WizardId>) -> Result<bool>;
+    /// Met la personne à la corbeille, hors de la recherche et du graphe ; une
+    /// personne encore parent exige `force`. `false` si elle n'existe pas.
+    async fn delete(&self, id: PersonId, force: bool, deleted_by: Option<WizardId>)
+        -> Result<bool>;
+    /// Sort la personne de la corbeille ; `false` si elle n'y est pas.
+    async fn restore(&self, id: PersonId, restored_by: Option<WizardId>) -> Result<bool>;
+    /// Personnes à la corbeille, les plus récemment supprimées d'abord.
+    async fn trash(&self) -> Result<Vec<Trashed<Person>>>;
+    /// Supprime définitivement les personnes mises à la corbeille avant `before`,
+    /// en les retirant de leurs familles ; renvoie leur nombre.
+    async fn purge_trash(&self, before: NaiveDateTime) -> Result<u64>;
+    /// Révisions de la personne, de la plus ancienne à la plus récente.
+    async fn history(&self, id: PersonId) -> Result<Vec<Revision>>;
+    /// Ramène la personne à l'état laissé par `revision`, dans une nouvelle révision ;
+    /// `None` si elle n'existe pas ou est à la corbeille.
+    async fn revert(
+        &self,
+        id: PersonId,
+        revision: i64,
+        reverted_by: Option<WizardId>,
+    ) -> Result<Option<Person>>;
+}
+
+/// Lecture et écriture des familles ; les liens `CHILD_OF` suivent leurs enfants.
+#[async_trait]
+pub trait FamilyStore: Send + Sync {
+    async fn create(&self, draft: FamilyDraft) -> Result<Family>;
+    async fn find_by_id(&self, id: FamilyId) -> Result<Option<Family>>;
+    async fn update(&self, id: FamilyId, changes: FamilyChanges) -> Result<Option<Family>>;
+    async fn append_child(
+        &self,
+        id: FamilyId,
+        child: PersonId,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family>;
+    async fn remove_child(
+        &self,
+        id: FamilyId,
+        child: PersonId,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family>;
+    async fn set_privacy(
+        &self,
+        id: FamilyId,
+        public: bool,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family>;
+    async fn add_event(&self, event: FamilyEventNew) -> Result<FamilyEventRecord>;
+    /// Met la famille à la corbeille avec ses arêtes `CHILD_OF` ; une famille qui a
+    /// encore des enfants exige `force`. `false` si elle n'existe pas.
+    async fn delete(&self, id: FamilyId, force: bool, deleted_by: Option<WizardId>)
+        -> Result<bool>;
+    /// Sort la famille de la corbeille ; `false` si elle n'y est pas.
+    async fn restore(&self, id: FamilyId, restored_by: Option<WizardId>) -> Result<bool>;
+    /// Familles à la corbeille, les plus récemment supprimées d'abord.
+    async fn trash(&self) -> Result<Vec<Trashed<Family>>>;
+    /// Supprime définitivement les familles mises à la corbeille avant `before` ;
+    /// renvoie leur nombre.
+    async fn purge_trash(&self, before: NaiveDateTime) -> Result<u64>;
+    /// Révisions de la famille, de la plus ancienne à la plus récente.
+    async fn history(&self, id: FamilyId) -> Result<Vec<Revision>>;
+    /// Ramène la famille à l'état laissé par `revision`, dans une nouvelle révision ;
+    /// `None` si elle n'existe pas ou est à la corbeille.
+    async fn revert(
+        &self,
+        id: FamilyId,
+        revision: i64,
+        reverted_by: Option<WizardId>,
+    ) -> Result<Option<Family>>;
+}
+
+/// Parcours du graphe de filiation `(enfant)-[:CHILD_OF]->(parent)`.
+#[async_trait]
+pub trait GraphStore: Send + Sync {
+    /// Parents directs, triés par identifiant.
+    async fn parents(&self, person_id: PersonId) -> Result<Vec<PersonId>>;
+    /// Ancêtres distincts sur au plus `generations` générations.
+    async fn ancestors(&self, person_id: PersonId, generations: u32) -> Result<Vec<PersonId>>;
+    /// Ancêtres communs à deux personnes, à n'importe quelle distance.
+    async fn common_ancestors(&self, first: PersonId, second: PersonId) -> Result<Vec<PersonId>>;
+    /// Chemins ascendants de `from` jusqu'à `to`, extrémités comprises.
+    async fn paths(&self, from: PersonId, to: PersonId) -> Result<Vec<Vec<PersonId>>>;
+    /// Nombre de liens du plus court chemin entre deux personnes, dans les deux sens.
+    async fn distance(&self, first: PersonId, second: PersonId) -> Result<Option<u32>>;
+}
+
+/// Backend complet : le serveur et `genealogy-core` n'utilisent que ces trois stores.
+pub trait Storage: Clone + Send + Sync + 'static {
+    type Persons: PersonStore;
+    type Families: FamilyStore;
+    type Graph: GraphStore;
+
+    fn persons(&self) -> Self::Persons;
+    fn families(&self) -> Self::Families;
+    fn graph(&self) -> Self::Graph;
+}
+
+/// PostgreSQL, avec Neo4j pour le graphe et Redis pour le cache.
+impl Storage for DatabasePool {
+    type Persons = PersonRepository;
+    type Families = FamilyRepository;
+    type Graph = GraphRepository;
+
+    fn persons(&self) -> PersonRepository {
+        PersonRepository::new(self.clone())
+    }
+
+    fn families(&self) -> FamilyRepository {
+        FamilyRepository::new(self.clone())
+    }
+
+    fn graph(&self) -> GraphRepository {
+        GraphRepository::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl PersonStore for PersonRepository {
+    async fn create(&self, person: &Person) -> Result<PersonId> {
+        PersonRepository::create(self, person).await
+    }
+
+    async fn find_by_id(&self, id: PersonId) -> Result<Option<Person>> {
+        PersonRepository::find_by_id(self, id).await
+    }
+
+    async fn find_many(&self, ids: &[PersonId]) -> Result<Vec<Person>> {
+        PersonRepository::find_many(self, ids).await
+    }
+
+    async fn search_by_name(&self, surname: &str, first_name: &str) -> Result<Vec<Person>> {
+        PersonRepository::search_by_name(self, surname, first_name).await
+    }
+
+    async fn update(&self, id: PersonId, updates: PersonUpdate) -> Result<Option<Person>> {
+        PersonRepository::update(self, id, updates).await
+    }
+
+    async fn events(&self, person_id: PersonId) -> Result<Vec<PersonEventRecord>> {
+        PersonRepository::events(self, person_id).await
+    }
+
+    async fn add_event(
+        &self,
+        person_id: PersonId,
+        event: LifeEvent,
+        changed_by: Option<WizardId>,
+    ) -> Result<Option<PersonEventRecord>> {
+        PersonRepository::add_event(self, person_id, event, changed_by).await
+    }
+
+    async fn update_event(
+        &self,
+        event_id: Uuid,
+        event: LifeEvent,
+        changed_by: Option<WizardId>,
+    ) -> Result<Option<PersonEventRecord>> {
+        PersonRepository::update_event(self, event_id, event, changed_by).await
+    }
+
+    async fn delete_event(&self, event_id: Uuid, changed_by: Option<WizardId>) -> Result<bool> {
+        PersonRepository::delete_event(self, event_id, changed_by).await
+    }
+
+    async fn delete(
+        &self,
+        id: PersonId,
+        force: bool,
+        deleted_by: Option<WizardId>,
+    ) -> Result<bool> {
+        PersonRepository::delete(self, id, force, deleted_by).await
+    }
+
+    async fn restore(&self, id: PersonId, restored_by: Option<WizardId>) -> Result<bool> {
+        PersonRepository::restore(self, id, restored_by).await
+    }
+
+    async fn trash(&self) -> Result<Vec<Trashed<Person>>> {
+        PersonRepository::trash(self).await
+    }
+
+    async fn purge_trash(&self, before: NaiveDateTime) -> Result<u64> {
+        PersonRepository::purge_trash(self, before).await
+    }
+
+    async fn history(&self, id: PersonId) -> Result<Vec<Revision>> {
+        PersonRepository::history(self, id).await
+    }
+
+    async fn revert(
+        &self,
+        id: PersonId,
+        revision: i64,
+        reverted_by: Option<WizardId>,
+    ) -> Result<Option<Person>> {
+        PersonRepository::revert(self, id, revision, reverted_by).await
+    }
+}
+
+#[async_trait]
+impl FamilyStore for FamilyRepository {
+    async fn create(&self, draft: FamilyDraft) -> Result<Family> {
+        FamilyRepository::create(self, draft).await
+    }
+
+    async fn find_by_id(&self, id: FamilyId) -> Result<Option<Family>> {
+        FamilyRepository::find_by_id(self, id).await
+    }
+
+    async fn update(&self, id: FamilyId, changes: FamilyChanges) -> Result<Option<Family>> {
+        FamilyRepository::update(self, id, changes).await
+    }
+
+    async fn append_child(
+        &self,
+        id: FamilyId,
+        child: PersonId,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
+        FamilyRepository::append_child(self, id, child, changed_by).await
+    }
+
+    async fn remove_child(
+        &self,
+        id: FamilyId,
+        child: PersonId,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
+        FamilyRepository::remove_child(self, id, child, changed_by).await
+    }
+
+    async fn set_privacy(
+        &self,
+        id: FamilyId,
+        public: bool,
+        changed_by: Option<WizardId>,
+    ) -> Result<Family> {
+        FamilyRepository::set_privacy(self, id, public, changed_by).await
+    }
+
+    async fn add_event(&self, event: FamilyEventNew) -> Result<FamilyEventRecord> {
+        FamilyRepository::add_event(self, event).await
+    }
+
+    async fn delete(
+        &self,
+        id: FamilyId,
+        force: bool,
+        deleted_by: Option<WizardId>,
+    ) -> Result<bool> {
+        FamilyRepository::delete(self, id, force, deleted_by).await
+    }
+
+    async fn restore(&self, id: FamilyId, restored_by: Option<WizardId>) -> Result<bool> {
+        FamilyRepository::restore(self, id, restored_by).await
+    }
+
+    async fn trash(&self) -> Result<Vec<Trashed<Family>>> {
+        FamilyRepository::trash(self).await
+    }
+
+    async fn purge_trash(&self, before: NaiveDateTime) -> Result<u64> {
+        FamilyRepository::purge_trash(self, before).await
+    }
+
+    async fn history(&self, id: FamilyId) -> Result<Vec<Revision>> {
+        FamilyRepository::history(self, id).await
+    }
+
+    async fn revert(
+        &self,
+        id: FamilyId,
+        revision: i64,
+        reverted_by: Option<WizardId>,
+    ) -> Result<Option<Family>> {
+        FamilyRepository::revert(self, id, revision, reverted_by).await
+    }
+}
@@ -1,10 +1,11 @@
 use anyhow::Result;
-use genealogy_types::Person;
+use genealogy_types::{Family, Person, Source};
 
 pub fn to_json(person: &Person) -> Result<String> {
     Ok(serde_json::to_string_pretty(person)?)
 }
 
-pub fn to_gedcom(person: &Person) -> Result<gedcom::GedcomRecord> {
-    gedcom::export_person(person)
+/// Fichier GEDCOM 5.5.1 complet pour un ensemble de personnes, familles et sources.
+pub fn to_gedcom(persons: &[Person], families: &[Family], sources: &[Source]) -> Result<String> {
+    Ok(gedcom::write_gedcom(persons, families, sources))
 }
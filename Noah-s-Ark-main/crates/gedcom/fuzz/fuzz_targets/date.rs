@@ -0,0 +1,21 @@
+#![no_main]
+
+use gedcom::date::{format_date, format_date7, parse_date, parse_date7};
+use libfuzzer_sys::fuzz_target;
+
+// Toute valeur `DATE` lue se réécrit et se relit à l'identique, en 5.5.1 comme en 7.0.
+fuzz_target!(|value: &str| {
+    let Some(date) = parse_date(value) else {
+        return;
+    };
+
+    let written = format_date(&date).expect("date lue mais non réécrite");
+    assert_eq!(parse_date(&written), Some(date.clone()), "{value:?} → {written:?}");
+
+    let (written, phrase) = format_date7(&date).expect("date lue mais non réécrite en 7.0");
+    assert_eq!(
+        parse_date7(&written, phrase.as_deref()),
+        Some(date),
+        "{value:?} → {written:?} ({phrase:?})"
+    );
+});
@@ -0,0 +1,698 @@
+use chrono::{Datelike, NaiveDate};
+use genealogy_types::{Calendar, CalendarDate, PreciseDate};
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+const FRENCH_MONTHS: [&str; 13] = [
+    "VEND", "BRUM", "FRIM", "NIVO", "PLUV", "VENT", "GERM", "FLOR", "PRAI", "MESS", "THER", "FRUC",
+    "COMP",
+];
+
+const HEBREW_MONTHS: [&str; 13] = [
+    "TSH", "CSH", "KSL", "TVT", "SHV", "ADR", "ADS", "NSN", "IYR", "SVN", "TMZ", "AAV", "ELL",
+];
+
+/// Échappements de calendrier GEDCOM 5.5.1 et leurs mots-clés GEDCOM 7.0.
+const CALENDAR_ESCAPES: [(Calendar, &str, &str); 4] = [
+    (Calendar::Gregorian, "@#DGREGORIAN@", "GREGORIAN"),
+    (Calendar::Julian, "@#DJULIAN@", "JULIAN"),
+    (Calendar::French, "@#DFRENCH R@", "FRENCH_R"),
+    (Calendar::Hebrew, "@#DHEBREW@", "HEBREW"),
+];
+
+/// Date GEDCOM partielle (jour, mois ou année seule).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DatePart {
+    Day(NaiveDate),
+    Month(i32, u32),
+    Year(i32),
+    /// Mois ou année d'un autre calendrier, convertis en jours grégoriens.
+    Span(NaiveDate, NaiveDate),
+}
+
+impl DatePart {
+    fn first_day(self) -> Option<NaiveDate> {
+        match self {
+            DatePart::Day(date) | DatePart::Span(date, _) => Some(date),
+            DatePart::Month(year, month) => NaiveDate::from_ymd_opt(year, month, 1),
+            DatePart::Year(year) => NaiveDate::from_ymd_opt(year, 1, 1),
+        }
+    }
+
+    fn last_day(self) -> Option<NaiveDate> {
+        match self {
+            DatePart::Day(date) | DatePart::Span(_, date) => Some(date),
+            DatePart::Month(year, month) => {
+                let (next_year, next_month) = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+                NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()
+            }
+            DatePart::Year(year) => NaiveDate::from_ymd_opt(year, 12, 31),
+        }
+    }
+}
+
+/// Date simple, dans son calendrier : `@#DJULIAN@ 12 MAR 1582`, `1699/00`, `44 B.C.`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SimpleDate {
+    calendar: Calendar,
+    day: Option<u32>,
+    month: Option<u32>,
+    year: i32,
+    /// Année double (`1699/00`), grégorienne seulement.
+    dual: bool,
+    before_christ: bool,
+}
+
+impl SimpleDate {
+    /// Date grégorienne que [`PreciseDate`] porte telle quelle.
+    fn part(self) -> Option<DatePart> {
+        if self.dual || self.calendar != Calendar::Gregorian {
+            return None;
+        }
+        self.gregorian()
+    }
+
+    /// Équivalent grégorien, en ramenant les années doubles à l'année nouveau style
+    /// et en convertissant exactement les autres calendriers.
+    fn gregorian(self) -> Option<DatePart> {
+        if self.before_christ {
+            return None;
+        }
+        if self.calendar != Calendar::Gregorian {
+            let date = CalendarDate::new(self.calendar, self.year, self.month, self.day)?;
+            return Some(match date.day {
+                Some(_) => DatePart::Day(date.first_day()?),
+                None => DatePart::Span(date.first_day()?, date.last_day()?),
+            });
+        }
+        let year = if self.dual { self.year + 1 } else { self.year };
+        match (self.day, self.month) {
+            (Some(day), Some(month)) => {
+                NaiveDate::from_ymd_opt(year, month, day).map(DatePart::Day)
+            }
+            (None, Some(month)) => Some(DatePart::Month(year, month)),
+            _ => Some(DatePart::Year(year)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DateValue {
+    Date(SimpleDate),
+    /// `ABT`, ou `CAL`/`EST` (`estimated` à vrai).
+    About(SimpleDate, bool),
+    Before(SimpleDate),
+    After(SimpleDate),
+    Between(SimpleDate, SimpleDate),
+    /// `FROM … TO …`, l'une des bornes pouvant manquer.
+    Period(Option<SimpleDate>, Option<SimpleDate>),
+    /// `INT 1650 (vers Pâques 1650)`.
+    Interpreted(SimpleDate),
+    Phrase,
+}
+
+/// Convertit une valeur `DATE` GEDCOM (5.5.1 ou 7.0) en [`PreciseDate`].
+///
+/// Ce que les variantes de [`PreciseDate`] ne portent pas exactement (`EST`,
+/// `FROM…TO`, autre calendrier, année double, phrase libre, valeur invalide) devient
+/// [`PreciseDate::Verbatim`] : la valeur est gardée pour être réécrite à l'identique,
+/// avec son équivalent grégorien approché s'il existe. Renvoie `None` pour une
+/// valeur vide.
+pub fn parse_date(value: &str) -> Option<PreciseDate> {
+    let value = canonical(value);
+    if value.is_empty() {
+        return None;
+    }
+    let parsed = parse_value(&value);
+    if let Some(date) = parsed.as_ref().and_then(native) {
+        return Some(date);
+    }
+    Some(PreciseDate::Verbatim {
+        approx: parsed.as_ref().and_then(approximate).map(Box::new),
+        value,
+    })
+}
+
+/// Valeur `DATE` GEDCOM 7.0 et sa sous-structure `PHRASE`, ramenées à la forme 5.5.1
+/// que garde [`PreciseDate::Verbatim`] (`INT 1650 (vers Pâques)`, `(vers Pâques)`).
+pub fn parse_date7(value: &str, phrase: Option<&str>) -> Option<PreciseDate> {
+    match phrase.filter(|phrase| !phrase.trim().is_empty()) {
+        Some(phrase) if value.trim().is_empty() => parse_date(&format!("({phrase})")),
+        Some(phrase) => parse_date(&format!("INT {value} ({phrase})")),
+        None => parse_date(value),
+    }
+}
+
+/// Espaces simples et mots-clés GEDCOM 7.0 (`JULIAN`, `FRENCH_R`, `BCE`) remplacés
+/// par leur forme 5.5.1, sauf dans les phrases entre parenthèses.
+fn canonical(value: &str) -> String {
+    let mut phrase = false;
+    value
+        .split_whitespace()
+        .map(|token| {
+            phrase |= token.starts_with('(');
+            match token {
+                _ if phrase => token,
+                "BCE" => "B.C.",
+                _ => CALENDAR_ESCAPES
+                    .iter()
+                    .find(|(_, _, keyword)| *keyword == token)
+                    .map_or(token, |(_, escape, _)| escape),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_value(value: &str) -> Option<DateValue> {
+    if value.starts_with('(') && value.ends_with(')') {
+        return Some(DateValue::Phrase);
+    }
+    let tokens: Vec<&str> = value.split(' ').collect();
+    let (keyword, rest) = tokens.split_first()?;
+    let keyword = keyword.to_ascii_uppercase();
+    Some(match keyword.as_str() {
+        "ABT" => DateValue::About(parse_calendar_date(rest)?, false),
+        "CAL" | "EST" => DateValue::About(parse_calendar_date(rest)?, true),
+        "BEF" => DateValue::Before(parse_calendar_date(rest)?),
+        "AFT" => DateValue::After(parse_calendar_date(rest)?),
+        "BET" => {
+            let (start, end) = split_at_keyword(rest, "AND")?;
+            DateValue::Between(parse_calendar_date(start)?, parse_calendar_date(end)?)
+        }
+        "FROM" => match split_at_keyword(rest, "TO") {
+            Some((start, end)) => DateValue::Period(
+                Some(parse_calendar_date(start)?),
+                Some(parse_calendar_date(end)?),
+            ),
+            None => DateValue::Period(Some(parse_calendar_date(rest)?), None),
+        },
+        "TO" => DateValue::Period(None, Some(parse_calendar_date(rest)?)),
+        "INT" => {
+            let open = rest.iter().position(|token| token.starts_with('('))?;
+            let phrase = rest[open..].join(" ");
+            if !phrase.ends_with(')') {
+                return None;
+            }
+            DateValue::Interpreted(parse_calendar_date(&rest[..open])?)
+        }
+        _ => DateValue::Date(parse_calendar_date(&tokens)?),
+    })
+}
+
+fn split_at_keyword<'a, 'b>(
+    tokens: &'a [&'b str],
+    keyword: &str,
+) -> Option<(&'a [&'b str], &'a [&'b str])> {
+    let index = tokens
+        .iter()
+        .position(|token| token.eq_ignore_ascii_case(keyword))?;
+    Some((&tokens[..index], &tokens[index + 1..]))
+}
+
+fn parse_calendar_date(tokens: &[&str]) -> Option<SimpleDate> {
+    let mut tokens = tokens;
+    let mut calendar = Calendar::Gregorian;
+    if let Some((first, rest)) = tokens.split_first() {
+        let first = first.to_ascii_uppercase();
+        let escape = match first.as_str() {
+            "@#DGREGORIAN@" | "GREGORIAN" => Some((Calendar::Gregorian, rest)),
+            "@#DJULIAN@" | "JULIAN" => Some((Calendar::Julian, rest)),
+            "@#DHEBREW@" | "HEBREW" => Some((Calendar::Hebrew, rest)),
+            "FRENCH_R" => Some((Calendar::French, rest)),
+            "@#DFRENCH" => match rest.split_first() {
+                Some((r, rest)) if r.eq_ignore_ascii_case("R@") => Some((Calendar::French, rest)),
+                _ => None,
+            },
+            _ if first.starts_with("@#D") => return None,
+            _ => None,
+        };
+        if let Some((escaped, rest)) = escape {
+            calendar = escaped;
+            tokens = rest;
+        }
+    }
+
+    let mut before_christ = false;
+    if let Some((last, rest)) = tokens.split_last() {
+        if matches!(last.to_ascii_uppercase().as_str(), "B.C." | "BCE" | "BC") {
+            before_christ = true;
+            tokens = rest;
+        }
+    }
+
+    let (year, dual, tokens) = match tokens {
+        // Notation révolutionnaire courante : `12 VEND AN III`.
+        [rest @ .., an, roman] if calendar == Calendar::French && an.eq_ignore_ascii_case("AN") => {
+            (parse_roman(roman)?, false, rest)
+        }
+        [rest @ .., year] => {
+            let (year, dual) = match year.split_once('/') {
+                Some((year, next)) if calendar == Calendar::Gregorian && is_digits(next) => {
+                    (year, true)
+                }
+                Some(_) => return None,
+                None => (*year, false),
+            };
+            if !is_digits(year) {
+                return None;
+            }
+            (year.parse().ok()?, dual, rest)
+        }
+        [] => return None,
+    };
+
+    let (day, month) = match tokens {
+        [] => (None, None),
+        [month] => (None, Some(parse_month(calendar, month)?)),
+        [day, month] if is_digits(day) => {
+            (Some(day.parse().ok()?), Some(parse_month(calendar, month)?))
+        }
+        _ => return None,
+    };
+
+    let date = SimpleDate {
+        calendar,
+        day,
+        month,
+        year,
+        dual,
+        before_christ,
+    };
+    is_valid(date).then_some(date)
+}
+
+fn month_names(calendar: Calendar) -> &'static [&'static str] {
+    match calendar {
+        Calendar::Gregorian | Calendar::Julian => &MONTHS,
+        Calendar::French => &FRENCH_MONTHS,
+        Calendar::Hebrew => &HEBREW_MONTHS,
+    }
+}
+
+fn parse_month(calendar: Calendar, token: &str) -> Option<u32> {
+    month_names(calendar)
+        .iter()
+        .position(|month| month.eq_ignore_ascii_case(token))
+        .map(|index| index as u32 + 1)
+}
+
+fn is_valid(date: SimpleDate) -> bool {
+    let Some(day) = date.day else {
+        return true;
+    };
+    let month = date.month.unwrap_or_default();
+    match date.calendar {
+        Calendar::Gregorian if !date.before_christ => {
+            let year = if date.dual { date.year + 1 } else { date.year };
+            NaiveDate::from_ymd_opt(year, month, day).is_some()
+        }
+        Calendar::Gregorian | Calendar::Julian => {
+            let length = match month {
+                2 if date.year % 4 == 0 => 29,
+                2 => 28,
+                4 | 6 | 9 | 11 => 30,
+                _ => 31,
+            };
+            (1..=length).contains(&day)
+        }
+        // Jours complémentaires (`COMP`) : cinq, six les années sextiles.
+        Calendar::French if month == 13 => (1..=6).contains(&day),
+        Calendar::French | Calendar::Hebrew => (1..=30).contains(&day),
+    }
+}
+
+fn is_digits(token: &str) -> bool {
+    !token.is_empty() && token.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+fn parse_roman(token: &str) -> Option<i32> {
+    let mut total = 0;
+    let mut previous = 0;
+    for c in token.chars().rev() {
+        let value = match c.to_ascii_uppercase() {
+            'I' => 1,
+            'V' => 5,
+            'X' => 10,
+            'L' => 50,
+            _ => return None,
+        };
+        if value < previous {
+            total -= value;
+        } else {
+            total += value;
+            previous = value;
+        }
+    }
+    (total > 0).then_some(total)
+}
+
+/// Calendrier commun aux dates d'une valeur.
+fn calendar(value: &DateValue) -> Option<Calendar> {
+    match value {
+        DateValue::Date(date)
+        | DateValue::About(date, _)
+        | DateValue::Before(date)
+        | DateValue::After(date)
+        | DateValue::Interpreted(date)
+        | DateValue::Period(Some(date), None)
+        | DateValue::Period(None, Some(date)) => Some(date.calendar),
+        DateValue::Between(start, end) | DateValue::Period(Some(start), Some(end)) => {
+            (start.calendar == end.calendar).then_some(start.calendar)
+        }
+        DateValue::Period(None, None) | DateValue::Phrase => None,
+    }
+}
+
+/// [`PreciseDate`] équivalente sans perte, si elle existe : telle quelle en grégorien,
+/// convertie et marquée de son calendrier sinon.
+fn native(value: &DateValue) -> Option<PreciseDate> {
+    match calendar(value)? {
+        Calendar::Gregorian => convert(value, SimpleDate::part),
+        calendar => Some(convert(value, SimpleDate::gregorian)?.in_calendar(calendar)),
+    }
+}
+
+fn convert(value: &DateValue, part: fn(SimpleDate) -> Option<DatePart>) -> Option<PreciseDate> {
+    Some(match value {
+        DateValue::Date(date) => exact(part(*date)?)?,
+        DateValue::About(date, false) => match part(*date)? {
+            DatePart::Day(day) => PreciseDate::About(day),
+            DatePart::Year(year) => PreciseDate::AboutYear(year),
+            DatePart::Month(..) | DatePart::Span(..) => return None,
+        },
+        DateValue::Before(date) => PreciseDate::Before(part(*date)?.first_day()?),
+        DateValue::After(date) => PreciseDate::After(part(*date)?.last_day()?),
+        DateValue::Between(start, end) => {
+            PreciseDate::Between(part(*start)?.first_day()?, part(*end)?.last_day()?)
+        }
+        _ => return None,
+    })
+}
+
+/// Équivalent grégorien le plus proche d'une date que [`native`] ne rend pas, marqué
+/// du calendrier d'origine.
+fn approximate(value: &DateValue) -> Option<PreciseDate> {
+    let calendar = calendar(value).unwrap_or(Calendar::Gregorian);
+    Some(approximate_gregorian(value)?.in_calendar(calendar))
+}
+
+fn approximate_gregorian(value: &DateValue) -> Option<PreciseDate> {
+    Some(match value {
+        DateValue::Date(date) | DateValue::Interpreted(date) => exact(date.gregorian()?)?,
+        DateValue::About(date, _) => match date.gregorian()? {
+            DatePart::Day(day) => PreciseDate::About(day),
+            DatePart::Year(year) => PreciseDate::AboutYear(year),
+            part @ (DatePart::Month(..) | DatePart::Span(..)) => exact(part)?,
+        },
+        DateValue::Before(date) => PreciseDate::Before(date.gregorian()?.first_day()?),
+        DateValue::After(date) => PreciseDate::After(date.gregorian()?.last_day()?),
+        // `FROM 1790` inclut 1790, `TO 1795` inclut 1795 : `After` et `Before` excluent
+        // leur borne.
+        DateValue::Period(Some(date), None) => {
+            PreciseDate::After(date.gregorian()?.first_day()?.pred_opt()?)
+        }
+        DateValue::Period(None, Some(date)) => {
+            PreciseDate::Before(date.gregorian()?.last_day()?.succ_opt()?)
+        }
+        DateValue::Between(start, end) | DateValue::Period(Some(start), Some(end)) => {
+            PreciseDate::Between(
+                start.gregorian()?.first_day()?,
+                end.gregorian()?.last_day()?,
+            )
+        }
+        DateValue::Period(None, None) | DateValue::Phrase => return None,
+    })
+}
+
+fn exact(part: DatePart) -> Option<PreciseDate> {
+    match part {
+        DatePart::Day(date) => Some(PreciseDate::Exact(date)),
+        DatePart::Month(..) | DatePart::Span(..) => {
+            Some(PreciseDate::Between(part.first_day()?, part.last_day()?))
+        }
+        DatePart::Year(year) => Some(PreciseDate::YearOnly(year)),
+    }
+}
+
+/// Convertit une [`PreciseDate`] en valeur `DATE` GEDCOM 5.5.1.
+///
+/// Les bornes correspondant à un mois ou une année entière sont écrites sous
+/// forme abrégée (`BEF 1801`, `MAR 1801`) afin de relire la même valeur à l'import.
+pub fn format_date(date: &PreciseDate) -> Option<String> {
+    Some(match date {
+        PreciseDate::Exact(date) => format_day(*date),
+        PreciseDate::About(date) => format!("ABT {}", format_day(*date)),
+        PreciseDate::Before(date) => format!("BEF {}", format_start(*date)),
+        PreciseDate::After(date) => format!("AFT {}", format_end(*date)),
+        PreciseDate::Between(start, end) => {
+            if start.day() == 1
+                && (start.year(), start.month()) == (end.year(), end.month())
+                && DatePart::Month(end.year(), end.month()).last_day() == Some(*end)
+            {
+                format!("{} {}", month_name(*start), start.year())
+            } else {
+                format!("BET {} AND {}", format_start(*start), format_end(*end))
+            }
+        }
+        PreciseDate::YearOnly(year) => year.to_string(),
+        PreciseDate::AboutYear(year) => format!("ABT {year}"),
+        PreciseDate::Verbatim { value, .. } if !value.trim().is_empty() => value.clone(),
+        PreciseDate::InCalendar { calendar, date } => {
+            return format_in_calendar(*calendar, date).or_else(|| format_date(date))
+        }
+        PreciseDate::Verbatim { .. } | PreciseDate::Unknown => return None,
+    })
+}
+
+/// Date grégorienne réécrite dans son calendrier d'origine, avec les mêmes formes
+/// abrégées qu'en grégorien (`@#DJULIAN@ 1580`, `BEF @#DFRENCH R@ VEND 3`).
+fn format_in_calendar(calendar: Calendar, date: &PreciseDate) -> Option<String> {
+    Some(match date {
+        PreciseDate::Exact(date) => calendar_day(CalendarDate::from_gregorian(calendar, *date))?,
+        PreciseDate::About(date) => format!(
+            "ABT {}",
+            calendar_day(CalendarDate::from_gregorian(calendar, *date))?
+        ),
+        PreciseDate::Before(date) => {
+            format!(
+                "BEF {}",
+                calendar_day(CalendarDate::start(calendar, *date))?
+            )
+        }
+        PreciseDate::After(date) => {
+            format!("AFT {}", calendar_day(CalendarDate::end(calendar, *date))?)
+        }
+        PreciseDate::Between(start, end) => match CalendarDate::span(calendar, *start, *end) {
+            Some(span) => calendar_day(span)?,
+            None => format!(
+                "BET {} AND {}",
+                calendar_day(CalendarDate::start(calendar, *start))?,
+                calendar_day(CalendarDate::end(calendar, *end))?
+            ),
+        },
+        _ => return None,
+    })
+}
+
+/// `@#DFRENCH R@ 12 VEND 3`, ou `None` hors des années que le calendrier connaît.
+fn calendar_day(date: CalendarDate) -> Option<String> {
+    let date = CalendarDate::new(date.calendar, date.year, date.month, date.day)?;
+    let (_, escape, _) = CALENDAR_ESCAPES
+        .iter()
+        .find(|(calendar, _, _)| *calendar == date.calendar)?;
+    let mut tokens = vec![escape.to_string()];
+    tokens.extend(date.day.map(|day| day.to_string()));
+    tokens.extend(
+        date.month
+            .map(|month| month_names(date.calendar)[month as usize - 1].to_string()),
+    );
+    tokens.push(date.year.to_string());
+    Some(tokens.join(" "))
+}
+
+/// Comme [`format_date`], en GEDCOM 7.0 : mots-clés de calendrier, `BCE`, et
+/// phrase (`INT`, `(…)`) renvoyée à part pour la sous-structure `PHRASE`.
+pub fn format_date7(date: &PreciseDate) -> Option<(String, Option<String>)> {
+    let value = format_date(date)?;
+    if let Some(phrase) = value.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+        if !phrase.trim().is_empty() {
+            return Some((String::new(), Some(phrase.to_string())));
+        }
+    }
+    let interpreted = value
+        .strip_prefix("INT ")
+        .and_then(|rest| rest.split_once(" ("))
+        .and_then(|(date, phrase)| Some((date, phrase.strip_suffix(')')?)))
+        .filter(|(_, phrase)| !phrase.trim().is_empty());
+    Some(match interpreted {
+        Some((date, phrase)) => (gedcom7_keywords(date), Some(phrase.to_string())),
+        None => (gedcom7_keywords(&value), None),
+    })
+}
+
+/// Inverse de [`canonical`] : échappements 5.5.1 remplacés par les mots-clés 7.0.
+fn gedcom7_keywords(value: &str) -> String {
+    let mut phrase = false;
+    let mut tokens = value.split(' ').peekable();
+    let mut keywords = Vec::new();
+    while let Some(token) = tokens.next() {
+        phrase |= token.starts_with('(');
+        keywords.push(match token {
+            _ if phrase => token,
+            "B.C." => "BCE",
+            "@#DFRENCH" if tokens.peek() == Some(&"R@") => {
+                tokens.next();
+                "FRENCH_R"
+            }
+            _ => CALENDAR_ESCAPES
+                .iter()
+                .find(|(_, escape, _)| *escape == token)
+                .map_or(token, |(_, _, keyword)| keyword),
+        });
+    }
+    keywords.join(" ")
+}
+
+fn format_day(date: NaiveDate) -> String {
+    format!("{} {} {}", date.day(), month_name(date), date.year())
+}
+
+fn format_start(date: NaiveDate) -> String {
+    match (date.month(), date.day()) {
+        (1, 1) => date.year().to_string(),
+        (_, 1) => format!("{} {}", month_name(date), date.year()),
+        _ => format_day(date),
+    }
+}
+
+fn format_end(date: NaiveDate) -> String {
+    if DatePart::Year(date.year()).last_day() == Some(date) {
+        date.year().to_string()
+    } else if DatePart::Month(date.year(), date.month()).last_day() == Some(date) {
+        format!("{} {}", month_name(date), date.year())
+    } else {
+        format_day(date)
+    }
+}
+
+fn month_name(date: NaiveDate) -> &'static str {
+    MONTHS[date.month0() as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// Relit la valeur écrite, en 5.5.1 comme en 7.0.
+    fn round_trip(date: &PreciseDate) -> String {
+        let value = format_date(date).unwrap();
+        assert_eq!(parse_date(&value).as_ref(), Some(date), "{value}");
+        let (value7, phrase) = format_date7(date).unwrap();
+        assert_eq!(
+            parse_date7(&value7, phrase.as_deref()).as_ref(),
+            Some(date),
+            "{value7}"
+        );
+        value
+    }
+
+    #[test]
+    fn qualified_dates_map_to_their_variant() {
+        let cases = [
+            ("ABT 1750", PreciseDate::AboutYear(1750)),
+            ("BEF 12 MAR 1801", PreciseDate::Before(day(1801, 3, 12))),
+            (
+                "BET 1700 AND 1710",
+                PreciseDate::Between(day(1700, 1, 1), day(1710, 12, 31)),
+            ),
+        ];
+        for (value, expected) in cases {
+            let date = parse_date(value).unwrap();
+            assert_eq!(date, expected, "{value}");
+            assert_eq!(round_trip(&date), value);
+        }
+    }
+
+    #[test]
+    fn periods_are_kept_verbatim_with_their_span() {
+        let date = parse_date("FROM 1790 TO 1795").unwrap();
+        assert_eq!(
+            date,
+            PreciseDate::Verbatim {
+                value: "FROM 1790 TO 1795".to_string(),
+                approx: Some(Box::new(PreciseDate::Between(
+                    day(1790, 1, 1),
+                    day(1795, 12, 31)
+                ))),
+            }
+        );
+        assert_eq!(round_trip(&date), "FROM 1790 TO 1795");
+    }
+
+    #[test]
+    fn other_calendars_are_converted_and_marked() {
+        let date = parse_date("@#DJULIAN@ 1582").unwrap();
+        let PreciseDate::InCalendar {
+            calendar,
+            date: gregorian,
+        } = &date
+        else {
+            panic!("{date:?}");
+        };
+        assert_eq!(*calendar, Calendar::Julian);
+        assert_eq!(
+            **gregorian,
+            PreciseDate::Between(day(1582, 1, 11), day(1583, 1, 10))
+        );
+        assert_eq!(round_trip(&date), "@#DJULIAN@ 1582");
+
+        let date = parse_date("@#DFRENCH R@ 12 VEND AN III").unwrap();
+        assert_eq!(
+            date,
+            PreciseDate::Exact(day(1794, 10, 3)).in_calendar(Calendar::French)
+        );
+        assert_eq!(round_trip(&date), "@#DFRENCH R@ 12 VEND 3");
+    }
+
+    #[test]
+    fn phrases_are_kept_verbatim() {
+        let date = parse_date("(about Easter 1650)").unwrap();
+        assert_eq!(
+            date,
+            PreciseDate::Verbatim {
+                value: "(about Easter 1650)".to_string(),
+                approx: None,
+            }
+        );
+        assert_eq!(round_trip(&date), "(about Easter 1650)");
+        assert_eq!(
+            format_date7(&date),
+            Some((String::new(), Some("about Easter 1650".to_string())))
+        );
+
+        let date = parse_date("INT 1650 (about Easter)").unwrap();
+        assert_eq!(
+            date,
+            PreciseDate::Verbatim {
+                value: "INT 1650 (about Easter)".to_string(),
+                approx: Some(Box::new(PreciseDate::YearOnly(1650))),
+            }
+        );
+        assert_eq!(round_trip(&date), "INT 1650 (about Easter)");
+        assert_eq!(
+            format_date7(&date),
+            Some(("1650".to_string(), Some("about Easter".to_string())))
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use std::io::{self, BufRead, Read};
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Encodage d'un fichier GEDCOM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ANSEL (ANSI Z39.47) : diacritiques combinants placés avant la lettre.
+    Ansel,
+    /// Windows-1252, utilisé pour `ANSI`, `ASCII` et `IBM WINDOWS`.
+    Windows1252,
+}
+
+/// Détecte l'encodage à partir du début du fichier : BOM, sinon `HEAD.CHAR`.
+///
+/// Un fichier déclaré ANSEL/ANSI dont les octets non ASCII forment de l'UTF-8
+/// valide est lu en UTF-8 ; un fichier « UTF-8 » invalide est lu en Windows-1252.
+pub fn detect_encoding(head: &[u8]) -> Encoding {
+    match head {
+        [0xEF, 0xBB, 0xBF, ..] => return Encoding::Utf8,
+        [0xFF, 0xFE, ..] | [b'0', 0, ..] => return Encoding::Utf16Le,
+        [0xFE, 0xFF, ..] | [0, b'0', ..] => return Encoding::Utf16Be,
+        _ => {}
+    }
+
+    let utf8 = match std::str::from_utf8(head) {
+        Ok(_) => true,
+        // Seul le dernier caractère, tronqué, est invalide.
+        Err(err) => err.error_len().is_none(),
+    };
+    let ascii = head.is_ascii();
+
+    match declared_charset(head).as_deref() {
+        Some("ANSEL") if ascii || !utf8 => Encoding::Ansel,
+        Some("ANSI" | "ASCII" | "IBM WINDOWS" | "WINDOWS-1252" | "CP1252" | "ISO-8859-1")
+            if ascii || !utf8 =>
+        {
+            Encoding::Windows1252
+        }
+        _ if utf8 => Encoding::Utf8,
+        _ => Encoding::Windows1252,
+    }
+}
+
+/// Valeur de `1 CHAR` dans l'en-tête, en majuscules.
+fn declared_charset(head: &[u8]) -> Option<String> {
+    head.split(|byte| *byte == b'\n' || *byte == b'\r')
+        .map(|line| String::from_utf8_lossy(line).trim().to_string())
+        .filter(|line| !line.is_empty())
+        .skip(1)
+        .take_while(|line| !line.starts_with('0'))
+        .find_map(|line| {
+            line.strip_prefix("1 CHAR")
+                .map(|value| value.trim().to_ascii_uppercase())
+        })
+}
+
+/// Décode un fichier GEDCOM complet en UTF-8, quel que soit son encodage.
+pub fn decode(bytes: &[u8]) -> io::Result<String> {
+    let mut text = String::new();
+    DecodingReader::new(bytes)?.read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Adaptateur qui transcode un flux GEDCOM en UTF-8 ligne par ligne,
+/// pour alimenter [`GedcomReader`](crate::parser::GedcomReader).
+pub struct DecodingReader<R> {
+    inner: R,
+    encoding: Encoding,
+    raw: Vec<u8>,
+    decoded: Vec<u8>,
+    position: usize,
+}
+
+impl<R: BufRead> DecodingReader<R> {
+    /// Détecte l'encodage sur le premier bloc lu et saute le BOM éventuel.
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let head = inner.fill_buf()?;
+        let encoding = detect_encoding(head);
+        let bom = match (encoding, head) {
+            (Encoding::Utf8, [0xEF, 0xBB, 0xBF, ..]) => 3,
+            (Encoding::Utf16Le, [0xFF, 0xFE, ..]) | (Encoding::Utf16Be, [0xFE, 0xFF, ..]) => 2,
+            _ => 0,
+        };
+        inner.consume(bom);
+        Ok(Self {
+            inner,
+            encoding,
+            raw: Vec::new(),
+            decoded: Vec::new(),
+            position: 0,
+        })
+    }
+
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Lit et transcode la ligne suivante ; `false` en fin de flux.
+    fn next_line(&mut self) -> io::Result<bool> {
+        self.raw.clear();
+        match self.encoding {
+            Encoding::Utf16Le | Encoding::Utf16Be => self.read_utf16_line()?,
+            _ => {
+                self.inner.read_until(b'\n', &mut self.raw)?;
+            }
+        }
+        if self.raw.is_empty() {
+            return Ok(false);
+        }
+
+        self.position = 0;
+        self.decoded.clear();
+        match self.encoding {
+            // La validation UTF-8 reste à la charge du lecteur GEDCOM.
+            Encoding::Utf8 => self.decoded.extend_from_slice(&self.raw),
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                let big_endian = self.encoding == Encoding::Utf16Be;
+                let units = self.raw.chunks(2).map(|pair| match (pair, big_endian) {
+                    ([high, low], true) | ([low, high], false) => u16::from_be_bytes([*high, *low]),
+                    _ => char::REPLACEMENT_CHARACTER as u16,
+                });
+                let text: String = char::decode_utf16(units)
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect();
+                self.decoded.extend_from_slice(text.as_bytes());
+            }
+            Encoding::Ansel => {
+                let text: String = decode_ansel(&self.raw).nfc().collect();
+                self.decoded.extend_from_slice(text.as_bytes());
+            }
+            Encoding::Windows1252 => {
+                let text: String = self.raw.iter().map(|byte| windows_1252(*byte)).collect();
+                self.decoded.extend_from_slice(text.as_bytes());
+            }
+        }
+        Ok(true)
+    }
+
+    /// Lit jusqu'à l'unité `U+000A` alignée (un octet `0x0A` isolé peut
+    /// appartenir à un autre caractère).
+    fn read_utf16_line(&mut self) -> io::Result<()> {
+        loop {
+            if self.inner.read_until(b'\n', &mut self.raw)? == 0 {
+                return Ok(());
+            }
+            if self.raw.last() != Some(&b'\n') {
+                return Ok(());
+            }
+            let index = self.raw.len() - 1;
+            match self.encoding {
+                Encoding::Utf16Le if index.is_multiple_of(2) => {
+                    let mut high = [0u8];
+                    if self.inner.read(&mut high)? == 0 {
+                        return Ok(());
+                    }
+                    self.raw.push(high[0]);
+                    if high[0] == 0 {
+                        return Ok(());
+                    }
+                }
+                Encoding::Utf16Be if !index.is_multiple_of(2) && self.raw[index - 1] == 0 => {
+                    return Ok(())
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        self.consume(count);
+        Ok(count)
+    }
+}
+
+impl<R: BufRead> BufRead for DecodingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.position >= self.decoded.len() {
+            if !self.next_line()? {
+                return Ok(&[]);
+            }
+        }
+        Ok(&self.decoded[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.decoded.len());
+    }
+}
+
+/// Transcode de l'ANSEL : les diacritiques (`0xE0`–`0xFE`) précèdent la lettre
+/// en ANSEL et la suivent en Unicode.
+fn decode_ansel(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len());
+    let mut pending = Vec::new();
+    for &byte in bytes {
+        if let Some(mark) = ansel_combining(byte) {
+            pending.push(mark);
+            continue;
+        }
+        let base = if byte.is_ascii() {
+            byte as char
+        } else {
+            ansel_spacing(byte).unwrap_or(char::REPLACEMENT_CHARACTER)
+        };
+        text.push(base);
+        text.extend(pending.drain(..));
+    }
+    // Diacritique sans lettre : conservé tel quel.
+    text.extend(pending);
+    text
+}
+
+fn ansel_spacing(byte: u8) -> Option<char> {
+    Some(match byte {
+        0xA1 => 'Ł',
+        0xA2 => 'Ø',
+        0xA3 => 'Đ',
+        0xA4 => 'Þ',
+        0xA5 => 'Æ',
+        0xA6 => 'Œ',
+        0xA7 => 'ʹ',
+        0xA8 => '·',
+        0xA9 => '♭',
+        0xAA => '®',
+        0xAB => '±',
+        0xAC => 'Ơ',
+        0xAD => 'Ư',
+        0xAE => 'ʼ',
+        0xB0 => 'ʻ',
+        0xB1 => 'ł',
+        0xB2 => 'ø',
+        0xB3 => 'đ',
+        0xB4 => 'þ',
+        0xB5 => 'æ',
+        0xB6 => 'œ',
+        0xB7 => 'ʺ',
+        0xB8 => 'ı',
+        0xB9 => '£',
+        0xBA => 'ð',
+        0xBC => 'ơ',
+        0xBD => 'ư',
+        // Extensions GEDCOM.
+        0xBE => '□',
+        0xBF => '■',
+        0xC0 => '°',
+        0xC1 => 'ℓ',
+        0xC2 => '℗',
+        0xC3 => '©',
+        0xC4 => '♯',
+        0xC5 => '¿',
+        0xC6 => '¡',
+        0xC7 | 0xCF => 'ß',
+        0xC8 => '€',
+        _ => return None,
+    })
+}
+
+fn ansel_combining(byte: u8) -> Option<char> {
+    Some(match byte {
+        0xE0 => '\u{0309}',
+        0xE1 => '\u{0300}',
+        0xE2 => '\u{0301}',
+        0xE3 => '\u{0302}',
+        0xE4 => '\u{0303}',
+        0xE5 => '\u{0304}',
+        0xE6 => '\u{0306}',
+        0xE7 => '\u{0307}',
+        0xE8 => '\u{0308}',
+        0xE9 => '\u{030C}',
+        0xEA => '\u{030A}',
+        0xEB => '\u{FE20}',
+        0xEC => '\u{FE21}',
+        0xED => '\u{0315}',
+        0xEE => '\u{030B}',
+        0xEF => '\u{0310}',
+        0xF0 => '\u{0327}',
+        0xF1 => '\u{0328}',
+        0xF2 => '\u{0323}',
+        0xF3 => '\u{0324}',
+        0xF4 => '\u{0325}',
+        0xF5 => '\u{0333}',
+        0xF6 => '\u{0332}',
+        0xF7 => '\u{0326}',
+        0xF8 => '\u{031C}',
+        0xF9 => '\u{032E}',
+        0xFA => '\u{FE22}',
+        0xFB => '\u{FE23}',
+        0xFE => '\u{0313}',
+        _ => return None,
+    })
+}
+
+/// Windows-1252 : Latin-1, sauf la plage `0x80`–`0x9F`.
+fn windows_1252(byte: u8) -> char {
+    match byte {
+        0x80 => '€',
+        0x82 => '‚',
+        0x83 => 'ƒ',
+        0x84 => '„',
+        0x85 => '…',
+        0x86 => '†',
+        0x87 => '‡',
+        0x88 => 'ˆ',
+        0x89 => '‰',
+        0x8A => 'Š',
+        0x8B => '‹',
+        0x8C => 'Œ',
+        0x8E => 'Ž',
+        0x91 => '\u{2018}',
+        0x92 => '\u{2019}',
+        0x93 => '\u{201C}',
+        0x94 => '\u{201D}',
+        0x95 => '•',
+        0x96 => '–',
+        0x97 => '—',
+        0x98 => '˜',
+        0x99 => '™',
+        0x9A => 'š',
+        0x9B => '›',
+        0x9C => 'œ',
+        0x9E => 'ž',
+        0x9F => 'Ÿ',
+        0x81 | 0x8D | 0x8F | 0x90 | 0x9D => char::REPLACEMENT_CHARACTER,
+        byte => byte as char,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ansel_accents_precede_their_letter() {
+        let bytes = b"0 HEAD\n1 CHAR ANSEL\n0 @I1@ INDI\n1 NAME Ren\xE2e /Dupont/\n0 TRLR\n";
+        assert_eq!(detect_encoding(bytes), Encoding::Ansel);
+        let text = decode(bytes).unwrap();
+        assert!(text.contains("1 NAME René /Dupont/\n"), "{text}");
+    }
+
+    #[test]
+    fn utf16_le_files_lose_their_bom() {
+        let text = "0 HEAD\n1 CHAR UNICODE\n0 @I1@ INDI\n1 NAME Zoë /Ła/\n0 TRLR\n";
+        let bytes: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        assert_eq!(detect_encoding(&bytes), Encoding::Utf16Le);
+        assert_eq!(decode(&bytes).unwrap(), text);
+    }
+
+    #[test]
+    fn ansi_files_are_read_as_windows_1252() {
+        let bytes = b"0 HEAD\n1 CHAR ANSI\n0 @I1@ INDI\n1 NAME Fran\xE7ois /C\x9Cur/\n0 TRLR\n";
+        assert_eq!(detect_encoding(bytes), Encoding::Windows1252);
+        let text = decode(bytes).unwrap();
+        assert!(text.contains("1 NAME François /Cœur/\n"), "{text}");
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::{BTreeMap, HashMap};
+
+use uuid::Uuid;
+
+use crate::parser::GedcomNode;
+use crate::vendor::VendorMapping;
+
+/// Identifiant externe (`EXID`) : valeur et URI de l'autorité qui l'attribue (`TYPE`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalId {
+    pub value: String,
+    pub kind: Option<String>,
+}
+
+/// Données GEDCOM sans équivalent dans les modèles métiers, conservées pour la réécriture.
+///
+/// Les clés sont les UUID des personnes, familles et sources importées.
+#[derive(Debug, Clone, Default)]
+pub struct GedcomExtras {
+    /// Déclarations `HEAD.SCHMA` : tag d'extension → URI.
+    pub schema: BTreeMap<String, String>,
+    /// Identifiants `EXID` par enregistrement.
+    pub external_ids: HashMap<Uuid, Vec<ExternalId>>,
+    /// Sous-structures d'extension (`_TAG`) de niveau 1, par enregistrement.
+    pub extensions: HashMap<Uuid, Vec<GedcomNode>>,
+    /// Enregistrements d'extension de niveau 0.
+    pub extension_records: Vec<GedcomNode>,
+    /// xref d'origine → UUID, pour réécrire les pointeurs des extensions.
+    pub pointers: HashMap<String, Uuid>,
+}
+
+/// Tag d'extension (`_UID`, `_MARNM`…).
+pub fn is_extension_tag(tag: &str) -> bool {
+    tag.starts_with('_')
+}
+
+/// Identifiants stables d'un enregistrement, pour le rapprocher d'un import précédent :
+/// `_UID`, `_FSFTID`, `REFN` et `EXID` qualifiés par un `TYPE` (voir
+/// [`VendorMapping::identifiers`]).
+///
+/// Renvoie des couples (type, valeur) ; un `_UID` est normalisé (majuscules, sans
+/// tirets ni somme de contrôle).
+pub fn record_identifiers(record: &GedcomNode) -> Vec<(String, String)> {
+    VendorMapping::builtin().identifiers(record)
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::io::{Read, Seek, Write};
+
+use anyhow::{bail, Context, Result};
+use genealogy_types::{Family, ImageReference, Person, Source};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::extras::GedcomExtras;
+use crate::import::{import_gedcom, GedcomData};
+use crate::writer::write_gedcom7;
+
+/// Nom imposé du jeu de données à la racine d'une archive GEDZIP.
+const DATASET: &str = "gedcom.ged";
+
+/// Fichier média embarqué dans une archive `.gdz`.
+///
+/// `image.url` est le chemin relatif utilisé par `FILE` dans `gedcom.ged`.
+#[derive(Debug, Clone)]
+pub struct GedzipMedia {
+    pub image: ImageReference,
+    pub content: Vec<u8>,
+}
+
+/// Contenu d'une archive `.gdz` : jeu de données importé et médias embarqués.
+#[derive(Debug, Default)]
+pub struct GedzipData {
+    pub data: GedcomData,
+    pub media: Vec<GedzipMedia>,
+}
+
+/// Lit une archive GEDZIP (`gedcom.ged` + médias).
+///
+/// Chaque fichier de l'archive devient un [`GedzipMedia`] ; sa description est
+/// reprise de l'`OBJE` qui le cite, s'il existe.
+pub fn read_gedzip<R: Read + Seek>(reader: R) -> Result<GedzipData> {
+    let mut archive = ZipArchive::new(reader).context("archive GEDZIP illisible")?;
+
+    let mut text = String::new();
+    archive
+        .by_name(DATASET)
+        .with_context(|| format!("`{DATASET}` absent de l'archive"))?
+        .read_to_string(&mut text)
+        .with_context(|| format!("`{DATASET}` n'est pas en UTF-8"))?;
+    let data = import_gedcom(&text)?;
+
+    // Premier `OBJE` citant chaque chemin.
+    let mut cited: HashMap<String, &ImageReference> = HashMap::new();
+    for image in data.persons.iter().flat_map(|person| person.images.iter()) {
+        cited.entry(percent_decode(&image.url)).or_insert(image);
+    }
+
+    let mut media = Vec::new();
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        if file.is_dir() || file.name() == DATASET {
+            continue;
+        }
+        let path = file.name().to_string();
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+
+        let image = cited
+            .get(&path)
+            .map(|image| (*image).clone())
+            .unwrap_or_else(|| ImageReference {
+                url: percent_encode(&path),
+                description: None,
+                is_primary: false,
+            });
+        media.push(GedzipMedia { image, content });
+    }
+
+    Ok(GedzipData { data, media })
+}
+
+/// Écrit une archive GEDZIP : `gedcom.ged` en GEDCOM 7.0 puis chaque média à son chemin.
+pub fn write_gedzip<W: Write + Seek>(
+    writer: W,
+    persons: &[Person],
+    families: &[Family],
+    sources: &[Source],
+    extras: &GedcomExtras,
+    media: &[GedzipMedia],
+) -> Result<W> {
+    let mut zip = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file(DATASET, options)?;
+    zip.write_all(write_gedcom7(persons, families, sources, extras).as_bytes())?;
+
+    for file in media {
+        let path = percent_decode(&file.image.url);
+        if path.contains("://")
+            || path.starts_with('/')
+            || path.split('/').any(|segment| segment == "..")
+        {
+            bail!("chemin de média invalide dans une archive GEDZIP : `{path}`");
+        }
+        zip.start_file(path, options)?;
+        zip.write_all(&file.content)?;
+    }
+
+    Ok(zip.finish()?)
+}
+
+/// Les `FILE` d'une archive GEDZIP sont des URI relatives : `%20` → espace, etc.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn percent_encode(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn media_keep_the_description_of_their_obje() {
+        let data = import_gedcom(
+            "0 HEAD\n1 GEDC\n2 VERS 7.0\n0 @I1@ INDI\n1 NAME Jean /Martin/\n\
+             1 OBJE\n2 FILE photos/jean%20martin.jpg\n3 FORM jpg\n3 TITL Portrait\n0 TRLR\n",
+        )
+        .unwrap();
+        let media = [
+            GedzipMedia {
+                image: data.persons[0].images[0].clone(),
+                content: b"jpeg".to_vec(),
+            },
+            GedzipMedia {
+                image: ImageReference {
+                    url: "scans/acte.pdf".to_string(),
+                    description: None,
+                    is_primary: false,
+                },
+                content: b"pdf".to_vec(),
+            },
+        ];
+        let archive = write_gedzip(
+            Cursor::new(Vec::new()),
+            &data.persons,
+            &data.families,
+            &data.sources,
+            &data.extras,
+            &media,
+        )
+        .unwrap();
+
+        let read = read_gedzip(Cursor::new(archive.into_inner())).unwrap();
+        assert_eq!(read.media.len(), 2);
+        assert_eq!(read.media[0].image.url, "photos/jean%20martin.jpg");
+        assert_eq!(read.media[0].image.description.as_deref(), Some("Portrait"));
+        assert_eq!(read.media[0].content, b"jpeg");
+        assert_eq!(read.media[1].image.description, None);
+    }
+}
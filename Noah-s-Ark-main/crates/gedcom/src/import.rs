@@ -0,0 +1,700 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use chrono::{NaiveDateTime, Utc};
+use genealogy_types::{
+    EventType, Family, FamilyId, ImageReference, ImportReport, ImportWarningKind, LifeEvent,
+    Person, PersonId, Place, PreciseDate, Sex, Source, Title,
+};
+use uuid::Uuid;
+
+use crate::date::parse_date7;
+use crate::encoding::DecodingReader;
+use crate::extras::{is_extension_tag, ExternalId, GedcomExtras};
+use crate::parser::{parse_records, GedcomNode, GedcomReader};
+use crate::report::{inspect, PointerUse};
+use crate::vendor::{plain_name, VendorField, VendorMapping, VendorScope};
+
+/// Résultat d'un import GEDCOM : entités métiers et correspondance xref → identifiant.
+#[derive(Debug, Default)]
+pub struct GedcomData {
+    pub persons: Vec<Person>,
+    pub families: Vec<Family>,
+    pub sources: Vec<Source>,
+    pub person_xrefs: HashMap<String, PersonId>,
+    pub family_xrefs: HashMap<String, FamilyId>,
+    pub source_xrefs: HashMap<String, Uuid>,
+    pub extras: GedcomExtras,
+    /// Tags ignorés, valeurs perdues, pointeurs orphelins et doublons relevés.
+    pub report: ImportReport,
+}
+
+/// Importe un fichier GEDCOM complet (5.5.1 ou 7.0).
+pub fn import_gedcom(input: &str) -> Result<GedcomData> {
+    let records = parse_records(input)?;
+    Ok(GedcomImporter::new().import(&records))
+}
+
+/// Importe un fichier GEDCOM brut : l'encodage (UTF-8, UTF-16, ANSEL, Windows-1252)
+/// est détecté par le BOM ou `HEAD.CHAR`.
+pub fn import_gedcom_bytes(input: &[u8]) -> Result<GedcomData> {
+    let records = GedcomReader::new(DecodingReader::new(input)?).collect::<Result<Vec<_>>>()?;
+    Ok(GedcomImporter::new().import(&records))
+}
+
+/// Convertit des enregistrements GEDCOM en entités métiers.
+///
+/// [`import`](Self::import) traite un fichier chargé en mémoire. Pour un import en
+/// flux, chaque enregistrement passe d'abord par [`index`](Self::index), puis
+/// [`resolve_sources`](Self::resolve_sources) est appelé une fois avant de convertir
+/// les `INDI` et `FAM` avec [`person`](Self::person) et [`family`](Self::family).
+///
+/// Les tags propriétaires connus (`_MARNM`, `_AKA`, `_PRIM`…) sont convertis selon
+/// une [`VendorMapping`], remplaçable par [`with_mapping`](Self::with_mapping).
+pub struct GedcomImporter {
+    notes: HashMap<String, GedcomNode>,
+    objects: HashMap<String, GedcomNode>,
+    repositories: HashMap<String, GedcomNode>,
+    source_records: Vec<GedcomNode>,
+    sources: HashMap<String, Source>,
+    /// xrefs déjà définis, pour repérer doublons et pointeurs orphelins.
+    defined: HashSet<String>,
+    /// Pointeurs vers des xrefs pas encore définis au moment de leur lecture.
+    pending: Vec<PointerUse>,
+    mapping: VendorMapping,
+    /// Un `NICK` unique est une liste séparée par des virgules, sauf en 7.0 et dans
+    /// les fichiers écrits par `write_gedcom` (un `NICK` par surnom).
+    nickname_lists: bool,
+    data: GedcomData,
+    now: NaiveDateTime,
+}
+
+impl Default for GedcomImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GedcomImporter {
+    pub fn new() -> Self {
+        Self {
+            notes: HashMap::new(),
+            objects: HashMap::new(),
+            repositories: HashMap::new(),
+            source_records: Vec::new(),
+            sources: HashMap::new(),
+            defined: HashSet::new(),
+            pending: Vec::new(),
+            mapping: VendorMapping::default(),
+            nickname_lists: true,
+            data: GedcomData::default(),
+            now: Utc::now().naive_utc(),
+        }
+    }
+
+    /// Reprend des identifiants déjà attribués (import interrompu puis relancé).
+    pub fn with_ids(
+        person_xrefs: HashMap<String, PersonId>,
+        family_xrefs: HashMap<String, FamilyId>,
+    ) -> Self {
+        let mut importer = Self::new();
+        importer.data.person_xrefs = person_xrefs;
+        importer.data.family_xrefs = family_xrefs;
+        importer
+    }
+
+    /// Remplace la table des tags propriétaires.
+    pub fn with_mapping(mut self, mapping: VendorMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    pub fn import(mut self, records: &[GedcomNode]) -> GedcomData {
+        // Premier passage : identifiants et enregistrements partagés, pour résoudre
+        // les références en avant.
+        for record in records {
+            self.index(record);
+        }
+        self.resolve_sources();
+
+        for record in records {
+            match record.tag.as_str() {
+                "INDI" => {
+                    let person = self.person(record);
+                    self.keep_extras(person.id.0, record);
+                    self.data.persons.push(person);
+                }
+                "FAM" => {
+                    let family = self.family(record);
+                    self.keep_extras(family.id.0, record);
+                    self.data.families.push(family);
+                }
+                _ => {}
+            }
+        }
+
+        self.data.report.persons = self.data.persons.len();
+        self.data.report.families = self.data.families.len();
+        self.data
+    }
+
+    /// Attribue un identifiant aux `INDI`/`FAM`/`SOUR` et garde en mémoire les
+    /// enregistrements partagés (`NOTE`, `SNOTE`, `OBJE`, `REPO`, `SOUR`).
+    ///
+    /// Alimente aussi le rapport d'import : un xref défini deux fois garde
+    /// l'identifiant de sa première définition.
+    pub fn index(&mut self, record: &GedcomNode) {
+        self.report(record);
+
+        if record.tag == "HEAD" {
+            let version = record
+                .child("GEDC")
+                .and_then(|gedc| gedc.child_value("VERS"));
+            self.nickname_lists = record.child_value("SOUR") != Some("ROGLO")
+                && !version.is_some_and(|version| version.starts_with('7'));
+            for tag in record
+                .child("SCHMA")
+                .into_iter()
+                .flat_map(|schema| schema.children_with("TAG"))
+                .filter_map(GedcomNode::text)
+            {
+                if let Some((tag, uri)) = tag.trim().split_once(' ') {
+                    self.data
+                        .extras
+                        .schema
+                        .insert(tag.to_string(), uri.trim().to_string());
+                }
+            }
+        } else if is_extension_tag(&record.tag) {
+            self.data.extras.extension_records.push(record.clone());
+        }
+
+        let Some(xref) = record.xref.clone() else {
+            return;
+        };
+        match record.tag.as_str() {
+            "INDI" => {
+                self.data
+                    .person_xrefs
+                    .entry(xref)
+                    .or_insert_with(|| PersonId(Uuid::new_v4()));
+            }
+            "FAM" => {
+                self.data
+                    .family_xrefs
+                    .entry(xref)
+                    .or_insert_with(|| FamilyId(Uuid::new_v4()));
+            }
+            "SOUR" => {
+                // Un doublon est déjà signalé (`duplicate_xref`) : seule la première
+                // définition est lue.
+                if let Entry::Vacant(entry) = self.data.source_xrefs.entry(xref) {
+                    entry.insert(Uuid::new_v4());
+                    self.source_records.push(record.clone());
+                }
+            }
+            "NOTE" | "SNOTE" => {
+                self.notes.insert(xref, record.clone());
+            }
+            "OBJE" => {
+                self.objects.insert(xref, record.clone());
+            }
+            "REPO" => {
+                self.repositories.insert(xref, record.clone());
+            }
+            _ => {}
+        }
+    }
+
+    /// Convertit les `SOUR` indexés, une fois tous les `REPO` et `NOTE` connus,
+    /// et signale les pointeurs restés sans cible.
+    pub fn resolve_sources(&mut self) {
+        for pointer in std::mem::take(&mut self.pending) {
+            if !self.defined.contains(&pointer.target) {
+                self.data.report.warn(
+                    ImportWarningKind::DanglingXref,
+                    Some(pointer.line),
+                    pointer.record.as_deref(),
+                    pointer.path,
+                    Some(&format!("@{}@", pointer.target)),
+                );
+            }
+        }
+
+        for record in std::mem::take(&mut self.source_records) {
+            if let Some(xref) = record.xref.as_deref() {
+                let source = self.source_record(xref, &record);
+                self.keep_extras(source.id, &record);
+                self.sources.insert(xref.to_string(), source.clone());
+                self.data.sources.push(source);
+            }
+        }
+
+        let data = &mut self.data;
+        data.extras.pointers = data
+            .person_xrefs
+            .iter()
+            .map(|(xref, id)| (xref.clone(), id.0))
+            .chain(data.family_xrefs.iter().map(|(xref, id)| (xref.clone(), id.0)))
+            .chain(data.source_xrefs.iter().map(|(xref, id)| (xref.clone(), *id)))
+            .collect();
+        data.report.sources = data.sources.len();
+    }
+
+    fn report(&mut self, record: &GedcomNode) {
+        if record.tag != "HEAD" && record.tag != "TRLR" {
+            self.data.report.count_record(&record.tag);
+        }
+        if let Some(xref) = &record.xref {
+            if !self.defined.insert(xref.clone()) {
+                self.data.report.warn(
+                    ImportWarningKind::DuplicateXref,
+                    Some(record.line_number),
+                    Some(xref),
+                    record.tag.as_str(),
+                    None,
+                );
+            }
+        }
+
+        let start = self.pending.len();
+        inspect(record, &self.mapping, &mut self.data.report, &mut self.pending);
+        // Les pointeurs vers un xref déjà défini sont résolus d'emblée.
+        let found: Vec<PointerUse> = self.pending.split_off(start);
+        let defined = &self.defined;
+        self.pending
+            .extend(found.into_iter().filter(|pointer| !defined.contains(&pointer.target)));
+    }
+
+    /// Rattache un `INDI` à une personne déjà en base (réimport).
+    pub fn reuse_person(&mut self, xref: &str, id: PersonId) {
+        self.data.person_xrefs.insert(xref.to_string(), id);
+    }
+
+    /// Rattache une `FAM` à une famille déjà en base (réimport).
+    pub fn reuse_family(&mut self, xref: &str, id: FamilyId) {
+        self.data.family_xrefs.insert(xref.to_string(), id);
+    }
+
+    /// Identifiants attribués et sources converties jusqu'ici.
+    pub fn data(&self) -> &GedcomData {
+        &self.data
+    }
+
+    /// Range les `EXID` et les sous-structures d'extension dans `GedcomData::extras`,
+    /// sauf les tags propriétaires convertis en champs.
+    fn keep_extras(&mut self, id: Uuid, record: &GedcomNode) {
+        let extras = &mut self.data.extras;
+        for child in &record.children {
+            let converted = record.tag == "INDI"
+                && self
+                    .mapping
+                    .get(VendorScope::Person, &child.tag)
+                    .is_some_and(|field| field != VendorField::Identifier);
+            if converted {
+                continue;
+            }
+            if child.tag == "EXID" {
+                if let Some(value) = child.text() {
+                    extras.external_ids.entry(id).or_default().push(ExternalId {
+                        value: value.to_string(),
+                        kind: child.child_value("TYPE").map(str::to_string),
+                    });
+                }
+            } else if is_extension_tag(&child.tag) {
+                extras.extensions.entry(id).or_default().push(child.clone());
+            }
+        }
+    }
+
+    fn source_record(&self, xref: &str, record: &GedcomNode) -> Source {
+        Source {
+            id: self.data.source_xrefs[xref],
+            title: record
+                .child_value("TITL")
+                .or_else(|| record.child_value("ABBR"))
+                .unwrap_or_default()
+                .to_string(),
+            author: record.child_value("AUTH").map(str::to_string),
+            publication_info: record.child_value("PUBL").map(str::to_string),
+            repository: record.child("REPO").and_then(|repo| self.repository_name(repo)),
+            notes: self.notes_of(record),
+            page: None,
+        }
+    }
+
+    fn repository_name(&self, node: &GedcomNode) -> Option<String> {
+        match node.pointer() {
+            Some(xref) => self
+                .repositories
+                .get(xref)
+                .and_then(|repo| repo.child_value("NAME"))
+                .map(str::to_string),
+            None => node.text().map(str::to_string),
+        }
+    }
+
+    pub fn person(&self, record: &GedcomNode) -> Person {
+        let id = record
+            .xref
+            .as_deref()
+            .and_then(|xref| self.data.person_xrefs.get(xref).copied())
+            .unwrap_or_else(|| PersonId(Uuid::new_v4()));
+
+        let mut person = Person {
+            id,
+            first_name: String::new(),
+            surname: String::new(),
+            surname_prefix: None,
+            nicknames: Vec::new(),
+            sex: Sex::Unknown,
+            birth: None,
+            death: None,
+            other_events: Vec::new(),
+            occupation: Vec::new(),
+            titles: Vec::new(),
+            notes: self.notes_of(record),
+            sources: self.sources_of(record),
+            images: Vec::new(),
+            public: true,
+            created_at: self.now,
+            updated_at: self.now,
+            updated_by: None,
+        };
+
+        if let Some(name) = record.child("NAME") {
+            self.apply_name(&mut person, name);
+        }
+
+        for child in &record.children {
+            match child.tag.as_str() {
+                "SEX" => {
+                    person.sex = match child.text().map(str::trim) {
+                        Some("M") => Sex::Male,
+                        Some("F") => Sex::Female,
+                        _ => Sex::Unknown,
+                    };
+                }
+                "NAME" => {
+                    // Un `NICK` par surnom ; un `NICK` unique peut aussi être une liste
+                    // séparée par des virgules (GEDCOM 5.5.1 d'autres logiciels).
+                    let nicks: Vec<&str> = child
+                        .children_with("NICK")
+                        .filter_map(GedcomNode::text)
+                        .collect();
+                    let nicks: Vec<&str> = match nicks.as_slice() {
+                        [list] if self.nickname_lists => list.split(',').collect(),
+                        _ => nicks,
+                    };
+                    for nick in nicks.into_iter().map(str::trim).filter(|n| !n.is_empty()) {
+                        push_unique(&mut person.nicknames, nick);
+                    }
+                    for vendor in &child.children {
+                        if let Some(field) = self.mapping.get(VendorScope::Name, &vendor.tag) {
+                            apply_vendor(&mut person, field, vendor);
+                        }
+                    }
+                }
+                "OCCU" => {
+                    if let Some(occupation) = child.text() {
+                        person.occupation.push(occupation.to_string());
+                    }
+                }
+                "TITL" => person.titles.extend(title(child)),
+                "OBJE" => {
+                    if let Some(image) = self.image(child) {
+                        person.images.push(image);
+                    }
+                }
+                "RESN" => {
+                    if is_restricted(child) {
+                        person.public = false;
+                    }
+                }
+                tag => {
+                    if let Some(field) = self.mapping.get(VendorScope::Person, tag) {
+                        apply_vendor(&mut person, field, child);
+                    } else if let Some(event_type) = person_event_type(tag, child) {
+                        let event = self.event(event_type, child);
+                        match tag {
+                            "BIRT" if person.birth.is_none() => person.birth = Some(event),
+                            "DEAT" if person.death.is_none() => person.death = Some(event),
+                            _ => person.other_events.push(event),
+                        }
+                    }
+                }
+            }
+        }
+
+        person
+    }
+
+    fn apply_name(&self, person: &mut Person, name: &GedcomNode) {
+        let raw = name.text().unwrap_or_default();
+        let (given, surname) = match raw.split_once('/') {
+            Some((given, rest)) => {
+                let surname = rest.split_once('/').map_or(rest, |(surname, _)| surname);
+                (given.trim(), surname.trim())
+            }
+            None => (raw.trim(), ""),
+        };
+
+        person.first_name = name.child_value("GIVN").unwrap_or(given).to_string();
+        person.surname_prefix = name.child_value("SPFX").map(str::to_string);
+        person.surname = match (name.child_value("SURN"), person.surname_prefix.as_deref()) {
+            (Some(surn), _) => surn.to_string(),
+            (None, Some(prefix)) => surname
+                .strip_prefix(prefix)
+                .map_or(surname, str::trim_start)
+                .to_string(),
+            (None, None) => surname.to_string(),
+        };
+    }
+
+    pub fn family(&self, record: &GedcomNode) -> Family {
+        let id = record
+            .xref
+            .as_deref()
+            .and_then(|xref| self.data.family_xrefs.get(xref).copied())
+            .unwrap_or_else(|| FamilyId(Uuid::new_v4()));
+
+        let mut family = Family {
+            id,
+            father: record.child("HUSB").and_then(|node| self.person_ref(node)),
+            mother: record.child("WIFE").and_then(|node| self.person_ref(node)),
+            children: Vec::new(),
+            marriage: None,
+            divorce: None,
+            other_events: Vec::new(),
+            notes: self.notes_of(record),
+            sources: self.sources_of(record),
+            public: true,
+            created_at: self.now,
+            updated_at: self.now,
+        };
+
+        for child in &record.children {
+            match child.tag.as_str() {
+                "CHIL" => {
+                    if let Some(person) = self.person_ref(child) {
+                        if !family.children.contains(&person) {
+                            family.children.push(person);
+                        }
+                    }
+                }
+                "RESN" => {
+                    if is_restricted(child) {
+                        family.public = false;
+                    }
+                }
+                tag => {
+                    if let Some(event_type) = family_event_type(tag, child) {
+                        let event = self.event(event_type, child);
+                        match tag {
+                            "MARR" if family.marriage.is_none() => family.marriage = Some(event),
+                            "DIV" if family.divorce.is_none() => family.divorce = Some(event),
+                            _ => family.other_events.push(event),
+                        }
+                    }
+                }
+            }
+        }
+
+        family
+    }
+
+    fn person_ref(&self, node: &GedcomNode) -> Option<PersonId> {
+        node.pointer()
+            .and_then(|xref| self.data.person_xrefs.get(xref).copied())
+    }
+
+    fn event(&self, event_type: EventType, node: &GedcomNode) -> LifeEvent {
+        LifeEvent {
+            event_type,
+            date: date_of(node).unwrap_or(PreciseDate::Unknown),
+            place: node.child("PLAC").and_then(place),
+            witnesses: node
+                .children_with("ASSO")
+                .filter(|asso| {
+                    // 5.5.1 : `RELA` en texte libre ; 7.0 : `ROLE WITN`.
+                    asso.child_value("RELA")
+                        .is_some_and(|rela| rela.to_lowercase().contains("witness"))
+                        || asso.child_value("ROLE").map(str::trim) == Some("WITN")
+                })
+                .filter_map(|asso| self.person_ref(asso))
+                .collect(),
+            notes: self.notes_of(node),
+            sources: self.sources_of(node),
+        }
+    }
+
+    fn image(&self, node: &GedcomNode) -> Option<ImageReference> {
+        let object = match node.pointer() {
+            Some(xref) => self.objects.get(xref)?,
+            None => node,
+        };
+        let file = object.child("FILE")?;
+        Some(ImageReference {
+            url: file.text()?.to_string(),
+            description: object
+                .child_value("TITL")
+                .or_else(|| file.child_value("TITL"))
+                .map(str::to_string),
+            is_primary: self.mapping.is_primary(node) || self.mapping.is_primary(object),
+        })
+    }
+
+    /// Concatène les notes en ligne et les notes partagées (`NOTE @N1@`, `SNOTE @N1@`).
+    fn notes_of(&self, node: &GedcomNode) -> Option<String> {
+        let notes: Vec<&str> = node
+            .children
+            .iter()
+            .filter(|child| child.tag == "NOTE" || child.tag == "SNOTE")
+            .filter_map(|note| match note.pointer() {
+                Some(xref) => self.notes.get(xref).and_then(|shared| shared.text()),
+                None => note.text(),
+            })
+            .collect();
+        if notes.is_empty() {
+            None
+        } else {
+            Some(notes.join("\n"))
+        }
+    }
+
+    /// Citations de sources : pointeurs vers un enregistrement `SOUR` ou sources en ligne,
+    /// avec le `PAGE` de chaque citation.
+    fn sources_of(&self, node: &GedcomNode) -> Vec<Source> {
+        node.children_with("SOUR")
+            .filter_map(|citation| {
+                let mut source = match citation.pointer() {
+                    Some(xref) => self.sources.get(xref).cloned()?,
+                    None => Source {
+                        id: Uuid::new_v4(),
+                        title: citation.text()?.to_string(),
+                        author: None,
+                        publication_info: None,
+                        repository: None,
+                        notes: self.notes_of(citation),
+                        page: None,
+                    },
+                };
+                source.page = citation.child_value("PAGE").map(str::to_string);
+                Some(source)
+            })
+            .collect()
+    }
+}
+
+/// Reporte un tag propriétaire dans le champ métier correspondant.
+fn apply_vendor(person: &mut Person, field: VendorField, node: &GedcomNode) {
+    let Some(value) = node.text().map(str::trim).filter(|value| !value.is_empty()) else {
+        return;
+    };
+    match field {
+        VendorField::Nickname => push_unique(&mut person.nicknames, &plain_name(value)),
+        VendorField::Occupation => push_unique(&mut person.occupation, value),
+        VendorField::Title => person.titles.extend(title(node)),
+        // Lu avec l'`OBJE` ; les identifiants restent dans `GedcomExtras`.
+        VendorField::PrimaryImage | VendorField::Identifier => {}
+    }
+}
+
+fn title(node: &GedcomNode) -> Option<Title> {
+    Some(Title {
+        name: node.text()?.to_string(),
+        place: node.child_value("PLAC").map(str::to_string),
+        date_start: date_of(node),
+        date_end: None,
+        nth: None,
+    })
+}
+
+/// `DATE` d'une structure, avec sa `PHRASE` GEDCOM 7.0.
+fn date_of(node: &GedcomNode) -> Option<PreciseDate> {
+    let date = node.child("DATE")?;
+    parse_date7(date.text().unwrap_or_default(), date.child_value("PHRASE"))
+}
+
+/// Événements individuels GEDCOM conservés tels quels dans `EventType::Custom`.
+pub(crate) const PERSON_EVENT_TAGS: &[&str] = &[
+    "ADOP", "BARM", "BASM", "BLES", "CHR", "CHRA", "CONF", "FCOM", "ORDN", "NATU", "EMIG",
+    "IMMI", "CENS", "PROB", "WILL", "GRAD", "RETI", "CREM", "RESI",
+];
+
+/// Événements familiaux GEDCOM conservés tels quels dans `EventType::Custom`.
+pub(crate) const FAMILY_EVENT_TAGS: &[&str] =
+    &["ANUL", "CENS", "DIVF", "MARB", "MARC", "MARL", "MARS", "RESI"];
+
+fn person_event_type(tag: &str, node: &GedcomNode) -> Option<EventType> {
+    Some(match tag {
+        "BIRT" => EventType::Birth,
+        "BAPM" => EventType::Baptism,
+        "DEAT" => EventType::Death,
+        "BURI" => EventType::Burial,
+        "EVEN" => EventType::Custom(node.child_value("TYPE").unwrap_or("EVEN").to_string()),
+        tag if PERSON_EVENT_TAGS.contains(&tag) => EventType::Custom(tag.to_string()),
+        _ => return None,
+    })
+}
+
+fn family_event_type(tag: &str, node: &GedcomNode) -> Option<EventType> {
+    Some(match tag {
+        "MARR" => EventType::Marriage,
+        "DIV" => EventType::Divorce,
+        "ENGA" => EventType::Engagement,
+        "EVEN" => EventType::Custom(node.child_value("TYPE").unwrap_or("EVEN").to_string()),
+        tag if FAMILY_EVENT_TAGS.contains(&tag) => EventType::Custom(tag.to_string()),
+        _ => return None,
+    })
+}
+
+fn place(node: &GedcomNode) -> Option<Place> {
+    let name = node.text()?.to_string();
+    let map = node.child("MAP");
+    Some(Place {
+        name,
+        locality: None,
+        city: None,
+        county: None,
+        state: None,
+        country: None,
+        latitude: map
+            .and_then(|map| map.child_value("LATI"))
+            .and_then(|value| parse_coordinate(value, 'N', 'S')),
+        longitude: map
+            .and_then(|map| map.child_value("LONG"))
+            .and_then(|value| parse_coordinate(value, 'E', 'W')),
+    })
+}
+
+/// Coordonnée GEDCOM (`N48.8566`, `W1.5`) en degrés décimaux signés.
+fn parse_coordinate(value: &str, positive: char, negative: char) -> Option<f64> {
+    let value = value.trim();
+    let mut chars = value.chars();
+    let sign = match chars.next()? {
+        c if c == positive => 1.0,
+        c if c == negative => -1.0,
+        _ => return value.parse().ok(),
+    };
+    chars.as_str().parse::<f64>().ok().map(|degrees| sign * degrees)
+}
+
+/// `RESN` confidentiel : `privacy` en 5.5.1, liste `CONFIDENTIAL, LOCKED` en 7.0.
+fn is_restricted(node: &GedcomNode) -> bool {
+    node.text().is_some_and(|value| {
+        value.split(',').any(|restriction| {
+            matches!(
+                restriction.trim().to_ascii_lowercase().as_str(),
+                "confidential" | "privacy"
+            )
+        })
+    })
+}
+
+fn push_unique(values: &mut Vec<String>, value: &str) {
+    if !values.iter().any(|existing| existing == value) {
+        values.push(value.to_string());
+    }
+}
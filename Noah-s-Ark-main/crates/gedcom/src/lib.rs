@@ -2,6 +2,24 @@ use anyhow::{anyhow, Result};
 use genealogy_types::Person;
 use serde::{Deserialize, Serialize};
 
+pub mod date;
+pub mod encoding;
+pub mod extras;
+pub mod gedzip;
+pub mod import;
+pub mod parser;
+mod report;
+pub mod vendor;
+pub mod writer;
+
+pub use encoding::{detect_encoding, DecodingReader, Encoding};
+pub use extras::{record_identifiers, ExternalId, GedcomExtras};
+pub use gedzip::{read_gedzip, write_gedzip, GedzipData, GedzipMedia};
+pub use import::{import_gedcom, import_gedcom_bytes, GedcomData, GedcomImporter};
+pub use parser::{parse_records, GedcomNode, GedcomReader};
+pub use vendor::{VendorField, VendorMapping, VendorScope};
+pub use writer::{write_gedcom, write_gedcom7, GedcomVersion};
+
 /// Représentation intermédiaire simplifiée d'une fiche GEDCOM.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GedcomRecord {
@@ -0,0 +1,277 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use anyhow::{anyhow, bail, Result};
+
+/// Ligne GEDCOM brute : `niveau [@xref@] TAG [valeur]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GedcomLine {
+    pub level: usize,
+    pub xref: Option<String>,
+    pub tag: String,
+    pub value: Option<String>,
+    pub line_number: usize,
+}
+
+/// Nœud de l'arbre GEDCOM, avec les lignes CONT/CONC déjà fusionnées dans `value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GedcomNode {
+    pub xref: Option<String>,
+    pub tag: String,
+    pub value: Option<String>,
+    pub children: Vec<GedcomNode>,
+    pub line_number: usize,
+}
+
+impl GedcomNode {
+    fn from_line(line: GedcomLine) -> Self {
+        Self {
+            xref: line.xref,
+            tag: line.tag,
+            value: line.value,
+            children: Vec::new(),
+            line_number: line.line_number,
+        }
+    }
+
+    /// Premier sous-nœud portant le tag donné.
+    pub fn child(&self, tag: &str) -> Option<&GedcomNode> {
+        self.children.iter().find(|child| child.tag == tag)
+    }
+
+    /// Tous les sous-nœuds portant le tag donné, dans l'ordre du fichier.
+    pub fn children_with<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a GedcomNode> {
+        self.children.iter().filter(move |child| child.tag == tag)
+    }
+
+    /// Valeur non vide du premier sous-nœud portant le tag donné.
+    pub fn child_value(&self, tag: &str) -> Option<&str> {
+        self.child(tag).and_then(|child| child.text())
+    }
+
+    /// Valeur non vide du nœud.
+    pub fn text(&self) -> Option<&str> {
+        self.value.as_deref().filter(|value| !value.trim().is_empty())
+    }
+
+    /// Identifiant pointé si la valeur est un pointeur `@X1@`.
+    pub fn pointer(&self) -> Option<&str> {
+        self.value.as_deref().and_then(parse_pointer)
+    }
+}
+
+/// Analyse une ligne GEDCOM 5.5.1 isolée (`@@` échappe `@` partout dans la valeur).
+pub fn parse_line(raw: &str, line_number: usize) -> Result<GedcomLine> {
+    parse_line_as(raw, line_number, false)
+}
+
+fn parse_line_as(raw: &str, line_number: usize, gedcom7: bool) -> Result<GedcomLine> {
+    let line = raw.trim_start_matches('\u{feff}').trim_start();
+    let (level_str, rest) = split_token(line);
+    let level: usize = level_str
+        .parse()
+        .map_err(|_| anyhow!("ligne {line_number} : niveau invalide `{level_str}`"))?;
+
+    let (mut token, mut rest) = split_token(rest);
+    let mut xref = None;
+    if token.starts_with('@') {
+        let id = parse_pointer(token)
+            .ok_or_else(|| anyhow!("ligne {line_number} : identifiant invalide `{token}`"))?;
+        xref = Some(id.to_string());
+        (token, rest) = split_token(rest);
+    }
+
+    if token.is_empty() {
+        bail!("ligne {line_number} : tag manquant");
+    }
+
+    let value = rest.strip_prefix(' ').unwrap_or(rest);
+    let value = if value.is_empty() {
+        None
+    } else if parse_pointer(value).is_some() {
+        Some(value.to_string())
+    } else if gedcom7 {
+        // GEDCOM 7.0 : seul le `@@` initial est un échappement.
+        Some(value.strip_prefix('@').unwrap_or(value).to_string())
+    } else {
+        Some(value.replace("@@", "@"))
+    };
+
+    Ok(GedcomLine {
+        level,
+        xref,
+        tag: token.to_string(),
+        value,
+        line_number,
+    })
+}
+
+/// Analyse un fichier GEDCOM complet et renvoie ses enregistrements de niveau 0.
+pub fn parse_records(input: &str) -> Result<Vec<GedcomNode>> {
+    GedcomReader::new(input.as_bytes()).collect()
+}
+
+/// Lecteur incrémental : produit les enregistrements de niveau 0 un par un,
+/// sans charger tout le fichier en mémoire.
+///
+/// La version (`HEAD.GEDC.VERS`) est relevée sur l'en-tête : les règles
+/// d'échappement de GEDCOM 7.0 s'appliquent aux enregistrements suivants.
+pub struct GedcomReader<R> {
+    source: R,
+    buffer: Vec<u8>,
+    pending_lines: VecDeque<(usize, String)>,
+    line_number: usize,
+    stack: Vec<GedcomNode>,
+    gedcom7: bool,
+    finished: bool,
+}
+
+impl<R: BufRead> GedcomReader<R> {
+    pub fn new(source: R) -> Self {
+        Self {
+            source,
+            buffer: Vec::new(),
+            pending_lines: VecDeque::new(),
+            line_number: 0,
+            stack: Vec::new(),
+            gedcom7: false,
+            finished: false,
+        }
+    }
+
+    /// Lit la prochaine ligne non vide. Les fins de ligne CR, LF, CRLF et LFCR sont acceptées.
+    fn next_raw_line(&mut self) -> Result<Option<(usize, String)>> {
+        loop {
+            if let Some(line) = self.pending_lines.pop_front() {
+                return Ok(Some(line));
+            }
+
+            self.buffer.clear();
+            if self.source.read_until(b'\n', &mut self.buffer)? == 0 {
+                return Ok(None);
+            }
+
+            let chunk = std::str::from_utf8(&self.buffer).map_err(|err| {
+                anyhow!(
+                    "ligne {} : contenu non UTF-8 ({err})",
+                    self.line_number + 1
+                )
+            })?;
+            for part in chunk.split(['\r', '\n']) {
+                if part.trim().is_empty() {
+                    continue;
+                }
+                self.line_number += 1;
+                self.pending_lines.push_back((self.line_number, part.to_string()));
+            }
+        }
+    }
+
+    /// Rattache le sommet de la pile à son parent (ou le renvoie s'il s'agit d'un enregistrement).
+    fn pop_node(&mut self) -> Option<GedcomNode> {
+        let node = self.stack.pop()?;
+        match self.stack.last_mut() {
+            Some(parent) => {
+                parent.children.push(node);
+                None
+            }
+            None => Some(node),
+        }
+    }
+
+    fn collapse_to(&mut self, depth: usize) -> Option<GedcomNode> {
+        let mut record = None;
+        while self.stack.len() > depth {
+            if let Some(node) = self.pop_node() {
+                record = Some(node);
+            }
+        }
+        record
+    }
+
+    fn read_record(&mut self) -> Result<Option<GedcomNode>> {
+        while let Some((line_number, raw)) = self.next_raw_line()? {
+            let line = parse_line_as(&raw, line_number, self.gedcom7)?;
+
+            if line.level > self.stack.len() {
+                bail!(
+                    "ligne {line_number} : saut de niveau ({} après {})",
+                    line.level,
+                    self.stack.len().saturating_sub(1)
+                );
+            }
+
+            let record = self.collapse_to(line.level);
+
+            if line.tag == "CONT" || line.tag == "CONC" {
+                let parent = self
+                    .stack
+                    .last_mut()
+                    .ok_or_else(|| anyhow!("ligne {line_number} : {} sans parent", line.tag))?;
+                let value = parent.value.get_or_insert_with(String::new);
+                if line.tag == "CONT" {
+                    value.push('\n');
+                }
+                if let Some(extra) = line.value {
+                    value.push_str(&extra);
+                }
+                continue;
+            }
+
+            self.stack.push(GedcomNode::from_line(line));
+
+            if record.is_some() {
+                return Ok(record);
+            }
+        }
+
+        Ok(self.collapse_to(0))
+    }
+}
+
+impl<R: BufRead> Iterator for GedcomReader<R> {
+    type Item = Result<GedcomNode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.read_record() {
+            Ok(Some(record)) => {
+                if record.tag == "HEAD" {
+                    self.gedcom7 = gedcom_version(&record).is_some_and(|v| v.starts_with('7'));
+                }
+                Some(Ok(record))
+            }
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(err) => {
+                self.finished = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Version déclarée par l'en-tête (`HEAD.GEDC.VERS`).
+pub fn gedcom_version(head: &GedcomNode) -> Option<&str> {
+    head.child("GEDC").and_then(|gedc| gedc.child_value("VERS"))
+}
+
+fn split_token(input: &str) -> (&str, &str) {
+    let input = input.trim_start_matches(' ');
+    match input.find(' ') {
+        Some(pos) => (&input[..pos], &input[pos..]),
+        None => (input, ""),
+    }
+}
+
+fn parse_pointer(value: &str) -> Option<&str> {
+    let inner = value.strip_prefix('@')?.strip_suffix('@')?;
+    if inner.is_empty() || inner.starts_with('#') || inner.contains('@') {
+        return None;
+    }
+    Some(inner)
+}
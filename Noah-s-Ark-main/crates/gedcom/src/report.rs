@@ -0,0 +1,301 @@
+use genealogy_types::{ImportReport, ImportWarningKind, PreciseDate};
+
+use crate::date::parse_date7;
+use crate::extras::is_extension_tag;
+use crate::import::{FAMILY_EVENT_TAGS, PERSON_EVENT_TAGS};
+use crate::parser::GedcomNode;
+use crate::vendor::{VendorField, VendorMapping, VendorScope};
+
+/// Pointeur relevé dans un enregistrement, vérifié une fois le fichier entièrement indexé.
+#[derive(Debug, Clone)]
+pub(crate) struct PointerUse {
+    pub target: String,
+    pub record: Option<String>,
+    pub path: String,
+    pub line: usize,
+}
+
+/// Structure GEDCOM dont l'import connaît les sous-tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    Person,
+    Family,
+    Name,
+    Event,
+    Place,
+    Map,
+    Association,
+    Title,
+    Media,
+    MediaFile,
+    SourceRecord,
+    Citation,
+    /// Citation d'un enregistrement `SOUR` : seul son `PAGE` lui est propre.
+    SourcePointer,
+    Note,
+    Repository,
+    ExternalId,
+    Date,
+    /// Valeur simple : tout sous-tag est perdu.
+    Leaf,
+    /// Métadonnées de modification ignorées (`CHAN`, `CREA`).
+    Skipped,
+    /// Tag reconnu dont l'import ne garde pas la valeur.
+    Dropped,
+}
+
+/// Relève les tags ignorés, les valeurs perdues et les pointeurs d'un enregistrement.
+pub(crate) fn inspect(
+    record: &GedcomNode,
+    mapping: &VendorMapping,
+    report: &mut ImportReport,
+    pointers: &mut Vec<PointerUse>,
+) {
+    let context = match record.tag.as_str() {
+        "INDI" => Context::Person,
+        "FAM" => Context::Family,
+        "SOUR" => Context::SourceRecord,
+        "NOTE" | "SNOTE" => Context::Note,
+        "OBJE" => Context::Media,
+        "REPO" => Context::Repository,
+        "HEAD" | "TRLR" | "SUBM" | "SUBN" => return,
+        tag if is_extension_tag(tag) => {
+            report.warn(
+                ImportWarningKind::PreservedTag,
+                Some(record.line_number),
+                record.xref.as_deref(),
+                tag,
+                record.text(),
+            );
+            return;
+        }
+        tag => {
+            report.warn(
+                ImportWarningKind::UnknownTag,
+                Some(record.line_number),
+                record.xref.as_deref(),
+                tag,
+                record.text(),
+            );
+            return;
+        }
+    };
+
+    let mut inspector = Inspector {
+        record: record.xref.as_deref(),
+        mapping,
+        report,
+        pointers,
+    };
+    inspector.children(context, record, &record.tag, 1);
+}
+
+struct Inspector<'a> {
+    record: Option<&'a str>,
+    mapping: &'a VendorMapping,
+    report: &'a mut ImportReport,
+    pointers: &'a mut Vec<PointerUse>,
+}
+
+impl Inspector<'_> {
+    fn children(&mut self, context: Context, node: &GedcomNode, path: &str, level: usize) {
+        for child in &node.children {
+            let path = format!("{path}.{}", child.tag);
+            let child_context = match child_context(context, &child.tag) {
+                Some(Context::Citation) if child.pointer().is_some() => {
+                    Some(Context::SourcePointer)
+                }
+                known => known.or_else(|| self.vendor(context, &child.tag)),
+            };
+            match child_context {
+                Some(Context::Skipped) => {}
+                Some(Context::Dropped) => self.warn(ImportWarningKind::LossyValue, child, &path),
+                Some(child_context) => {
+                    self.value(child_context, child, &path);
+                    self.children(child_context, child, &path, level + 1);
+                }
+                None => {
+                    // Les extensions de niveau 1 des INDI/FAM/SOUR sont gardées dans `extras`.
+                    let preserved = level == 1
+                        && is_extension_tag(&child.tag)
+                        && matches!(
+                            context,
+                            Context::Person | Context::Family | Context::SourceRecord
+                        );
+                    let kind = if preserved {
+                        ImportWarningKind::PreservedTag
+                    } else {
+                        ImportWarningKind::UnknownTag
+                    };
+                    self.warn(kind, child, &path);
+                }
+            }
+        }
+    }
+
+    /// Tag propriétaire converti par la [`VendorMapping`].
+    fn vendor(&self, context: Context, tag: &str) -> Option<Context> {
+        let scope = match context {
+            Context::Person => VendorScope::Person,
+            Context::Name => VendorScope::Name,
+            Context::Media => VendorScope::Media,
+            _ => return None,
+        };
+        self.mapping.get(scope, tag).map(|field| match field {
+            VendorField::Title => Context::Title,
+            _ => Context::Leaf,
+        })
+    }
+
+    /// Vérifie la valeur d'un tag reconnu.
+    fn value(&mut self, context: Context, node: &GedcomNode, path: &str) {
+        if let Some(target) = node.pointer() {
+            self.pointers.push(PointerUse {
+                target: target.to_string(),
+                record: self.record.map(str::to_string),
+                path: path.to_string(),
+                line: node.line_number,
+            });
+        }
+
+        let Some(value) = node.text() else {
+            return;
+        };
+        let lossy = match (context, node.tag.as_str()) {
+            // Une date gardée telle quelle mais sans équivalent grégorien est inexploitable.
+            (Context::Date, _) => matches!(
+                parse_date7(value, node.child_value("PHRASE")),
+                None | Some(PreciseDate::Verbatim { approx: None, .. })
+            ),
+            (Context::Leaf, "SEX") => !matches!(value.trim(), "M" | "F" | "U"),
+            (Context::Leaf, "LATI") => !is_coordinate(value, 'N', 'S'),
+            (Context::Leaf, "LONG") => !is_coordinate(value, 'E', 'W'),
+            // Seuls les témoins sont repris des associations.
+            (Context::Association, _) => {
+                let witness = node
+                    .child_value("RELA")
+                    .is_some_and(|rela| rela.to_lowercase().contains("witness"))
+                    || node.child_value("ROLE").map(str::trim) == Some("WITN");
+                !witness
+            }
+            _ => false,
+        };
+        if lossy {
+            self.warn(ImportWarningKind::LossyValue, node, path);
+        }
+    }
+
+    fn warn(&mut self, kind: ImportWarningKind, node: &GedcomNode, path: &str) {
+        self.report.warn(
+            kind,
+            Some(node.line_number),
+            self.record,
+            path,
+            node.value.as_deref(),
+        );
+    }
+}
+
+/// Contexte d'un sous-tag reconnu, `None` s'il est ignoré par l'import.
+fn child_context(context: Context, tag: &str) -> Option<Context> {
+    Some(match (context, tag) {
+        (_, "CHAN" | "CREA") => Context::Skipped,
+        (Context::Person, "NAME") => Context::Name,
+        (Context::Person, "SEX" | "OCCU" | "RESN" | "FAMC" | "FAMS") => Context::Leaf,
+        (Context::Person, "TITL") => Context::Title,
+        (Context::Person, tag) if is_person_event(tag) => Context::Event,
+        (Context::Family, "HUSB" | "WIFE" | "CHIL" | "RESN") => Context::Leaf,
+        (Context::Family, tag) if is_family_event(tag) => Context::Event,
+        (Context::Person | Context::Family | Context::Event, "OBJE") => Context::Media,
+        (Context::Person | Context::Family | Context::SourceRecord, "EXID") => Context::ExternalId,
+        (
+            Context::Person
+            | Context::Family
+            | Context::Event
+            | Context::SourceRecord
+            | Context::Citation,
+            "NOTE" | "SNOTE",
+        ) => Context::Note,
+        (Context::Person | Context::Family | Context::Event, "SOUR") => Context::Citation,
+        (Context::Name, "GIVN" | "SURN" | "SPFX" | "NICK") => Context::Leaf,
+        (Context::Event | Context::Title, "DATE") => Context::Date,
+        (Context::Event, "TYPE") => Context::Leaf,
+        (Context::Date, "PHRASE") => Context::Leaf,
+        (Context::Event | Context::Title, "PLAC") => Context::Place,
+        (Context::Event, "ASSO") => Context::Association,
+        (Context::Place, "MAP") => Context::Map,
+        (Context::Map, "LATI" | "LONG") => Context::Leaf,
+        (Context::Association, "RELA" | "ROLE") => Context::Leaf,
+        (Context::Media, "FILE") => Context::MediaFile,
+        (Context::Media, "TITL" | "FORM") => Context::Leaf,
+        (Context::MediaFile, "FORM" | "TITL") => Context::Leaf,
+        (Context::SourceRecord, "TITL" | "ABBR" | "AUTH" | "PUBL" | "REPO") => Context::Leaf,
+        (Context::Citation | Context::SourcePointer, "PAGE") => Context::Leaf,
+        // Les notes d'une citation par pointeur sont remplacées par celles de la source.
+        (Context::SourcePointer, "NOTE" | "SNOTE") => Context::Dropped,
+        (Context::Repository, "NAME") => Context::Leaf,
+        (Context::ExternalId, "TYPE") => Context::Leaf,
+        _ => return None,
+    })
+}
+
+fn is_person_event(tag: &str) -> bool {
+    matches!(tag, "BIRT" | "CHR" | "BAPM" | "DEAT" | "BURI" | "EVEN")
+        || PERSON_EVENT_TAGS.contains(&tag)
+}
+
+fn is_family_event(tag: &str) -> bool {
+    matches!(tag, "MARR" | "DIV" | "ENGA" | "EVEN") || FAMILY_EVENT_TAGS.contains(&tag)
+}
+
+fn is_coordinate(value: &str, positive: char, negative: char) -> bool {
+    let value = value.trim();
+    value
+        .strip_prefix(positive)
+        .or_else(|| value.strip_prefix(negative))
+        .unwrap_or(value)
+        .parse::<f64>()
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::import::import_gedcom;
+    use genealogy_types::ImportWarningKind;
+
+    #[test]
+    fn pointer_citation_notes_are_reported_as_lossy() {
+        let data = import_gedcom(
+            "0 HEAD\n0 @S1@ SOUR\n1 TITL Registre\n0 @I1@ INDI\n1 NAME Jean /Martin/\n\
+             1 BIRT\n2 SOUR @S1@\n3 PAGE p. 12\n3 NOTE acte abîmé\n\
+             2 SOUR Acte notarié\n3 PAGE acte 4\n3 NOTE copie\n0 TRLR\n",
+        )
+        .unwrap();
+        let lossy: Vec<&str> = data
+            .report
+            .warnings
+            .iter()
+            .filter(|warning| warning.kind == ImportWarningKind::LossyValue)
+            .map(|warning| warning.tag.as_str())
+            .collect();
+        assert_eq!(lossy, ["INDI.BIRT.SOUR.NOTE"]);
+    }
+
+    #[test]
+    fn a_duplicate_source_keeps_its_first_definition() {
+        let data = import_gedcom(
+            "0 HEAD\n0 @S1@ SOUR\n1 TITL Registre\n0 @I1@ INDI\n1 NAME Jean /Martin/\n\
+             1 SOUR @S1@\n0 @S1@ SOUR\n1 TITL Doublon\n0 TRLR\n",
+        )
+        .unwrap();
+        assert_eq!(data.sources.len(), 1);
+        assert_eq!(data.sources[0].title, "Registre");
+        assert_eq!(data.persons[0].sources[0].id, data.sources[0].id);
+        assert_eq!(data.source_xrefs["S1"], data.sources[0].id);
+        assert!(data
+            .report
+            .warnings
+            .iter()
+            .any(|warning| warning.kind == ImportWarningKind::DuplicateXref));
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::parser::GedcomNode;
+
+/// Champ métier alimenté par un tag propriétaire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VendorField {
+    /// Surnom, alias ou nom marital, ajouté à `Person::nicknames`.
+    Nickname,
+    Occupation,
+    /// Titre, avec `DATE` et `PLAC` comme un `TITL`.
+    Title,
+    /// Drapeau `Y`/`N` d'un `OBJE` : `ImageReference::is_primary`.
+    PrimaryImage,
+    /// Identifiant stable, conservé tel quel et utilisé pour rapprocher les réimports.
+    Identifier,
+}
+
+/// Structure sous laquelle le tag propriétaire apparaît.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VendorScope {
+    /// Niveau 1 d'un `INDI`.
+    Person,
+    /// Sous un `INDI.NAME`.
+    Name,
+    /// Sous un `OBJE`, en ligne, cité ou enregistrement.
+    Media,
+}
+
+/// Tags reconnus par défaut.
+const DEFAULT_TAGS: &[(VendorScope, &str, VendorField)] = &[
+    // Heredis, Ancestry, Gramps, RootsMagic.
+    (VendorScope::Person, "_UID", VendorField::Identifier),
+    // FamilySearch Family Tree (Ancestry, RootsMagic, Legacy).
+    (VendorScope::Person, "_FSFTID", VendorField::Identifier),
+    // Nom marital : Heredis, Ancestry, Gramps.
+    (VendorScope::Person, "_MARNM", VendorField::Nickname),
+    (VendorScope::Name, "_MARNM", VendorField::Nickname),
+    // Alias : Ancestry, Family Tree Maker, PAF.
+    (VendorScope::Person, "_AKA", VendorField::Nickname),
+    (VendorScope::Name, "_AKA", VendorField::Nickname),
+    (VendorScope::Person, "_AKAN", VendorField::Nickname),
+    (VendorScope::Name, "_AKAN", VendorField::Nickname),
+    // Geneanet.
+    (VendorScope::Person, "_OCCU", VendorField::Occupation),
+    (VendorScope::Person, "_OCCUPATION", VendorField::Occupation),
+    (VendorScope::Person, "_TITL", VendorField::Title),
+    // Image principale : Heredis, Ancestry, Gramps, Legacy.
+    (VendorScope::Media, "_PRIM", VendorField::PrimaryImage),
+    (VendorScope::Media, "_PRIMARY", VendorField::PrimaryImage),
+];
+
+static BUILTIN: LazyLock<VendorMapping> = LazyLock::new(VendorMapping::default);
+
+/// Table des tags propriétaires convertis en champs métiers.
+///
+/// Un tag absent de la table reste dans `GedcomExtras` et est réécrit tel quel.
+#[derive(Debug, Clone)]
+pub struct VendorMapping {
+    fields: HashMap<(VendorScope, String), VendorField>,
+}
+
+impl Default for VendorMapping {
+    fn default() -> Self {
+        let mut mapping = Self::empty();
+        for (scope, tag, field) in DEFAULT_TAGS {
+            mapping.insert(*scope, *tag, *field);
+        }
+        mapping
+    }
+}
+
+impl VendorMapping {
+    pub fn empty() -> Self {
+        Self {
+            fields: HashMap::new(),
+        }
+    }
+
+    /// Table par défaut, partagée.
+    pub fn builtin() -> &'static Self {
+        &BUILTIN
+    }
+
+    /// Ajoute ou remplace la conversion d'un tag.
+    pub fn insert(
+        &mut self,
+        scope: VendorScope,
+        tag: impl Into<String>,
+        field: VendorField,
+    ) -> &mut Self {
+        self.fields.insert((scope, tag.into()), field);
+        self
+    }
+
+    /// Retire un tag, qui sera alors conservé tel quel.
+    pub fn remove(&mut self, scope: VendorScope, tag: &str) -> Option<VendorField> {
+        self.fields.remove(&(scope, tag.to_string()))
+    }
+
+    pub fn get(&self, scope: VendorScope, tag: &str) -> Option<VendorField> {
+        self.fields.get(&(scope, tag.to_string())).copied()
+    }
+
+    /// Identifiants stables d'un enregistrement : tags [`VendorField::Identifier`],
+    /// `REFN` et `EXID`, ces deux derniers qualifiés par leur `TYPE`. Sans `TYPE`,
+    /// un `REFN` ou un `EXID` n'est qu'un numéro propre au fichier : il est ignoré,
+    /// pour ne pas rapprocher les personnes de deux arbres sans rapport.
+    pub fn identifiers(&self, record: &GedcomNode) -> Vec<(String, String)> {
+        let mut identifiers = Vec::new();
+        for child in &record.children {
+            let Some(value) = child.text().map(str::trim) else {
+                continue;
+            };
+            let identifier = match child.tag.as_str() {
+                "_UID" => normalize_uid(value),
+                _ => value.to_string(),
+            };
+            let kind = match child.tag.as_str() {
+                "REFN" | "EXID" => match child.child_value("TYPE") {
+                    Some(kind) => format!("{}:{}", child.tag, kind.trim()),
+                    None => continue,
+                },
+                tag if self.get(VendorScope::Person, tag) == Some(VendorField::Identifier) => {
+                    tag.to_string()
+                }
+                _ => continue,
+            };
+            identifiers.push((kind, identifier));
+        }
+        identifiers
+    }
+
+    /// Sous-tag [`VendorField::PrimaryImage`] à `Y` dans un `OBJE`.
+    pub(crate) fn is_primary(&self, media: &GedcomNode) -> bool {
+        media.children.iter().any(|child| {
+            self.get(VendorScope::Media, &child.tag) == Some(VendorField::PrimaryImage)
+                && child.text().is_some_and(|value| {
+                    matches!(
+                        value.trim().to_ascii_lowercase().as_str(),
+                        "y" | "yes" | "true" | "1"
+                    )
+                })
+        })
+    }
+}
+
+/// `_UID` : 32 chiffres hexadécimaux, suivis chez certains logiciels de 4 chiffres
+/// de contrôle ou écrits avec des tirets.
+fn normalize_uid(value: &str) -> String {
+    let uid: String = value
+        .chars()
+        .filter(|c| !matches!(c, '-' | '{' | '}') && !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_uppercase();
+    if uid.len() == 36 && uid.chars().all(|c| c.is_ascii_hexdigit()) {
+        uid[..32].to_string()
+    } else {
+        uid
+    }
+}
+
+/// Nom propriétaire (`Marie /Dupont/`) en texte simple.
+pub(crate) fn plain_name(value: &str) -> String {
+    value
+        .replace('/', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_records;
+
+    #[test]
+    fn untyped_refn_and_exid_are_not_identifiers() {
+        let records = parse_records(
+            "0 @I1@ INDI\n1 _UID 0123456789abcdef0123456789ABCDEF\n1 REFN 42\n\
+             1 REFN 7\n2 TYPE AFN\n1 EXID 99\n1 EXID 123\n2 TYPE https://www.wikidata.org/\n",
+        )
+        .unwrap();
+        assert_eq!(
+            VendorMapping::builtin().identifiers(&records[0]),
+            vec![
+                (
+                    "_UID".to_string(),
+                    "0123456789ABCDEF0123456789ABCDEF".to_string()
+                ),
+                ("REFN:AFN".to_string(), "7".to_string()),
+                (
+                    "EXID:https://www.wikidata.org/".to_string(),
+                    "123".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,764 @@
+use std::collections::{HashMap, HashSet};
+
+use genealogy_types::{
+    EventType, Family, FamilyId, ImageReference, LifeEvent, Person, PersonId, Place, PreciseDate,
+    Sex, Source,
+};
+use uuid::Uuid;
+
+use crate::date::{format_date, format_date7};
+use crate::extras::GedcomExtras;
+use crate::import::{FAMILY_EVENT_TAGS, PERSON_EVENT_TAGS};
+use crate::parser::GedcomNode;
+
+/// Longueur maximale d'une valeur avant découpage en `CONC`.
+const MAX_VALUE_LEN: usize = 200;
+
+/// Version de la norme GEDCOM produite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GedcomVersion {
+    V551,
+    V70,
+}
+
+/// Écrit un fichier GEDCOM 5.5.1 complet (HEAD, INDI, FAM, SOUR, REPO, TRLR).
+///
+/// Les xrefs sont dérivés des UUID (`@I3F2A…@`) : un même enregistrement garde
+/// le même identifiant d'un export à l'autre.
+pub fn write_gedcom(persons: &[Person], families: &[Family], sources: &[Source]) -> String {
+    let extras = GedcomExtras::default();
+    GedcomWriter::new(persons, families, sources, GedcomVersion::V551, &extras).finish()
+}
+
+/// Écrit un fichier GEDCOM 7.0 : `SCHMA`, `EXID` et extensions repris de `extras`,
+/// notes partagées en `SNOTE`, médias en enregistrements `OBJE`.
+pub fn write_gedcom7(
+    persons: &[Person],
+    families: &[Family],
+    sources: &[Source],
+    extras: &GedcomExtras,
+) -> String {
+    GedcomWriter::new(persons, families, sources, GedcomVersion::V70, extras).finish()
+}
+
+struct GedcomWriter<'a> {
+    persons: &'a [Person],
+    families: &'a [Family],
+    sources: Vec<&'a Source>,
+    repositories: Vec<&'a str>,
+    person_xrefs: HashMap<PersonId, String>,
+    family_xrefs: HashMap<FamilyId, String>,
+    source_xrefs: HashMap<Uuid, String>,
+    /// Familles où la personne est enfant (`FAMC`), dans l'ordre des familles.
+    child_in: HashMap<PersonId, Vec<FamilyId>>,
+    /// Familles où la personne est parent (`FAMS`), dans l'ordre des familles.
+    parent_in: HashMap<PersonId, Vec<FamilyId>>,
+    version: GedcomVersion,
+    extras: &'a GedcomExtras,
+    /// Notes citées plusieurs fois, écrites en `SNOTE` (7.0).
+    shared_notes: Vec<&'a str>,
+    /// Médias écrits en enregistrements `OBJE` (7.0).
+    objects: Vec<&'a ImageReference>,
+    out: String,
+}
+
+impl<'a> GedcomWriter<'a> {
+    fn new(
+        persons: &'a [Person],
+        families: &'a [Family],
+        sources: &'a [Source],
+        version: GedcomVersion,
+        extras: &'a GedcomExtras,
+    ) -> Self {
+        // Les sources citées mais absentes de la liste fournie sont exportées aussi.
+        let mut all_sources: Vec<&Source> = Vec::new();
+        let mut known_sources = HashSet::new();
+        let cited = sources
+            .iter()
+            .chain(persons.iter().flat_map(person_sources))
+            .chain(families.iter().flat_map(family_sources));
+        for source in cited {
+            if known_sources.insert(source.id) {
+                all_sources.push(source);
+            }
+        }
+
+        let mut repositories: Vec<&str> = Vec::new();
+        for name in all_sources.iter().filter_map(|s| s.repository.as_deref()) {
+            if !repositories.contains(&name) {
+                repositories.push(name);
+            }
+        }
+
+        let mut used = HashSet::new();
+        let person_xrefs = persons
+            .iter()
+            .map(|p| (p.id, stable_xref('I', p.id.0, &mut used)))
+            .collect();
+        let family_xrefs = families
+            .iter()
+            .map(|f| (f.id, stable_xref('F', f.id.0, &mut used)))
+            .collect();
+        let source_xrefs = all_sources
+            .iter()
+            .map(|s| (s.id, stable_xref('S', s.id, &mut used)))
+            .collect();
+
+        let mut child_in: HashMap<PersonId, Vec<FamilyId>> = HashMap::new();
+        let mut parent_in: HashMap<PersonId, Vec<FamilyId>> = HashMap::new();
+        for family in families {
+            for child in &family.children {
+                let links = child_in.entry(*child).or_default();
+                if links.last() != Some(&family.id) {
+                    links.push(family.id);
+                }
+            }
+            for parent in family.father.iter().chain(family.mother.iter()) {
+                let links = parent_in.entry(*parent).or_default();
+                if links.last() != Some(&family.id) {
+                    links.push(family.id);
+                }
+            }
+        }
+
+        let mut shared_notes = Vec::new();
+        let mut objects = Vec::new();
+        if version == GedcomVersion::V70 {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            let notes = persons
+                .iter()
+                .flat_map(|p| p.notes.iter().chain(person_events(p).flat_map(|e| e.notes.iter())))
+                .chain(families.iter().flat_map(|f| {
+                    f.notes.iter().chain(family_events(f).flat_map(|e| e.notes.iter()))
+                }))
+                .chain(all_sources.iter().flat_map(|s| s.notes.iter()))
+                .map(String::as_str)
+                .filter(|note| !note.trim().is_empty());
+            for note in notes {
+                let count = counts.entry(note).or_default();
+                *count += 1;
+                if *count == 2 {
+                    shared_notes.push(note);
+                }
+            }
+
+            for image in persons.iter().flat_map(|p| p.images.iter()) {
+                if !objects.iter().any(|known: &&ImageReference| same_media(known, image)) {
+                    objects.push(image);
+                }
+            }
+        }
+
+        Self {
+            persons,
+            families,
+            sources: all_sources,
+            repositories,
+            person_xrefs,
+            family_xrefs,
+            source_xrefs,
+            child_in,
+            parent_in,
+            version,
+            extras,
+            shared_notes,
+            objects,
+            out: String::new(),
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.header();
+        for person in self.persons {
+            self.person(person);
+        }
+        for family in self.families {
+            self.family(family);
+        }
+        for index in 0..self.sources.len() {
+            self.source(self.sources[index]);
+        }
+        for (index, name) in self.repositories.clone().into_iter().enumerate() {
+            self.record(&format!("R{}", index + 1), "REPO");
+            self.text(1, "NAME", name);
+        }
+        for (index, image) in self.objects.clone().into_iter().enumerate() {
+            self.record(&format!("O{}", index + 1), "OBJE");
+            self.text(1, "FILE", &image.url);
+            self.line(2, "FORM", Some(media_type(&image.url)));
+            if let Some(description) = image.description.as_deref() {
+                self.text(2, "TITL", description);
+            }
+        }
+        for (index, note) in self.shared_notes.clone().into_iter().enumerate() {
+            self.text(0, &format!("@N{}@ SNOTE", index + 1), note);
+        }
+        for record in self.extras.extension_records.iter() {
+            self.node(0, record);
+        }
+        self.line(0, "TRLR", None);
+        self.out
+    }
+
+    fn header(&mut self) {
+        self.line(0, "HEAD", None);
+        if self.version == GedcomVersion::V70 {
+            // En 7.0, `GEDC` doit être la première sous-structure de l'en-tête.
+            self.line(1, "GEDC", None);
+            self.line(2, "VERS", Some("7.0"));
+            if !self.extras.schema.is_empty() {
+                self.line(1, "SCHMA", None);
+                for (tag, uri) in &self.extras.schema {
+                    self.line(2, "TAG", Some(&format!("{tag} {uri}")));
+                }
+            }
+        }
+        self.line(1, "SOUR", Some("ROGLO"));
+        self.line(2, "NAME", Some("Roglo"));
+        self.line(2, "VERS", Some(env!("CARGO_PKG_VERSION")));
+        if self.version == GedcomVersion::V551 {
+            self.line(1, "GEDC", None);
+            self.line(2, "VERS", Some("5.5.1"));
+            self.line(2, "FORM", Some("LINEAGE-LINKED"));
+            self.line(1, "CHAR", Some("UTF-8"));
+        }
+    }
+
+    fn person(&mut self, person: &Person) {
+        let xref = self.person_xrefs[&person.id].clone();
+        self.record(&xref, "INDI");
+
+        let surname = match person.surname_prefix.as_deref() {
+            Some(prefix) => format!("{prefix} {}", person.surname),
+            None => person.surname.clone(),
+        };
+        self.text(1, "NAME", &format!("{} /{}/", person.first_name, surname));
+        if !person.first_name.is_empty() {
+            self.text(2, "GIVN", &person.first_name);
+        }
+        if let Some(prefix) = person.surname_prefix.as_deref() {
+            self.text(2, "SPFX", prefix);
+        }
+        if !person.surname.is_empty() {
+            self.text(2, "SURN", &person.surname);
+        }
+        for nickname in &person.nicknames {
+            self.text(2, "NICK", nickname);
+        }
+
+        let sex = match person.sex {
+            Sex::Male => "M",
+            Sex::Female => "F",
+            Sex::Unknown => "U",
+        };
+        self.line(1, "SEX", Some(sex));
+        if !person.public {
+            self.restriction();
+        }
+
+        for event in person_events(person) {
+            self.event(event, PERSON_EVENT_TAGS);
+        }
+
+        for occupation in &person.occupation {
+            self.text(1, "OCCU", occupation);
+        }
+        for title in &person.titles {
+            self.text(1, "TITL", &title.name);
+            if let Some(date) = &title.date_start {
+                self.date(2, date);
+            }
+            if let Some(place) = title.place.as_deref() {
+                self.text(2, "PLAC", place);
+            }
+        }
+        for image in &person.images {
+            if self.version == GedcomVersion::V70 {
+                let index = self.objects.iter().position(|known| same_media(known, image));
+                if let Some(index) = index {
+                    self.pointer(1, "OBJE", &format!("O{}", index + 1));
+                    if image.is_primary {
+                        self.line(2, "_PRIM", Some("Y"));
+                    }
+                }
+                continue;
+            }
+            self.line(1, "OBJE", None);
+            self.text(2, "FILE", &image.url);
+            self.line(3, "FORM", Some(media_format(&image.url)));
+            if let Some(description) = image.description.as_deref() {
+                self.text(3, "TITL", description);
+            }
+            // Extension Heredis/Ancestry, relue par `VendorMapping`.
+            if image.is_primary {
+                self.line(2, "_PRIM", Some("Y"));
+            }
+        }
+
+        self.notes(1, person.notes.as_deref());
+        self.citations(1, &person.sources);
+
+        let famc = family_links(&self.child_in, &self.family_xrefs, person.id);
+        for xref in famc {
+            self.pointer(1, "FAMC", &xref);
+        }
+        let fams = family_links(&self.parent_in, &self.family_xrefs, person.id);
+        for xref in fams {
+            self.pointer(1, "FAMS", &xref);
+        }
+        self.extras_of(person.id.0);
+    }
+
+    fn family(&mut self, family: &Family) {
+        let xref = self.family_xrefs[&family.id].clone();
+        self.record(&xref, "FAM");
+        if !family.public {
+            self.restriction();
+        }
+        if let Some(father) = family.father.and_then(|id| self.person_xrefs.get(&id).cloned()) {
+            self.pointer(1, "HUSB", &father);
+        }
+        if let Some(mother) = family.mother.and_then(|id| self.person_xrefs.get(&id).cloned()) {
+            self.pointer(1, "WIFE", &mother);
+        }
+        for child in &family.children {
+            if let Some(child) = self.person_xrefs.get(child).cloned() {
+                self.pointer(1, "CHIL", &child);
+            }
+        }
+        for event in family_events(family) {
+            self.event(event, FAMILY_EVENT_TAGS);
+        }
+        self.notes(1, family.notes.as_deref());
+        self.citations(1, &family.sources);
+        self.extras_of(family.id.0);
+    }
+
+    fn source(&mut self, source: &Source) {
+        let xref = self.source_xrefs[&source.id].clone();
+        self.record(&xref, "SOUR");
+        if !source.title.is_empty() {
+            self.text(1, "TITL", &source.title);
+        }
+        if let Some(author) = source.author.as_deref() {
+            self.text(1, "AUTH", author);
+        }
+        if let Some(publication) = source.publication_info.as_deref() {
+            self.text(1, "PUBL", publication);
+        }
+        if let Some(repository) = source.repository.as_deref() {
+            if let Some(index) = self.repositories.iter().position(|r| *r == repository) {
+                self.pointer(1, "REPO", &format!("R{}", index + 1));
+            }
+        }
+        self.notes(1, source.notes.as_deref());
+        self.extras_of(source.id);
+    }
+
+    /// `EXID` et sous-structures d'extension conservées à l'import (7.0 seulement).
+    fn extras_of(&mut self, id: Uuid) {
+        if self.version != GedcomVersion::V70 {
+            return;
+        }
+        let extras = self.extras;
+        for exid in extras.external_ids.get(&id).into_iter().flatten() {
+            self.text(1, "EXID", &exid.value);
+            if let Some(kind) = exid.kind.as_deref() {
+                self.text(2, "TYPE", kind);
+            }
+        }
+        for node in extras.extensions.get(&id).into_iter().flatten() {
+            self.node(1, node);
+        }
+    }
+
+    /// Réécrit un nœud conservé tel quel ; les pointeurs suivent les nouveaux xrefs.
+    fn node(&mut self, level: usize, node: &GedcomNode) {
+        let tag = match node.xref.as_deref() {
+            Some(xref) => format!("@{xref}@ {}", node.tag),
+            None => node.tag.clone(),
+        };
+        match node.pointer() {
+            Some(target) => {
+                let target = self.remap(target);
+                self.pointer(level, &tag, &target);
+            }
+            None => match node.value.as_deref() {
+                Some(value) => self.text(level, &tag, value),
+                None => self.line(level, &tag, None),
+            },
+        }
+        for child in &node.children {
+            self.node(level + 1, child);
+        }
+    }
+
+    /// xref d'origine → xref écrit (`VOID` si la cible n'est plus exportée).
+    fn remap(&self, target: &str) -> String {
+        let Some(id) = self.extras.pointers.get(target).copied() else {
+            return target.to_string();
+        };
+        self.person_xrefs
+            .get(&PersonId(id))
+            .or_else(|| self.family_xrefs.get(&FamilyId(id)))
+            .or_else(|| self.source_xrefs.get(&id))
+            .cloned()
+            .unwrap_or_else(|| "VOID".to_string())
+    }
+
+    fn restriction(&mut self) {
+        let value = match self.version {
+            GedcomVersion::V551 => "privacy",
+            GedcomVersion::V70 => "PRIVACY",
+        };
+        self.line(1, "RESN", Some(value));
+    }
+
+    fn event(&mut self, event: &LifeEvent, custom_tags: &[&str]) {
+        let (tag, kind) = match &event.event_type {
+            EventType::Birth => ("BIRT", None),
+            EventType::Baptism => ("BAPM", None),
+            EventType::Death => ("DEAT", None),
+            EventType::Burial => ("BURI", None),
+            EventType::Marriage => ("MARR", None),
+            EventType::Divorce => ("DIV", None),
+            EventType::Engagement => ("ENGA", None),
+            EventType::Custom(name) => match custom_tags.iter().find(|tag| **tag == name) {
+                Some(tag) => (*tag, None),
+                None => ("EVEN", Some(name.as_str())),
+            },
+        };
+
+        let has_details = format_date(&event.date).is_some()
+            || event.place.is_some()
+            || event.notes.is_some()
+            || !event.sources.is_empty()
+            || !event.witnesses.is_empty();
+        // `1 BIRT Y` signale un événement attesté sans détail.
+        self.line(1, tag, if has_details || kind.is_some() { None } else { Some("Y") });
+
+        if let Some(kind) = kind {
+            self.text(2, "TYPE", kind);
+        }
+        self.date(2, &event.date);
+        if let Some(place) = &event.place {
+            self.place(place);
+        }
+        for witness in &event.witnesses {
+            if let Some(xref) = self.person_xrefs.get(witness).cloned() {
+                self.pointer(2, "ASSO", &xref);
+                match self.version {
+                    GedcomVersion::V551 => self.line(3, "RELA", Some("Witness")),
+                    GedcomVersion::V70 => self.line(3, "ROLE", Some("WITN")),
+                }
+            }
+        }
+        self.notes(2, event.notes.as_deref());
+        self.citations(2, &event.sources);
+    }
+
+    /// `DATE`, suivie en 7.0 de sa `PHRASE`.
+    fn date(&mut self, level: usize, date: &PreciseDate) {
+        match self.version {
+            GedcomVersion::V551 => {
+                if let Some(value) = format_date(date) {
+                    self.line(level, "DATE", Some(&value));
+                }
+            }
+            GedcomVersion::V70 => {
+                if let Some((value, phrase)) = format_date7(date) {
+                    self.line(level, "DATE", Some(value.as_str()).filter(|value| !value.is_empty()));
+                    if let Some(phrase) = phrase {
+                        self.text(level + 1, "PHRASE", &phrase);
+                    }
+                }
+            }
+        }
+    }
+
+    fn place(&mut self, place: &Place) {
+        self.text(2, "PLAC", &place.name);
+        if let (Some(latitude), Some(longitude)) = (place.latitude, place.longitude) {
+            self.line(3, "MAP", None);
+            let lati = format!("{}{}", if latitude < 0.0 { 'S' } else { 'N' }, latitude.abs());
+            let long = format!("{}{}", if longitude < 0.0 { 'W' } else { 'E' }, longitude.abs());
+            self.line(4, "LATI", Some(&lati));
+            self.line(4, "LONG", Some(&long));
+        }
+    }
+
+    fn notes(&mut self, level: usize, notes: Option<&str>) {
+        if let Some(notes) = notes.filter(|n| !n.trim().is_empty()) {
+            match self.shared_notes.iter().position(|shared| *shared == notes) {
+                Some(index) => self.pointer(level, "SNOTE", &format!("N{}", index + 1)),
+                None => self.text(level, "NOTE", notes),
+            }
+        }
+    }
+
+    fn citations(&mut self, level: usize, sources: &[Source]) {
+        for source in sources {
+            if let Some(xref) = self.source_xrefs.get(&source.id).cloned() {
+                self.pointer(level, "SOUR", &xref);
+                if let Some(page) = source.page.as_deref() {
+                    self.text(level + 1, "PAGE", page);
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, xref: &str, tag: &str) {
+        self.out.push_str(&format!("0 @{xref}@ {tag}\n"));
+    }
+
+    fn pointer(&mut self, level: usize, tag: &str, xref: &str) {
+        self.out.push_str(&format!("{level} {tag} @{xref}@\n"));
+    }
+
+    fn line(&mut self, level: usize, tag: &str, value: Option<&str>) {
+        match value {
+            Some(value) => self.out.push_str(&format!("{level} {tag} {value}\n")),
+            None => self.out.push_str(&format!("{level} {tag}\n")),
+        }
+    }
+
+    /// Écrit une valeur libre : `@` échappé, retours à la ligne en `CONT`,
+    /// lignes trop longues découpées en `CONC` (5.5.1 seulement, `CONC` n'existe plus en 7.0).
+    fn text(&mut self, level: usize, tag: &str, value: &str) {
+        let escaped = match self.version {
+            GedcomVersion::V551 => value.replace('@', "@@"),
+            // En 7.0, seul un `@` en début de ligne est doublé.
+            GedcomVersion::V70 => {
+                let escaped = value.replace("\n@", "\n@@");
+                if escaped.starts_with('@') {
+                    format!("@{escaped}")
+                } else {
+                    escaped
+                }
+            }
+        };
+        for (index, line) in escaped.split('\n').enumerate() {
+            let line = line.trim_end_matches('\r');
+            let mut chunks = match self.version {
+                GedcomVersion::V551 => split_value(line),
+                GedcomVersion::V70 => vec![line],
+            }
+            .into_iter();
+            let first = chunks.next().filter(|chunk| !chunk.is_empty());
+            if index == 0 {
+                self.line(level, tag, first);
+            } else {
+                self.line(level + 1, "CONT", first);
+            }
+            for chunk in chunks {
+                self.line(level + 1, "CONC", Some(chunk));
+            }
+        }
+    }
+}
+
+/// Découpe une valeur en morceaux de `MAX_VALUE_LEN` octets au plus,
+/// sans couper un caractère, un `@@` échappé ni placer d'espace en bordure de morceau.
+fn split_value(value: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = value;
+    while rest.len() > MAX_VALUE_LEN {
+        let mut cut = MAX_VALUE_LEN;
+        while cut > 1
+            && (!rest.is_char_boundary(cut)
+                || rest[..cut].ends_with([' ', '@'])
+                || rest[cut..].starts_with(' '))
+        {
+            cut -= 1;
+        }
+        if cut <= 1 {
+            cut = MAX_VALUE_LEN;
+            while !rest.is_char_boundary(cut) {
+                cut -= 1;
+            }
+        }
+        chunks.push(&rest[..cut]);
+        rest = &rest[cut..];
+    }
+    chunks.push(rest);
+    chunks
+}
+
+/// Xref dérivé des 12 premiers chiffres hexadécimaux de l'UUID (allongé en cas de collision).
+/// Les majuscules sont exigées par GEDCOM 7.0.
+fn stable_xref(prefix: char, id: Uuid, used: &mut HashSet<String>) -> String {
+    let hex = id.simple().to_string().to_uppercase();
+    let mut len = 12;
+    loop {
+        let xref = format!("{prefix}{}", &hex[..len]);
+        if !used.contains(&xref) || len == hex.len() {
+            used.insert(xref.clone());
+            return xref;
+        }
+        len += 1;
+    }
+}
+
+/// Xrefs des familles liées à la personne.
+fn family_links(
+    links: &HashMap<PersonId, Vec<FamilyId>>,
+    xrefs: &HashMap<FamilyId, String>,
+    person: PersonId,
+) -> Vec<String> {
+    let families = links.get(&person).into_iter().flatten();
+    families.map(|family| xrefs[family].clone()).collect()
+}
+
+fn media_format(url: &str) -> &'static str {
+    let extension = url
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "jpg" | "jpeg" => "jpg",
+        "png" => "png",
+        "gif" => "gif",
+        "bmp" => "bmp",
+        "tif" | "tiff" => "tif",
+        "pdf" => "pdf",
+        _ => "url",
+    }
+}
+
+/// Type MIME exigé par `FILE.FORM` en 7.0.
+fn media_type(url: &str) -> &'static str {
+    match media_format(url) {
+        "jpg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "tif" => "image/tiff",
+        "pdf" => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+fn same_media(a: &ImageReference, b: &ImageReference) -> bool {
+    a.url == b.url && a.description == b.description
+}
+
+fn person_events(person: &Person) -> impl Iterator<Item = &LifeEvent> {
+    person
+        .birth
+        .iter()
+        .chain(person.death.iter())
+        .chain(person.other_events.iter())
+}
+
+fn family_events(family: &Family) -> impl Iterator<Item = &LifeEvent> {
+    family
+        .marriage
+        .iter()
+        .chain(family.divorce.iter())
+        .chain(family.other_events.iter())
+}
+
+fn person_sources(person: &Person) -> impl Iterator<Item = &Source> {
+    person
+        .sources
+        .iter()
+        .chain(person_events(person).flat_map(|event| event.sources.iter()))
+}
+
+fn family_sources(family: &Family) -> impl Iterator<Item = &Source> {
+    family
+        .sources
+        .iter()
+        .chain(family_events(family).flat_map(|event| event.sources.iter()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::import_gedcom;
+
+    #[test]
+    fn each_nickname_gets_its_own_nick() {
+        let data = import_gedcom(
+            "0 HEAD\n0 @I1@ INDI\n1 NAME Jean /Martin/\n2 NICK Jeannot\n\
+             2 NICK le Grand, dit le Sage\n0 TRLR\n",
+        )
+        .unwrap();
+        let nicknames = vec!["Jeannot".to_string(), "le Grand, dit le Sage".to_string()];
+        assert_eq!(data.persons[0].nicknames, nicknames);
+
+        let output = write_gedcom(&data.persons, &data.families, &data.sources);
+        assert!(output.contains("2 NICK Jeannot\n2 NICK le Grand, dit le Sage\n"));
+        let reimported = import_gedcom(&output).unwrap();
+        assert_eq!(reimported.persons[0].nicknames, nicknames);
+
+        let list =
+            import_gedcom("0 HEAD\n0 @I1@ INDI\n1 NAME Jean /Martin/\n2 NICK Jeannot, le Grand\n")
+                .unwrap();
+        assert_eq!(list.persons[0].nicknames, ["Jeannot", "le Grand"]);
+    }
+
+    #[test]
+    fn citation_pages_are_kept() {
+        let data = import_gedcom(
+            "0 HEAD\n0 @S1@ SOUR\n1 TITL Registre paroissial\n1 PUBL Archives\n\
+             0 @I1@ INDI\n1 NAME Jean /Martin/\n1 BIRT\n2 SOUR @S1@\n3 PAGE p. 12\n\
+             1 SOUR @S1@\n2 PAGE f° 3\n1 SOUR Acte notarié\n2 PAGE acte 4\n0 TRLR\n",
+        )
+        .unwrap();
+        let person = &data.persons[0];
+        let cited = &person.birth.as_ref().unwrap().sources[0];
+        assert_eq!(cited.page.as_deref(), Some("p. 12"));
+        assert_eq!(cited.publication_info.as_deref(), Some("Archives"));
+        assert_eq!(person.sources[0].id, cited.id);
+        assert_eq!(person.sources[0].page.as_deref(), Some("f° 3"));
+        assert_eq!(person.sources[1].page.as_deref(), Some("acte 4"));
+        assert_eq!(data.sources[0].page, None);
+
+        let output = write_gedcom(&data.persons, &data.families, &data.sources);
+        assert!(output.contains("3 PAGE p. 12\n"));
+        let reimported = import_gedcom(&output).unwrap();
+        let pages = |sources: &[Source]| -> Vec<_> {
+            sources
+                .iter()
+                .map(|s| (s.title.clone(), s.page.clone()))
+                .collect()
+        };
+        assert_eq!(
+            pages(&reimported.persons[0].sources),
+            pages(&person.sources)
+        );
+    }
+
+    #[test]
+    fn a_single_nickname_keeps_its_commas() {
+        let mut data = import_gedcom("0 HEAD\n0 @I1@ INDI\n1 NAME Jean /Martin/\n").unwrap();
+        let nicknames = vec!["Jean, dit le Grand".to_string()];
+        data.persons[0].nicknames = nicknames.clone();
+
+        for output in [
+            write_gedcom(&data.persons, &data.families, &data.sources),
+            write_gedcom7(&data.persons, &data.families, &data.sources, &data.extras),
+        ] {
+            assert!(output.contains("2 NICK Jean, dit le Grand\n"));
+            let reimported = import_gedcom(&output).unwrap();
+            assert_eq!(reimported.persons[0].nicknames, nicknames);
+        }
+    }
+
+    #[test]
+    fn christening_keeps_its_tag() {
+        let data = import_gedcom(
+            "0 HEAD\n0 @I1@ INDI\n1 NAME Jean /Martin/\n1 CHR\n2 DATE 3 MAY 1850\n\
+             1 BAPM\n2 DATE 1 JUN 1860\n0 TRLR\n",
+        )
+        .unwrap();
+        let events = &data.persons[0].other_events;
+        assert_eq!(events[0].event_type, EventType::Custom("CHR".to_string()));
+        assert_eq!(events[1].event_type, EventType::Baptism);
+
+        let output = write_gedcom(&data.persons, &data.families, &data.sources);
+        assert!(output.contains("1 CHR\n2 DATE 3 MAY 1850\n"));
+        assert!(output.contains("1 BAPM\n2 DATE 1 JUN 1860\n"));
+    }
+}
@@ -2,21 +2,19 @@ use std::collections::HashMap;
 
 use anyhow::Result;
 use async_recursion::async_recursion;
-use database::DatabasePool;
+use database::GraphStore;
 use genealogy_types::PersonId;
-use neo4rs::query;
-use uuid::Uuid;
 
-/// Calculateur de consanguinité basé sur Neo4j + PostgreSQL.
-pub struct ConsanguinityCalculator {
-    db: DatabasePool,
+/// Calculateur de consanguinité sur le graphe de filiation d'un [`GraphStore`].
+pub struct ConsanguinityCalculator<G> {
+    graph: G,
     cache: HashMap<PersonId, f64>,
 }
 
-impl ConsanguinityCalculator {
-    pub fn new(db: DatabasePool) -> Self {
+impl<G: GraphStore> ConsanguinityCalculator<G> {
+    pub fn new(graph: G) -> Self {
         Self {
-            db,
+            graph,
             cache: HashMap::new(),
         }
     }
@@ -28,7 +26,7 @@ impl ConsanguinityCalculator {
             return Ok(*value);
         }
 
-        let parents = self.get_parents(person_id).await?;
+        let parents = self.graph.parents(person_id).await?;
         if parents.len() < 2 {
             self.cache.insert(person_id, 0.0);
             return Ok(0.0);
@@ -36,16 +34,17 @@ impl ConsanguinityCalculator {
 
         let father = parents[0];
         let mother = parents[1];
-        let common_ancestors = self.find_common_ancestors(father, mother).await?;
+        let common_ancestors = self.graph.common_ancestors(father, mother).await?;
 
         let mut coeff = 0.0;
         for ancestor in common_ancestors {
-            let father_paths = self.find_paths(father, ancestor).await?;
-            let mother_paths = self.find_paths(mother, ancestor).await?;
+            let father_paths = self.graph.paths(father, ancestor).await?;
+            let mother_paths = self.graph.paths(mother, ancestor).await?;
 
             for f_path in &father_paths {
                 for m_path in &mother_paths {
-                    let n = f_path.len() + m_path.len();
+                    // Les chemins comprennent leurs extrémités : n compte les générations.
+                    let n = f_path.len() + m_path.len() - 2;
                     let ancestor_coeff = self.calculate(ancestor).await?;
                     coeff += (0.5_f64).powi((n as i32) + 1) * (1.0 + ancestor_coeff);
                 }
@@ -56,69 +55,98 @@ impl ConsanguinityCalculator {
         Ok(coeff)
     }
 
-    async fn get_parents(&self, person_id: PersonId) -> Result<Vec<PersonId>> {
-        let cypher = query(
-            "MATCH (child:Person {id: $id})-[:CHILD_OF]->(parent:Person) RETURN parent.id as id ORDER BY id",
-        )
-        .param("id", person_id.0.to_string());
-
-        let mut result = self.db.neo4j.execute(cypher).await?;
-        let mut parents = Vec::new();
-        while let Some(row) = result.next().await? {
-            let id: String = row.get("id")?;
-            parents.push(PersonId(Uuid::parse_str(&id)?));
-        }
-        Ok(parents)
+    pub fn cache_len(&self) -> usize {
+        self.cache.len()
     }
 
-    async fn find_common_ancestors(
-        &self,
-        father: PersonId,
-        mother: PersonId,
-    ) -> Result<Vec<PersonId>> {
-        let cypher = query(
-            "MATCH (f:Person {id: $father})-[:CHILD_OF*1..]->(ancestor:Person),
-                   (m:Person {id: $mother})-[:CHILD_OF*1..]->(ancestor)
-             RETURN DISTINCT ancestor.id as id",
-        )
-        .param("father", father.0.to_string())
-        .param("mother", mother.0.to_string());
-
-        let mut result = self.db.neo4j.execute(cypher).await?;
-        let mut ancestors = Vec::new();
-        while let Some(row) = result.next().await? {
-            let id: String = row.get("id")?;
-            ancestors.push(PersonId(Uuid::parse_str(&id)?));
-        }
-        Ok(ancestors)
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
     }
+}
 
-    async fn find_paths(&self, from: PersonId, to: PersonId) -> Result<Vec<Vec<PersonId>>> {
-        let cypher = query(
-            "MATCH path = (start:Person {id: $from})-[:CHILD_OF*1..]->(end:Person {id: $to})
-             RETURN [node in nodes(path) | node.id] as ids",
-        )
-        .param("from", from.0.to_string())
-        .param("to", to.0.to_string());
-
-        let mut result = self.db.neo4j.execute(cypher).await?;
-        let mut paths = Vec::new();
-        while let Some(row) = result.next().await? {
-            let ids: Vec<String> = row.get("ids")?;
-            let path: Vec<PersonId> = ids
-                .into_iter()
-                .map(|id| Uuid::parse_str(&id).map(PersonId))
-                .collect::<Result<_, _>>()?;
-            paths.push(path);
-        }
-        Ok(paths)
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use database::{FamilyDraft, FamilyStore, MemoryStore, PersonStore, Storage};
+    use genealogy_types::{FamilyId, Person, Sex};
+    use uuid::Uuid;
+
+    use super::*;
+
+    async fn person(store: &MemoryStore, first_name: &str) -> PersonId {
+        let now = Utc::now().naive_utc();
+        let person = Person {
+            id: PersonId(Uuid::new_v4()),
+            first_name: first_name.to_string(),
+            surname: "Martin".to_string(),
+            surname_prefix: None,
+            nicknames: Vec::new(),
+            sex: Sex::Unknown,
+            birth: None,
+            death: None,
+            other_events: Vec::new(),
+            occupation: Vec::new(),
+            titles: Vec::new(),
+            notes: None,
+            sources: Vec::new(),
+            images: Vec::new(),
+            public: true,
+            created_at: now,
+            updated_at: now,
+            updated_by: None,
+        };
+        store.persons().create(&person).await.unwrap();
+        person.id
     }
 
-    pub fn cache_len(&self) -> usize {
-        self.cache.len()
+    async fn family(
+        store: &MemoryStore,
+        father: PersonId,
+        mother: PersonId,
+        children: &[PersonId],
+    ) {
+        store
+            .families()
+            .create(FamilyDraft {
+                id: FamilyId(Uuid::new_v4()),
+                father_id: Some(father),
+                mother_id: Some(mother),
+                children: children.to_vec(),
+                marriage: None,
+                divorce: None,
+                other_events: Vec::new(),
+                notes: None,
+                sources: Vec::new(),
+                public: true,
+                created_by: None,
+            })
+            .await
+            .unwrap();
     }
 
-    pub fn clear_cache(&mut self) {
-        self.cache.clear();
+    #[tokio::test]
+    async fn child_of_first_cousins_has_one_sixteenth() {
+        let store = MemoryStore::new();
+        let (grandfather, grandmother) =
+            (person(&store, "Jean").await, person(&store, "Anne").await);
+        let (brother, sister) = (person(&store, "Paul").await, person(&store, "Marie").await);
+        let (aunt, uncle) = (
+            person(&store, "Louise").await,
+            person(&store, "Pierre").await,
+        );
+        let (cousin1, cousin2) = (
+            person(&store, "Jacques").await,
+            person(&store, "Claire").await,
+        );
+        let child = person(&store, "Lucie").await;
+        family(&store, grandfather, grandmother, &[brother, sister]).await;
+        family(&store, brother, aunt, &[cousin1]).await;
+        family(&store, uncle, sister, &[cousin2]).await;
+        family(&store, cousin1, cousin2, &[child]).await;
+
+        let mut calculator = ConsanguinityCalculator::new(store.graph());
+        assert_eq!(calculator.calculate(child).await.unwrap(), 1.0 / 16.0);
+        assert_eq!(calculator.calculate(cousin1).await.unwrap(), 0.0);
+        assert_eq!(calculator.calculate(grandfather).await.unwrap(), 0.0);
     }
 }
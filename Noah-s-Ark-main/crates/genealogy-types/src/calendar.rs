@@ -0,0 +1,357 @@
+use std::fmt;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::DateLocale;
+
+/// Calendrier dans lequel une date a été relevée.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Calendar {
+    Gregorian,
+    /// Registres paroissiaux antérieurs à la réforme de 1582.
+    Julian,
+    /// Calendrier républicain (état civil de 1793 à 1805).
+    French,
+    Hebrew,
+}
+
+/// Jour fixe (`chrono` : 1 = 1er janvier de l'an 1 grégorien) du 1er janvier de l'an 1 julien.
+const JULIAN_EPOCH: i64 = -1;
+/// Jour fixe du 1er vendémiaire an I (22 septembre 1792).
+const FRENCH_EPOCH: i64 = 654_415;
+/// Jour fixe du 1er tichri de l'an 1 (7 octobre 3761 av. J.-C., julien).
+const HEBREW_EPOCH: i64 = -1_373_427;
+
+impl Calendar {
+    /// Nombre de mois de l'année : 13 pour le républicain (jours complémentaires)
+    /// et l'hébreu (`ADS`, Adar II, sans jour les années communes).
+    pub fn months(self) -> u32 {
+        match self {
+            Calendar::Gregorian | Calendar::Julian => 12,
+            Calendar::French | Calendar::Hebrew => 13,
+        }
+    }
+
+    /// Nombre de jours du mois, 0 si le mois n'existe pas cette année-là.
+    pub fn days_in_month(self, year: i32, month: u32) -> u32 {
+        match (self, month) {
+            (_, 0) => 0,
+            (_, month) if month > self.months() => 0,
+            (Calendar::Gregorian | Calendar::Julian, 2) if self.is_leap(year) => 29,
+            (Calendar::Gregorian | Calendar::Julian, 2) => 28,
+            (Calendar::Gregorian | Calendar::Julian, 4 | 6 | 9 | 11) => 30,
+            (Calendar::Gregorian | Calendar::Julian, _) => 31,
+            (Calendar::French, 13) if self.is_leap(year) => 6,
+            (Calendar::French, 13) => 5,
+            (Calendar::French, _) => 30,
+            (Calendar::Hebrew, month) => {
+                let year = year as i64;
+                let length = hebrew_year_length(year);
+                match month {
+                    // Hechvan long, kislev court.
+                    2 if length % 10 == 5 => 30,
+                    3 if length % 10 == 3 => 29,
+                    6 if hebrew_leap(year) => 30,
+                    7 if !hebrew_leap(year) => 0,
+                    1 | 3 | 5 | 8 | 10 | 12 => 30,
+                    _ => 29,
+                }
+            }
+        }
+    }
+
+    /// Année bissextile (grégorien, julien), sextile (républicain) ou embolismique (hébreu).
+    pub fn is_leap(self, year: i32) -> bool {
+        match self {
+            Calendar::Gregorian => {
+                year.rem_euclid(4) == 0 && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0)
+            }
+            Calendar::Julian => year.rem_euclid(4) == 0,
+            Calendar::French => {
+                french_fixed(year as i64 + 1, 1, 1) - french_fixed(year as i64, 1, 1) == 366
+            }
+            Calendar::Hebrew => hebrew_leap(year as i64),
+        }
+    }
+
+    /// Jour fixe d'une date valide de ce calendrier.
+    fn fixed(self, year: i32, month: u32, day: u32) -> i64 {
+        // Mars et les mois suivants perdent les jours que février n'a pas.
+        let correction = match month {
+            1 | 2 => 0,
+            _ if self.is_leap(year) => -1,
+            _ => -2,
+        };
+        let (year, month, day) = (year as i64, month as i64, day as i64);
+        match self {
+            Calendar::Gregorian => {
+                let elapsed = year - 1;
+                365 * elapsed + elapsed.div_euclid(4) - elapsed.div_euclid(100)
+                    + elapsed.div_euclid(400)
+                    + (367 * month - 362) / 12
+                    + correction
+                    + day
+            }
+            Calendar::Julian => {
+                JULIAN_EPOCH - 1
+                    + 365 * (year - 1)
+                    + (year - 1).div_euclid(4)
+                    + (367 * month - 362) / 12
+                    + correction
+                    + day
+            }
+            Calendar::French => french_fixed(year, month, day),
+            Calendar::Hebrew => {
+                hebrew_new_year(year)
+                    + (1..month as u32)
+                        .map(|month| self.days_in_month(year as i32, month) as i64)
+                        .sum::<i64>()
+                    + day
+                    - 1
+            }
+        }
+    }
+
+    /// Date de ce calendrier correspondant à un jour fixe.
+    fn date(self, fixed: i64) -> (i32, u32, u32) {
+        let mut year = match self {
+            Calendar::Gregorian | Calendar::Julian => (fixed * 4).div_euclid(1461) as i32,
+            Calendar::French => (fixed - FRENCH_EPOCH).div_euclid(365) as i32,
+            Calendar::Hebrew => ((fixed - HEBREW_EPOCH) * 98_496).div_euclid(35_975_351) as i32,
+        };
+        // L'estimation peut être décalée d'une année dans un sens ou dans l'autre.
+        while self.fixed(year + 1, 1, 1) <= fixed {
+            year += 1;
+        }
+        while self.fixed(year, 1, 1) > fixed {
+            year -= 1;
+        }
+        let mut month = 1;
+        while month < self.months() && self.fixed(year, month + 1, 1) <= fixed {
+            month += 1;
+        }
+        let day = fixed - self.fixed(year, month, 1) + 1;
+        (year, month, day as u32)
+    }
+}
+
+impl fmt::Display for Calendar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Calendar::Gregorian => "grégorien",
+            Calendar::Julian => "julien",
+            Calendar::French => "républicain",
+            Calendar::Hebrew => "hébraïque",
+        })
+    }
+}
+
+/// Le calendrier républicain suit ici la règle de Romme : années sextiles III, VII, XI…
+/// tous les quatre ans, comme l'arithmétique de GeneWeb.
+fn french_fixed(year: i64, month: i64, day: i64) -> i64 {
+    FRENCH_EPOCH - 1 + (year - 1) * 365 + year.div_euclid(4) + (month - 1) * 30 + day
+}
+
+fn hebrew_leap(year: i64) -> bool {
+    (7 * year + 1).rem_euclid(19) < 7
+}
+
+/// Jours écoulés entre l'époque et le molad de tichri, avec les reports d'usage.
+fn hebrew_elapsed_days(year: i64) -> i64 {
+    let months = (235 * year - 234).div_euclid(19);
+    let parts = 12_084 + 13_753 * months;
+    let days = 29 * months + parts.div_euclid(25_920);
+    if (3 * (days + 1)).rem_euclid(7) < 3 {
+        days + 1
+    } else {
+        days
+    }
+}
+
+fn hebrew_new_year(year: i64) -> i64 {
+    let elapsed = hebrew_elapsed_days(year);
+    let correction = if hebrew_elapsed_days(year + 1) - elapsed == 356 {
+        2
+    } else if elapsed - hebrew_elapsed_days(year - 1) == 382 {
+        1
+    } else {
+        0
+    };
+    HEBREW_EPOCH + elapsed + correction
+}
+
+fn hebrew_year_length(year: i64) -> i64 {
+    hebrew_new_year(year + 1) - hebrew_new_year(year)
+}
+
+/// Date partielle dans un calendrier donné : année, mois de l'année, jour du mois.
+///
+/// Les mois suivent l'ordre GEDCOM : `VEND`…`COMP` pour le républicain,
+/// `TSH`…`ELL` pour l'hébreu (`ADR` est Adar I les années embolismiques).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarDate {
+    pub calendar: Calendar,
+    pub year: i32,
+    pub month: Option<u32>,
+    pub day: Option<u32>,
+}
+
+impl CalendarDate {
+    /// Date vérifiée : jour réservé aux dates avec mois, an I au plus tôt hors grégorien.
+    pub fn new(
+        calendar: Calendar,
+        year: i32,
+        month: Option<u32>,
+        day: Option<u32>,
+    ) -> Option<Self> {
+        let date = Self {
+            calendar,
+            year,
+            month,
+            day,
+        };
+        let valid = match (month, day) {
+            _ if calendar != Calendar::Gregorian && year < 1 => false,
+            (None, None) => true,
+            (None, Some(_)) => false,
+            (Some(month), None) => calendar.days_in_month(year, month) > 0,
+            (Some(month), Some(day)) => (1..=calendar.days_in_month(year, month)).contains(&day),
+        };
+        // Les bornes doivent tenir dans la plage de `NaiveDate`.
+        (valid && date.first_day().is_some() && date.last_day().is_some()).then_some(date)
+    }
+
+    /// Jour `date` du calendrier grégorien, exprimé dans `calendar`.
+    pub fn from_gregorian(calendar: Calendar, date: NaiveDate) -> Self {
+        let (year, month, day) = calendar.date(date.num_days_from_ce() as i64);
+        Self {
+            calendar,
+            year,
+            month: Some(month),
+            day: Some(day),
+        }
+    }
+
+    /// Borne de début : l'année ou le mois seuls quand `date` en est le premier jour.
+    pub fn start(calendar: Calendar, date: NaiveDate) -> Self {
+        let full = Self::from_gregorian(calendar, date);
+        [full.year_only(), full.month_only()]
+            .into_iter()
+            .find(|partial| partial.first_day() == Some(date))
+            .unwrap_or(full)
+    }
+
+    /// Borne de fin : l'année ou le mois seuls quand `date` en est le dernier jour.
+    pub fn end(calendar: Calendar, date: NaiveDate) -> Self {
+        let full = Self::from_gregorian(calendar, date);
+        [full.year_only(), full.month_only()]
+            .into_iter()
+            .find(|partial| partial.last_day() == Some(date))
+            .unwrap_or(full)
+    }
+
+    /// Jour, mois ou année couvrant exactement `start..=end`.
+    pub fn span(calendar: Calendar, start: NaiveDate, end: NaiveDate) -> Option<Self> {
+        let full = Self::from_gregorian(calendar, start);
+        [full, full.month_only(), full.year_only()]
+            .into_iter()
+            .find(|partial| partial.first_day() == Some(start) && partial.last_day() == Some(end))
+    }
+
+    /// Premier jour couvert, en grégorien.
+    pub fn first_day(&self) -> Option<NaiveDate> {
+        let month = self.month.unwrap_or(1);
+        let fixed = self.calendar.fixed(self.year, month, self.day.unwrap_or(1));
+        NaiveDate::from_num_days_from_ce_opt(i32::try_from(fixed).ok()?)
+    }
+
+    /// Dernier jour couvert, en grégorien.
+    pub fn last_day(&self) -> Option<NaiveDate> {
+        let fixed = match (self.month, self.day) {
+            (Some(month), Some(day)) => self.calendar.fixed(self.year, month, day),
+            (Some(month), None) => {
+                let length = self.calendar.days_in_month(self.year, month);
+                self.calendar.fixed(self.year, month, length)
+            }
+            _ => self.calendar.fixed(self.year.checked_add(1)?, 1, 1) - 1,
+        };
+        NaiveDate::from_num_days_from_ce_opt(i32::try_from(fixed).ok()?)
+    }
+
+    fn month_only(self) -> Self {
+        Self { day: None, ..self }
+    }
+
+    fn year_only(self) -> Self {
+        Self {
+            month: None,
+            day: None,
+            ..self
+        }
+    }
+}
+
+/// Affichage en français ; voir [`CalendarDate::format`] pour les autres langues.
+impl fmt::Display for CalendarDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format(DateLocale::French))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// Le jour `(year, month, day)` de `calendar` tombe le `gregorian`, et inversement.
+    fn assert_same_day(
+        calendar: Calendar,
+        (year, month, day): (i32, u32, u32),
+        gregorian: NaiveDate,
+    ) {
+        let date = CalendarDate::new(calendar, year, Some(month), Some(day)).unwrap();
+        assert_eq!(date.first_day(), Some(gregorian), "{date:?}");
+        assert_eq!(date.last_day(), Some(gregorian), "{date:?}");
+        assert_eq!(CalendarDate::from_gregorian(calendar, gregorian), date);
+    }
+
+    #[test]
+    fn julian_dates_lag_behind_the_gregorian_reform() {
+        assert_same_day(Calendar::Julian, (1582, 10, 5), day(1582, 10, 15));
+        assert_same_day(Calendar::Julian, (1700, 1, 1), day(1700, 1, 11));
+        // 1700 est bissextile en julien seulement.
+        assert_same_day(Calendar::Julian, (1700, 2, 29), day(1700, 3, 11));
+    }
+
+    #[test]
+    fn republican_dates_start_on_22_september_1792() {
+        assert_same_day(Calendar::French, (1, 1, 1), day(1792, 9, 22));
+        assert_same_day(Calendar::French, (3, 1, 12), day(1794, 10, 3));
+        // L'an III est sextile : six jours complémentaires.
+        assert_eq!(Calendar::French.days_in_month(2, 13), 5);
+        assert_eq!(Calendar::French.days_in_month(3, 13), 6);
+    }
+
+    #[test]
+    fn hebrew_years_start_on_1_tishri() {
+        assert_same_day(Calendar::Hebrew, (5784, 1, 1), day(2023, 9, 16));
+        // Adar II n'existe que les années embolismiques, dont 5784.
+        assert_eq!(Calendar::Hebrew.days_in_month(5783, 7), 0);
+        assert_eq!(Calendar::Hebrew.days_in_month(5784, 7), 29);
+    }
+
+    #[test]
+    fn a_year_spans_its_first_and_last_day() {
+        let year = CalendarDate::new(Calendar::French, 1, None, None).unwrap();
+        assert_eq!(year.first_day(), Some(day(1792, 9, 22)));
+        assert_eq!(year.last_day(), Some(day(1793, 9, 21)));
+        assert_eq!(
+            CalendarDate::span(Calendar::French, day(1792, 9, 22), day(1793, 9, 21)),
+            Some(year)
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::cmp::Ordering;
+
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::PreciseDate;
+
+/// Marge, en années, de part et d'autre d'une date approximative (`ABT`, `~`).
+pub const ABOUT_MARGIN_YEARS: u32 = 5;
+
+/// Plage des jours où une date peut tomber ; une borne absente n'est pas connue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DateInterval {
+    pub earliest: Option<NaiveDate>,
+    pub latest: Option<NaiveDate>,
+}
+
+impl DateInterval {
+    pub fn new(earliest: Option<NaiveDate>, latest: Option<NaiveDate>) -> Self {
+        Self { earliest, latest }
+    }
+
+    pub fn unbounded() -> Self {
+        Self::new(None, None)
+    }
+
+    pub fn day(date: NaiveDate) -> Self {
+        Self::new(Some(date), Some(date))
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.earliest.is_none_or(|earliest| earliest <= date)
+            && self.latest.is_none_or(|latest| date <= latest)
+    }
+
+    /// Tous les jours de `self` précèdent tous ceux de `other`.
+    pub fn is_before(&self, other: &DateInterval) -> bool {
+        matches!((self.latest, other.earliest), (Some(latest), Some(earliest)) if latest < earliest)
+    }
+
+    /// Au moins un jour de `self` précède un jour de `other`.
+    pub fn may_be_before(&self, other: &DateInterval) -> bool {
+        match (self.earliest, other.latest) {
+            (Some(earliest), Some(latest)) => earliest < latest,
+            _ => true,
+        }
+    }
+
+    pub fn overlaps(&self, other: &DateInterval) -> bool {
+        !self.is_before(other) && !other.is_before(self)
+    }
+}
+
+/// Clé de tri totale : jour de référence, puis les dates les plus précises d'abord,
+/// les dates inconnues à la fin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateSortKey {
+    unknown: bool,
+    reference: NaiveDate,
+    latest: NaiveDate,
+}
+
+impl PreciseDate {
+    /// Premier et dernier jours possibles, en grégorien.
+    ///
+    /// `Before` et `After` excluent leur borne ; `About` et `AboutYear` s'étendent de
+    /// [`ABOUT_MARGIN_YEARS`] de part et d'autre. Une date inconnue, ou sans équivalent
+    /// grégorien, n'est pas bornée.
+    pub fn interval(&self) -> DateInterval {
+        let margin = Months::new(12 * ABOUT_MARGIN_YEARS);
+        match self {
+            PreciseDate::Exact(date) => DateInterval::day(*date),
+            PreciseDate::About(date) => DateInterval::new(
+                date.checked_sub_months(margin),
+                date.checked_add_months(margin),
+            ),
+            PreciseDate::Before(date) => DateInterval::new(None, date.pred_opt()),
+            PreciseDate::After(date) => DateInterval::new(date.succ_opt(), None),
+            PreciseDate::Between(start, end) => DateInterval::new(Some(*start), Some(*end)),
+            PreciseDate::YearOnly(year) => DateInterval::new(
+                NaiveDate::from_ymd_opt(*year, 1, 1),
+                NaiveDate::from_ymd_opt(*year, 12, 31),
+            ),
+            PreciseDate::AboutYear(year) => DateInterval::new(
+                NaiveDate::from_ymd_opt(*year, 1, 1)
+                    .and_then(|date| date.checked_sub_months(margin)),
+                NaiveDate::from_ymd_opt(*year, 12, 31)
+                    .and_then(|date| date.checked_add_months(margin)),
+            ),
+            PreciseDate::Verbatim { approx, .. } => approx
+                .as_ref()
+                .map_or_else(DateInterval::unbounded, |approx| approx.interval()),
+            PreciseDate::InCalendar { date, .. } => date.interval(),
+            PreciseDate::Unknown => DateInterval::unbounded(),
+        }
+    }
+
+    /// Certainement antérieure à `other` : aucun jour possible ne se recouvre.
+    pub fn is_definitely_before(&self, other: &PreciseDate) -> bool {
+        self.interval().is_before(&other.interval())
+    }
+
+    /// Peut-être antérieure à `other` ; toujours vrai si l'une des deux est inconnue.
+    pub fn is_possibly_before(&self, other: &PreciseDate) -> bool {
+        self.interval().may_be_before(&other.interval())
+    }
+
+    /// Clé pour trier enfants, événements ou chronologies.
+    ///
+    /// Le jour de référence est la date elle-même pour `Exact` et `About`, le
+    /// 1er janvier pour une année, la borne connue sinon.
+    pub fn sort_key(&self) -> DateSortKey {
+        let interval = self.interval();
+        let reference = match self.gregorian() {
+            PreciseDate::Exact(date) | PreciseDate::About(date) => Some(*date),
+            PreciseDate::AboutYear(year) => NaiveDate::from_ymd_opt(*year, 1, 1),
+            PreciseDate::Verbatim {
+                approx: Some(approx),
+                ..
+            } => return approx.sort_key(),
+            _ => interval.earliest.or(interval.latest),
+        };
+        match reference {
+            Some(reference) => DateSortKey {
+                unknown: false,
+                reference,
+                latest: interval.latest.unwrap_or(NaiveDate::MAX),
+            },
+            None => DateSortKey {
+                unknown: true,
+                reference: NaiveDate::MIN,
+                latest: NaiveDate::MAX,
+            },
+        }
+    }
+
+    /// Clé de tri d'un événement dans une chronologie de vie : `rank` vaut 0 pour la
+    /// naissance, 1 pour les autres événements et 2 pour le décès, qui départage les
+    /// dates égales. Sans date, la naissance reste en tête et le décès à la fin.
+    pub fn timeline_key(&self, rank: u8) -> (u8, DateSortKey, u8) {
+        let key = self.sort_key();
+        let pinned = match rank {
+            0 | 2 if key.unknown => rank,
+            _ => 1,
+        };
+        (pinned, key, rank)
+    }
+}
+
+/// Ordre partiel : `Less` quand la date est certainement antérieure, `Greater`
+/// certainement postérieure, `None` quand les plages se recouvrent.
+impl PartialOrd for PreciseDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if self.is_definitely_before(other) {
+            Some(Ordering::Less)
+        } else if other.is_definitely_before(self) {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn overlapping_ranges_are_not_ordered() {
+        let between = PreciseDate::Between(day(1700, 1, 1), day(1710, 12, 31));
+        let about = PreciseDate::AboutYear(1712);
+        assert_eq!(between.partial_cmp(&about), None);
+        assert_eq!(about.partial_cmp(&between), None);
+        assert!(between.is_possibly_before(&about));
+        assert!(!between.is_definitely_before(&about));
+
+        let inside = PreciseDate::Exact(day(1705, 6, 1));
+        assert_eq!(between.partial_cmp(&inside), None);
+        assert_eq!(
+            between.partial_cmp(&PreciseDate::AboutYear(1720)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            PreciseDate::After(day(1710, 12, 31)).partial_cmp(&between),
+            Some(Ordering::Greater)
+        );
+    }
+
+    #[test]
+    fn undated_values_sort_last_and_precise_ones_first() {
+        let phrase = PreciseDate::Verbatim {
+            value: "(vers Pâques)".to_string(),
+            approx: None,
+        };
+        let mut dates = vec![
+            PreciseDate::Unknown,
+            PreciseDate::YearOnly(1750),
+            phrase.clone(),
+            PreciseDate::Exact(day(1750, 1, 1)),
+            PreciseDate::Before(day(1700, 1, 1)),
+            PreciseDate::Exact(day(1700, 5, 1)),
+        ];
+        dates.sort_by_key(PreciseDate::sort_key);
+        assert_eq!(
+            dates,
+            [
+                PreciseDate::Before(day(1700, 1, 1)),
+                PreciseDate::Exact(day(1700, 5, 1)),
+                PreciseDate::Exact(day(1750, 1, 1)),
+                PreciseDate::YearOnly(1750),
+                PreciseDate::Unknown,
+                phrase,
+            ]
+        );
+        assert_eq!(
+            PreciseDate::Unknown.sort_key(),
+            PreciseDate::Verbatim {
+                value: "?".to_string(),
+                approx: None,
+            }
+            .sort_key()
+        );
+    }
+}
@@ -1,7 +1,18 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
 use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+pub mod calendar;
+pub mod interval;
+pub mod locale;
+
+pub use calendar::{Calendar, CalendarDate};
+pub use interval::{DateInterval, DateSortKey, ABOUT_MARGIN_YEARS};
+pub use locale::DateLocale;
+
 /// Identifiant unique d'une personne
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PersonId(pub Uuid);
@@ -27,11 +38,108 @@ pub enum PreciseDate {
     After(NaiveDate),
     Between(NaiveDate, NaiveDate),
     YearOnly(i32),
+    /// Année approximative (`ABT 1750`, `~1750`).
+    AboutYear(i32),
+    /// Date que les variantes précédentes déformeraient (`EST 1750`, `FROM 1790 TO 1795`,
+    /// calendrier julien ou républicain, phrase libre) : valeur GEDCOM d'origine,
+    /// réécrite telle quelle, et son équivalent grégorien approché s'il existe.
+    Verbatim {
+        value: String,
+        approx: Option<Box<PreciseDate>>,
+    },
+    /// Date relevée dans un autre calendrier : `date` en est l'équivalent grégorien
+    /// exact, `calendar` sert à l'affichage et à l'export.
+    InCalendar {
+        calendar: Calendar,
+        date: Box<PreciseDate>,
+    },
     Unknown,
 }
 
+impl PreciseDate {
+    /// Date exacte, ou plage couverte par un mois ou une année, dans son calendrier.
+    pub fn from_calendar(date: CalendarDate) -> Option<Self> {
+        let gregorian = match date.day {
+            Some(_) => PreciseDate::Exact(date.first_day()?),
+            None => PreciseDate::Between(date.first_day()?, date.last_day()?),
+        };
+        Some(gregorian.in_calendar(date.calendar))
+    }
+
+    /// Calendrier d'origine.
+    pub fn calendar(&self) -> Calendar {
+        match self {
+            PreciseDate::InCalendar { calendar, .. } => *calendar,
+            _ => Calendar::Gregorian,
+        }
+    }
+
+    /// Équivalent grégorien : la date elle-même hors [`PreciseDate::InCalendar`].
+    pub fn gregorian(&self) -> &PreciseDate {
+        match self {
+            PreciseDate::InCalendar { date, .. } => date.gregorian(),
+            date => date,
+        }
+    }
+
+    /// Même date, affichée et exportée dans `calendar`.
+    ///
+    /// Une année seule devient la plage de ses jours ; les années approximatives,
+    /// les valeurs d'origine, les dates inconnues et celles antérieures à l'an I du
+    /// calendrier restent grégoriennes.
+    pub fn in_calendar(self, calendar: Calendar) -> Self {
+        let date = match self {
+            PreciseDate::InCalendar { date, .. } => *date,
+            date => date,
+        };
+        let date = match date {
+            PreciseDate::YearOnly(year) if calendar != Calendar::Gregorian => {
+                match (
+                    NaiveDate::from_ymd_opt(year, 1, 1),
+                    NaiveDate::from_ymd_opt(year, 12, 31),
+                ) {
+                    (Some(start), Some(end)) => PreciseDate::Between(start, end),
+                    _ => return PreciseDate::YearOnly(year),
+                }
+            }
+            PreciseDate::AboutYear(_) | PreciseDate::Verbatim { .. } | PreciseDate::Unknown => {
+                return date
+            }
+            date => date,
+        };
+        // Avant l'an I, la date n'a pas d'équivalent dans `calendar`.
+        let bounds = match &date {
+            PreciseDate::Exact(day)
+            | PreciseDate::About(day)
+            | PreciseDate::Before(day)
+            | PreciseDate::After(day) => vec![*day],
+            PreciseDate::Between(start, end) => vec![*start, *end],
+            _ => Vec::new(),
+        };
+        let representable = bounds
+            .into_iter()
+            .all(|day| CalendarDate::from_gregorian(calendar, day).year >= 1);
+        match calendar {
+            Calendar::Gregorian => date,
+            _ if !representable => date,
+            calendar => PreciseDate::InCalendar {
+                calendar,
+                date: Box::new(date),
+            },
+        }
+    }
+}
+
+/// Affichage en français, dans le calendrier d'origine : `vers 1750`,
+/// `avant le 3 mars 1801`, `12 vendémiaire an III`.
+impl fmt::Display for PreciseDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.format_localized(DateLocale::French))
+    }
+}
+
 /// Lieu géographique
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Place {
     pub name: String,
     pub locality: Option<String>,
@@ -44,7 +152,7 @@ pub struct Place {
 }
 
 /// Source d'information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Source {
     pub id: Uuid,
     pub title: String,
@@ -52,10 +160,12 @@ pub struct Source {
     pub publication_info: Option<String>,
     pub repository: Option<String>,
     pub notes: Option<String>,
+    /// Emplacement cité (`PAGE` GEDCOM), propre à cette citation de la source.
+    pub page: Option<String>,
 }
 
 /// Événement de vie
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LifeEvent {
     pub event_type: EventType,
     pub date: PreciseDate,
@@ -65,7 +175,7 @@ pub struct LifeEvent {
     pub sources: Vec<Source>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EventType {
     Birth,
     Baptism,
@@ -77,8 +187,39 @@ pub enum EventType {
     Custom(String),
 }
 
+impl EventType {
+    /// Nom stocké et exposé par l'API : `birth`, `baptism`…, ou le libellé d'un
+    /// événement personnalisé.
+    pub fn name(&self) -> &str {
+        match self {
+            EventType::Birth => "birth",
+            EventType::Baptism => "baptism",
+            EventType::Death => "death",
+            EventType::Burial => "burial",
+            EventType::Marriage => "marriage",
+            EventType::Divorce => "divorce",
+            EventType::Engagement => "engagement",
+            EventType::Custom(name) => name,
+        }
+    }
+
+    /// Inverse de [`EventType::name`] ; tout autre nom est un événement personnalisé.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "birth" => EventType::Birth,
+            "baptism" => EventType::Baptism,
+            "death" => EventType::Death,
+            "burial" => EventType::Burial,
+            "marriage" => EventType::Marriage,
+            "divorce" => EventType::Divorce,
+            "engagement" => EventType::Engagement,
+            other => EventType::Custom(other.to_string()),
+        }
+    }
+}
+
 /// Titre nobiliaire ou honorifique
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Title {
     pub name: String,
     pub place: Option<String>,
@@ -88,7 +229,7 @@ pub struct Title {
 }
 
 /// Référence à une image
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ImageReference {
     pub url: String,
     pub description: Option<String>,
@@ -120,7 +261,7 @@ pub enum Permission {
 }
 
 /// Personne complète
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Person {
     pub id: PersonId,
     pub first_name: String,
@@ -142,8 +283,25 @@ pub struct Person {
     pub updated_by: Option<WizardId>,
 }
 
+impl Person {
+    /// Naissance, autres événements et décès dans l'ordre chronologique ; à date égale
+    /// ou inconnue, l'ordre d'origine est conservé, mais une naissance sans date reste
+    /// la première et un décès sans date le dernier.
+    pub fn timeline(&self) -> Vec<&LifeEvent> {
+        let mut events: Vec<(u8, &LifeEvent)> = self
+            .birth
+            .iter()
+            .map(|event| (0, event))
+            .chain(self.other_events.iter().map(|event| (1, event)))
+            .chain(self.death.iter().map(|event| (2, event)))
+            .collect();
+        events.sort_by_key(|(rank, event)| event.date.timeline_key(*rank));
+        events.into_iter().map(|(_, event)| event).collect()
+    }
+}
+
 /// Famille
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Family {
     pub id: FamilyId,
     pub father: Option<PersonId>,
@@ -158,3 +316,150 @@ pub struct Family {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
+
+/// Rapport d'un import GEDCOM ou `.gw`, présenté au wizard qui l'a lancé.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Nombre d'enregistrements lus par type (`INDI`, `FAM`, `fam`, `pevt`…).
+    pub records: BTreeMap<String, usize>,
+    pub persons: usize,
+    pub families: usize,
+    pub sources: usize,
+    pub warnings: Vec<ImportWarning>,
+}
+
+impl ImportReport {
+    pub fn count_record(&mut self, record_type: &str) {
+        *self.records.entry(record_type.to_string()).or_default() += 1;
+    }
+
+    pub fn warn(
+        &mut self,
+        kind: ImportWarningKind,
+        line: Option<usize>,
+        record: Option<&str>,
+        tag: impl Into<String>,
+        value: Option<&str>,
+    ) {
+        self.warnings.push(ImportWarning {
+            kind,
+            line,
+            record: record.map(str::to_string),
+            tag: tag.into(),
+            value: value.map(str::to_string),
+        });
+    }
+
+    /// Nombre d'avertissements par catégorie.
+    pub fn summary(&self) -> BTreeMap<ImportWarningKind, usize> {
+        let mut summary = BTreeMap::new();
+        for warning in &self.warnings {
+            *summary.entry(warning.kind).or_default() += 1;
+        }
+        summary
+    }
+}
+
+/// Anomalie ou perte d'information relevée pendant un import.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportWarning {
+    pub kind: ImportWarningKind,
+    /// Ligne du fichier source.
+    pub line: Option<usize>,
+    /// Enregistrement concerné : xref GEDCOM ou clé `.gw`.
+    pub record: Option<String>,
+    /// Chemin du tag (`INDI.BIRT.DATE`) ou instruction `.gw` (`#nsck`).
+    pub tag: String,
+    /// Valeur d'origine.
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportWarningKind {
+    /// Tag ou instruction ignoré par l'import.
+    UnknownTag,
+    /// Tag d'extension sans équivalent, conservé tel quel pour l'export.
+    PreservedTag,
+    /// Valeur non représentable dans les modèles (date en texte libre…).
+    LossyValue,
+    /// Pointeur vers un enregistrement absent du fichier.
+    DanglingXref,
+    /// Identifiant défini plusieurs fois.
+    DuplicateXref,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(event_type: EventType, date: PreciseDate) -> LifeEvent {
+        LifeEvent {
+            event_type,
+            date,
+            place: None,
+            witnesses: Vec::new(),
+            notes: None,
+            sources: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn undated_birth_and_death_stay_at_the_ends() {
+        let now = NaiveDateTime::default();
+        let person = Person {
+            id: PersonId(Uuid::new_v4()),
+            first_name: "Jean".to_string(),
+            surname: "Martin".to_string(),
+            surname_prefix: None,
+            nicknames: Vec::new(),
+            sex: Sex::Male,
+            birth: Some(event(EventType::Birth, PreciseDate::Unknown)),
+            death: Some(event(EventType::Death, PreciseDate::Unknown)),
+            other_events: vec![
+                event(EventType::Burial, PreciseDate::Unknown),
+                event(EventType::Baptism, PreciseDate::YearOnly(1750)),
+            ],
+            occupation: Vec::new(),
+            titles: Vec::new(),
+            notes: None,
+            sources: Vec::new(),
+            images: Vec::new(),
+            public: true,
+            created_at: now,
+            updated_at: now,
+            updated_by: None,
+        };
+        let types: Vec<&EventType> = person
+            .timeline()
+            .into_iter()
+            .map(|event| &event.event_type)
+            .collect();
+        assert_eq!(
+            types,
+            [
+                &EventType::Birth,
+                &EventType::Baptism,
+                &EventType::Burial,
+                &EventType::Death
+            ]
+        );
+
+        let mut dated = person.clone();
+        dated.death = Some(event(EventType::Death, PreciseDate::YearOnly(1800)));
+        let types: Vec<&EventType> = dated
+            .timeline()
+            .into_iter()
+            .map(|event| &event.event_type)
+            .collect();
+        assert_eq!(
+            types,
+            [
+                &EventType::Birth,
+                &EventType::Baptism,
+                &EventType::Death,
+                &EventType::Burial
+            ]
+        );
+    }
+}
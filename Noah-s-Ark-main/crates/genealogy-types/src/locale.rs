@@ -0,0 +1,848 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Calendar, CalendarDate, PreciseDate};
+
+/// Langue de saisie et d'affichage des dates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DateLocale {
+    #[default]
+    French,
+    English,
+    German,
+    Italian,
+}
+
+/// Qualificatif d'une date : mots reconnus à la lecture, le premier servant à
+/// l'écriture, et article placé devant un jour ou devant une année seule.
+struct Qualifier {
+    words: &'static [&'static str],
+    day: &'static str,
+    year: &'static str,
+}
+
+struct LocalePack {
+    tag: &'static str,
+    about: Qualifier,
+    before: Qualifier,
+    after: Qualifier,
+    between: Qualifier,
+    and: Qualifier,
+    unknown: &'static [&'static str],
+    months: [&'static str; 12],
+    hebrew_months: [&'static str; 13],
+    /// Mot précédant l'année républicaine (`an III`).
+    year: &'static str,
+    julian: &'static str,
+    hebrew: &'static str,
+}
+
+const FRENCH: LocalePack = LocalePack {
+    tag: "fr",
+    about: Qualifier {
+        words: &["vers", "environ", "env", "ca", "c", "circa"],
+        day: "le",
+        year: "",
+    },
+    before: Qualifier {
+        words: &["avant", "av"],
+        day: "le",
+        year: "",
+    },
+    after: Qualifier {
+        words: &["après", "ap"],
+        day: "le",
+        year: "",
+    },
+    between: Qualifier {
+        words: &["entre"],
+        day: "le",
+        year: "",
+    },
+    and: Qualifier {
+        words: &["et"],
+        day: "le",
+        year: "",
+    },
+    unknown: &["date inconnue", "inconnue", "inconnu"],
+    months: [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    hebrew_months: [
+        "tichri", "hechvan", "kislev", "tevet", "chevat", "adar", "adar II", "nissan", "iyar",
+        "sivan", "tamouz", "av", "eloul",
+    ],
+    year: "an",
+    julian: "julien",
+    hebrew: "hébraïque",
+};
+
+const ENGLISH: LocalePack = LocalePack {
+    tag: "en",
+    about: Qualifier {
+        words: &["about", "abt", "around", "approx", "ca", "c", "circa"],
+        day: "",
+        year: "",
+    },
+    before: Qualifier {
+        words: &["before", "bef"],
+        day: "",
+        year: "",
+    },
+    after: Qualifier {
+        words: &["after", "aft"],
+        day: "",
+        year: "",
+    },
+    between: Qualifier {
+        words: &["between", "bet", "btw"],
+        day: "",
+        year: "",
+    },
+    and: Qualifier {
+        words: &["and"],
+        day: "",
+        year: "",
+    },
+    unknown: &["unknown date", "unknown"],
+    months: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    hebrew_months: [
+        "Tishri", "Heshvan", "Kislev", "Tevet", "Shevat", "Adar", "Adar II", "Nisan", "Iyar",
+        "Sivan", "Tammuz", "Av", "Elul",
+    ],
+    year: "year",
+    julian: "Julian",
+    hebrew: "Hebrew",
+};
+
+const GERMAN: LocalePack = LocalePack {
+    tag: "de",
+    about: Qualifier {
+        words: &["um", "etwa", "ungefähr", "gegen", "ca", "circa"],
+        day: "den",
+        year: "",
+    },
+    before: Qualifier {
+        words: &["vor"],
+        day: "dem",
+        year: "",
+    },
+    after: Qualifier {
+        words: &["nach"],
+        day: "dem",
+        year: "",
+    },
+    between: Qualifier {
+        words: &["zwischen"],
+        day: "dem",
+        year: "",
+    },
+    and: Qualifier {
+        words: &["und"],
+        day: "dem",
+        year: "",
+    },
+    unknown: &["unbekanntes Datum", "unbekannt"],
+    months: [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+    hebrew_months: [
+        "Tischri",
+        "Cheschwan",
+        "Kislew",
+        "Tevet",
+        "Schevat",
+        "Adar",
+        "Adar II",
+        "Nisan",
+        "Ijar",
+        "Siwan",
+        "Tammus",
+        "Aw",
+        "Elul",
+    ],
+    year: "Jahr",
+    julian: "julianisch",
+    hebrew: "jüdisch",
+};
+
+const ITALIAN: LocalePack = LocalePack {
+    tag: "it",
+    about: Qualifier {
+        words: &["circa", "verso", "ca", "c"],
+        day: "il",
+        year: "",
+    },
+    before: Qualifier {
+        words: &["prima"],
+        day: "del",
+        year: "del",
+    },
+    after: Qualifier {
+        words: &["dopo"],
+        day: "il",
+        year: "il",
+    },
+    between: Qualifier {
+        words: &["tra", "fra"],
+        day: "il",
+        year: "il",
+    },
+    and: Qualifier {
+        words: &["e"],
+        day: "il",
+        year: "il",
+    },
+    unknown: &["data sconosciuta", "sconosciuta", "ignota"],
+    months: [
+        "gennaio",
+        "febbraio",
+        "marzo",
+        "aprile",
+        "maggio",
+        "giugno",
+        "luglio",
+        "agosto",
+        "settembre",
+        "ottobre",
+        "novembre",
+        "dicembre",
+    ],
+    hebrew_months: [
+        "tishri", "cheshvan", "kislev", "tevet", "shevat", "adar", "adar II", "nisan", "iyar",
+        "sivan", "tammuz", "av", "elul",
+    ],
+    year: "anno",
+    julian: "giuliano",
+    hebrew: "ebraico",
+};
+
+/// Les mois républicains gardent leur nom français dans toutes les langues.
+const FRENCH_MONTHS: [&str; 12] = [
+    "vendémiaire",
+    "brumaire",
+    "frimaire",
+    "nivôse",
+    "pluviôse",
+    "ventôse",
+    "germinal",
+    "floréal",
+    "prairial",
+    "messidor",
+    "thermidor",
+    "fructidor",
+];
+
+/// Mots ignorés à la lecture, en plus des articles des qualificatifs.
+const FILLERS: &[&str] = &["the", "on", "of", "du", "des", "der", "jour", "jours"];
+
+impl DateLocale {
+    pub const ALL: [DateLocale; 4] = [
+        DateLocale::French,
+        DateLocale::English,
+        DateLocale::German,
+        DateLocale::Italian,
+    ];
+
+    /// Locale d'une étiquette de langue : `fr`, `en-GB`, `de_DE`…
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.pack().tag == language)
+    }
+
+    pub fn tag(self) -> &'static str {
+        self.pack().tag
+    }
+
+    fn pack(self) -> &'static LocalePack {
+        match self {
+            DateLocale::French => &FRENCH,
+            DateLocale::English => &ENGLISH,
+            DateLocale::German => &GERMAN,
+            DateLocale::Italian => &ITALIAN,
+        }
+    }
+
+    /// Cette locale d'abord, les autres ensuite.
+    fn packs(self) -> impl Iterator<Item = &'static LocalePack> {
+        std::iter::once(self)
+            .chain(Self::ALL.into_iter().filter(move |locale| *locale != self))
+            .map(DateLocale::pack)
+    }
+}
+
+impl PreciseDate {
+    /// Lit une date saisie en toutes lettres : `vers 1750`, `avant le 3 mars 1801`,
+    /// `entre 1700 et 1710`, `ca. 1650`, `12 vendémiaire an III`, `1580 (julien)`,
+    /// ainsi que `1801-03-03` et `3/3/1801` (jour d'abord).
+    ///
+    /// Les mots de `locale` sont cherchés en premier, puis ceux des autres langues.
+    /// Renvoie `None` pour une saisie vide, illisible ou qu'aucune variante ne porte
+    /// (`vers mars 1750`).
+    pub fn parse_localized(input: &str, locale: DateLocale) -> Option<PreciseDate> {
+        let folded = fold(input);
+        if folded.is_empty() {
+            return None;
+        }
+        if folded == "?"
+            || locale
+                .packs()
+                .flat_map(|pack| pack.unknown)
+                .any(|word| fold(word) == folded)
+        {
+            return Some(PreciseDate::Unknown);
+        }
+
+        let mut calendar = None;
+        let mut words: Vec<String> = Vec::new();
+        for token in folded
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | '(' | ')'))
+            // Article élidé : `l'an III`, `dell'anno`.
+            .map(|token| token.rsplit(['\'', '’']).next().unwrap_or(token))
+            .map(|token| token.trim_end_matches('.'))
+            .filter(|token| !token.is_empty())
+        {
+            match words.last_mut() {
+                // `adar I`, `adar II` : un seul mois.
+                Some(adar) if adar == "adar" && matches!(token, "i" | "ii") => {
+                    adar.push(' ');
+                    adar.push_str(token);
+                }
+                _ => match calendar_name(token, locale) {
+                    Some(named) => calendar = Some(named),
+                    None if is_filler(token) => {}
+                    None => words.push(token.to_string()),
+                },
+            }
+        }
+        let tokens: Vec<&str> = words.iter().map(String::as_str).collect();
+
+        let (first, rest) = tokens.split_first()?;
+        let is = |qualifier: fn(&LocalePack) -> &Qualifier| {
+            locale.packs().any(|pack| {
+                qualifier(pack)
+                    .words
+                    .iter()
+                    .any(|word| fold(word) == *first)
+            })
+        };
+        let gregorian = if is(|pack| &pack.between) {
+            let split = rest.iter().position(|token| {
+                locale
+                    .packs()
+                    .any(|pack| pack.and.words.iter().any(|word| fold(word) == *token))
+            })?;
+            let start = parse_day(&rest[..split], &mut calendar, locale)?;
+            let end = parse_day(&rest[split + 1..], &mut calendar, locale)?;
+            PreciseDate::Between(start.first_day()?, end.last_day()?)
+        } else if is(|pack| &pack.before) {
+            PreciseDate::Before(parse_day(rest, &mut calendar, locale)?.first_day()?)
+        } else if is(|pack| &pack.after) {
+            PreciseDate::After(parse_day(rest, &mut calendar, locale)?.last_day()?)
+        } else if is(|pack| &pack.about) {
+            let date = parse_day(rest, &mut calendar, locale)?;
+            match (date.calendar, date.month, date.day) {
+                (_, _, Some(_)) => PreciseDate::About(date.first_day()?),
+                (Calendar::Gregorian, None, None) => PreciseDate::AboutYear(date.year),
+                _ => return None,
+            }
+        } else {
+            let date = parse_day(&tokens, &mut calendar, locale)?;
+            match (date.calendar, date.month) {
+                (Calendar::Gregorian, None) => PreciseDate::YearOnly(date.year),
+                _ => PreciseDate::from_calendar(date)?,
+            }
+        };
+        Some(gregorian.in_calendar(calendar.unwrap_or(Calendar::Gregorian)))
+    }
+
+    /// Écrit la date dans `locale` et dans son calendrier d'origine.
+    pub fn format_localized(&self, locale: DateLocale) -> String {
+        let pack = locale.pack();
+        let calendar = self.calendar();
+        let text = match self.gregorian() {
+            PreciseDate::Exact(date) => {
+                CalendarDate::from_gregorian(calendar, *date).format(locale)
+            }
+            PreciseDate::About(date) => qualified(
+                &pack.about,
+                CalendarDate::from_gregorian(calendar, *date),
+                locale,
+            ),
+            PreciseDate::Before(date) => {
+                qualified(&pack.before, CalendarDate::start(calendar, *date), locale)
+            }
+            PreciseDate::After(date) => {
+                qualified(&pack.after, CalendarDate::end(calendar, *date), locale)
+            }
+            PreciseDate::Between(start, end) => match CalendarDate::span(calendar, *start, *end) {
+                Some(span) => span.format(locale),
+                None => format!(
+                    "{} {}",
+                    qualified(&pack.between, CalendarDate::start(calendar, *start), locale),
+                    qualified(&pack.and, CalendarDate::end(calendar, *end), locale)
+                ),
+            },
+            PreciseDate::YearOnly(year) => year.to_string(),
+            PreciseDate::AboutYear(year) => qualified(
+                &pack.about,
+                CalendarDate {
+                    calendar: Calendar::Gregorian,
+                    year: *year,
+                    month: None,
+                    day: None,
+                },
+                locale,
+            ),
+            PreciseDate::Verbatim { approx, value } => {
+                return match approx {
+                    Some(approx) => approx.format_localized(locale),
+                    None => value.clone(),
+                }
+            }
+            PreciseDate::InCalendar { date, .. } => return date.format_localized(locale),
+            PreciseDate::Unknown => return pack.unknown[0].to_string(),
+        };
+        // Le républicain se reconnaît à son année (`an III`), les autres non.
+        match calendar {
+            Calendar::Julian => format!("{text} ({})", pack.julian),
+            Calendar::Hebrew => format!("{text} ({})", pack.hebrew),
+            Calendar::Gregorian | Calendar::French => text,
+        }
+    }
+}
+
+impl CalendarDate {
+    /// `1er mars 1580`, `3. März 1580`, `12 vendémiaire an III`, sans le nom du calendrier.
+    pub fn format(&self, locale: DateLocale) -> String {
+        let pack = locale.pack();
+        let complementary = self.calendar == Calendar::French && self.month == Some(13);
+        let mut parts = Vec::new();
+        if let Some(day) = self.day {
+            parts.push(match locale {
+                DateLocale::French if day == 1 => "1er".to_string(),
+                // Les jours complémentaires se comptent : `3e jour complémentaire`.
+                DateLocale::French if complementary => format!("{day}e"),
+                DateLocale::German => format!("{day}."),
+                _ => day.to_string(),
+            });
+        }
+        if let Some(month) = self.month {
+            let index = (month as usize).saturating_sub(1);
+            let name = match self.calendar {
+                Calendar::Gregorian | Calendar::Julian => {
+                    pack.months.get(index).map(|m| m.to_string())
+                }
+                Calendar::French if complementary && self.day.is_some() => {
+                    Some("jour complémentaire".to_string())
+                }
+                Calendar::French if complementary => Some("jours complémentaires".to_string()),
+                Calendar::French => FRENCH_MONTHS.get(index).map(|m| m.to_string()),
+                Calendar::Hebrew if month == 6 && Calendar::Hebrew.is_leap(self.year) => {
+                    Some(format!("{} I", pack.hebrew_months[5]))
+                }
+                Calendar::Hebrew => pack.hebrew_months.get(index).map(|m| m.to_string()),
+            };
+            parts.extend(name);
+        }
+        parts.push(match self.calendar {
+            Calendar::French => format!("{} {}", pack.year, roman(self.year)),
+            _ => self.year.to_string(),
+        });
+        parts.join(" ")
+    }
+}
+
+fn qualified(qualifier: &Qualifier, date: CalendarDate, locale: DateLocale) -> String {
+    let article = match (date.day, date.month) {
+        (Some(_), _) => qualifier.day,
+        (None, Some(_)) => "",
+        (None, None) => qualifier.year,
+    };
+    let date = date.format(locale);
+    [qualifier.words[0], article, &date]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Jour, mois ou année, dans le calendrier nommé, celui de ses mois, ou le grégorien.
+fn parse_day(
+    tokens: &[&str],
+    calendar: &mut Option<Calendar>,
+    locale: DateLocale,
+) -> Option<CalendarDate> {
+    let (mut day, mut month, year) = match tokens {
+        // `1801-03-03`, `1801-03`.
+        [iso] if iso.contains('-') => {
+            let mut parts = iso.split('-');
+            let year = parts.next()?;
+            let month = parts.next().map(str::parse).transpose().ok()?;
+            let day = parts.next().map(str::parse).transpose().ok()?;
+            if parts.next().is_some() {
+                return None;
+            }
+            (day, month, year.to_string())
+        }
+        // `3/3/1801`, `03.03.1801`, `3/1801`.
+        [numeric] if numeric.contains(['/', '.']) => {
+            let parts: Vec<&str> = numeric.split(['/', '.']).collect();
+            match parts.as_slice() {
+                [month, year] => (None, Some(month.parse().ok()?), year.to_string()),
+                [day, month, year] => (
+                    Some(day.parse().ok()?),
+                    Some(month.parse().ok()?),
+                    year.to_string(),
+                ),
+                _ => return None,
+            }
+        }
+        [rest @ .., word, year] if is_republican_year(word, locale) => {
+            set_calendar(calendar, Calendar::French)?;
+            let year = parse_roman(year).map_or_else(|| year.to_string(), |year| year.to_string());
+            let (day, month) = parse_day_month(rest, calendar, locale)?;
+            (day, month, year)
+        }
+        [rest @ .., year] => {
+            let (day, month) = parse_day_month(rest, calendar, locale)?;
+            (day, month, year.to_string())
+        }
+        [] => return None,
+    };
+    if !year.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    day = day.filter(|day| *day != 0);
+    month = month.filter(|month| *month != 0);
+    CalendarDate::new(
+        calendar.unwrap_or(Calendar::Gregorian),
+        year.parse().ok()?,
+        month,
+        day,
+    )
+}
+
+/// `3 mars`, `March 3`, `mars`, ou rien.
+fn parse_day_month(
+    tokens: &[&str],
+    calendar: &mut Option<Calendar>,
+    locale: DateLocale,
+) -> Option<(Option<u32>, Option<u32>)> {
+    match tokens {
+        [] => Some((None, None)),
+        [month] => Some((None, Some(parse_month(month, calendar, locale)?))),
+        [day, month] | [month, day] if parse_ordinal(day).is_some() => Some((
+            parse_ordinal(day),
+            Some(parse_month(month, calendar, locale)?),
+        )),
+        _ => None,
+    }
+}
+
+/// `1er`, `1st`, `3.`, `3e`, `1º`.
+fn parse_ordinal(token: &str) -> Option<u32> {
+    let digits =
+        token.trim_end_matches(|c: char| c.is_alphabetic() || matches!(c, '.' | 'º' | '°'));
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Mois grégorien, républicain ou hébreu ; le calendrier en découle s'il n'est pas nommé.
+fn parse_month(token: &str, calendar: &mut Option<Calendar>, locale: DateLocale) -> Option<u32> {
+    let hebrew = |pack: &'static LocalePack| pack.hebrew_months.as_slice();
+    let candidates: [(Calendar, Vec<&[&str]>); 3] = [
+        (
+            Calendar::Gregorian,
+            locale.packs().map(|pack| pack.months.as_slice()).collect(),
+        ),
+        (Calendar::French, vec![FRENCH_MONTHS.as_slice()]),
+        (Calendar::Hebrew, locale.packs().map(hebrew).collect()),
+    ];
+    match token {
+        "complementaire" | "complementaires" | "sans-culottides" => {
+            set_calendar(calendar, Calendar::French)?;
+            return Some(13);
+        }
+        "adar i" => {
+            set_calendar(calendar, Calendar::Hebrew)?;
+            return Some(6);
+        }
+        _ => {}
+    }
+    for (month_calendar, tables) in candidates {
+        for names in tables {
+            if let Some(month) = find_month(token, names) {
+                // Julien et grégorien partagent leurs mois.
+                match (*calendar, month_calendar) {
+                    (Some(Calendar::Julian), Calendar::Gregorian) => {}
+                    _ if month_calendar == Calendar::Gregorian && calendar.is_none() => {}
+                    _ => set_calendar(calendar, month_calendar)?,
+                }
+                return Some(month);
+            }
+        }
+    }
+    None
+}
+
+/// Nom complet, ou abréviation d'au moins trois lettres qui ne désigne qu'un mois.
+fn find_month(token: &str, names: &[&str]) -> Option<u32> {
+    let names: Vec<String> = names.iter().map(|name| fold(name)).collect();
+    if let Some(index) = names.iter().position(|name| name == token) {
+        return Some(index as u32 + 1);
+    }
+    if token.chars().count() < 3 {
+        return None;
+    }
+    let mut matches = names
+        .iter()
+        .enumerate()
+        .filter(|(_, name)| name.starts_with(token));
+    match (matches.next(), matches.next()) {
+        (Some((index, _)), None) => Some(index as u32 + 1),
+        _ => None,
+    }
+}
+
+fn set_calendar(calendar: &mut Option<Calendar>, found: Calendar) -> Option<()> {
+    match calendar {
+        Some(named) if *named != found => None,
+        _ => {
+            *calendar = Some(found);
+            Some(())
+        }
+    }
+}
+
+fn calendar_name(token: &str, locale: DateLocale) -> Option<Calendar> {
+    locale.packs().find_map(|pack| {
+        if fold(pack.julian) == token {
+            Some(Calendar::Julian)
+        } else if fold(pack.hebrew) == token {
+            Some(Calendar::Hebrew)
+        } else {
+            None
+        }
+    })
+}
+
+fn is_republican_year(token: &str, locale: DateLocale) -> bool {
+    locale.packs().any(|pack| fold(pack.year) == token)
+}
+
+fn is_filler(token: &str) -> bool {
+    FILLERS.contains(&token)
+        || DateLocale::ALL.into_iter().any(|locale| {
+            let pack = locale.pack();
+            [
+                &pack.about,
+                &pack.before,
+                &pack.after,
+                &pack.between,
+                &pack.and,
+            ]
+            .into_iter()
+            .any(|qualifier| {
+                [qualifier.day, qualifier.year]
+                    .into_iter()
+                    .any(|article| !article.is_empty() && article == token)
+            })
+        })
+}
+
+/// Minuscules sans accents ni espaces superflus, pour comparer les saisies.
+fn fold(value: &str) -> String {
+    value
+        .trim()
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'à' | 'â' | 'ä' => 'a',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'î' | 'ï' => 'i',
+            'ô' | 'ö' => 'o',
+            'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            c => c,
+        })
+        .collect()
+}
+
+fn roman(year: i32) -> String {
+    const NUMERALS: [(i32, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    if !(1..4000).contains(&year) {
+        return year.to_string();
+    }
+    let mut rest = year;
+    let mut numeral = String::new();
+    for (value, letters) in NUMERALS {
+        while rest >= value {
+            numeral.push_str(letters);
+            rest -= value;
+        }
+    }
+    numeral
+}
+
+fn parse_roman(token: &str) -> Option<i32> {
+    let mut total = 0;
+    let mut previous = 0;
+    for c in token.chars().rev() {
+        let value = match c.to_ascii_uppercase() {
+            'I' => 1,
+            'V' => 5,
+            'X' => 10,
+            'L' => 50,
+            'C' => 100,
+            'D' => 500,
+            'M' => 1000,
+            _ => return None,
+        };
+        if value < previous {
+            total -= value;
+        } else {
+            total += value;
+            previous = value;
+        }
+    }
+    (total > 0).then_some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn formatted_dates_read_back_in_each_locale() {
+        let dates = [
+            PreciseDate::Exact(day(1801, 3, 1)),
+            PreciseDate::About(day(1750, 6, 12)),
+            PreciseDate::AboutYear(1750),
+            PreciseDate::Before(day(1801, 3, 3)),
+            PreciseDate::After(day(1801, 12, 31)),
+            PreciseDate::Between(day(1700, 1, 1), day(1710, 12, 31)),
+            PreciseDate::Between(day(1801, 3, 1), day(1801, 3, 31)),
+            PreciseDate::YearOnly(1801),
+            PreciseDate::Exact(day(1794, 10, 3)).in_calendar(Calendar::French),
+            PreciseDate::Exact(day(1793, 9, 21)).in_calendar(Calendar::French),
+            PreciseDate::Exact(day(1582, 10, 15)).in_calendar(Calendar::Julian),
+            PreciseDate::Unknown,
+        ];
+        for locale in DateLocale::ALL {
+            for date in &dates {
+                let text = date.format_localized(locale);
+                assert_eq!(
+                    PreciseDate::parse_localized(&text, locale).as_ref(),
+                    Some(date),
+                    "{locale:?} : {text}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn qualifiers_follow_the_locale() {
+        let cases = [
+            (DateLocale::French, "vers 1750"),
+            (DateLocale::English, "about 1750"),
+            (DateLocale::German, "um 1750"),
+            (DateLocale::Italian, "circa 1750"),
+        ];
+        for (locale, text) in cases {
+            assert_eq!(PreciseDate::AboutYear(1750).format_localized(locale), text);
+            assert_eq!(
+                PreciseDate::parse_localized(text, locale),
+                Some(PreciseDate::AboutYear(1750))
+            );
+        }
+        // `circa` est compris quelle que soit la locale.
+        for locale in DateLocale::ALL {
+            assert_eq!(
+                PreciseDate::parse_localized("circa 1750", locale),
+                Some(PreciseDate::AboutYear(1750))
+            );
+        }
+    }
+
+    #[test]
+    fn republican_months_keep_their_french_names() {
+        let date = PreciseDate::Exact(day(1794, 10, 3)).in_calendar(Calendar::French);
+        let cases = [
+            (DateLocale::French, "12 vendémiaire an III"),
+            (DateLocale::English, "12 vendémiaire year III"),
+            (DateLocale::German, "12. vendémiaire Jahr III"),
+            (DateLocale::Italian, "12 vendémiaire anno III"),
+        ];
+        for (locale, text) in cases {
+            assert_eq!(date.format_localized(locale), text);
+            assert_eq!(
+                PreciseDate::parse_localized(text, locale),
+                Some(date.clone())
+            );
+        }
+        let complementary = PreciseDate::Exact(day(1793, 9, 21)).in_calendar(Calendar::French);
+        assert_eq!(
+            complementary.format_localized(DateLocale::French),
+            "5e jour complémentaire an I"
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use chrono::{Datelike, NaiveDate};
+use genealogy_types::{Calendar, CalendarDate, PreciseDate};
+
+/// Date GeneWeb partielle (`12/5/1850`, `5/1850`, `1850`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DatePart {
+    Day(NaiveDate),
+    Month(i32, u32),
+    Year(i32),
+}
+
+impl DatePart {
+    fn first_day(self) -> Option<NaiveDate> {
+        match self {
+            DatePart::Day(date) => Some(date),
+            DatePart::Month(year, month) => NaiveDate::from_ymd_opt(year, month, 1),
+            DatePart::Year(year) => NaiveDate::from_ymd_opt(year, 1, 1),
+        }
+    }
+
+    fn last_day(self) -> Option<NaiveDate> {
+        match self {
+            DatePart::Day(date) => Some(date),
+            DatePart::Month(year, month) => {
+                let (next_year, next_month) = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+                NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()
+            }
+            DatePart::Year(year) => NaiveDate::from_ymd_opt(year, 12, 31),
+        }
+    }
+}
+
+/// Indique si un jeton `.gw` est une date (ou le marqueur `0` de date inconnue).
+pub fn is_date_token(token: &str) -> bool {
+    token
+        .trim_start_matches(['~', '?', '<', '>'])
+        .starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Convertit une date GeneWeb en [`PreciseDate`].
+///
+/// Les suffixes `J`, `F` et `H` (julien, républicain, hébreu) donnent une
+/// [`PreciseDate::InCalendar`], une date texte `0(...)` une [`PreciseDate::Verbatim`]
+/// sans équivalent grégorien. Renvoie `None` pour le marqueur `0` et les
+/// approximations d'un mois seul.
+pub fn parse_date(token: &str) -> Option<PreciseDate> {
+    if token == "0" {
+        return None;
+    }
+    if let Some(text) = token.strip_prefix("0(") {
+        return Some(PreciseDate::Verbatim {
+            value: crate::decode(text.strip_suffix(')')?),
+            approx: None,
+        });
+    }
+
+    let (prefix, body) = match token.chars().next()? {
+        c @ ('~' | '?' | '<' | '>') => (Some(c), &token[1..]),
+        _ => (None, token),
+    };
+
+    let calendar = match body.chars().last()? {
+        'J' => Calendar::Julian,
+        'F' => Calendar::French,
+        'H' => Calendar::Hebrew,
+        _ => Calendar::Gregorian,
+    };
+    if calendar != Calendar::Gregorian {
+        return parse_in_calendar(prefix, &body[..body.len() - 1], calendar);
+    }
+
+    if let Some((start, end)) = body.split_once("..").or_else(|| body.split_once('|')) {
+        let start = parse_part(start)?.first_day()?;
+        let end = parse_part(end)?.last_day()?;
+        return Some(PreciseDate::Between(start, end));
+    }
+
+    let part = parse_part(body)?;
+    match prefix {
+        Some('~' | '?') => match part {
+            DatePart::Day(date) => Some(PreciseDate::About(date)),
+            DatePart::Year(year) => Some(PreciseDate::AboutYear(year)),
+            DatePart::Month(..) => None,
+        },
+        Some('<') => Some(PreciseDate::Before(part.first_day()?)),
+        Some('>') => Some(PreciseDate::After(part.last_day()?)),
+        _ => match part {
+            DatePart::Day(date) => Some(PreciseDate::Exact(date)),
+            DatePart::Month(..) => Some(PreciseDate::Between(part.first_day()?, part.last_day()?)),
+            DatePart::Year(year) => Some(PreciseDate::YearOnly(year)),
+        },
+    }
+}
+
+/// Date dans un autre calendrier : jour, mois et année y sont exprimés, le suffixe
+/// s'applique aux deux bornes d'un intervalle.
+fn parse_in_calendar(prefix: Option<char>, body: &str, calendar: Calendar) -> Option<PreciseDate> {
+    if let Some((start, end)) = body.split_once("..").or_else(|| body.split_once('|')) {
+        let start = parse_calendar_part(start, calendar)?.first_day()?;
+        let end = parse_calendar_part(end, calendar)?.last_day()?;
+        return Some(PreciseDate::Between(start, end).in_calendar(calendar));
+    }
+
+    let date = parse_calendar_part(body, calendar)?;
+    let gregorian = match prefix {
+        Some('~' | '?') => PreciseDate::About(date.day.and(date.first_day())?),
+        Some('<') => PreciseDate::Before(date.first_day()?),
+        Some('>') => PreciseDate::After(date.last_day()?),
+        _ => return PreciseDate::from_calendar(date),
+    };
+    Some(gregorian.in_calendar(calendar))
+}
+
+/// Convertit une [`PreciseDate`] en jeton `.gw` (`0` pour une date inconnue).
+pub fn format_date(date: &PreciseDate) -> String {
+    match date {
+        PreciseDate::Exact(date) => format_day(*date),
+        PreciseDate::About(date) => format!("~{}", format_day(*date)),
+        PreciseDate::Before(date) => format!("<{}", format_start(*date)),
+        PreciseDate::After(date) => format!(">{}", format_end(*date)),
+        PreciseDate::Between(start, end) => {
+            if start.day() == 1
+                && (start.year(), start.month()) == (end.year(), end.month())
+                && DatePart::Month(end.year(), end.month()).last_day() == Some(*end)
+            {
+                format!("{}/{}", start.month(), start.year())
+            } else {
+                format!("{}..{}", format_start(*start), format_end(*end))
+            }
+        }
+        PreciseDate::YearOnly(year) => year.to_string(),
+        PreciseDate::AboutYear(year) => format!("~{year}"),
+        // Valeur GEDCOM : son équivalent grégorien, à défaut une date texte.
+        PreciseDate::Verbatim { approx, value } => match approx {
+            Some(approx) => format_date(approx),
+            None => format!("0({})", crate::encode(value)),
+        },
+        PreciseDate::InCalendar { calendar, date } => {
+            format_in_calendar(*calendar, date).unwrap_or_else(|| format_date(date))
+        }
+        PreciseDate::Unknown => "0".to_string(),
+    }
+}
+
+/// Date grégorienne réécrite dans son calendrier d'origine, suffixe compris.
+fn format_in_calendar(calendar: Calendar, date: &PreciseDate) -> Option<String> {
+    let suffix = match calendar {
+        Calendar::Gregorian => return None,
+        Calendar::Julian => 'J',
+        Calendar::French => 'F',
+        Calendar::Hebrew => 'H',
+    };
+    let body = match date {
+        PreciseDate::Exact(date) => calendar_day(CalendarDate::from_gregorian(calendar, *date))?,
+        PreciseDate::About(date) => format!(
+            "~{}",
+            calendar_day(CalendarDate::from_gregorian(calendar, *date))?
+        ),
+        PreciseDate::Before(date) => {
+            format!("<{}", calendar_day(CalendarDate::start(calendar, *date))?)
+        }
+        PreciseDate::After(date) => {
+            format!(">{}", calendar_day(CalendarDate::end(calendar, *date))?)
+        }
+        PreciseDate::Between(start, end) => match CalendarDate::span(calendar, *start, *end) {
+            Some(span) => calendar_day(span)?,
+            None => format!(
+                "{}..{}",
+                calendar_day(CalendarDate::start(calendar, *start))?,
+                calendar_day(CalendarDate::end(calendar, *end))?
+            ),
+        },
+        _ => return None,
+    };
+    Some(format!("{body}{suffix}"))
+}
+
+fn parse_part(value: &str) -> Option<DatePart> {
+    let parts: Vec<&str> = value.split('/').collect();
+    match parts.as_slice() {
+        [year] => Some(DatePart::Year(year.parse().ok()?)),
+        [month, year] => {
+            let month: u32 = month.parse().ok()?;
+            let year: i32 = year.parse().ok()?;
+            if month == 0 {
+                Some(DatePart::Year(year))
+            } else {
+                Some(DatePart::Month(year, month)).filter(|_| month <= 12)
+            }
+        }
+        [day, month, year] => {
+            let day: u32 = day.parse().ok()?;
+            let month: u32 = month.parse().ok()?;
+            let year: i32 = year.parse().ok()?;
+            match (day, month) {
+                (0, 0) => Some(DatePart::Year(year)),
+                (0, _) => Some(DatePart::Month(year, month)).filter(|_| month <= 12),
+                _ => NaiveDate::from_ymd_opt(year, month, day).map(DatePart::Day),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn parse_calendar_part(value: &str, calendar: Calendar) -> Option<CalendarDate> {
+    let parts: Vec<&str> = value.split('/').collect();
+    let (day, month, year) = match parts.as_slice() {
+        [year] => (0, 0, year),
+        [month, year] => (0, month.parse().ok()?, year),
+        [day, month, year] => (day.parse().ok()?, month.parse().ok()?, year),
+        _ => return None,
+    };
+    let month = Some(month).filter(|month| *month != 0);
+    let day = Some(day).filter(|day| *day != 0);
+    CalendarDate::new(calendar, year.parse().ok()?, month, day)
+}
+
+/// `12/1/3`, ou `None` hors des années que le calendrier connaît.
+fn calendar_day(date: CalendarDate) -> Option<String> {
+    let date = CalendarDate::new(date.calendar, date.year, date.month, date.day)?;
+    Some(match (date.day, date.month) {
+        (Some(day), Some(month)) => format!("{day}/{month}/{}", date.year),
+        (None, Some(month)) => format!("{month}/{}", date.year),
+        _ => date.year.to_string(),
+    })
+}
+
+fn format_day(date: NaiveDate) -> String {
+    format!("{}/{}/{}", date.day(), date.month(), date.year())
+}
+
+fn format_start(date: NaiveDate) -> String {
+    match (date.month(), date.day()) {
+        (1, 1) => date.year().to_string(),
+        (_, 1) => format!("{}/{}", date.month(), date.year()),
+        _ => format_day(date),
+    }
+}
+
+fn format_end(date: NaiveDate) -> String {
+    if DatePart::Year(date.year()).last_day() == Some(date) {
+        date.year().to_string()
+    } else if DatePart::Month(date.year(), date.month()).last_day() == Some(date) {
+        format!("{}/{}", date.month(), date.year())
+    } else {
+        format_day(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_dates_are_read_back() {
+        let date = PreciseDate::Verbatim {
+            value: "vers la Saint-Jean".to_string(),
+            approx: None,
+        };
+        assert_eq!(format_date(&date), "0(vers_la_Saint-Jean)");
+        assert_eq!(parse_date("0(vers_la_Saint-Jean)"), Some(date));
+        assert_eq!(parse_date("0"), None);
+    }
+
+    fn day(year: i32, month: u32, day: u32) -> PreciseDate {
+        PreciseDate::Exact(NaiveDate::from_ymd_opt(year, month, day).unwrap())
+    }
+
+    #[test]
+    fn calendar_dates_convert_to_gregorian() {
+        let cases = [
+            ("1/1/1F", Calendar::French, day(1792, 9, 22)),
+            ("18/2/8F", Calendar::French, day(1799, 11, 9)),
+            ("5/10/1582J", Calendar::Julian, day(1582, 10, 15)),
+            ("1/1/1700J", Calendar::Julian, day(1700, 1, 11)),
+            ("1/1/5784H", Calendar::Hebrew, day(2023, 9, 16)),
+        ];
+        for (token, calendar, gregorian) in cases {
+            let date = parse_date(token).unwrap();
+            assert_eq!(date.calendar(), calendar, "{token}");
+            assert_eq!(date.gregorian(), &gregorian, "{token}");
+        }
+    }
+
+    #[test]
+    fn calendar_dates_round_trip() {
+        for token in [
+            "1/1/1F",
+            "3/13/3F",
+            "~12/5/1650J",
+            "<5/1700J",
+            ">1650J",
+            "2/1700J",
+            "1..3/2F",
+            "15/7/5600H",
+            "12/5/1850",
+            "5/1850",
+            "~1850",
+        ] {
+            assert_eq!(format_date(&parse_date(token).unwrap()), token);
+        }
+    }
+}
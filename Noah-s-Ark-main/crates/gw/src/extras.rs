@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+
+use genealogy_types::{FamilyId, PersonId};
+use uuid::Uuid;
+
+/// Données `.gw` sans équivalent dans les modèles métiers, conservées pour la réécriture.
+///
+/// Chaque élément conservé est aussi signalé dans le rapport (`preserved_tag`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GwExtras {
+    /// Jetons d'une personne tels qu'écrits : noms publics `(…)`, prénoms
+    /// alternatifs `{…}`, `#alias`, `#salias`, `#fn`, `#sn`, `#semipub`.
+    pub person_tokens: HashMap<PersonId, Vec<String>>,
+    /// Décès sans date (`mj`, `od`), incertain (`?`), ou raison d'un décès
+    /// (`k`, `m`, `e`, `s`) placée devant sa date.
+    pub death_status: HashMap<PersonId, String>,
+    /// Drapeaux d'une ligne `fam` : nature de l'union (`#eng`, `#nm`), `#nsck`,
+    /// `#noment`, `#banns`, `#contract`, `#license`, `#pacs`, `#residence`, `#sep`.
+    pub family_flags: HashMap<FamilyId, Vec<String>>,
+    /// Causes `#c` des événements d'un bloc `pevt` ou `fevt`, par propriétaire et
+    /// rang de l'événement : naissance, décès puis autres pour une personne ;
+    /// mariage, divorce puis autres pour une famille.
+    pub event_causes: HashMap<(Uuid, usize), String>,
+    /// Blocs `rel`, `notes-db`, `page-ext` et `wizard-note`, lignes de début et de
+    /// fin comprises, réécrits en fin de fichier.
+    pub blocks: Vec<String>,
+}
@@ -0,0 +1,1173 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{NaiveDateTime, Utc};
+use genealogy_types::{
+    EventType, Family, FamilyId, ImageReference, ImportReport, ImportWarningKind, LifeEvent,
+    Person, PersonId, Place, PreciseDate, Sex, Source, Title,
+};
+use uuid::Uuid;
+
+use crate::date::{is_date_token, parse_date};
+use crate::extras::GwExtras;
+use crate::{decode, decode_list, split_escaped, split_particle, unescape_line, EVENT_CODES};
+
+/// Résultat d'un import `.gw` : entités métiers et correspondance clé → identifiant.
+#[derive(Debug, Default)]
+pub struct GwData {
+    pub persons: Vec<Person>,
+    pub families: Vec<Family>,
+    pub sources: Vec<Source>,
+    /// Clé normalisée (`nom prénom.occ` en minuscules) → personne.
+    pub person_keys: HashMap<String, PersonId>,
+    /// Instructions ignorées, dates perdues et personnes définies deux fois.
+    ///
+    /// Les personnes sont créées à leur première mention : un fichier `.gw` n'a
+    /// pas de référence orpheline.
+    pub report: ImportReport,
+    /// Données sans équivalent dans les modèles, pour [`crate::write_gw_with_extras`].
+    pub extras: GwExtras,
+}
+
+/// Importe un fichier `.gw` complet.
+pub fn import_gw(input: &str) -> Result<GwData> {
+    let mut importer = GwImporter::new();
+    importer.run(input)?;
+    Ok(importer.finish())
+}
+
+/// Clé de personne `Nom Prénom[.occ]`.
+struct PersonKey {
+    surname: String,
+    first_name: String,
+    occ: u32,
+}
+
+impl PersonKey {
+    fn parse(surname: &str, first_name: &str) -> Self {
+        let (first_name, occ) = match first_name.rsplit_once('.') {
+            Some((name, occ)) if !occ.is_empty() && occ.chars().all(|c| c.is_ascii_digit()) => {
+                (name, occ.parse().unwrap_or(0))
+            }
+            _ => (first_name, 0),
+        };
+        Self {
+            surname: decode(surname),
+            first_name: decode(first_name),
+            occ,
+        }
+    }
+
+    fn normalized(&self) -> String {
+        format!(
+            "{} {}.{}",
+            self.surname.to_lowercase(),
+            self.first_name.to_lowercase(),
+            self.occ
+        )
+    }
+
+    /// Clé telle qu'écrite dans le fichier, pour le rapport d'import.
+    fn label(&self) -> String {
+        match self.occ {
+            0 => format!("{} {}", self.surname, self.first_name),
+            occ => format!("{} {}.{occ}", self.surname, self.first_name),
+        }
+    }
+
+    /// `? ?` désigne un conjoint absent.
+    fn is_unknown(&self) -> bool {
+        self.surname.is_empty() && self.first_name.is_empty() && self.occ == 0
+    }
+}
+
+#[derive(Default)]
+struct EventInfo {
+    present: bool,
+    date: Option<PreciseDate>,
+    place: Option<String>,
+    sources: Vec<String>,
+}
+
+/// Informations portées par une personne à sa première définition.
+#[derive(Default)]
+struct PersonInfo {
+    public: Option<bool>,
+    nicknames: Vec<String>,
+    image: Option<String>,
+    occupation: Vec<String>,
+    titles: Vec<Title>,
+    sources: Vec<String>,
+    birth: EventInfo,
+    baptism: EventInfo,
+    death: EventInfo,
+    burial: EventInfo,
+    cremated: bool,
+    /// Jetons sans équivalent dans le modèle, conservés tels qu'écrits.
+    tokens: Vec<String>,
+    /// `mj`, `od`, `?` ou raison `k`/`m`/`e`/`s` d'un décès daté.
+    death_status: Option<String>,
+    /// Avertissements à rattacher à la personne : (catégorie, jeton, valeur).
+    warnings: Vec<(ImportWarningKind, String, Option<String>)>,
+}
+
+impl PersonInfo {
+    /// Simple mention de la personne, sans information.
+    fn is_empty(&self) -> bool {
+        self.public.is_none()
+            && self.nicknames.is_empty()
+            && self.image.is_none()
+            && self.occupation.is_empty()
+            && self.titles.is_empty()
+            && self.sources.is_empty()
+            && self.tokens.is_empty()
+            && self.death_status.is_none()
+            && [&self.birth, &self.baptism, &self.death, &self.burial]
+                .iter()
+                .all(|event| !event.present)
+    }
+
+    /// Date illisible ou non représentable : conservée dans le rapport.
+    fn date(&mut self, tag: &str, token: &str) -> Option<PreciseDate> {
+        let date = parse_date(token);
+        if date.is_none() && !token.is_empty() && token != "0" {
+            self.warnings.push((
+                ImportWarningKind::LossyValue,
+                tag.to_string(),
+                Some(token.to_string()),
+            ));
+        }
+        date
+    }
+
+    /// Statut ou raison du décès, conservé dans les [`GwExtras`].
+    fn death_status(&mut self, status: &str) {
+        self.death_status = Some(status.to_string());
+        self.warnings.push((
+            ImportWarningKind::PreservedTag,
+            "death".to_string(),
+            Some(status.to_string()),
+        ));
+    }
+
+    /// Jeton conservé dans les [`GwExtras`] et signalé dans le rapport.
+    fn preserve(&mut self, raw: String, tag: &str, value: Option<String>) {
+        self.tokens.push(raw);
+        self.warnings
+            .push((ImportWarningKind::PreservedTag, tag.to_string(), value));
+    }
+}
+
+/// Curseur sur les lignes non vides du fichier.
+struct Lines<'a> {
+    lines: Vec<(usize, &'a str)>,
+    pos: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(input: &'a str) -> Self {
+        let lines = input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim_start_matches('\u{feff}').trim_end()))
+            .filter(|(_, line)| !line.trim().is_empty())
+            .collect();
+        Self { lines, pos: 0 }
+    }
+
+    fn next(&mut self) -> Option<(usize, &'a str)> {
+        let line = self.lines.get(self.pos).copied();
+        self.pos += 1;
+        line
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.lines.get(self.pos).map(|(_, line)| *line)
+    }
+
+    /// Consomme les lignes jusqu'au marqueur de fin inclus.
+    fn until(&mut self, end: &str) -> Result<Vec<&'a str>> {
+        let mut block = Vec::new();
+        while let Some((_, line)) = self.next() {
+            if line.trim() == end {
+                return Ok(block);
+            }
+            block.push(line);
+        }
+        bail!("bloc non terminé : `{end}` attendu")
+    }
+}
+
+struct GwImporter {
+    data: GwData,
+    person_index: HashMap<String, usize>,
+    id_index: HashMap<PersonId, usize>,
+    source_index: HashMap<String, usize>,
+    /// Personnes dont les informations ont déjà été données.
+    defined: HashSet<usize>,
+    /// Ligne de l'instruction en cours, pour le rapport.
+    line: usize,
+    now: NaiveDateTime,
+}
+
+impl GwImporter {
+    fn new() -> Self {
+        Self {
+            data: GwData::default(),
+            person_index: HashMap::new(),
+            id_index: HashMap::new(),
+            source_index: HashMap::new(),
+            defined: HashSet::new(),
+            line: 0,
+            now: Utc::now().naive_utc(),
+        }
+    }
+
+    fn finish(mut self) -> GwData {
+        let data = &mut self.data;
+        data.report.persons = data.persons.len();
+        data.report.families = data.families.len();
+        data.report.sources = data.sources.len();
+        self.data
+    }
+
+    fn warn(
+        &mut self,
+        kind: ImportWarningKind,
+        record: Option<&str>,
+        tag: &str,
+        value: Option<&str>,
+    ) {
+        self.data
+            .report
+            .warn(kind, Some(self.line), record, tag, value);
+    }
+
+    /// Comme [`parse_date`], en signalant les dates perdues.
+    fn date(&mut self, tag: &str, token: &str) -> Option<PreciseDate> {
+        let date = parse_date(token);
+        if date.is_none() && !token.is_empty() && token != "0" {
+            self.warn(ImportWarningKind::LossyValue, None, tag, Some(token));
+        }
+        date
+    }
+
+    fn run(&mut self, input: &str) -> Result<()> {
+        let mut lines = Lines::new(input);
+        while let Some((line_number, line)) = lines.next() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            self.line = line_number;
+            if !matches!(tokens[0], "encoding:" | "gwplus") {
+                self.data.report.count_record(tokens[0]);
+            }
+            let result = match tokens[0] {
+                "encoding:" | "gwplus" => Ok(()),
+                "fam" => self.family(&tokens, &mut lines),
+                "notes" => self.person_notes(&tokens, &mut lines),
+                "pevt" => self.person_events(&tokens, &mut lines),
+                "rel" | "notes-db" | "page-ext" | "wizard-note" => {
+                    self.warn(ImportWarningKind::PreservedTag, None, tokens[0], None);
+                    let end = match tokens[0] {
+                        "rel" => "end".to_string(),
+                        block => format!("end {block}"),
+                    };
+                    lines.until(&end).map(|body| {
+                        let mut block = vec![line];
+                        block.extend(body);
+                        block.push(&end);
+                        self.data.extras.blocks.push(block.join("\n"));
+                    })
+                }
+                other => Err(anyhow!("instruction inconnue `{other}`")),
+            };
+            result.map_err(|err| anyhow!("ligne {line_number} : {err}"))?;
+        }
+        Ok(())
+    }
+
+    fn family(&mut self, tokens: &[&str], lines: &mut Lines<'_>) -> Result<()> {
+        let mut i = 1;
+        let father = self.spouse(tokens, &mut i, Sex::Male)?;
+
+        let marriage_token = tokens
+            .get(i)
+            .filter(|token| token.starts_with('+'))
+            .ok_or_else(|| anyhow!("mariage `+` attendu"))?;
+        i += 1;
+        let mut marriage = EventInfo {
+            date: self.date("+", &marriage_token[1..]),
+            ..Default::default()
+        };
+        marriage.present = marriage.date.is_some();
+        let mut engaged = false;
+        let mut divorce: Option<EventInfo> = None;
+        let mut flags = Vec::new();
+
+        while let Some(token) = tokens.get(i) {
+            match *token {
+                "#eng" => {
+                    engaged = true;
+                    flags.push(token.to_string());
+                }
+                "#nm" | "#nsck" | "#noment" | "#banns" | "#contract" | "#license" | "#pacs"
+                | "#residence" | "#sep" => {
+                    self.warn(ImportWarningKind::PreservedTag, None, token, None);
+                    flags.push(token.to_string());
+                }
+                "#mp" => {
+                    i += 1;
+                    marriage.place = tokens.get(i).map(|t| decode(t));
+                    marriage.present = true;
+                }
+                "#ms" => {
+                    i += 1;
+                    marriage.sources = tokens
+                        .get(i)
+                        .map(|t| decode_list(t, ';'))
+                        .unwrap_or_default();
+                    marriage.present = true;
+                }
+                token if token.starts_with('-') => {
+                    divorce = Some(EventInfo {
+                        present: true,
+                        date: self.date("-", &token[1..]),
+                        ..Default::default()
+                    });
+                }
+                _ => break,
+            }
+            i += 1;
+        }
+
+        let mother = self.spouse(tokens, &mut i, Sex::Female)?;
+
+        let mut family = Family {
+            id: FamilyId(Uuid::new_v4()),
+            father,
+            mother,
+            children: Vec::new(),
+            marriage: None,
+            divorce: None,
+            other_events: Vec::new(),
+            notes: None,
+            sources: Vec::new(),
+            public: true,
+            created_at: self.now,
+            updated_at: self.now,
+        };
+
+        let mut witnesses = Vec::new();
+        let mut children_sources = Vec::new();
+        let mut children_birth_place = None;
+        let mut events = None;
+
+        while let Some(line) = lines.peek() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens[0] {
+                "wit" | "wit:" => {
+                    lines.next();
+                    if let Some(witness) = self.witness(&tokens)? {
+                        witnesses.push(witness);
+                    }
+                }
+                "src" => {
+                    lines.next();
+                    for title in decode_list(&tokens[1..].join("_"), ';') {
+                        family.sources.push(self.source(&title));
+                    }
+                }
+                "csrc" => {
+                    lines.next();
+                    children_sources = decode_list(&tokens[1..].join("_"), ';');
+                }
+                "cbp" => {
+                    lines.next();
+                    children_birth_place = Some(decode(&tokens[1..].join("_")));
+                }
+                "comm" => {
+                    lines.next();
+                    family.notes = Some(unescape_line(line.trim_start()["comm".len()..].trim()));
+                }
+                "fevt" => {
+                    lines.next();
+                    let block = lines.until("end fevt")?;
+                    events = Some(self.events(&block)?);
+                }
+                "beg" => {
+                    lines.next();
+                    for child_line in lines.until("end")? {
+                        let child = self.child(child_line, father)?;
+                        if !family.children.contains(&child) {
+                            family.children.push(child);
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        let (mut marriage_cause, mut divorce_cause, mut other_causes) = (None, None, Vec::new());
+        for (event, cause) in events.unwrap_or_default() {
+            match event.event_type {
+                EventType::Marriage if family.marriage.is_none() => {
+                    family.marriage = Some(event);
+                    marriage_cause = Some(cause);
+                }
+                EventType::Divorce if family.divorce.is_none() => {
+                    family.divorce = Some(event);
+                    divorce_cause = Some(cause);
+                }
+                _ => {
+                    family.other_events.push(event);
+                    other_causes.push(cause);
+                }
+            }
+        }
+
+        // Ligne `fam` : ce que le bloc `fevt` ne reprend pas déjà. `#eng` en fait des
+        // fiançailles ; `#nm` garde le mariage, le drapeau marque l'union libre.
+        if marriage.present || !witnesses.is_empty() {
+            let kind = if engaged {
+                EventType::Engagement
+            } else {
+                EventType::Marriage
+            };
+            let known = match kind {
+                EventType::Marriage => family.marriage.is_some(),
+                _ => family
+                    .other_events
+                    .iter()
+                    .any(|event| event.event_type == kind),
+            };
+            if !known {
+                let mut event = self.event(kind, marriage);
+                event.witnesses = witnesses;
+                if engaged {
+                    family.other_events.push(event);
+                    other_causes.push(None);
+                } else {
+                    family.marriage = Some(event);
+                    marriage_cause = Some(None);
+                }
+            }
+        }
+        if family.divorce.is_none() {
+            family.divorce = divorce.map(|info| self.event(EventType::Divorce, info));
+            divorce_cause = family.divorce.as_ref().map(|_| None);
+        }
+        let causes = marriage_cause
+            .into_iter()
+            .chain(divorce_cause)
+            .chain(other_causes);
+        self.keep_causes(family.id.0, causes);
+
+        for child in &family.children {
+            let index = self.id_index[child];
+            let person = &mut self.data.persons[index];
+            if let Some(place) = &children_birth_place {
+                let birth = person.birth.get_or_insert_with(|| empty_event(EventType::Birth));
+                if birth.place.is_none() {
+                    birth.place = Some(place_named(place));
+                }
+            }
+            if person.birth.as_ref().is_some_and(|b| b.sources.is_empty()) {
+                let sources: Vec<Source> = children_sources
+                    .iter()
+                    .map(|title| self.source(title))
+                    .collect();
+                let person = &mut self.data.persons[index];
+                if let Some(birth) = person.birth.as_mut() {
+                    birth.sources = sources;
+                }
+            }
+        }
+
+        if !flags.is_empty() {
+            self.data.extras.family_flags.insert(family.id, flags);
+        }
+        self.data.families.push(family);
+        Ok(())
+    }
+
+    /// Lit un conjoint (`Nom Prénom[.occ] [infos]`) dans une ligne `fam`.
+    fn spouse(&mut self, tokens: &[&str], i: &mut usize, sex: Sex) -> Result<Option<PersonId>> {
+        let (surname, first_name) = match (tokens.get(*i), tokens.get(*i + 1)) {
+            (Some(surname), Some(first_name)) => (*surname, *first_name),
+            _ => bail!("conjoint incomplet"),
+        };
+        *i += 2;
+        let key = PersonKey::parse(surname, first_name);
+        let info = parse_info(tokens, i);
+        if key.is_unknown() {
+            return Ok(None);
+        }
+        let label = key.label();
+        let index = self.person(key);
+        let person = &mut self.data.persons[index];
+        if person.sex == Sex::Unknown {
+            person.sex = sex;
+        }
+        self.apply_info(index, &label, info);
+        Ok(Some(self.data.persons[index].id))
+    }
+
+    /// Ligne enfant : `- [h|f] Prénom[.occ] [Nom] [infos]`.
+    fn child(&mut self, line: &str, father: Option<PersonId>) -> Result<PersonId> {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.first() != Some(&"-") {
+            bail!("ligne enfant invalide `{line}`");
+        }
+        let mut i = 1;
+        let sex = match tokens.get(i) {
+            Some(&"h") => Sex::Male,
+            Some(&"f") => Sex::Female,
+            _ => Sex::Unknown,
+        };
+        if sex != Sex::Unknown {
+            i += 1;
+        }
+        let first_name = tokens
+            .get(i)
+            .ok_or_else(|| anyhow!("prénom manquant `{line}`"))?;
+        i += 1;
+
+        let surname = match tokens.get(i) {
+            Some(token) if !is_info_token(token) || *token == "?" => {
+                i += 1;
+                token.to_string()
+            }
+            _ => match father {
+                Some(father) => {
+                    let father = &self.data.persons[self.id_index[&father]];
+                    crate::encode(&crate::join_particle(
+                        father.surname_prefix.as_deref(),
+                        &father.surname,
+                    ))
+                }
+                None => "?".to_string(),
+            },
+        };
+
+        let key = PersonKey::parse(&surname, first_name);
+        let info = parse_info(&tokens, &mut i);
+        let label = key.label();
+        let index = self.person(key);
+        if sex != Sex::Unknown {
+            self.data.persons[index].sex = sex;
+        }
+        self.apply_info(index, &label, info);
+        Ok(self.data.persons[index].id)
+    }
+
+    /// Ligne `wit: [m:|f:] Nom Prénom [infos]`.
+    fn witness(&mut self, tokens: &[&str]) -> Result<Option<PersonId>> {
+        let mut i = 1;
+        let mut sex = Sex::Unknown;
+        while let Some(token) = tokens.get(i) {
+            match *token {
+                "m:" => sex = Sex::Male,
+                "f:" => sex = Sex::Female,
+                ":" => {}
+                _ => break,
+            }
+            i += 1;
+        }
+        self.spouse(tokens, &mut i, sex)
+    }
+
+    fn person_notes(&mut self, tokens: &[&str], lines: &mut Lines<'_>) -> Result<()> {
+        let index = self.header_person(tokens)?;
+        if lines.next().map(|(_, line)| line.trim()) != Some("beg") {
+            bail!("`beg` attendu après `notes`");
+        }
+        let notes = lines.until("end notes")?.join("\n");
+        self.data.persons[index].notes = Some(notes);
+        Ok(())
+    }
+
+    fn person_events(&mut self, tokens: &[&str], lines: &mut Lines<'_>) -> Result<()> {
+        let index = self.header_person(tokens)?;
+        let block = lines.until("end pevt")?;
+        let events = self.events(&block)?;
+        let person = &mut self.data.persons[index];
+        if !events.is_empty() {
+            person.birth = None;
+            person.death = None;
+            person.other_events.clear();
+        }
+        let (mut birth_cause, mut death_cause, mut other_causes) = (None, None, Vec::new());
+        for (event, cause) in events {
+            match event.event_type {
+                EventType::Birth if person.birth.is_none() => {
+                    person.birth = Some(event);
+                    birth_cause = Some(cause);
+                }
+                EventType::Death if person.death.is_none() => {
+                    person.death = Some(event);
+                    death_cause = Some(cause);
+                }
+                _ => {
+                    person.other_events.push(event);
+                    other_causes.push(cause);
+                }
+            }
+        }
+        let owner = person.id.0;
+        let causes = birth_cause
+            .into_iter()
+            .chain(death_cause)
+            .chain(other_causes);
+        self.keep_causes(owner, causes);
+        Ok(())
+    }
+
+    fn header_person(&mut self, tokens: &[&str]) -> Result<usize> {
+        match (tokens.get(1), tokens.get(2)) {
+            (Some(surname), Some(first_name)) => Ok(self.person(PersonKey::parse(surname, first_name))),
+            _ => bail!("personne attendue après `{}`", tokens[0]),
+        }
+    }
+
+    /// Causes `#c` rangées au rang d'écriture de leur événement.
+    fn keep_causes(&mut self, owner: Uuid, causes: impl IntoIterator<Item = Option<String>>) {
+        for (index, cause) in causes.into_iter().enumerate() {
+            if let Some(cause) = cause {
+                self.data.extras.event_causes.insert((owner, index), cause);
+            }
+        }
+    }
+
+    /// Contenu d'un bloc `pevt` ou `fevt`, avec la cause `#c` de chaque événement.
+    fn events(&mut self, block: &[&str]) -> Result<Vec<(LifeEvent, Option<String>)>> {
+        let mut events: Vec<(LifeEvent, Option<String>)> = Vec::new();
+        for line in block {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens[0] {
+                "wit" | "wit:" => {
+                    let witness = self.witness(&tokens)?;
+                    let (event, _) = events
+                        .last_mut()
+                        .ok_or_else(|| anyhow!("témoin hors événement"))?;
+                    event.witnesses.extend(witness);
+                }
+                "note" => {
+                    let (event, _) = events
+                        .last_mut()
+                        .ok_or_else(|| anyhow!("note hors événement"))?;
+                    let text = line.trim_start()["note".len()..].trim_start();
+                    match event.notes.as_mut() {
+                        Some(notes) => {
+                            notes.push('\n');
+                            notes.push_str(text);
+                        }
+                        None => event.notes = Some(text.to_string()),
+                    }
+                }
+                code if code.starts_with('#') => {
+                    let mut info = EventInfo {
+                        present: true,
+                        ..Default::default()
+                    };
+                    let mut cause = None;
+                    let mut i = 1;
+                    while let Some(token) = tokens.get(i) {
+                        match *token {
+                            "#p" => {
+                                i += 1;
+                                info.place = tokens.get(i).map(|t| decode(t));
+                            }
+                            "#s" => {
+                                i += 1;
+                                info.sources = tokens
+                                    .get(i)
+                                    .map(|t| decode_list(t, ';'))
+                                    .unwrap_or_default();
+                            }
+                            "#c" => {
+                                i += 1;
+                                cause = tokens.get(i).map(|t| t.to_string());
+                                self.warn(
+                                    ImportWarningKind::PreservedTag,
+                                    None,
+                                    "#c",
+                                    cause.as_deref(),
+                                );
+                            }
+                            token if is_date_token(token) => info.date = self.date(code, token),
+                            token => {
+                                self.warn(ImportWarningKind::UnknownTag, None, code, Some(token));
+                            }
+                        }
+                        i += 1;
+                    }
+                    let event = self.event(event_type(&code[1..]), info);
+                    events.push((event, cause));
+                }
+                other => bail!("ligne d'événement inconnue `{other}`"),
+            }
+        }
+        Ok(events)
+    }
+
+    fn person(&mut self, key: PersonKey) -> usize {
+        let normalized = key.normalized();
+        if let Some(index) = self.person_index.get(&normalized) {
+            return *index;
+        }
+
+        let (surname_prefix, surname) = split_particle(&key.surname);
+        let id = PersonId(Uuid::new_v4());
+        self.data.persons.push(Person {
+            id,
+            first_name: key.first_name,
+            surname,
+            surname_prefix,
+            nicknames: Vec::new(),
+            sex: Sex::Unknown,
+            birth: None,
+            death: None,
+            other_events: Vec::new(),
+            occupation: Vec::new(),
+            titles: Vec::new(),
+            notes: None,
+            sources: Vec::new(),
+            images: Vec::new(),
+            public: true,
+            created_at: self.now,
+            updated_at: self.now,
+            updated_by: None,
+        });
+        let index = self.data.persons.len() - 1;
+        self.person_index.insert(normalized.clone(), index);
+        self.id_index.insert(id, index);
+        self.data.person_keys.insert(normalized, id);
+        index
+    }
+
+    fn apply_info(&mut self, index: usize, label: &str, mut info: PersonInfo) {
+        for (kind, tag, value) in std::mem::take(&mut info.warnings) {
+            self.warn(kind, Some(label), &tag, value.as_deref());
+        }
+        if !info.is_empty() && !self.defined.insert(index) {
+            self.warn(ImportWarningKind::DuplicateXref, Some(label), "fam", None);
+        }
+
+        let birth = info
+            .birth
+            .present
+            .then(|| self.event(EventType::Birth, info.birth));
+        let baptism = info
+            .baptism
+            .present
+            .then(|| self.event(EventType::Baptism, info.baptism));
+        let death = info
+            .death
+            .present
+            .then(|| self.event(EventType::Death, info.death));
+        let burial_type = if info.cremated {
+            EventType::Custom("CREM".into())
+        } else {
+            EventType::Burial
+        };
+        let burial = info
+            .burial
+            .present
+            .then(|| self.event(burial_type, info.burial));
+        let sources: Vec<Source> = info
+            .sources
+            .iter()
+            .map(|title| self.source(title))
+            .collect();
+
+        let id = self.data.persons[index].id;
+        if !info.tokens.is_empty() {
+            let tokens = self.data.extras.person_tokens.entry(id).or_default();
+            for token in info.tokens {
+                if !tokens.contains(&token) {
+                    tokens.push(token);
+                }
+            }
+        }
+        if let Some(status) = info.death_status {
+            self.data.extras.death_status.insert(id, status);
+        }
+
+        let person = &mut self.data.persons[index];
+        if let Some(public) = info.public {
+            person.public = public;
+        }
+        for nick in info.nicknames {
+            if !person.nicknames.contains(&nick) {
+                person.nicknames.push(nick);
+            }
+        }
+        if let Some(url) = info.image {
+            person.images.push(ImageReference {
+                url,
+                description: None,
+                is_primary: true,
+            });
+        }
+        person.occupation.extend(info.occupation);
+        person.titles.extend(info.titles);
+        person.sources.extend(sources);
+        if birth.is_some() {
+            person.birth = birth;
+        }
+        if death.is_some() {
+            person.death = death;
+        }
+        person.other_events.extend(baptism);
+        person.other_events.extend(burial);
+    }
+
+    fn event(&mut self, event_type: EventType, info: EventInfo) -> LifeEvent {
+        LifeEvent {
+            event_type,
+            date: info.date.unwrap_or(PreciseDate::Unknown),
+            place: info.place.as_deref().map(place_named),
+            witnesses: Vec::new(),
+            notes: None,
+            sources: info
+                .sources
+                .iter()
+                .map(|title| self.source(title))
+                .collect(),
+        }
+    }
+
+    /// Source partagée par titre, créée à la première occurrence.
+    fn source(&mut self, title: &str) -> Source {
+        if let Some(index) = self.source_index.get(title) {
+            return self.data.sources[*index].clone();
+        }
+        let source = Source {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            author: None,
+            publication_info: None,
+            repository: None,
+            notes: None,
+            page: None,
+        };
+        self.source_index
+            .insert(title.to_string(), self.data.sources.len());
+        self.data.sources.push(source.clone());
+        source
+    }
+}
+
+/// Lit les informations d'une personne jusqu'au premier jeton structurel.
+fn parse_info(tokens: &[&str], i: &mut usize) -> PersonInfo {
+    #[derive(PartialEq)]
+    enum Slot {
+        Birth,
+        Death,
+        Done,
+    }
+
+    let mut info = PersonInfo::default();
+    let mut slot = Slot::Birth;
+    let value = |i: &mut usize| {
+        *i += 1;
+        tokens.get(*i).map(|t| decode(t))
+    };
+    let list = |i: &mut usize, separator: char| {
+        *i += 1;
+        tokens
+            .get(*i)
+            .map(|t| decode_list(t, separator))
+            .unwrap_or_default()
+    };
+
+    while let Some(token) = tokens.get(*i).copied() {
+        match token {
+            "#nick" => info.nicknames.extend(value(i)),
+            "#image" => info.image = value(i),
+            "#occu" => info.occupation = list(i, ','),
+            "#src" => info.sources = list(i, ';'),
+            "#alias" | "#salias" | "#fn" | "#sn" => {
+                let raw = tokens.get(*i + 1).copied().unwrap_or_default();
+                let preserved = value(i);
+                info.preserve(format!("{token} {raw}"), token, preserved);
+            }
+            "#apubl" => info.public = Some(true),
+            "#apriv" => info.public = Some(false),
+            "#semipub" => info.preserve(token.to_string(), token, None),
+            "#bp" => set_place(&mut info.birth, value(i)),
+            "#bs" => set_source(&mut info.birth, list(i, ';')),
+            "#pp" => set_place(&mut info.baptism, value(i)),
+            "#ps" => set_source(&mut info.baptism, list(i, ';')),
+            "#dp" => set_place(&mut info.death, value(i)),
+            "#ds" => set_source(&mut info.death, list(i, ';')),
+            "#rp" => set_place(&mut info.burial, value(i)),
+            "#rs" => set_source(&mut info.burial, list(i, ';')),
+            "#buri" | "#crem" => {
+                info.burial.present = true;
+                info.cremated = token == "#crem";
+                if tokens.get(*i + 1).is_some_and(|next| is_date_token(next)) {
+                    *i += 1;
+                    info.burial.date = info.date(token, tokens[*i]);
+                }
+            }
+            "?" | "mj" | "od" if slot != Slot::Done => {
+                info.death.present = token != "?";
+                info.death_status(token);
+                slot = Slot::Done;
+            }
+            _ if token.starts_with('(') || token.starts_with('{') => {
+                let (close, tag) = if token.starts_with('(') {
+                    (')', "(…)")
+                } else {
+                    ('}', "{…}")
+                };
+                let start = *i;
+                while tokens.get(*i).is_some_and(|t| !t.ends_with(close)) {
+                    *i += 1;
+                }
+                let end = (*i + 1).min(tokens.len());
+                let raw = tokens[start..end].join(" ");
+                info.preserve(raw.clone(), tag, Some(raw));
+            }
+            _ if token.starts_with('[') => {
+                let mut raw = token.to_string();
+                while !raw.ends_with(']') && *i + 1 < tokens.len() {
+                    *i += 1;
+                    raw.push('_');
+                    raw.push_str(tokens[*i]);
+                }
+                let title = parse_title(&raw, &mut info);
+                info.titles.extend(title);
+            }
+            _ if token.starts_with('!') => {
+                info.baptism.present = true;
+                info.baptism.date = info.date("!", &token[1..]);
+                if slot == Slot::Birth {
+                    slot = Slot::Death;
+                }
+            }
+            _ if is_date_token(token) && slot == Slot::Birth => {
+                info.birth.date = info.date("birth", token);
+                info.birth.present = info.birth.date.is_some();
+                slot = Slot::Death;
+            }
+            _ if slot != Slot::Done
+                && (is_date_token(token)
+                    || token.starts_with(['k', 'm', 'e', 's']) && is_date_token(&token[1..])) =>
+            {
+                let date = token.trim_start_matches(['k', 'm', 'e', 's']);
+                if date.len() < token.len() {
+                    info.death_status(&token[..1]);
+                }
+                info.death.present = true;
+                info.death.date = info.date("death", date);
+                slot = Slot::Done;
+            }
+            _ => break,
+        }
+        *i += 1;
+    }
+
+    info
+}
+
+fn set_place(event: &mut EventInfo, place: Option<String>) {
+    event.present |= place.is_some();
+    event.place = place;
+}
+
+fn set_source(event: &mut EventInfo, sources: Vec<String>) {
+    event.present |= !sources.is_empty();
+    event.sources = sources;
+}
+
+/// Titre `[titre:lieu]` ou `[titre:lieu:début:fin[:n]]`.
+fn parse_title(raw: &str, info: &mut PersonInfo) -> Option<Title> {
+    let inner = raw.strip_prefix('[').unwrap_or(raw);
+    let inner = inner.strip_suffix(']').unwrap_or(inner);
+    let parts = split_escaped(inner, ':');
+    let parts = match parts.len() {
+        3 | 6 => &parts[1..],
+        _ => &parts[..],
+    };
+    let name = decode(parts.first()?);
+    let optional = |index: usize| parts.get(index).filter(|value| !value.is_empty());
+    Some(Title {
+        name,
+        place: optional(1).map(|place| decode(place)),
+        date_start: optional(2).and_then(|date| info.date("[", date)),
+        date_end: optional(3).and_then(|date| info.date("[", date)),
+        nth: optional(4).and_then(|nth| nth.parse().ok()),
+    })
+}
+
+fn is_info_token(token: &str) -> bool {
+    token.starts_with(['#', '!', '[', '{', '('])
+        || is_date_token(token)
+        || matches!(token, "?" | "mj" | "od")
+}
+
+fn event_type(code: &str) -> EventType {
+    match code {
+        "birt" => EventType::Birth,
+        "bapt" => EventType::Baptism,
+        "deat" => EventType::Death,
+        "buri" => EventType::Burial,
+        "marr" => EventType::Marriage,
+        "div" => EventType::Divorce,
+        "enga" => EventType::Engagement,
+        code if EVENT_CODES.contains(&code) => EventType::Custom(code.to_uppercase()),
+        code => EventType::Custom(decode(code)),
+    }
+}
+
+fn empty_event(event_type: EventType) -> LifeEvent {
+    LifeEvent {
+        event_type,
+        date: PreciseDate::Unknown,
+        place: None,
+        witnesses: Vec::new(),
+        notes: None,
+        sources: Vec::new(),
+    }
+}
+
+fn place_named(name: &str) -> Place {
+    Place {
+        name: name.to_string(),
+        locality: None,
+        city: None,
+        county: None,
+        state: None,
+        country: None,
+        latitude: None,
+        longitude: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::write_gw_with_extras;
+
+    const INPUT: &str = "encoding: utf-8
+gwplus
+
+fam Dupont Jean (Jean_le_Grand) #alias Jeannot 1750 k1790 + #nsck #pacs Martin Marie {Mariette} 1755 mj
+fevt
+#marr 1775 #c Dispense
+end fevt
+beg
+- h Pierre #fn Pierrot 1776 ?
+end
+
+pevt Dupont Jean
+#birt 1750
+#deat 1790 #c Guillotine
+end pevt
+
+notes-db
+Notes générales
+end notes-db
+";
+
+    fn preserved(data: &GwData) -> usize {
+        data.report
+            .warnings
+            .iter()
+            .filter(|warning| warning.kind == ImportWarningKind::PreservedTag)
+            .count()
+    }
+
+    #[test]
+    fn unmapped_data_is_preserved_and_rewritten() {
+        let data = import_gw(INPUT).unwrap();
+        let id = |key: &str| data.person_keys[key];
+        let extras = &data.extras;
+        assert_eq!(
+            extras.person_tokens[&id("dupont jean.0")],
+            ["(Jean_le_Grand)", "#alias Jeannot"]
+        );
+        assert_eq!(extras.person_tokens[&id("martin marie.0")], ["{Mariette}"]);
+        assert_eq!(
+            extras.person_tokens[&id("dupont pierre.0")],
+            ["#fn Pierrot"]
+        );
+        assert_eq!(extras.death_status[&id("dupont jean.0")], "k");
+        assert_eq!(extras.death_status[&id("martin marie.0")], "mj");
+        assert_eq!(extras.death_status[&id("dupont pierre.0")], "?");
+        assert_eq!(
+            extras.family_flags[&data.families[0].id],
+            ["#nsck", "#pacs"]
+        );
+        assert_eq!(extras.event_causes[&(data.families[0].id.0, 0)], "Dispense");
+        assert_eq!(
+            extras.event_causes[&(id("dupont jean.0").0, 1)],
+            "Guillotine"
+        );
+        assert_eq!(extras.blocks, ["notes-db\nNotes générales\nend notes-db"]);
+        assert!(data
+            .report
+            .warnings
+            .iter()
+            .all(|w| w.kind != ImportWarningKind::UnknownTag));
+        assert_eq!(preserved(&data), 12);
+
+        let output = write_gw_with_extras(&data.persons, &data.families, extras);
+        let again = import_gw(&output).unwrap();
+        assert_eq!(again.extras.person_tokens.len(), 3);
+        assert_eq!(again.extras.death_status.len(), 3);
+        assert_eq!(again.extras.family_flags.len(), 1);
+        assert_eq!(again.extras.event_causes.len(), 2);
+        assert_eq!(again.extras.blocks, extras.blocks);
+        assert_eq!(preserved(&again), preserved(&data));
+    }
+    #[test]
+    fn relation_kinds_survive_a_round_trip() {
+        let input = "fam Dupont Jean +1780 #mp Paris #nm Martin Marie
+wit m: Durand Paul
+
+fam Dupont Louis +1779 #eng Petit Anne
+
+fam Dupont Marc +1790 #nm Roux Jeanne
+fevt
+#div 1795
+end fevt
+";
+        let data = import_gw(input).unwrap();
+        let [free, engaged, separated] = &data.families[..] else {
+            panic!("trois familles attendues");
+        };
+        let marriage = free.marriage.as_ref().unwrap();
+        assert_eq!(marriage.date, PreciseDate::YearOnly(1780));
+        assert_eq!(marriage.place.as_ref().unwrap().name, "Paris");
+        assert_eq!(marriage.witnesses.len(), 1);
+        assert_eq!(data.extras.family_flags[&free.id], ["#nm"]);
+        assert!(engaged.marriage.is_none());
+        assert_eq!(engaged.other_events[0].event_type, EventType::Engagement);
+        assert_eq!(engaged.other_events[0].date, PreciseDate::YearOnly(1779));
+        assert_eq!(data.extras.family_flags[&engaged.id], ["#eng"]);
+        assert_eq!(
+            separated.marriage.as_ref().unwrap().date,
+            PreciseDate::YearOnly(1790)
+        );
+        assert!(separated.divorce.is_some());
+        assert_eq!(data.extras.family_flags[&separated.id], ["#nm"]);
+
+        let output = write_gw_with_extras(&data.persons, &data.families, &data.extras);
+        assert!(output.contains("+1780 #mp Paris #nm Martin Marie"));
+        assert!(output.contains("#eng Petit Anne"));
+        let again = import_gw(&output).unwrap();
+        for (before, after) in data.families.iter().zip(&again.families) {
+            assert_eq!(
+                after.marriage.as_ref().map(|e| &e.date),
+                before.marriage.as_ref().map(|e| &e.date)
+            );
+            assert_eq!(after.other_events.len(), before.other_events.len());
+            assert_eq!(
+                again.extras.family_flags[&after.id],
+                data.extras.family_flags[&before.id]
+            );
+        }
+    }
+}
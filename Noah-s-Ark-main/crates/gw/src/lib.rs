@@ -0,0 +1,152 @@
+//! Format texte natif de GeneWeb (`.gw`) : blocs `fam`/`beg`/`end`, `notes`,
+//! `pevt`/`fevt`, personnes identifiées par `Nom Prénom[.occ]`.
+
+pub mod date;
+pub mod extras;
+pub mod import;
+pub mod writer;
+
+pub use extras::GwExtras;
+pub use import::{import_gw, GwData};
+pub use writer::{write_gw, write_gw_with_extras};
+
+/// Particules séparées du nom de famille (`de La Fontaine` → `de` + `La Fontaine`).
+const PARTICLES: &[&str] = &[
+    "de ", "d'", "du ", "des ", "van der ", "van den ", "van ", "von ", "di ", "da ", "del ",
+    "della ",
+];
+
+/// Codes d'événements `.gw` repris tels quels (en majuscules) dans `EventType::Custom`.
+const EVENT_CODES: &[&str] = &[
+    "adop", "barm", "basm", "bles", "chr", "chra", "conf", "fcom", "ordn", "natu", "emig",
+    "immi", "cens", "prob", "will", "grad", "reti", "crem", "resi", "occu", "anul", "divf",
+    "marb", "marc", "marl", "mars",
+];
+
+/// Décode un jeton `.gw` : `_` remplace les espaces, `?` un nom inconnu, `\`
+/// protège le caractère suivant.
+fn decode(token: &str) -> String {
+    if token == "?" {
+        return String::new();
+    }
+    let mut out = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            '_' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Encode une valeur en jeton `.gw` unique.
+fn encode(value: &str) -> String {
+    encode_with(value, &[])
+}
+
+/// Comme [`encode`], en protégeant aussi les séparateurs donnés.
+fn encode_with(value: &str, separators: &[char]) -> String {
+    let value = value.trim();
+    match value {
+        "" => return "?".to_string(),
+        "?" => return "\\?".to_string(),
+        _ => {}
+    }
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c == '\\' || c == '_' || separators.contains(&c) {
+            out.push('\\');
+            out.push(c);
+        } else if c.is_whitespace() {
+            out.push('_');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Encode une liste en un seul jeton : `Registre;_Acte\;_12` pour
+/// `["Registre", "Acte; 12"]` avec le séparateur `;`.
+fn encode_list<'v>(values: impl IntoIterator<Item = &'v str>, separator: char) -> String {
+    values
+        .into_iter()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| encode_with(value, &[separator]))
+        .collect::<Vec<_>>()
+        .join(&format!("{separator}_"))
+}
+
+/// Découpe un jeton sur les séparateurs qui ne sont pas protégés par `\`.
+fn split_escaped(token: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (index, c) in token.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == separator {
+            parts.push(&token[start..index]);
+            start = index + c.len_utf8();
+        }
+    }
+    parts.push(&token[start..]);
+    parts
+}
+
+/// Liste écrite par [`encode_list`], éléments vides ignorés.
+fn decode_list(token: &str, separator: char) -> Vec<String> {
+    split_escaped(token, separator)
+        .into_iter()
+        .map(|part| decode(part).trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Texte sur une seule ligne (`comm`) : `\n` pour les retours à la ligne.
+fn escape_line(text: &str) -> String {
+    text.trim().replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// Inverse de [`escape_line`].
+fn unescape_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        out.push(match chars.next() {
+            Some('n') => '\n',
+            Some(next) => next,
+            None => '\\',
+        });
+    }
+    out
+}
+
+/// Sépare la particule éventuelle d'un nom de famille complet.
+fn split_particle(full: &str) -> (Option<String>, String) {
+    for particle in PARTICLES {
+        if let Some(rest) = full.strip_prefix(particle) {
+            if !rest.is_empty() {
+                return (Some(particle.trim_end().to_string()), rest.to_string());
+            }
+        }
+    }
+    (None, full.to_string())
+}
+
+/// Nom de famille complet, particule incluse.
+fn join_particle(prefix: Option<&str>, surname: &str) -> String {
+    match prefix {
+        Some(prefix) if prefix.ends_with('\'') => format!("{prefix}{surname}"),
+        Some(prefix) => format!("{prefix} {surname}"),
+        None => surname.to_string(),
+    }
+}
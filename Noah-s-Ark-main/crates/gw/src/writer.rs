@@ -0,0 +1,537 @@
+use std::collections::{HashMap, HashSet};
+
+use genealogy_types::{EventType, Family, LifeEvent, Person, PersonId, Sex, Source};
+use uuid::Uuid;
+
+use crate::date::format_date;
+use crate::extras::GwExtras;
+use crate::{encode, encode_list, encode_with, escape_line, join_particle, EVENT_CODES};
+
+/// Lieu où une personne est décrite en entier (absente : bloc `pevt` seul).
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Site {
+    /// Ligne enfant de la famille d'indice donné.
+    Child(usize),
+    /// Ligne `fam` de la famille d'indice donné.
+    Spouse(usize),
+}
+
+/// Écrit un fichier `.gw` (format `gwplus`).
+///
+/// Chaque personne est décrite une seule fois : dans la famille de ses parents
+/// si elle en a une, sinon comme conjoint. Les notes de personnes vont dans des
+/// blocs `notes`, les événements détaillés (témoins, notes, événements
+/// supplémentaires) dans des blocs `pevt`/`fevt`.
+pub fn write_gw(persons: &[Person], families: &[Family]) -> String {
+    write_gw_with_extras(persons, families, &GwExtras::default())
+}
+
+/// Comme [`write_gw`], en réécrivant les données conservées par l'import.
+pub fn write_gw_with_extras(persons: &[Person], families: &[Family], extras: &GwExtras) -> String {
+    GwWriter::new(persons, families, extras).finish()
+}
+
+struct GwWriter<'a> {
+    persons: &'a [Person],
+    families: &'a [Family],
+    extras: &'a GwExtras,
+    by_id: HashMap<PersonId, &'a Person>,
+    keys: HashMap<PersonId, (String, String)>,
+    sites: HashMap<PersonId, Site>,
+    /// Témoins hors familles déjà décrits sur une ligne `wit`.
+    witnesses: HashSet<PersonId>,
+    out: String,
+}
+
+impl<'a> GwWriter<'a> {
+    fn new(persons: &'a [Person], families: &'a [Family], extras: &'a GwExtras) -> Self {
+        let by_id: HashMap<PersonId, &Person> = persons.iter().map(|p| (p.id, p)).collect();
+
+        // Numéros d'occurrence pour distinguer les homonymes.
+        let mut counters: HashMap<(String, String), u32> = HashMap::new();
+        let mut keys = HashMap::new();
+        for person in persons {
+            let surname = encode(&full_surname(person));
+            let first_name = encode(&person.first_name);
+            let counter = counters
+                .entry((surname.to_lowercase(), first_name.to_lowercase()))
+                .or_insert(if surname == "?" && first_name == "?" { 1 } else { 0 });
+            let first_name = if *counter == 0 {
+                first_name
+            } else {
+                format!("{first_name}.{counter}")
+            };
+            *counter += 1;
+            keys.insert(person.id, (surname, first_name));
+        }
+
+        let mut sites = HashMap::new();
+        for (index, family) in families.iter().enumerate() {
+            for child in &family.children {
+                sites.entry(*child).or_insert(Site::Child(index));
+            }
+        }
+        for (index, family) in families.iter().enumerate() {
+            for spouse in family.father.iter().chain(family.mother.iter()) {
+                sites.entry(*spouse).or_insert(Site::Spouse(index));
+            }
+        }
+
+        Self {
+            persons,
+            families,
+            extras,
+            by_id,
+            keys,
+            sites,
+            witnesses: HashSet::new(),
+            out: String::new(),
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.out.push_str("encoding: utf-8\ngwplus\n\n");
+
+        for (index, family) in self.families.iter().enumerate() {
+            self.family(index, family);
+        }
+
+        for person in self.persons {
+            if let Some(notes) = person.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+                let key = self.key(person.id);
+                self.out
+                    .push_str(&format!("notes {key}\nbeg\n{notes}\nend notes\n\n"));
+            }
+        }
+
+        for person in self.persons {
+            if self.needs_person_block(person) {
+                let key = self.key(person.id);
+                self.out.push_str(&format!("pevt {key}\n"));
+                for (rank, event) in person_events(person).enumerate() {
+                    self.event_block(event, self.cause(person.id.0, rank));
+                }
+                self.out.push_str("end pevt\n\n");
+            }
+        }
+
+        // Personnes sans famille ni témoignage : un bloc vide suffit à les créer.
+        for person in self.persons {
+            let isolated = !self.sites.contains_key(&person.id)
+                && !self.witnesses.contains(&person.id)
+                && !self.needs_person_block(person);
+            if isolated {
+                let key = self.key(person.id);
+                self.out.push_str(&format!("pevt {key}\nend pevt\n\n"));
+            }
+        }
+
+        for block in &self.extras.blocks {
+            self.out.push_str(block);
+            self.out.push_str("\n\n");
+        }
+
+        self.out
+    }
+
+    fn family(&mut self, index: usize, family: &Family) {
+        let events = family.marriage.iter().chain(family.divorce.iter()).count();
+        let detailed = needs_family_block(family)
+            || self.has_causes(family.id.0, events + family.other_events.len());
+
+        let mut line = String::from("fam ");
+        line.push_str(&self.spouse(family.father, index));
+        line.push(' ');
+        match &family.marriage {
+            Some(marriage) => {
+                line.push('+');
+                if marriage.date != genealogy_types::PreciseDate::Unknown {
+                    line.push_str(&format_date(&marriage.date));
+                }
+                if !detailed {
+                    push_option(&mut line, "#mp", marriage.place.as_ref().map(|p| p.name.as_str()));
+                    push_token(&mut line, "#ms", sources_token(&marriage.sources));
+                }
+            }
+            None => line.push('+'),
+        }
+        if let Some(divorce) = &family.divorce {
+            line.push_str(" -");
+            if divorce.date != genealogy_types::PreciseDate::Unknown {
+                line.push_str(&format_date(&divorce.date));
+            }
+        }
+        if let Some(flags) = self.extras.family_flags.get(&family.id) {
+            for flag in flags {
+                line.push(' ');
+                line.push_str(flag);
+            }
+        }
+        line.push(' ');
+        line.push_str(&self.spouse(family.mother, index));
+        self.out.push_str(&line);
+        self.out.push('\n');
+
+        if !detailed {
+            if let Some(marriage) = &family.marriage {
+                for witness in &marriage.witnesses {
+                    self.witness(*witness);
+                }
+            }
+        }
+        if let Some(token) = sources_token(&family.sources) {
+            self.out.push_str(&format!("src {token}\n"));
+        }
+        if let Some(notes) = family.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+            self.out.push_str(&format!("comm {}\n", escape_line(notes)));
+        }
+        if detailed {
+            self.out.push_str("fevt\n");
+            for (rank, event) in family
+                .marriage
+                .iter()
+                .chain(family.divorce.iter())
+                .chain(family.other_events.iter())
+                .enumerate()
+            {
+                self.event_block(event, self.cause(family.id.0, rank));
+            }
+            self.out.push_str("end fevt\n");
+        }
+
+        if !family.children.is_empty() {
+            let father_surname = family
+                .father
+                .and_then(|id| self.by_id.get(&id))
+                .map(|father| encode(&full_surname(father)));
+            self.out.push_str("beg\n");
+            for child in &family.children {
+                let Some((surname, first_name)) = self.keys.get(child).cloned() else {
+                    continue;
+                };
+                let mut line = String::from("-");
+                match self.by_id[child].sex {
+                    Sex::Male => line.push_str(" h"),
+                    Sex::Female => line.push_str(" f"),
+                    Sex::Unknown => {}
+                }
+                line.push(' ');
+                line.push_str(&first_name);
+                if father_surname.as_deref() != Some(surname.as_str()) {
+                    line.push(' ');
+                    line.push_str(&surname);
+                }
+                if self.sites.get(child) == Some(&Site::Child(index)) {
+                    line.push_str(&person_info(self.by_id[child], self.extras));
+                }
+                self.out.push_str(&line);
+                self.out.push('\n');
+            }
+            self.out.push_str("end\n");
+        }
+        self.out.push('\n');
+    }
+
+    /// Clé d'un conjoint, suivie de ses informations s'il est décrit ici.
+    fn spouse(&self, id: Option<PersonId>, index: usize) -> String {
+        let Some(id) = id.filter(|id| self.keys.contains_key(id)) else {
+            return "? ?".to_string();
+        };
+        let mut out = self.key(id);
+        if self.sites.get(&id) == Some(&Site::Spouse(index)) {
+            out.push_str(&person_info(self.by_id[&id], self.extras));
+        }
+        out
+    }
+
+    /// Ligne `wit`, qui décrit le témoin s'il n'apparaît dans aucune famille.
+    fn witness(&mut self, id: PersonId) {
+        if !self.keys.contains_key(&id) {
+            return;
+        }
+        let person = self.by_id[&id];
+        let mut line = match person.sex {
+            Sex::Male => String::from("wit m: "),
+            Sex::Female => String::from("wit f: "),
+            Sex::Unknown => String::from("wit: "),
+        };
+        line.push_str(&self.key(id));
+        if !self.sites.contains_key(&id) && self.witnesses.insert(id) {
+            line.push_str(&person_info(person, self.extras));
+        }
+        self.out.push_str(&line);
+        self.out.push('\n');
+    }
+
+    fn key(&self, id: PersonId) -> String {
+        let (surname, first_name) = &self.keys[&id];
+        format!("{surname} {first_name}")
+    }
+
+    /// Événements avec une cause `#c` : ils ne s'écrivent que dans un bloc.
+    fn has_causes(&self, owner: Uuid, events: usize) -> bool {
+        (0..events).any(|rank| self.extras.event_causes.contains_key(&(owner, rank)))
+    }
+
+    fn needs_person_block(&self, person: &Person) -> bool {
+        needs_person_block(person) || self.has_causes(person.id.0, person_events(person).count())
+    }
+
+    /// Cause `#c` conservée pour l'événement de rang donné.
+    fn cause(&self, owner: Uuid, rank: usize) -> Option<&'a str> {
+        self.extras
+            .event_causes
+            .get(&(owner, rank))
+            .map(String::as_str)
+    }
+
+    fn event_block(&mut self, event: &LifeEvent, cause: Option<&str>) {
+        let mut line = format!("#{}", event_code(&event.event_type));
+        if event.date != genealogy_types::PreciseDate::Unknown {
+            line.push(' ');
+            line.push_str(&format_date(&event.date));
+        }
+        push_option(&mut line, "#p", event.place.as_ref().map(|p| p.name.as_str()));
+        push_token(&mut line, "#s", sources_token(&event.sources));
+        if let Some(cause) = cause {
+            line.push_str(&format!(" #c {cause}"));
+        }
+        self.out.push_str(&line);
+        self.out.push('\n');
+        for witness in &event.witnesses {
+            self.witness(*witness);
+        }
+        if let Some(notes) = event.notes.as_deref() {
+            for note in notes.lines() {
+                self.out.push_str(&format!("note {note}\n"));
+            }
+        }
+    }
+}
+
+/// Informations d'une personne, précédées d'une espace (vide si rien à écrire).
+fn person_info(person: &Person, extras: &GwExtras) -> String {
+    let mut info = String::new();
+    for token in extras.person_tokens.get(&person.id).into_iter().flatten() {
+        info.push(' ');
+        info.push_str(token);
+    }
+    if !person.public {
+        info.push_str(" #apriv");
+    }
+    for nick in &person.nicknames {
+        push_option(&mut info, "#nick", Some(nick));
+    }
+    let image = person
+        .images
+        .iter()
+        .find(|image| image.is_primary)
+        .or(person.images.first());
+    push_option(&mut info, "#image", image.map(|image| image.url.as_str()));
+    if !person.occupation.is_empty() {
+        let occupations = person.occupation.iter().map(String::as_str);
+        push_token(&mut info, "#occu", Some(encode_list(occupations, ',')));
+    }
+    for title in &person.titles {
+        let part = |value: &str| encode_with(value, &[':', '[', ']']);
+        let mut parts = vec![part(&title.name), title.place.as_deref().map(part).unwrap_or_default()];
+        if title.date_start.is_some() || title.date_end.is_some() || title.nth.is_some() {
+            parts.push(title.date_start.as_ref().map(format_date).unwrap_or_default());
+            parts.push(title.date_end.as_ref().map(format_date).unwrap_or_default());
+            if let Some(nth) = title.nth {
+                parts.push(nth.to_string());
+            }
+        }
+        info.push_str(&format!(" [{}]", parts.join(":")));
+    }
+    push_token(&mut info, "#src", sources_token(&person.sources));
+
+    let baptism = first_event(person, |t| matches!(t, EventType::Baptism));
+    let burial = first_event(person, is_burial);
+
+    match &person.birth {
+        Some(birth) => {
+            info.push(' ');
+            info.push_str(&format_date(&birth.date));
+            push_option(&mut info, "#bp", birth.place.as_ref().map(|p| p.name.as_str()));
+            push_token(&mut info, "#bs", sources_token(&birth.sources));
+        }
+        None if person.death.is_some() && baptism.is_none() => info.push_str(" 0"),
+        None => {}
+    }
+    if let Some(baptism) = baptism {
+        if baptism.date != genealogy_types::PreciseDate::Unknown {
+            info.push_str(&format!(" !{}", format_date(&baptism.date)));
+        }
+        push_option(&mut info, "#pp", baptism.place.as_ref().map(|p| p.name.as_str()));
+        push_token(&mut info, "#ps", sources_token(&baptism.sources));
+    }
+    let status = extras.death_status.get(&person.id).map(String::as_str);
+    if let Some(death) = &person.death {
+        info.push(' ');
+        match status {
+            Some(status @ ("mj" | "od")) if death.date == genealogy_types::PreciseDate::Unknown => {
+                info.push_str(status)
+            }
+            Some(reason @ ("k" | "m" | "e" | "s")) => {
+                info.push_str(reason);
+                info.push_str(&format_date(&death.date));
+            }
+            _ => info.push_str(&format_date(&death.date)),
+        }
+        push_option(&mut info, "#dp", death.place.as_ref().map(|p| p.name.as_str()));
+        push_token(&mut info, "#ds", sources_token(&death.sources));
+    } else if status == Some("?") {
+        info.push_str(" ?");
+    }
+    if let Some(burial) = burial {
+        let cremated = matches!(&burial.event_type, EventType::Custom(name) if name == "CREM");
+        info.push_str(if cremated { " #crem" } else { " #buri" });
+        if burial.date != genealogy_types::PreciseDate::Unknown {
+            info.push(' ');
+            info.push_str(&format_date(&burial.date));
+        }
+        push_option(&mut info, "#rp", burial.place.as_ref().map(|p| p.name.as_str()));
+        push_token(&mut info, "#rs", sources_token(&burial.sources));
+    }
+    info
+}
+
+/// Un bloc `pevt` est nécessaire dès que la ligne d'informations ne suffit plus.
+fn needs_person_block(person: &Person) -> bool {
+    let baptisms = person
+        .other_events
+        .iter()
+        .filter(|e| matches!(e.event_type, EventType::Baptism))
+        .count();
+    let burials = person
+        .other_events
+        .iter()
+        .filter(|e| is_burial(&e.event_type))
+        .count();
+    baptisms > 1
+        || burials > 1
+        || baptisms + burials < person.other_events.len()
+        || person_events(person).any(is_detailed)
+}
+
+fn needs_family_block(family: &Family) -> bool {
+    !family.other_events.is_empty()
+        || family
+            .marriage
+            .iter()
+            .chain(family.divorce.iter())
+            .any(|event| event.notes.is_some() || event.sources.len() > 1)
+        || family.divorce.as_ref().is_some_and(|divorce| {
+            divorce.place.is_some() || !divorce.sources.is_empty() || !divorce.witnesses.is_empty()
+        })
+}
+
+fn is_detailed(event: &LifeEvent) -> bool {
+    event.notes.is_some() || !event.witnesses.is_empty() || event.sources.len() > 1
+}
+
+fn is_burial(event_type: &EventType) -> bool {
+    match event_type {
+        EventType::Burial => true,
+        EventType::Custom(name) => name == "CREM",
+        _ => false,
+    }
+}
+
+fn first_event(person: &Person, filter: impl Fn(&EventType) -> bool) -> Option<&LifeEvent> {
+    person.other_events.iter().find(|event| filter(&event.event_type))
+}
+
+fn person_events(person: &Person) -> impl Iterator<Item = &LifeEvent> {
+    person
+        .birth
+        .iter()
+        .chain(person.death.iter())
+        .chain(person.other_events.iter())
+}
+
+fn event_code(event_type: &EventType) -> String {
+    match event_type {
+        EventType::Birth => "birt".into(),
+        EventType::Baptism => "bapt".into(),
+        EventType::Death => "deat".into(),
+        EventType::Burial => "buri".into(),
+        EventType::Marriage => "marr".into(),
+        EventType::Divorce => "div".into(),
+        EventType::Engagement => "enga".into(),
+        EventType::Custom(name) => {
+            let code = name.to_lowercase();
+            if EVENT_CODES.contains(&code.as_str()) && name.to_uppercase() == *name {
+                code
+            } else {
+                encode(name)
+            }
+        }
+    }
+}
+
+fn full_surname(person: &Person) -> String {
+    join_particle(person.surname_prefix.as_deref(), &person.surname)
+}
+
+/// Titres des sources réunis en un seul jeton `.gw`, séparés par `;`.
+fn sources_token(sources: &[Source]) -> Option<String> {
+    let titles = sources.iter().map(|source| source.title.as_str());
+    Some(encode_list(titles, ';')).filter(|token| !token.is_empty())
+}
+
+fn push_option(out: &mut String, option: &str, value: Option<&str>) {
+    if let Some(value) = value.filter(|v| !v.trim().is_empty()) {
+        out.push_str(&format!(" {option} {}", encode(value)));
+    }
+}
+
+/// Comme [`push_option`], pour un jeton déjà encodé.
+fn push_token(out: &mut String, option: &str, token: Option<String>) {
+    if let Some(token) = token.filter(|token| !token.is_empty()) {
+        out.push_str(&format!(" {option} {token}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import_gw;
+
+    /// Fichier déjà dans la forme écrite par [`write_gw`].
+    const INPUT: &str = r"encoding: utf-8
+gwplus
+
+fam Le\_Gall Jean__Marie #occu Meunier,_maire\,_adjoint [Comte:Rennes\:_ville] #src Registre;_Acte\;_12 1750 + Durand Anne 1752
+src Contrat\;_notaire;_Témoins
+comm Première ligne\nSeconde \\ fin
+beg
+- h Paul 1780
+end
+
+";
+
+    fn titles(sources: &[Source]) -> Vec<&str> {
+        sources.iter().map(|source| source.title.as_str()).collect()
+    }
+
+    #[test]
+    fn gw_round_trip_keeps_separators_and_escapes() {
+        let data = import_gw(INPUT).unwrap();
+        let jean = &data.persons[0];
+        assert_eq!(jean.surname, "Le_Gall");
+        assert_eq!(jean.first_name, "Jean  Marie");
+        assert_eq!(jean.occupation, ["Meunier", "maire, adjoint"]);
+        assert_eq!(titles(&jean.sources), ["Registre", "Acte; 12"]);
+        assert_eq!(jean.titles[0].place.as_deref(), Some("Rennes: ville"));
+        let family = &data.families[0];
+        assert_eq!(
+            family.notes.as_deref(),
+            Some("Première ligne\nSeconde \\ fin")
+        );
+        assert_eq!(titles(&family.sources), ["Contrat; notaire", "Témoins"]);
+
+        assert_eq!(write_gw(&data.persons, &data.families), INPUT);
+    }
+}
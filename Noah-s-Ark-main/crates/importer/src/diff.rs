@@ -0,0 +1,165 @@
+use genealogy_types::{Person, PersonId};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Changements qu'un import apporte à la base, calculés avant écriture.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportDiff {
+    /// Personnes absentes de la base, créées par l'import.
+    pub created: Vec<CreatedPerson>,
+    /// Personnes rapprochées d'une personne existante et modifiées.
+    pub updated: Vec<PersonDiff>,
+    /// Personnes rapprochées sans aucun changement.
+    pub unchanged: usize,
+    pub families_created: usize,
+    /// Familles retrouvées par leurs parents ; seuls les nouveaux enfants y sont ajoutés.
+    pub families_matched: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreatedPerson {
+    pub xref: String,
+    pub person_id: PersonId,
+    pub name: String,
+}
+
+/// Personne existante et champs que l'import remplace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonDiff {
+    pub xref: String,
+    pub person_id: PersonId,
+    /// Identifiant qui a permis le rapprochement (`_UID 0123…`, `REFN:AFN 42`).
+    pub matched_by: String,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Champs enregistrés par `PersonRepository::update_batch` qui diffèrent ; les
+/// événements, titres, sources et images sont présentés en JSON.
+pub fn person_changes(before: &Person, after: &Person) -> Vec<FieldChange> {
+    let detailed = |person: &Person| -> [(&'static str, Option<String>); 8] {
+        [
+            ("nicknames", json(&person.nicknames)),
+            ("occupation", json(&person.occupation)),
+            ("birth", json(&person.birth)),
+            ("death", json(&person.death)),
+            ("other_events", json(&person.other_events)),
+            ("titles", json(&person.titles)),
+            ("sources", json(&person.sources)),
+            ("images", json(&person.images)),
+        ]
+    };
+    let details = detailed(before)
+        .into_iter()
+        .zip(detailed(after))
+        .map(|((field, before), (_, after))| (field, before, after));
+
+    let fields = [
+        (
+            "first_name",
+            Some(before.first_name.clone()),
+            Some(after.first_name.clone()),
+        ),
+        (
+            "surname",
+            Some(before.surname.clone()),
+            Some(after.surname.clone()),
+        ),
+        (
+            "surname_prefix",
+            before.surname_prefix.clone(),
+            after.surname_prefix.clone(),
+        ),
+        (
+            "sex",
+            Some(format!("{:?}", before.sex)),
+            Some(format!("{:?}", after.sex)),
+        ),
+        ("notes", before.notes.clone(), after.notes.clone()),
+        (
+            "public",
+            Some(before.public.to_string()),
+            Some(after.public.to_string()),
+        ),
+    ];
+
+    fields
+        .into_iter()
+        .chain(details)
+        .filter(|(_, before, after)| before != after)
+        .map(|(field, before, after)| FieldChange {
+            field: field.to_string(),
+            before,
+            after,
+        })
+        .collect()
+}
+
+/// Valeur en JSON ; `None` pour une valeur absente ou une liste vide.
+fn json<T: Serialize>(value: &T) -> Option<String> {
+    match serde_json::to_value(value).ok()? {
+        Value::Null => None,
+        Value::Array(items) if items.is_empty() => None,
+        value => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use genealogy_types::{EventType, LifeEvent, PreciseDate, Sex};
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn person() -> Person {
+        Person {
+            id: PersonId(Uuid::new_v4()),
+            first_name: "Jean".to_string(),
+            surname: "Martin".to_string(),
+            surname_prefix: None,
+            nicknames: Vec::new(),
+            sex: Sex::Male,
+            birth: None,
+            death: None,
+            other_events: Vec::new(),
+            occupation: vec!["Laboureur".to_string()],
+            titles: Vec::new(),
+            notes: None,
+            sources: Vec::new(),
+            images: Vec::new(),
+            public: true,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_by: None,
+        }
+    }
+
+    #[test]
+    fn events_and_lists_are_compared() {
+        let before = person();
+        let mut after = before.clone();
+        assert!(person_changes(&before, &after).is_empty());
+
+        after.birth = Some(LifeEvent {
+            event_type: EventType::Birth,
+            date: PreciseDate::YearOnly(1750),
+            place: None,
+            witnesses: Vec::new(),
+            notes: None,
+            sources: Vec::new(),
+        });
+        after.occupation.clear();
+        let changes = person_changes(&before, &after);
+        let fields: Vec<&str> = changes.iter().map(|change| change.field.as_str()).collect();
+        assert_eq!(fields, ["occupation", "birth"]);
+        assert_eq!(changes[0].before.as_deref(), Some(r#"["Laboureur"]"#));
+        assert_eq!(changes[0].after, None);
+        assert_eq!(changes[1].before, None);
+    }
+}
@@ -0,0 +1,639 @@
+//! Import en base de fichiers généalogiques volumineux, sans charger l'arbre en mémoire.
+
+use std::collections::HashMap;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use database::{
+    DatabasePool, ExternalIdRepository, FamilyDraft, FamilyRepository, ImportRepository,
+    PersonRepository, XrefEntry,
+};
+use gedcom::{record_identifiers, DecodingReader, GedcomImporter, GedcomNode, GedcomReader};
+use genealogy_types::{Family, ImportReport, Person, PersonId, Source};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+mod diff;
+
+pub use diff::{person_changes, CreatedPerson, FieldChange, ImportDiff, PersonDiff};
+
+/// Taille par défaut d'un lot (une transaction).
+pub const DEFAULT_BATCH_SIZE: usize = 1000;
+
+/// Format d'un fichier à importer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Gedcom,
+    Gw,
+}
+
+impl ImportFormat {
+    /// Format déduit de l'extension (`.ged`, `.gedcom`, `.gw`).
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "ged" | "gedcom" => Some(Self::Gedcom),
+            "gw" => Some(Self::Gw),
+            _ => None,
+        }
+    }
+}
+
+/// Rapport d'import d'un fichier, sans rien écrire en base.
+///
+/// Un GEDCOM est lu en flux ; un `.gw` est converti en mémoire.
+pub fn check_import<R: Read>(source: R, format: ImportFormat) -> Result<ImportReport> {
+    match format {
+        ImportFormat::Gedcom => {
+            let mut importer = GedcomImporter::new();
+            for record in GedcomReader::new(DecodingReader::new(BufReader::new(source))?) {
+                importer.index(&record?);
+            }
+            importer.resolve_sources();
+            let mut report = importer.data().report.clone();
+            report.persons = report.records.get("INDI").copied().unwrap_or_default();
+            report.families = report.records.get("FAM").copied().unwrap_or_default();
+            Ok(report)
+        }
+        ImportFormat::Gw => {
+            let mut input = String::new();
+            BufReader::new(source)
+                .read_to_string(&mut input)
+                .context("un fichier .gw doit être en UTF-8")?;
+            Ok(gw::import_gw(&input)?.report)
+        }
+    }
+}
+
+/// Étape en cours d'un import.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImportPhase {
+    /// Attribution des identifiants et lecture des enregistrements partagés.
+    #[default]
+    Indexing,
+    Persons,
+    Families,
+    /// Création des arêtes Neo4j `CHILD_OF`.
+    Edges,
+    Done,
+}
+
+/// Avancement transmis après chaque lot.
+#[derive(Debug, Clone, Default)]
+pub struct ImportProgress {
+    pub phase: ImportPhase,
+    pub records: usize,
+    pub persons: usize,
+    pub families: usize,
+    pub edges: usize,
+}
+
+/// Résultat d'un import ou d'un aperçu : rapport d'anomalies et changements.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportOutcome {
+    pub report: ImportReport,
+    pub diff: ImportDiff,
+}
+
+/// Import GEDCOM en flux vers `PersonRepository` et `FamilyRepository`.
+///
+/// Le fichier est relu trois fois : indexation (xref → identifiant, enregistrés
+/// dans `gedcom_xrefs`), personnes, puis familles, qui peuvent ainsi citer des
+/// personnes définies plus loin. Les arêtes `CHILD_OF` sont posées à la fin.
+/// Relancer un import avec le même identifiant reprend les mêmes UUID.
+///
+/// Un `INDI` dont un `_UID`, `REFN` ou `EXID` est déjà connu (`person_external_ids`)
+/// met à jour la personne existante au lieu de la dupliquer ; une `FAM` dont les
+/// parents existent déjà est rattachée à leur famille. [`preview`](Self::preview)
+/// calcule ces changements sans rien écrire.
+pub struct GedcomStreamImporter {
+    pub pool: DatabasePool,
+    pub batch_size: usize,
+}
+
+/// Père et mère d'une famille.
+type Couple = (Option<PersonId>, Option<PersonId>);
+
+/// État d'un import en cours.
+struct Run {
+    importer: GedcomImporter,
+    progress: ImportProgress,
+    diff: ImportDiff,
+    /// xref → identifiant qui l'a rapproché d'une personne existante.
+    matched: HashMap<String, String>,
+    /// (xref, type, valeur) de tous les identifiants externes du fichier.
+    identifiers: Vec<(String, String, String)>,
+}
+
+impl GedcomStreamImporter {
+    pub fn new(pool: DatabasePool) -> Self {
+        Self {
+            pool,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Rapport et changements qu'apporterait l'import, sans écriture en base.
+    pub async fn preview<R: Read + Seek + Send>(&self, mut source: R) -> Result<ImportOutcome> {
+        let (_, outcome) = self.run(None, &mut source, &mut |_| {}).await?;
+        Ok(outcome)
+    }
+
+    pub async fn import<R, F>(
+        &self,
+        import_id: Uuid,
+        file_name: Option<&str>,
+        mut source: R,
+        mut on_progress: F,
+    ) -> Result<ImportOutcome>
+    where
+        R: Read + Seek + Send,
+        F: FnMut(&ImportProgress) + Send,
+    {
+        let imports = ImportRepository::new(self.pool.clone());
+        imports.start(import_id, file_name).await?;
+
+        match self
+            .run(Some(import_id), &mut source, &mut on_progress)
+            .await
+        {
+            Ok((mut progress, outcome)) => {
+                imports.finish(import_id, &outcome.report).await?;
+                progress.phase = ImportPhase::Done;
+                on_progress(&progress);
+                Ok(outcome)
+            }
+            Err(err) => {
+                imports.mark_failed(import_id).await?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Sans `import_id`, rien n'est écrit : seuls le rapport et le diff sont calculés.
+    async fn run<R, F>(
+        &self,
+        import_id: Option<Uuid>,
+        source: &mut R,
+        on_progress: &mut F,
+    ) -> Result<(ImportProgress, ImportOutcome)>
+    where
+        R: Read + Seek + Send,
+        F: FnMut(&ImportProgress) + Send,
+    {
+        let imports = ImportRepository::new(self.pool.clone());
+        let importer = match import_id {
+            Some(import_id) => {
+                let (person_xrefs, family_xrefs) = imports.load_xrefs(import_id).await?;
+                GedcomImporter::with_ids(person_xrefs, family_xrefs)
+            }
+            None => GedcomImporter::new(),
+        };
+        let mut run = Run {
+            importer,
+            progress: ImportProgress::default(),
+            diff: ImportDiff::default(),
+            matched: HashMap::new(),
+            identifiers: Vec::new(),
+        };
+
+        self.index(&mut run, import_id, source, on_progress).await?;
+        self.persons(&mut run, import_id, source, on_progress)
+            .await?;
+        self.families(&mut run, import_id, source, on_progress)
+            .await?;
+        if let Some(import_id) = import_id {
+            self.edges(&mut run, import_id, on_progress).await?;
+        }
+
+        let Run {
+            importer,
+            progress,
+            diff,
+            ..
+        } = run;
+        let mut report = importer.data().report.clone();
+        report.persons = progress.persons;
+        report.families = progress.families;
+        Ok((progress, ImportOutcome { report, diff }))
+    }
+
+    /// Premier passage : identifiants, rapprochement des personnes et familles
+    /// existantes, sauvegarde des xrefs.
+    async fn index<R, F>(
+        &self,
+        run: &mut Run,
+        import_id: Option<Uuid>,
+        source: &mut R,
+        on_progress: &mut F,
+    ) -> Result<()>
+    where
+        R: Read + Seek + Send,
+        F: FnMut(&ImportProgress) + Send,
+    {
+        let batch_size = self.batch_size.max(1);
+        let mut batch = Vec::new();
+        let mut parents = Vec::new();
+        for record in records(source)? {
+            let record = record?;
+            run.importer.index(&record);
+            run.progress.records += 1;
+
+            match (record.tag.as_str(), record.xref.clone()) {
+                ("INDI", Some(xref)) => batch.push((xref, record_identifiers(&record))),
+                ("FAM", Some(xref)) => parents.push((
+                    xref,
+                    record
+                        .child("HUSB")
+                        .and_then(GedcomNode::pointer)
+                        .map(str::to_string),
+                    record
+                        .child("WIFE")
+                        .and_then(GedcomNode::pointer)
+                        .map(str::to_string),
+                )),
+                _ => {}
+            }
+            if batch.len() >= batch_size {
+                self.match_persons(run, import_id, &mut batch).await?;
+                on_progress(&run.progress);
+            }
+        }
+        self.match_persons(run, import_id, &mut batch).await?;
+        self.match_families(run, import_id, parents).await?;
+        run.importer.resolve_sources();
+        Ok(())
+    }
+
+    /// Rattache les `INDI` du lot aux personnes connues par un identifiant externe.
+    async fn match_persons(
+        &self,
+        run: &mut Run,
+        import_id: Option<Uuid>,
+        batch: &mut Vec<(String, Vec<(String, String)>)>,
+    ) -> Result<()> {
+        let wanted: Vec<(String, String)> = batch
+            .iter()
+            .flat_map(|(_, identifiers)| identifiers.iter().cloned())
+            .collect();
+        let known = ExternalIdRepository::new(self.pool.clone())
+            .find_persons(&wanted)
+            .await?;
+
+        let mut entries = Vec::with_capacity(batch.len());
+        for (xref, identifiers) in batch.drain(..) {
+            let existing = identifiers
+                .iter()
+                .find_map(|key| known.get(key).map(|person| (key, *person)));
+            if let Some(((kind, value), person)) = existing {
+                run.importer.reuse_person(&xref, person);
+                run.matched.insert(xref.clone(), format!("{kind} {value}"));
+            }
+            if let Some(person) = run.importer.data().person_xrefs.get(&xref) {
+                entries.push(XrefEntry {
+                    xref: xref.clone(),
+                    record_type: "INDI",
+                    entity_id: person.0,
+                });
+            }
+            run.identifiers.extend(
+                identifiers
+                    .into_iter()
+                    .map(|(kind, value)| (xref.clone(), kind, value)),
+            );
+        }
+
+        if let Some(import_id) = import_id {
+            ImportRepository::new(self.pool.clone())
+                .save_xrefs(import_id, &entries)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Rattache les `FAM` dont un parent existait déjà à la famille de mêmes parents.
+    async fn match_families(
+        &self,
+        run: &mut Run,
+        import_id: Option<Uuid>,
+        parents: Vec<(String, Option<String>, Option<String>)>,
+    ) -> Result<()> {
+        let data = run.importer.data();
+        let resolve = |xref: &Option<String>| {
+            xref.as_deref()
+                .and_then(|xref| data.person_xrefs.get(xref).copied())
+        };
+        let families: Vec<(String, Couple)> = parents
+            .iter()
+            .map(|(xref, father, mother)| (xref.clone(), (resolve(father), resolve(mother))))
+            .collect();
+        let candidates: Vec<Couple> = parents
+            .iter()
+            .zip(&families)
+            .filter(|((_, father, mother), _)| {
+                [father, mother]
+                    .into_iter()
+                    .flatten()
+                    .any(|xref| run.matched.contains_key(xref))
+            })
+            .map(|(_, (_, couple))| *couple)
+            .collect();
+
+        let mut known = HashMap::new();
+        for chunk in candidates.chunks(self.batch_size.max(1)) {
+            known.extend(
+                FamilyRepository::new(self.pool.clone())
+                    .find_by_parents(chunk)
+                    .await?,
+            );
+        }
+
+        let mut entries = Vec::with_capacity(families.len());
+        for (xref, couple) in families {
+            if let Some(family) = known.get(&couple) {
+                run.importer.reuse_family(&xref, *family);
+                run.diff.families_matched += 1;
+            }
+            if let Some(family) = run.importer.data().family_xrefs.get(&xref) {
+                entries.push(XrefEntry {
+                    xref,
+                    record_type: "FAM",
+                    entity_id: family.0,
+                });
+            }
+        }
+
+        if let Some(import_id) = import_id {
+            let imports = ImportRepository::new(self.pool.clone());
+            for chunk in entries.chunks(self.batch_size.max(1)) {
+                imports.save_xrefs(import_id, chunk).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deuxième passage : création des nouvelles personnes, mise à jour des autres.
+    async fn persons<R, F>(
+        &self,
+        run: &mut Run,
+        import_id: Option<Uuid>,
+        source: &mut R,
+        on_progress: &mut F,
+    ) -> Result<()>
+    where
+        R: Read + Seek + Send,
+        F: FnMut(&ImportProgress) + Send,
+    {
+        let batch_size = self.batch_size.max(1);
+        run.progress.phase = ImportPhase::Persons;
+        on_progress(&run.progress);
+
+        let mut batch = Vec::with_capacity(batch_size);
+        for record in records(source)? {
+            let record = record?;
+            if record.tag != "INDI" {
+                continue;
+            }
+            let person = run.importer.person(&record);
+            batch.push((record.xref.clone(), person, record.child("RESN").is_some()));
+            if batch.len() >= batch_size {
+                self.write_persons(run, import_id.is_some(), &mut batch)
+                    .await?;
+                on_progress(&run.progress);
+            }
+        }
+        self.write_persons(run, import_id.is_some(), &mut batch)
+            .await?;
+
+        if import_id.is_some() {
+            let person_xrefs = &run.importer.data().person_xrefs;
+            let links: Vec<(String, String, PersonId)> = run
+                .identifiers
+                .drain(..)
+                .filter_map(|(xref, kind, value)| {
+                    person_xrefs.get(&xref).map(|person| (kind, value, *person))
+                })
+                .collect();
+            ExternalIdRepository::new(self.pool.clone())
+                .link(&links)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn write_persons(
+        &self,
+        run: &mut Run,
+        commit: bool,
+        batch: &mut Vec<(Option<String>, Person, bool)>,
+    ) -> Result<()> {
+        let repository = PersonRepository::new(self.pool.clone());
+        let matched_ids: Vec<PersonId> = batch
+            .iter()
+            .filter(|(xref, _, _)| {
+                xref.as_ref()
+                    .is_some_and(|xref| run.matched.contains_key(xref))
+            })
+            .map(|(_, person, _)| person.id)
+            .collect();
+        let existing: HashMap<PersonId, Person> = repository
+            .find_many(&matched_ids)
+            .await?
+            .into_iter()
+            .map(|person| (person.id, person))
+            .collect();
+
+        let mut created = Vec::new();
+        let mut updated = Vec::new();
+        for (xref, mut person, has_resn) in batch.drain(..) {
+            let xref = xref.unwrap_or_default();
+            match existing.get(&person.id) {
+                Some(before) => {
+                    // Sans `RESN`, le fichier ne dit rien de la visibilité choisie en base.
+                    if !has_resn {
+                        person.public = before.public;
+                    }
+                    keep_source_ids(before, &mut person);
+                    let changes = person_changes(before, &person);
+                    if changes.is_empty() {
+                        run.diff.unchanged += 1;
+                        continue;
+                    }
+                    run.diff.updated.push(PersonDiff {
+                        matched_by: run.matched.get(&xref).cloned().unwrap_or_default(),
+                        xref,
+                        person_id: person.id,
+                        changes,
+                    });
+                    updated.push(person);
+                }
+                None => {
+                    run.diff.created.push(CreatedPerson {
+                        xref,
+                        person_id: person.id,
+                        name: format!("{} {}", person.first_name, person.surname)
+                            .trim()
+                            .to_string(),
+                    });
+                    created.push(person);
+                }
+            }
+        }
+
+        if commit {
+            repository.create_batch(&created).await?;
+            repository.update_batch(&updated).await?;
+        }
+        run.progress.persons += created.len() + updated.len();
+        Ok(())
+    }
+
+    /// Troisième passage : familles et enfants (les familles existantes gardent
+    /// leurs données, seuls les enfants manquants sont ajoutés).
+    async fn families<R, F>(
+        &self,
+        run: &mut Run,
+        import_id: Option<Uuid>,
+        source: &mut R,
+        on_progress: &mut F,
+    ) -> Result<()>
+    where
+        R: Read + Seek + Send,
+        F: FnMut(&ImportProgress) + Send,
+    {
+        let batch_size = self.batch_size.max(1);
+        let repository = FamilyRepository::new(self.pool.clone());
+        run.progress.phase = ImportPhase::Families;
+        on_progress(&run.progress);
+
+        let mut batch = Vec::with_capacity(batch_size);
+        for record in records(source)? {
+            let record = record?;
+            if record.tag != "FAM" {
+                continue;
+            }
+            batch.push(draft(run.importer.family(&record)));
+            if batch.len() >= batch_size {
+                if import_id.is_some() {
+                    repository.create_batch(&batch).await?;
+                }
+                run.progress.families += batch.len();
+                batch.clear();
+                on_progress(&run.progress);
+            }
+        }
+        if import_id.is_some() {
+            repository.create_batch(&batch).await?;
+        }
+        run.progress.families += batch.len();
+
+        run.diff.families_created = run
+            .progress
+            .families
+            .saturating_sub(run.diff.families_matched);
+        Ok(())
+    }
+
+    /// Dernier passage : arêtes `CHILD_OF`, relues depuis `gedcom_xrefs`.
+    async fn edges<F>(&self, run: &mut Run, import_id: Uuid, on_progress: &mut F) -> Result<()>
+    where
+        F: FnMut(&ImportProgress) + Send,
+    {
+        let imports = ImportRepository::new(self.pool.clone());
+        let families = FamilyRepository::new(self.pool.clone());
+        run.progress.phase = ImportPhase::Edges;
+        on_progress(&run.progress);
+
+        let mut after = None;
+        loop {
+            let links = imports
+                .family_links(import_id, after, self.batch_size.max(1) as i64)
+                .await?;
+            let Some(last) = links.last() else {
+                break;
+            };
+            after = Some((last.family_id, last.child));
+
+            let edges: Vec<(PersonId, PersonId)> = links
+                .iter()
+                .flat_map(|link| {
+                    link.father
+                        .into_iter()
+                        .chain(link.mother)
+                        .map(|parent| (link.child, parent))
+                })
+                .collect();
+            families.link_edges(&edges).await?;
+            run.progress.edges += edges.len();
+            on_progress(&run.progress);
+        }
+        Ok(())
+    }
+}
+
+/// Relit le fichier depuis le début, en détectant son encodage.
+fn records<R: Read + Seek>(
+    source: &mut R,
+) -> Result<GedcomReader<DecodingReader<BufReader<&mut R>>>> {
+    source.seek(SeekFrom::Start(0))?;
+    Ok(GedcomReader::new(DecodingReader::new(BufReader::new(
+        source,
+    ))?))
+}
+
+/// Reprend l'identifiant des sources déjà citées par la personne : sans `SOUR`
+/// rapproché, chaque import leur en attribue un nouveau.
+fn keep_source_ids(before: &Person, person: &mut Person) {
+    let known: Vec<&Source> = person_sources(before).collect();
+    for source in person_sources_mut(person) {
+        let same = known.iter().find(|known| {
+            Source {
+                id: source.id,
+                page: source.page.clone(),
+                ..(**known).clone()
+            } == *source
+        });
+        if let Some(known) = same {
+            source.id = known.id;
+        }
+    }
+}
+
+fn person_sources(person: &Person) -> impl Iterator<Item = &Source> {
+    person.sources.iter().chain(
+        person
+            .birth
+            .iter()
+            .chain(&person.death)
+            .chain(&person.other_events)
+            .flat_map(|event| &event.sources),
+    )
+}
+
+fn person_sources_mut(person: &mut Person) -> impl Iterator<Item = &mut Source> {
+    person.sources.iter_mut().chain(
+        person
+            .birth
+            .iter_mut()
+            .chain(&mut person.death)
+            .chain(&mut person.other_events)
+            .flat_map(|event| &mut event.sources),
+    )
+}
+
+fn draft(family: Family) -> FamilyDraft {
+    FamilyDraft {
+        id: family.id,
+        father_id: family.father,
+        mother_id: family.mother,
+        children: family.children,
+        marriage: family.marriage,
+        divorce: family.divorce,
+        other_events: family.other_events,
+        notes: family.notes,
+        sources: family.sources,
+        public: family.public,
+        created_by: None,
+    }
+}
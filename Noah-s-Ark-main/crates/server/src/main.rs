@@ -1,4 +1,6 @@
 use std::env;
+use std::marker::PhantomData;
+use std::time::Duration;
 
 use anyhow::Result;
 use async_graphql::{
@@ -7,28 +9,39 @@ use async_graphql::{
 };
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 use axum::{
-    extract::{Path, Query, State},
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::StatusCode,
     response::Html,
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use database::{
-    DatabasePool, FamilyChanges, FamilyDraft, FamilyEventNew, FamilyEventRecord, FamilyRepository,
-    PersonRepository, PersonUpdate,
+    diff_revisions, DatabasePool, FamilyChanges, FamilyDraft, FamilyEventNew, FamilyEventRecord,
+    FamilyStore, FieldChange, GraphRepository, GraphStore, ImportRepository, MemoryStore,
+    OutboxDispatcher, PersonEventRecord, PersonStore, PersonUpdate, Revision, SqliteStore, Storage,
+    Trashed,
 };
 use genealogy_core::consanguinity::ConsanguinityCalculator;
-use genealogy_types::{Family, FamilyId, Person, PersonId, Sex, WizardId};
-use chrono::{NaiveDate, Utc};
-use serde::Deserialize;
+use genealogy_types::{
+    DateLocale, EventType, Family, FamilyId, ImportReport, LifeEvent, Person, PersonId, Place,
+    PreciseDate, Sex, Source, WizardId,
+};
+use importer::{GedcomStreamImporter, ImportDiff, ImportFormat, ImportOutcome};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use tokio::signal;
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use uuid::Uuid;
 
+/// Taille maximale d'un fichier envoyé à `/api/imports`.
+const IMPORT_BODY_LIMIT: usize = 512 * 1024 * 1024;
+
 #[derive(Clone)]
-struct AppState {
-    db: DatabasePool,
-    schema: Schema<QueryRoot, MutationRoot, EmptySubscription>,
+struct AppState<S: Storage> {
+    storage: S,
+    schema: GenealogySchema<S>,
 }
 
 #[tokio::main]
@@ -38,40 +51,134 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let db = DatabasePool::new(
-        &env::var("DATABASE_URL").unwrap_or_else(|_| "postgres://localhost/geneweb".into()),
-        &env::var("NEO4J_URL").unwrap_or_else(|_| "neo4j://localhost:7687".into()),
-        &env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".into()),
-    )
-    .await?;
+    let database_url =
+        env::var("DATABASE_URL").unwrap_or_else(|_| "postgres://localhost/geneweb".into());
+    let app = if database_url.starts_with("memory:") {
+        tracing::warn!("stockage en mémoire : les données sont perdues à l'arrêt");
+        api_routes(MemoryStore::new())
+    } else if database_url.starts_with("sqlite:") {
+        let redis_url = env::var("REDIS_URL").ok();
+        api_routes(SqliteStore::connect(&database_url, redis_url.as_deref()).await?)
+    } else {
+        let db = DatabasePool::new(
+            &database_url,
+            env::var("NEO4J_URL").ok().as_deref(),
+            &env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".into()),
+        )
+        .await?;
+        if let Some(secs) = env::var("GRAPH_RECONCILE_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+        {
+            spawn_graph_reconciliation(db.clone(), Duration::from_secs(secs));
+        }
+        let outbox_secs = env::var("OUTBOX_POLL_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(5);
+        spawn_outbox_dispatcher(db.clone(), Duration::from_secs(outbox_secs));
+        api_routes(db.clone()).merge(import_routes(db))
+    };
+    let app = app.layer(CorsLayer::permissive());
+
+    let addr = "0.0.0.0:3000";
+    tracing::info!("🚀 GeneWeb Rust server listening on {addr}");
 
-    let schema = Schema::build(QueryRoot, MutationRoot, EmptySubscription)
-        .data(db.clone())
-        .finish();
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    Ok(())
+}
 
-    let state = AppState { db: db.clone(), schema };
+/// GraphQL et lecture des personnes, pour n'importe quel backend.
+fn api_routes<S: Storage>(storage: S) -> Router {
+    let schema = build_schema(storage.clone());
+    let state = AppState { storage, schema };
 
-    let app = Router::new()
+    Router::new()
         .route("/", get(index))
         .route(
             "/api/graphql",
-            get(graphql_handler).post(graphql_handler),
+            get(graphql_handler::<S>).post(graphql_handler::<S>),
         )
-        .route("/api/persons/:id", get(get_person))
-        .route("/api/search", get(search_persons))
+        .route("/api/persons/{id}", get(get_person::<S>))
+        .route("/api/search", get(search_persons::<S>))
         .route("/playground", get(graphql_playground))
         .with_state(state)
-        .layer(CorsLayer::permissive());
+}
 
-    let addr = "0.0.0.0:3000";
-    tracing::info!("🚀 GeneWeb Rust server listening on {addr}");
+fn build_schema<S: Storage>(storage: S) -> GenealogySchema<S> {
+    Schema::build(
+        QueryRoot::<S>(PhantomData),
+        MutationRoot::<S>(PhantomData),
+        EmptySubscription,
+    )
+    .data(storage)
+    .finish()
+}
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+/// Imports GEDCOM, écrits directement dans PostgreSQL.
+fn import_routes(db: DatabasePool) -> Router {
+    Router::new()
+        .route(
+            "/api/imports",
+            post(import_file).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/api/imports/preview",
+            post(preview_import).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route(
+            "/api/imports/check",
+            post(check_import).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route("/api/imports/{id}/report", get(get_import_report))
+        .with_state(db)
+}
 
-    Ok(())
+/// Réconcilie périodiquement les arêtes `CHILD_OF` de Neo4j avec PostgreSQL.
+fn spawn_graph_reconciliation(db: DatabasePool, every: Duration) {
+    if db.neo4j.is_none() {
+        return;
+    }
+    tokio::spawn(async move {
+        let graph = GraphRepository::new(db);
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            match graph.reconcile(true).await {
+                Ok(report) if !report.is_consistent() => tracing::warn!(
+                    "graphe réconcilié : {} arêtes CHILD_OF créées, {} supprimées",
+                    report.missing.len(),
+                    report.stale.len()
+                ),
+                Ok(_) => {}
+                Err(err) => tracing::error!("réconciliation du graphe impossible : {err:#}"),
+            }
+        }
+    });
+}
+
+/// Rejoue périodiquement les effets Neo4j et Redis restés en attente dans `outbox`.
+fn spawn_outbox_dispatcher(db: DatabasePool, every: Duration) {
+    tokio::spawn(async move {
+        let dispatcher = OutboxDispatcher::new(db);
+        let mut ticker = tokio::time::interval(every);
+        loop {
+            ticker.tick().await;
+            match dispatcher.dispatch_pending().await {
+                Ok(report) if report.failed > 0 => tracing::warn!(
+                    "outbox : {} événements appliqués, {} en échec",
+                    report.delivered,
+                    report.failed
+                ),
+                Ok(_) => {}
+                Err(err) => tracing::error!("outbox inaccessible : {err:#}"),
+            }
+        }
+    });
 }
 
 async fn shutdown_signal() {
@@ -102,7 +209,10 @@ async fn index() -> &'static str {
     "Genealogy API prête - consultez /api/graphql"
 }
 
-async fn graphql_handler(State(state): State<AppState>, req: GraphQLRequest) -> GraphQLResponse {
+async fn graphql_handler<S: Storage>(
+    State(state): State<AppState<S>>,
+    req: GraphQLRequest,
+) -> GraphQLResponse {
     state.schema.execute(req.into_inner()).await.into()
 }
 
@@ -113,8 +223,11 @@ async fn graphql_playground() -> Html<String> {
     ))
 }
 
-async fn get_person(State(state): State<AppState>, Path(id): Path<Uuid>) -> Json<Option<Person>> {
-    let repo = PersonRepository::new(state.db.clone());
+async fn get_person<S: Storage>(
+    State(state): State<AppState<S>>,
+    Path(id): Path<Uuid>,
+) -> Json<Option<Person>> {
+    let repo = state.storage.persons();
     let person = repo.find_by_id(PersonId(id)).await.ok().flatten();
     Json(person)
 }
@@ -125,11 +238,11 @@ struct SearchQuery {
     first_name: String,
 }
 
-async fn search_persons(
-    State(state): State<AppState>,
+async fn search_persons<S: Storage>(
+    State(state): State<AppState<S>>,
     Query(query): Query<SearchQuery>,
 ) -> Json<Vec<Person>> {
-    let repo = PersonRepository::new(state.db.clone());
+    let repo = state.storage.persons();
     let persons = repo
         .search_by_name(&query.surname, &query.first_name)
         .await
@@ -137,57 +250,229 @@ async fn search_persons(
     Json(persons)
 }
 
-pub struct QueryRoot;
+#[derive(Debug, Deserialize)]
+struct ImportQuery {
+    /// `gedcom` ou `gw` ; à défaut, déduit de `file_name`.
+    format: Option<String>,
+    file_name: Option<String>,
+}
+
+impl ImportQuery {
+    fn format(&self) -> Result<ImportFormat, (StatusCode, String)> {
+        match self.format.as_deref() {
+            Some("gedcom") => Ok(ImportFormat::Gedcom),
+            Some("gw") => Ok(ImportFormat::Gw),
+            Some(other) => Err((
+                StatusCode::BAD_REQUEST,
+                format!("format inconnu `{other}`"),
+            )),
+            None => Ok(self
+                .file_name
+                .as_deref()
+                .and_then(ImportFormat::from_path)
+                .unwrap_or(ImportFormat::Gedcom)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ImportResult {
+    import_id: Uuid,
+    report: ImportReport,
+    diff: ImportDiff,
+}
+
+fn require_gedcom(query: &ImportQuery) -> Result<(), (StatusCode, String)> {
+    if query.format()? != ImportFormat::Gedcom {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "seuls les fichiers GEDCOM peuvent être importés en base".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Importe un fichier GEDCOM envoyé dans le corps de la requête.
+async fn import_file(
+    State(db): State<DatabasePool>,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<Json<ImportResult>, (StatusCode, String)> {
+    require_gedcom(&query)?;
+    let import_id = Uuid::new_v4();
+    let ImportOutcome { report, diff } = GedcomStreamImporter::new(db)
+        .import(
+            import_id,
+            query.file_name.as_deref(),
+            std::io::Cursor::new(body),
+            |progress| tracing::debug!(?progress, "import {import_id}"),
+        )
+        .await
+        .map_err(|err| (StatusCode::UNPROCESSABLE_ENTITY, err.to_string()))?;
+    Ok(Json(ImportResult {
+        import_id,
+        report,
+        diff,
+    }))
+}
+
+/// Personnes créées ou modifiées par l'import d'un GEDCOM, sans écriture en base.
+async fn preview_import(
+    State(db): State<DatabasePool>,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<Json<ImportOutcome>, (StatusCode, String)> {
+    require_gedcom(&query)?;
+    GedcomStreamImporter::new(db)
+        .preview(std::io::Cursor::new(body))
+        .await
+        .map(Json)
+        .map_err(|err| (StatusCode::UNPROCESSABLE_ENTITY, err.to_string()))
+}
+
+/// Rapport d'import d'un GEDCOM ou d'un `.gw`, sans écriture en base.
+async fn check_import(
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<Json<ImportReport>, (StatusCode, String)> {
+    let format = query.format()?;
+    importer::check_import(body.as_ref(), format)
+        .map(Json)
+        .map_err(|err| (StatusCode::UNPROCESSABLE_ENTITY, err.to_string()))
+}
+
+async fn get_import_report(
+    State(db): State<DatabasePool>,
+    Path(id): Path<Uuid>,
+) -> Json<Option<ImportReport>> {
+    let repo = ImportRepository::new(db);
+    Json(repo.report(id).await.ok().flatten())
+}
+
+pub struct QueryRoot<S>(PhantomData<S>);
 
 #[Object]
-impl QueryRoot {
+impl<S: Storage> QueryRoot<S> {
     async fn person(&self, ctx: &Context<'_>, id: ID) -> async_graphql::Result<Option<PersonPayload>> {
         let uuid = parse_uuid(&id)?;
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let repo = PersonRepository::new(db);
+        let repo = ctx.data::<S>()?.persons();
         Ok(repo
             .find_by_id(PersonId(uuid))
             .await?
             .map(PersonPayload::from))
     }
 
+    /// Événements d'une personne dans l'ordre chronologique, dates dans `locale`.
+    async fn person_events(
+        &self,
+        ctx: &Context<'_>,
+        person_id: ID,
+        locale: Option<String>,
+    ) -> async_graphql::Result<Vec<PersonEventPayload>> {
+        let locale = parse_locale(locale.as_deref())?;
+        let repo = ctx.data::<S>()?.persons();
+        let events = repo.events(PersonId(parse_uuid(&person_id)?)).await?;
+        Ok(events
+            .into_iter()
+            .map(|record| PersonEventPayload::new(record, locale))
+            .collect())
+    }
+
     async fn search_persons(
         &self,
         ctx: &Context<'_>,
         surname: String,
         first_name: String,
     ) -> async_graphql::Result<Vec<PersonPayload>> {
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let repo = PersonRepository::new(db);
+        let repo = ctx.data::<S>()?.persons();
         let persons = repo.search_by_name(&surname, &first_name).await?;
         Ok(persons.into_iter().map(PersonPayload::from).collect())
     }
 
+    /// Personnes à la corbeille, les plus récemment supprimées d'abord.
+    async fn trashed_persons(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<TrashedPersonPayload>> {
+        let repo = ctx.data::<S>()?.persons();
+        let trash = repo.trash().await?;
+        Ok(trash.into_iter().map(TrashedPersonPayload::from).collect())
+    }
+
+    /// Familles à la corbeille, les plus récemment supprimées d'abord.
+    async fn trashed_families(
+        &self,
+        ctx: &Context<'_>,
+    ) -> async_graphql::Result<Vec<TrashedFamilyPayload>> {
+        let repo = ctx.data::<S>()?.families();
+        let trash = repo.trash().await?;
+        Ok(trash.into_iter().map(TrashedFamilyPayload::from).collect())
+    }
+
+    /// Révisions de la personne, de la plus ancienne à la plus récente.
+    async fn person_history(
+        &self,
+        ctx: &Context<'_>,
+        person_id: ID,
+    ) -> async_graphql::Result<Vec<RevisionPayload>> {
+        let repo = ctx.data::<S>()?.persons();
+        let history = repo.history(PersonId(parse_uuid(&person_id)?)).await?;
+        Ok(history.into_iter().map(RevisionPayload::from).collect())
+    }
+
+    /// Révisions de la famille, de la plus ancienne à la plus récente.
+    async fn family_history(
+        &self,
+        ctx: &Context<'_>,
+        family_id: ID,
+    ) -> async_graphql::Result<Vec<RevisionPayload>> {
+        let repo = ctx.data::<S>()?.families();
+        let history = repo.history(FamilyId(parse_uuid(&family_id)?)).await?;
+        Ok(history.into_iter().map(RevisionPayload::from).collect())
+    }
+
+    /// Champs de la personne modifiés entre deux de ses révisions.
+    async fn person_revision_diff(
+        &self,
+        ctx: &Context<'_>,
+        person_id: ID,
+        from: ID,
+        to: ID,
+    ) -> async_graphql::Result<Vec<FieldChangePayload>> {
+        let repo = ctx.data::<S>()?.persons();
+        let history = repo.history(PersonId(parse_uuid(&person_id)?)).await?;
+        revision_diff(&history, &from, &to)
+    }
+
+    /// Champs de la famille modifiés entre deux de ses révisions.
+    async fn family_revision_diff(
+        &self,
+        ctx: &Context<'_>,
+        family_id: ID,
+        from: ID,
+        to: ID,
+    ) -> async_graphql::Result<Vec<FieldChangePayload>> {
+        let repo = ctx.data::<S>()?.families();
+        let history = repo.history(FamilyId(parse_uuid(&family_id)?)).await?;
+        revision_diff(&history, &from, &to)
+    }
+
     async fn ancestors(
         &self,
         ctx: &Context<'_>,
         person_id: ID,
         generations: u32,
     ) -> async_graphql::Result<Vec<PersonPayload>> {
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let uuid = parse_uuid(&person_id)?;
-        let query = neo4rs::query(
-            "MATCH (p:Person {id: $id})-[:CHILD_OF*1..$gen]->(ancestor:Person) RETURN ancestor.id as id",
-        )
-        .param("id", uuid.to_string())
-        .param("gen", generations as i64);
-
-        let mut result = db.neo4j.execute(query).await?;
-        let mut ids = Vec::new();
-        while let Some(row) = result.next().await? {
-            let id: String = row.get("id")?;
-            ids.push(Uuid::parse_str(&id)?);
-        }
+        let storage = ctx.data::<S>()?;
+        let ids = storage
+            .graph()
+            .ancestors(PersonId(parse_uuid(&person_id)?), generations)
+            .await?;
 
-        let repo = PersonRepository::new(db);
+        let repo = storage.persons();
         let mut ancestors = Vec::new();
         for id in ids {
-            if let Some(person) = repo.find_by_id(PersonId(id)).await? {
+            if let Some(person) = repo.find_by_id(id).await? {
                 ancestors.push(PersonPayload::from(person));
             }
         }
@@ -200,8 +485,7 @@ impl QueryRoot {
         person_id: ID,
     ) -> async_graphql::Result<f64> {
         let uuid = parse_uuid(&person_id)?;
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let mut calculator = ConsanguinityCalculator::new(db);
+        let mut calculator = ConsanguinityCalculator::new(ctx.data::<S>()?.graph());
         Ok(calculator.calculate(PersonId(uuid)).await?)
     }
 
@@ -213,39 +497,30 @@ impl QueryRoot {
     ) -> async_graphql::Result<Option<RelationshipPayload>> {
         let uuid1 = parse_uuid(&person1_id)?;
         let uuid2 = parse_uuid(&person2_id)?;
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let query = neo4rs::query(
-            "MATCH path = shortestPath((p1:Person {id: $id1})-[*]-(p2:Person {id: $id2})) RETURN length(path) as distance",
-        )
-        .param("id1", uuid1.to_string())
-        .param("id2", uuid2.to_string());
-
-        let mut result = db.neo4j.execute(query).await?;
-        if let Some(row) = result.next().await? {
-            let distance: i64 = row.get("distance")?;
-            Ok(Some(RelationshipPayload {
-                person1: person1_id,
-                person2: person2_id,
-                degree: distance as u32,
-                description: format_relationship(distance as u32),
-            }))
-        } else {
-            Ok(None)
-        }
+        let distance = ctx
+            .data::<S>()?
+            .graph()
+            .distance(PersonId(uuid1), PersonId(uuid2))
+            .await?;
+        Ok(distance.map(|distance| RelationshipPayload {
+            person1: person1_id,
+            person2: person2_id,
+            degree: distance,
+            description: format_relationship(distance),
+        }))
     }
 }
 
-pub struct MutationRoot;
+pub struct MutationRoot<S>(PhantomData<S>);
 
 #[Object]
-impl MutationRoot {
+impl<S: Storage> MutationRoot<S> {
     async fn create_person(
         &self,
         ctx: &Context<'_>,
         input: CreatePersonInput,
     ) -> async_graphql::Result<PersonPayload> {
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let repo = PersonRepository::new(db.clone());
+        let repo = ctx.data::<S>()?.persons();
         let now = Utc::now().naive_utc();
         let CreatePersonInput {
             first_name,
@@ -288,12 +563,12 @@ impl MutationRoot {
         id: ID,
         input: UpdatePersonInput,
     ) -> async_graphql::Result<PersonPayload> {
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let repo = PersonRepository::new(db);
+        let repo = ctx.data::<S>()?.persons();
         let UpdatePersonInput {
             first_name,
             surname,
             surname_prefix,
+            nicknames,
             sex,
             notes,
             public,
@@ -303,10 +578,12 @@ impl MutationRoot {
             first_name,
             surname,
             surname_prefix,
+            nicknames,
             sex: sex.map(Into::into),
             notes,
             public,
             updated_by: parse_optional_wizard_id(wizard_id)?,
+            ..Default::default()
         };
 
         let updated = repo
@@ -324,19 +601,14 @@ impl MutationRoot {
         public: bool,
         wizard_id: Option<ID>,
     ) -> async_graphql::Result<PersonPayload> {
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let repo = PersonRepository::new(db);
+        let repo = ctx.data::<S>()?.persons();
         let updated = repo
             .update(
                 PersonId(parse_uuid(&id)?),
                 PersonUpdate {
-                    first_name: None,
-                    surname: None,
-                    surname_prefix: None,
-                    sex: None,
-                    notes: None,
                     public: Some(public),
                     updated_by: parse_optional_wizard_id(wizard_id)?,
+                    ..Default::default()
                 },
             )
             .await?
@@ -344,13 +616,117 @@ impl MutationRoot {
         Ok(updated.into())
     }
 
+    async fn add_person_event(
+        &self,
+        ctx: &Context<'_>,
+        person_id: ID,
+        event: LifeEventInput,
+        wizard_id: Option<ID>,
+    ) -> async_graphql::Result<PersonEventPayload> {
+        let repo = ctx.data::<S>()?.persons();
+        let (event, locale) = event.into_event()?;
+        let record = repo
+            .add_event(
+                PersonId(parse_uuid(&person_id)?),
+                event,
+                parse_optional_wizard_id(wizard_id)?,
+            )
+            .await?
+            .ok_or_else(|| async_graphql::Error::new("Personne introuvable"))?;
+        Ok(PersonEventPayload::new(record, locale))
+    }
+
+    async fn update_person_event(
+        &self,
+        ctx: &Context<'_>,
+        event_id: ID,
+        event: LifeEventInput,
+        wizard_id: Option<ID>,
+    ) -> async_graphql::Result<PersonEventPayload> {
+        let repo = ctx.data::<S>()?.persons();
+        let (event, locale) = event.into_event()?;
+        let record = repo
+            .update_event(
+                parse_uuid(&event_id)?,
+                event,
+                parse_optional_wizard_id(wizard_id)?,
+            )
+            .await?
+            .ok_or_else(|| async_graphql::Error::new("Événement introuvable"))?;
+        Ok(PersonEventPayload::new(record, locale))
+    }
+
+    async fn delete_person_event(
+        &self,
+        ctx: &Context<'_>,
+        event_id: ID,
+        wizard_id: Option<ID>,
+    ) -> async_graphql::Result<bool> {
+        let repo = ctx.data::<S>()?.persons();
+        Ok(repo
+            .delete_event(parse_uuid(&event_id)?, parse_optional_wizard_id(wizard_id)?)
+            .await?)
+    }
+
+    /// Met la personne à la corbeille ; refuse pour un parent, sauf avec `force`.
+    async fn delete_person(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        force: Option<bool>,
+        deleted_by: Option<ID>,
+    ) -> async_graphql::Result<bool> {
+        let repo = ctx.data::<S>()?.persons();
+        Ok(repo
+            .delete(
+                PersonId(parse_uuid(&id)?),
+                force.unwrap_or(false),
+                parse_optional_wizard_id(deleted_by)?,
+            )
+            .await?)
+    }
+
+    async fn restore_person(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        restored_by: Option<ID>,
+    ) -> async_graphql::Result<bool> {
+        let repo = ctx.data::<S>()?.persons();
+        Ok(repo
+            .restore(
+                PersonId(parse_uuid(&id)?),
+                parse_optional_wizard_id(restored_by)?,
+            )
+            .await?)
+    }
+
+    /// Ramène la personne à l'état laissé par une révision de son historique.
+    async fn revert_person(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        revision_id: ID,
+        wizard_id: Option<ID>,
+    ) -> async_graphql::Result<PersonPayload> {
+        let repo = ctx.data::<S>()?.persons();
+        let person = repo
+            .revert(
+                PersonId(parse_uuid(&id)?),
+                parse_revision_id(&revision_id)?,
+                parse_optional_wizard_id(wizard_id)?,
+            )
+            .await?
+            .ok_or_else(|| async_graphql::Error::new("Personne introuvable"))?;
+        Ok(person.into())
+    }
+
     async fn create_family(
         &self,
         ctx: &Context<'_>,
         input: CreateFamilyInput,
     ) -> async_graphql::Result<FamilyPayload> {
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let repo = FamilyRepository::new(db);
+        let repo = ctx.data::<S>()?.families();
         let children = ids_to_person_ids(input.child_ids)?;
         let family = repo
             .create(FamilyDraft {
@@ -358,8 +734,13 @@ impl MutationRoot {
                 father_id: parse_optional_person_id(input.father_id)?,
                 mother_id: parse_optional_person_id(input.mother_id)?,
                 children,
+                marriage: None,
+                divorce: None,
+                other_events: Vec::new(),
                 notes: input.notes,
+                sources: Vec::new(),
                 public: input.public.unwrap_or(true),
+                created_by: parse_optional_wizard_id(input.wizard_id)?,
             })
             .await?;
         Ok(family.into())
@@ -371,8 +752,7 @@ impl MutationRoot {
         id: ID,
         input: UpdateFamilyInput,
     ) -> async_graphql::Result<FamilyPayload> {
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let repo = FamilyRepository::new(db);
+        let repo = ctx.data::<S>()?.families();
         let child_ids = match input.child_ids {
             Some(ids) => Some(ids_to_person_ids(ids)?),
             None => None,
@@ -386,6 +766,8 @@ impl MutationRoot {
                     children: child_ids,
                     notes: input.notes,
                     public: input.public,
+                    updated_by: parse_optional_wizard_id(input.wizard_id)?,
+                    ..Default::default()
                 },
             )
             .await?;
@@ -401,13 +783,14 @@ impl MutationRoot {
         ctx: &Context<'_>,
         family_id: ID,
         child_id: ID,
+        wizard_id: Option<ID>,
     ) -> async_graphql::Result<FamilyPayload> {
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let repo = FamilyRepository::new(db);
+        let repo = ctx.data::<S>()?.families();
         let family = repo
             .append_child(
                 FamilyId(parse_uuid(&family_id)?),
                 PersonId(parse_uuid(&child_id)?),
+                parse_optional_wizard_id(wizard_id)?,
             )
             .await?;
         Ok(family.into())
@@ -418,28 +801,86 @@ impl MutationRoot {
         ctx: &Context<'_>,
         family_id: ID,
         child_id: ID,
+        wizard_id: Option<ID>,
     ) -> async_graphql::Result<FamilyPayload> {
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let repo = FamilyRepository::new(db);
+        let repo = ctx.data::<S>()?.families();
         let family = repo
             .remove_child(
                 FamilyId(parse_uuid(&family_id)?),
                 PersonId(parse_uuid(&child_id)?),
+                parse_optional_wizard_id(wizard_id)?,
             )
             .await?;
         Ok(family.into())
     }
 
+    /// Met la famille à la corbeille ; refuse si elle a des enfants, sauf avec `force`.
+    async fn delete_family(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        force: Option<bool>,
+        deleted_by: Option<ID>,
+    ) -> async_graphql::Result<bool> {
+        let repo = ctx.data::<S>()?.families();
+        Ok(repo
+            .delete(
+                FamilyId(parse_uuid(&id)?),
+                force.unwrap_or(false),
+                parse_optional_wizard_id(deleted_by)?,
+            )
+            .await?)
+    }
+
+    async fn restore_family(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        restored_by: Option<ID>,
+    ) -> async_graphql::Result<bool> {
+        let repo = ctx.data::<S>()?.families();
+        Ok(repo
+            .restore(
+                FamilyId(parse_uuid(&id)?),
+                parse_optional_wizard_id(restored_by)?,
+            )
+            .await?)
+    }
+
+    /// Ramène la famille à l'état laissé par une révision de son historique.
+    async fn revert_family(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        revision_id: ID,
+        wizard_id: Option<ID>,
+    ) -> async_graphql::Result<FamilyPayload> {
+        let repo = ctx.data::<S>()?.families();
+        let family = repo
+            .revert(
+                FamilyId(parse_uuid(&id)?),
+                parse_revision_id(&revision_id)?,
+                parse_optional_wizard_id(wizard_id)?,
+            )
+            .await?
+            .ok_or_else(|| async_graphql::Error::new("Famille introuvable"))?;
+        Ok(family.into())
+    }
+
     async fn set_family_privacy(
         &self,
         ctx: &Context<'_>,
         family_id: ID,
         public: bool,
+        wizard_id: Option<ID>,
     ) -> async_graphql::Result<FamilyPayload> {
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let repo = FamilyRepository::new(db);
+        let repo = ctx.data::<S>()?.families();
         let family = repo
-            .set_privacy(FamilyId(parse_uuid(&family_id)?), public)
+            .set_privacy(
+                FamilyId(parse_uuid(&family_id)?),
+                public,
+                parse_optional_wizard_id(wizard_id)?,
+            )
             .await?;
         Ok(family.into())
     }
@@ -450,17 +891,18 @@ impl MutationRoot {
         family_id: ID,
         event: FamilyEventInput,
     ) -> async_graphql::Result<FamilyEventPayload> {
-        let db = ctx.data::<DatabasePool>()?.clone();
-        let repo = FamilyRepository::new(db);
+        let repo = ctx.data::<S>()?.families();
+        let locale = parse_locale(event.locale.as_deref())?;
         let record = repo
             .add_event(FamilyEventNew {
                 family_id: FamilyId(parse_uuid(&family_id)?),
                 event_type: event.event_type,
-                date: parse_optional_date(event.date)?,
+                date: parse_optional_date(event.date, locale)?,
                 notes: event.notes,
+                created_by: parse_optional_wizard_id(event.wizard_id)?,
             })
             .await?;
-        Ok(record.into())
+        Ok(FamilyEventPayload::new(record, locale))
     }
 }
 
@@ -480,6 +922,7 @@ struct UpdatePersonInput {
     first_name: Option<String>,
     surname: Option<String>,
     surname_prefix: Option<Option<String>>,
+    nicknames: Option<Vec<String>>,
     sex: Option<SexGql>,
     notes: Option<Option<String>>,
     public: Option<bool>,
@@ -493,6 +936,7 @@ struct CreateFamilyInput {
     child_ids: Vec<ID>,
     notes: Option<String>,
     public: Option<bool>,
+    wizard_id: Option<ID>,
 }
 
 #[derive(InputObject)]
@@ -502,13 +946,108 @@ struct UpdateFamilyInput {
     child_ids: Option<Vec<ID>>,
     notes: Option<Option<String>>,
     public: Option<bool>,
+    wizard_id: Option<ID>,
 }
 
 #[derive(InputObject)]
 struct FamilyEventInput {
     event_type: String,
+    /// `vers 1750`, `avant le 3 mars 1801`, `between 1700 and 1710`, `1801-03-03`…
     date: Option<String>,
     notes: Option<String>,
+    /// Langue de `date` et de la date renvoyée (`fr`, `en`, `de`, `it`) ; `fr` par défaut.
+    locale: Option<String>,
+    wizard_id: Option<ID>,
+}
+
+#[derive(InputObject)]
+struct LifeEventInput {
+    /// `birth`, `baptism`, `death`, `burial`… ou le libellé d'un événement personnalisé.
+    event_type: String,
+    date: Option<String>,
+    place: Option<PlaceInput>,
+    witness_ids: Option<Vec<ID>>,
+    notes: Option<String>,
+    sources: Option<Vec<SourceInput>>,
+    /// Langue de `date` et de la date renvoyée ; `fr` par défaut.
+    locale: Option<String>,
+}
+
+impl LifeEventInput {
+    fn into_event(self) -> async_graphql::Result<(LifeEvent, DateLocale)> {
+        let locale = parse_locale(self.locale.as_deref())?;
+        let event = LifeEvent {
+            event_type: EventType::from_name(&self.event_type),
+            date: parse_optional_date(self.date, locale)?.unwrap_or(PreciseDate::Unknown),
+            place: self.place.map(Place::from),
+            witnesses: ids_to_person_ids(self.witness_ids.unwrap_or_default())?,
+            notes: self.notes,
+            sources: self
+                .sources
+                .unwrap_or_default()
+                .into_iter()
+                .map(SourceInput::into_source)
+                .collect::<async_graphql::Result<_>>()?,
+        };
+        Ok((event, locale))
+    }
+}
+
+#[derive(InputObject)]
+struct PlaceInput {
+    name: String,
+    locality: Option<String>,
+    city: Option<String>,
+    county: Option<String>,
+    state: Option<String>,
+    country: Option<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+}
+
+impl From<PlaceInput> for Place {
+    fn from(input: PlaceInput) -> Self {
+        Self {
+            name: input.name,
+            locality: input.locality,
+            city: input.city,
+            county: input.county,
+            state: input.state,
+            country: input.country,
+            latitude: input.latitude,
+            longitude: input.longitude,
+        }
+    }
+}
+
+#[derive(InputObject)]
+struct SourceInput {
+    /// Source existante à citer (ses champs sont mis à jour) ; nouvelle source sinon.
+    id: Option<ID>,
+    title: String,
+    author: Option<String>,
+    publication_info: Option<String>,
+    repository: Option<String>,
+    notes: Option<String>,
+    /// Emplacement cité dans la source, propre à cette citation.
+    page: Option<String>,
+}
+
+impl SourceInput {
+    fn into_source(self) -> async_graphql::Result<Source> {
+        Ok(Source {
+            id: match self.id {
+                Some(id) => parse_uuid(&id)?,
+                None => Uuid::new_v4(),
+            },
+            title: self.title,
+            author: self.author,
+            publication_info: self.publication_info,
+            repository: self.repository,
+            notes: self.notes,
+            page: self.page,
+        })
+    }
 }
 
 #[derive(SimpleObject)]
@@ -558,6 +1097,93 @@ impl From<Person> for PersonPayload {
     }
 }
 
+#[derive(Clone, SimpleObject)]
+struct PersonEventPayload {
+    id: ID,
+    person_id: ID,
+    event_type: String,
+    date: Option<String>,
+    place: Option<PlacePayload>,
+    witness_ids: Vec<ID>,
+    notes: Option<String>,
+    sources: Vec<SourcePayload>,
+}
+
+impl PersonEventPayload {
+    fn new(record: PersonEventRecord, locale: DateLocale) -> Self {
+        let event = record.event;
+        Self {
+            id: ID::from(record.id.to_string()),
+            person_id: ID::from(record.person_id.0.to_string()),
+            event_type: event.event_type.name().to_string(),
+            date: match event.date {
+                PreciseDate::Unknown => None,
+                date => Some(date.format_localized(locale)),
+            },
+            place: event.place.map(PlacePayload::from),
+            witness_ids: event
+                .witnesses
+                .into_iter()
+                .map(|witness| ID::from(witness.0.to_string()))
+                .collect(),
+            notes: event.notes,
+            sources: event.sources.into_iter().map(SourcePayload::from).collect(),
+        }
+    }
+}
+
+#[derive(Clone, SimpleObject)]
+struct PlacePayload {
+    name: String,
+    locality: Option<String>,
+    city: Option<String>,
+    county: Option<String>,
+    state: Option<String>,
+    country: Option<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+}
+
+impl From<Place> for PlacePayload {
+    fn from(place: Place) -> Self {
+        Self {
+            name: place.name,
+            locality: place.locality,
+            city: place.city,
+            county: place.county,
+            state: place.state,
+            country: place.country,
+            latitude: place.latitude,
+            longitude: place.longitude,
+        }
+    }
+}
+
+#[derive(Clone, SimpleObject)]
+struct SourcePayload {
+    id: ID,
+    title: String,
+    author: Option<String>,
+    publication_info: Option<String>,
+    repository: Option<String>,
+    notes: Option<String>,
+    page: Option<String>,
+}
+
+impl From<Source> for SourcePayload {
+    fn from(source: Source) -> Self {
+        Self {
+            id: ID::from(source.id.to_string()),
+            title: source.title,
+            author: source.author,
+            publication_info: source.publication_info,
+            repository: source.repository,
+            notes: source.notes,
+            page: source.page,
+        }
+    }
+}
+
 #[derive(Clone, SimpleObject)]
 struct FamilyEventPayload {
     id: ID,
@@ -567,13 +1193,13 @@ struct FamilyEventPayload {
     notes: Option<String>,
 }
 
-impl From<FamilyEventRecord> for FamilyEventPayload {
-    fn from(record: FamilyEventRecord) -> Self {
+impl FamilyEventPayload {
+    fn new(record: FamilyEventRecord, locale: DateLocale) -> Self {
         Self {
             id: ID::from(record.id.to_string()),
             family_id: ID::from(record.family_id.0.to_string()),
             event_type: record.event_type,
-            date: record.date.map(|d| d.to_string()),
+            date: record.date.map(|d| d.format_localized(locale)),
             notes: record.notes,
         }
     }
@@ -606,6 +1232,91 @@ impl From<Family> for FamilyPayload {
     }
 }
 
+#[derive(Clone, SimpleObject)]
+struct TrashedPersonPayload {
+    person: PersonPayload,
+    deleted_at: String,
+    deleted_by: Option<ID>,
+}
+
+impl From<Trashed<Person>> for TrashedPersonPayload {
+    fn from(trashed: Trashed<Person>) -> Self {
+        Self {
+            person: trashed.record.into(),
+            deleted_at: trashed.deleted_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            deleted_by: trashed
+                .deleted_by
+                .map(|wizard| ID::from(wizard.0.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, SimpleObject)]
+struct TrashedFamilyPayload {
+    family: FamilyPayload,
+    deleted_at: String,
+    deleted_by: Option<ID>,
+}
+
+impl From<Trashed<Family>> for TrashedFamilyPayload {
+    fn from(trashed: Trashed<Family>) -> Self {
+        Self {
+            family: trashed.record.into(),
+            deleted_at: trashed.deleted_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            deleted_by: trashed
+                .deleted_by
+                .map(|wizard| ID::from(wizard.0.to_string())),
+        }
+    }
+}
+
+/// Révision d'une personne ou d'une famille ; `before` et `after` sont les états
+/// complets en JSON.
+#[derive(Clone, SimpleObject)]
+struct RevisionPayload {
+    id: ID,
+    /// `create`, `update`, `delete`, `restore`, `revert` ou `purge`.
+    action: String,
+    changed_by: Option<ID>,
+    changed_at: String,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+impl From<Revision> for RevisionPayload {
+    fn from(revision: Revision) -> Self {
+        Self {
+            id: ID::from(revision.id.to_string()),
+            action: revision.action.as_str().to_string(),
+            changed_by: revision
+                .changed_by
+                .map(|wizard| ID::from(wizard.0.to_string())),
+            changed_at: revision.changed_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            before: revision.before.map(|state| state.to_string()),
+            after: revision.after.map(|state| state.to_string()),
+        }
+    }
+}
+
+/// Champ modifié entre deux révisions, valeurs en JSON ; absent d'un côté s'il
+/// n'y était pas renseigné.
+#[derive(Clone, SimpleObject)]
+struct FieldChangePayload {
+    field: String,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+impl From<FieldChange> for FieldChangePayload {
+    fn from(change: FieldChange) -> Self {
+        Self {
+            field: change.field,
+            before: change.before.map(|value| value.to_string()),
+            after: change.after.map(|value| value.to_string()),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Enum, Eq, PartialEq)]
 enum SexGql {
     Male,
@@ -663,17 +1374,181 @@ fn parse_optional_wizard_id(id: Option<ID>) -> async_graphql::Result<Option<Wiza
     }
 }
 
-fn parse_optional_date(value: Option<String>) -> async_graphql::Result<Option<NaiveDate>> {
+fn parse_revision_id(id: &ID) -> async_graphql::Result<i64> {
+    id.parse()
+        .map_err(|_| async_graphql::Error::new(format!("Révision invalide: {}", id.as_str())))
+}
+
+fn revision_diff(
+    history: &[Revision],
+    from: &ID,
+    to: &ID,
+) -> async_graphql::Result<Vec<FieldChangePayload>> {
+    let find = |id: &ID| -> async_graphql::Result<&Revision> {
+        let id = parse_revision_id(id)?;
+        history
+            .iter()
+            .find(|revision| revision.id == id)
+            .ok_or_else(|| async_graphql::Error::new("Révision introuvable"))
+    };
+    Ok(diff_revisions(find(from)?, find(to)?)
+        .into_iter()
+        .map(FieldChangePayload::from)
+        .collect())
+}
+
+fn parse_optional_date(
+    value: Option<String>,
+    locale: DateLocale,
+) -> async_graphql::Result<Option<PreciseDate>> {
     match value {
-        Some(raw) => NaiveDate::parse_from_str(&raw, "%Y-%m-%d")
+        Some(raw) => PreciseDate::parse_localized(&raw, locale)
             .map(Some)
-            .map_err(|err| async_graphql::Error::new(format!("Date invalide: {err}"))),
+            .ok_or_else(|| async_graphql::Error::new(format!("Date invalide: {raw}"))),
         None => Ok(None),
     }
 }
 
+fn parse_locale(tag: Option<&str>) -> async_graphql::Result<DateLocale> {
+    match tag {
+        Some(tag) => DateLocale::from_tag(tag)
+            .ok_or_else(|| async_graphql::Error::new(format!("Langue inconnue: {tag}"))),
+        None => Ok(DateLocale::default()),
+    }
+}
+
 fn parse_uuid(id: &ID) -> async_graphql::Result<Uuid> {
     Uuid::parse_str(id.as_str()).map_err(|err| async_graphql::Error::new(err.to_string()))
 }
 
-pub type GenealogySchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+pub type GenealogySchema<S> = Schema<QueryRoot<S>, MutationRoot<S>, EmptySubscription>;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    async fn run(schema: &GenealogySchema<MemoryStore>, query: &str) -> Value {
+        let response = schema.execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        response.data.into_json().unwrap()
+    }
+
+    async fn create_person(schema: &GenealogySchema<MemoryStore>, first_name: &str) -> String {
+        let data = run(
+            schema,
+            &format!(
+                r#"mutation {{ createPerson(input: {{ firstName: "{first_name}", surname: "Martin", sex: UNKNOWN }}) {{ id }} }}"#
+            ),
+        )
+        .await;
+        data["createPerson"]["id"].as_str().unwrap().to_string()
+    }
+
+    async fn create_family(
+        schema: &GenealogySchema<MemoryStore>,
+        father: &str,
+        mother: &str,
+        children: &[&str],
+    ) {
+        let children = children
+            .iter()
+            .map(|id| format!("\"{id}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        run(
+            schema,
+            &format!(
+                r#"mutation {{ createFamily(input: {{ fatherId: "{father}", motherId: "{mother}", childIds: [{children}] }}) {{ id }} }}"#
+            ),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn graph_queries_follow_the_families() {
+        let schema = build_schema(MemoryStore::new());
+        let grandfather = create_person(&schema, "Jean").await;
+        let grandmother = create_person(&schema, "Anne").await;
+        let brother = create_person(&schema, "Paul").await;
+        let sister = create_person(&schema, "Marie").await;
+        let aunt = create_person(&schema, "Louise").await;
+        let uncle = create_person(&schema, "Pierre").await;
+        let cousin1 = create_person(&schema, "Jacques").await;
+        let cousin2 = create_person(&schema, "Claire").await;
+        let child = create_person(&schema, "Lucie").await;
+        create_family(&schema, &grandfather, &grandmother, &[&brother, &sister]).await;
+        create_family(&schema, &brother, &aunt, &[&cousin1]).await;
+        create_family(&schema, &uncle, &sister, &[&cousin2]).await;
+        create_family(&schema, &cousin1, &cousin2, &[&child]).await;
+
+        let data = run(
+            &schema,
+            &format!(
+                r#"{{ ancestors(personId: "{child}", generations: 2) {{ firstName }}
+                     consanguinity(personId: "{child}")
+                     calculateRelationship(person1Id: "{child}", person2Id: "{grandfather}") {{ degree }} }}"#
+            ),
+        )
+        .await;
+        let mut names: Vec<&str> = data["ancestors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|person| person["firstName"].as_str().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            ["Claire", "Jacques", "Louise", "Marie", "Paul", "Pierre"]
+        );
+        assert_eq!(data["consanguinity"], 1.0 / 16.0);
+        assert_eq!(data["calculateRelationship"]["degree"], 3);
+    }
+
+    #[tokio::test]
+    async fn person_mutations_are_versioned_and_trashed() {
+        let schema = build_schema(MemoryStore::new());
+        let father = create_person(&schema, "Jean").await;
+        let mother = create_person(&schema, "Anne").await;
+        let child = create_person(&schema, "Paul").await;
+        create_family(&schema, &father, &mother, &[&child]).await;
+
+        run(
+            &schema,
+            &format!(
+                r#"mutation {{ updatePerson(id: "{child}", input: {{ firstName: "Pierre" }}) {{ id }} }}"#
+            ),
+        )
+        .await;
+        let refused = schema
+            .execute(format!(r#"mutation {{ deletePerson(id: "{father}") }}"#))
+            .await;
+        assert_eq!(refused.errors.len(), 1);
+        run(
+            &schema,
+            &format!(r#"mutation {{ deletePerson(id: "{child}") }}"#),
+        )
+        .await;
+
+        let data = run(
+            &schema,
+            &format!(
+                r#"{{ person(id: "{child}") {{ id }}
+                     personHistory(personId: "{child}") {{ action }}
+                     trashedPersons {{ person {{ firstName }} }} }}"#
+            ),
+        )
+        .await;
+        assert_eq!(data["person"], Value::Null);
+        let actions: Vec<&str> = data["personHistory"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|revision| revision["action"].as_str().unwrap())
+            .collect();
+        assert_eq!(actions, ["create", "update", "delete"]);
+        assert_eq!(data["trashedPersons"][0]["person"]["firstName"], "Pierre");
+    }
+}